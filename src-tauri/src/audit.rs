@@ -0,0 +1,267 @@
+//! Append-only JSONL audit log of tool calls routed through the proxy.
+//!
+//! One record per call, written to `audit.jsonl` in the app data dir. When the
+//! file would exceed the configured size it is rotated to `audit.jsonl.1`
+//! (replacing any previous rotation), so disk use stays under ~2× the cap.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+use crate::state::{AuditConfig, SharedState};
+use crate::stats::unix_now;
+
+const LOG_FILE: &str = "audit.jsonl";
+const ROTATED_FILE: &str = "audit.jsonl.1";
+
+/// One tool call as written to the log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+    pub server_id: String,
+    pub server_name: String,
+    pub tool: String,
+    pub session_id: Option<String>,
+    pub client: String,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub arguments: Value,
+    pub result: Value,
+}
+
+/// What happened on a single call, before redaction.
+pub struct ToolCallAudit<'a> {
+    pub server_id: &'a str,
+    pub server_name: &'a str,
+    pub tool: &'a str,
+    pub session_id: Option<&'a str>,
+    pub client: &'a str,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub arguments: &'a Value,
+    pub result: &'a Value,
+}
+
+/// Managed handle to the log file. The mutex serializes appends and rotation;
+/// clones share it.
+#[derive(Clone)]
+pub struct AuditLog {
+    /// `None` when the app data dir couldn't be resolved.
+    dir: Option<PathBuf>,
+    lock: Arc<Mutex<()>>,
+}
+
+impl AuditLog {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Arc::default(),
+        }
+    }
+
+    /// A log that refuses every write, for when there's nowhere to keep it.
+    pub fn unavailable() -> Self {
+        Self {
+            dir: None,
+            lock: Arc::default(),
+        }
+    }
+
+    fn dir(&self) -> io::Result<&Path> {
+        self.dir
+            .as_deref()
+            .ok_or_else(|| io::Error::other("audit log directory is unavailable"))
+    }
+
+    fn current_path(&self) -> io::Result<PathBuf> {
+        Ok(self.dir()?.join(LOG_FILE))
+    }
+
+    fn rotated_path(&self) -> io::Result<PathBuf> {
+        Ok(self.dir()?.join(ROTATED_FILE))
+    }
+
+    /// Append one record, rotating first if it would push the file past `max_bytes`.
+    /// Blocks on file I/O; async callers go through [`record_tool_call`].
+    pub fn append(&self, record: &AuditRecord, max_bytes: u64) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let _guard = self.lock.lock().unwrap();
+        fs::create_dir_all(self.dir()?)?;
+
+        let current = self.current_path()?;
+        let size = fs::metadata(&current).map(|m| m.len()).unwrap_or(0);
+        if size > 0 && size + line.len() as u64 > max_bytes {
+            fs::rename(&current, self.rotated_path()?)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&current)?;
+        file.write_all(&line)
+    }
+
+    /// Copy the full log (rotated file first, so records stay in order) to
+    /// `dest`. Returns the number of bytes written.
+    pub fn export(&self, dest: &Path) -> io::Result<u64> {
+        let _guard = self.lock.lock().unwrap();
+        let mut out = File::create(dest)?;
+        let mut written = 0;
+        for path in [self.rotated_path()?, self.current_path()?] {
+            match File::open(&path) {
+                Ok(mut f) => written += io::copy(&mut f, &mut out)?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        out.flush()?;
+        Ok(written)
+    }
+}
+
+/// Build the record for `call`, applying redaction per `config`.
+pub fn build_record(call: &ToolCallAudit<'_>, config: &AuditConfig) -> AuditRecord {
+    let capture = |v: &Value| {
+        if config.redact {
+            redact(v)
+        } else {
+            v.clone()
+        }
+    };
+    AuditRecord {
+        timestamp: unix_now(),
+        server_id: call.server_id.to_string(),
+        server_name: call.server_name.to_string(),
+        tool: call.tool.to_string(),
+        session_id: call.session_id.map(str::to_string),
+        client: call.client.to_string(),
+        success: call.success,
+        duration_ms: call.duration_ms,
+        arguments: capture(call.arguments),
+        result: capture(call.result),
+    }
+}
+
+/// Replace a value with its serialized length and SHA-256, so calls can be
+/// correlated without storing secrets.
+pub fn redact(value: &Value) -> Value {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    serde_json::json!({
+        "bytes": bytes.len(),
+        "sha256": format!("{:x}", Sha256::digest(&bytes)),
+    })
+}
+
+/// Record a proxied tool call if auditing is enabled. The write happens on the
+/// blocking pool, off the request path. Failures are logged, never surfaced —
+/// auditing must not break the call itself.
+pub(crate) fn record_tool_call(app: &AppHandle, call: ToolCallAudit<'_>) {
+    let config = {
+        let state = app.state::<SharedState>();
+        let s = state.lock().unwrap();
+        s.proxy_settings.audit.clone()
+    };
+    if !config.enabled {
+        return;
+    }
+
+    let record = build_record(&call, &config);
+    let log = app.state::<AuditLog>().inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = log.append(&record, config.max_bytes) {
+            warn!("Failed to write audit log: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> AuditLog {
+        let dir = std::env::temp_dir().join(format!("agent-hub-audit-{name}-{}", unix_now()));
+        let _ = fs::remove_dir_all(&dir);
+        AuditLog::new(dir)
+    }
+
+    fn sample_call<'a>(args: &'a Value, result: &'a Value) -> ToolCallAudit<'a> {
+        ToolCallAudit {
+            server_id: "srv",
+            server_name: "Server",
+            tool: "echo",
+            session_id: Some("sess"),
+            client: "claude",
+            success: true,
+            duration_ms: 5,
+            arguments: args,
+            result,
+        }
+    }
+
+    #[test]
+    fn redacted_record_omits_contents() {
+        let args = serde_json::json!({ "token": "secret" });
+        let result = serde_json::json!({ "content": [] });
+        let record = build_record(&sample_call(&args, &result), &AuditConfig::default());
+
+        let line = serde_json::to_string(&record).unwrap();
+        assert!(!line.contains("secret"));
+        assert_eq!(record.arguments["bytes"], 18);
+        assert_eq!(record.arguments["sha256"].as_str().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn unredacted_record_keeps_contents() {
+        let args = serde_json::json!({ "q": "hello" });
+        let config = AuditConfig {
+            redact: false,
+            ..AuditConfig::default()
+        };
+        let record = build_record(&sample_call(&args, &Value::Null), &config);
+        assert_eq!(record.arguments, args);
+    }
+
+    #[test]
+    fn append_rotates_when_cap_exceeded() {
+        let log = temp_log("rotate");
+        let args = Value::Null;
+        let record = build_record(&sample_call(&args, &args), &AuditConfig::default());
+        let line_len = serde_json::to_vec(&record).unwrap().len() as u64 + 1;
+
+        // Room for two lines; the third forces a rotation
+        for _ in 0..3 {
+            log.append(&record, line_len * 2).unwrap();
+        }
+        let current = fs::read_to_string(log.current_path().unwrap()).unwrap();
+        let rotated = fs::read_to_string(log.rotated_path().unwrap()).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert_eq!(rotated.lines().count(), 2);
+
+        let dir = log.dir().unwrap();
+        let export = dir.join("export.jsonl");
+        assert_eq!(log.export(&export).unwrap(), line_len * 3);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn unavailable_log_refuses_writes() {
+        let log = AuditLog::unavailable();
+        let args = Value::Null;
+        let record = build_record(&sample_call(&args, &args), &AuditConfig::default());
+        assert!(log.append(&record, 1024).is_err());
+        assert!(log
+            .export(&std::env::temp_dir().join("unused.jsonl"))
+            .is_err());
+    }
+}
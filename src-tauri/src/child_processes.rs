@@ -0,0 +1,252 @@
+//! Stdio server processes spawned by this app, persisted so a crash doesn't
+//! leave them running unnoticed.
+//!
+//! Every spawned child is recorded with its start time and written to the
+//! store straight away. On the next launch the previous session's records are
+//! checked against the running processes; a PID only counts as an orphan if a
+//! process with that PID and the same start time is still alive, so a reused
+//! PID is never mistaken for one of ours.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessesToUpdate, Signal, System};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::persistence::{flush_store, save_child_processes};
+
+/// A child process spawned for a stdio server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedProcess {
+    pub pid: u32,
+    pub server_id: String,
+    pub command: String,
+    /// Process start time in seconds since the epoch, as reported by the OS.
+    pub started_at: u64,
+}
+
+/// Live children of this session and the records left by the previous one.
+pub struct ChildProcesses {
+    live: Mutex<Vec<TrackedProcess>>,
+    previous: Mutex<Vec<TrackedProcess>>,
+}
+
+impl ChildProcesses {
+    pub fn new(previous: Vec<TrackedProcess>) -> Self {
+        Self {
+            live: Mutex::new(Vec::new()),
+            previous: Mutex::new(previous),
+        }
+    }
+
+    /// Records from the previous session whose process is still running.
+    /// Records of processes that have since exited are dropped.
+    pub fn orphans(&self, sys: &mut System) -> Vec<TrackedProcess> {
+        let mut previous = self.previous.lock().unwrap();
+        let pids: Vec<Pid> = previous.iter().map(|p| Pid::from_u32(p.pid)).collect();
+        sys.refresh_processes(ProcessesToUpdate::Some(&pids), true);
+        previous.retain(|record| is_same_process(sys, record));
+        previous.clone()
+    }
+
+    /// Kill the given orphans and their descendants. PIDs that aren't a
+    /// verified orphan from the previous session are ignored. Returns the
+    /// PIDs that were signalled; [`Self::persist`] the change afterwards.
+    pub fn kill_orphans(&self, sys: &mut System, pids: &[u32]) -> Vec<u32> {
+        let targets: Vec<TrackedProcess> = self
+            .orphans(sys)
+            .into_iter()
+            .filter(|record| pids.contains(&record.pid))
+            .collect();
+        if targets.is_empty() {
+            return Vec::new();
+        }
+
+        // npx and friends run the real server as a grandchild
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        let mut killed = Vec::new();
+        for target in &targets {
+            let root = Pid::from_u32(target.pid);
+            let mut doomed: Vec<Pid> = sys
+                .processes()
+                .keys()
+                .copied()
+                .filter(|pid| *pid != root && descends_from(sys, *pid, root))
+                .collect();
+            doomed.push(root);
+            for pid in doomed {
+                if let Some(process) = sys.process(pid) {
+                    if process
+                        .kill_with(Signal::Term)
+                        .unwrap_or_else(|| process.kill())
+                    {
+                        killed.push(pid.as_u32());
+                    }
+                }
+            }
+            info!(
+                "Killed orphaned process {} for server {}",
+                target.pid, target.server_id
+            );
+        }
+
+        let handled: Vec<u32> = targets.iter().map(|t| t.pid).collect();
+        self.previous
+            .lock()
+            .unwrap()
+            .retain(|record| !handled.contains(&record.pid));
+        killed
+    }
+
+    /// Write the live children, plus previous-session records not yet dealt
+    /// with, through to disk rather than waiting on the save debounce: the
+    /// point is to survive a crash. The write happens on a blocking thread,
+    /// since callers are on the async spawn and shutdown paths.
+    pub fn persist(&self, app: &AppHandle) {
+        let mut records = self.live.lock().unwrap().clone();
+        records.extend(self.previous.lock().unwrap().iter().cloned());
+        save_child_processes(app, &records);
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || flush_store(&app));
+    }
+}
+
+/// How many parent links to follow when checking ancestry.
+const MAX_PROCESS_DEPTH: usize = 32;
+
+fn descends_from(sys: &System, pid: Pid, ancestor: Pid) -> bool {
+    let mut current = pid;
+    for _ in 0..MAX_PROCESS_DEPTH {
+        match sys.process(current).and_then(|p| p.parent()) {
+            Some(parent) if parent == ancestor => return true,
+            Some(parent) if parent != current => current = parent,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Start times are whole seconds; allow for rounding between reads.
+fn is_same_process(sys: &System, record: &TrackedProcess) -> bool {
+    sys.process(Pid::from_u32(record.pid))
+        .is_some_and(|p| p.start_time().abs_diff(record.started_at) <= 1)
+}
+
+/// Record a newly spawned child and persist the live set immediately.
+pub fn track(app: &AppHandle, server_id: &str, pid: u32, command: &str) {
+    let Some(children) = app.try_state::<ChildProcesses>() else {
+        return;
+    };
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[Pid::from_u32(pid)]), true);
+    let Some(started_at) = sys.process(Pid::from_u32(pid)).map(|p| p.start_time()) else {
+        warn!("Server {server_id}: child {pid} exited before it could be tracked");
+        return;
+    };
+
+    children.live.lock().unwrap().push(TrackedProcess {
+        pid,
+        server_id: server_id.to_string(),
+        command: command.to_string(),
+        started_at,
+    });
+    children.persist(app);
+}
+
+/// Forget a child that has exited or been killed.
+pub fn untrack(app: &AppHandle, pid: u32) {
+    let Some(children) = app.try_state::<ChildProcesses>() else {
+        return;
+    };
+    let removed = {
+        let mut live = children.live.lock().unwrap();
+        let before = live.len();
+        live.retain(|p| p.pid != pid);
+        live.len() != before
+    };
+    if removed {
+        children.persist(app);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::{Child, Command};
+
+    fn spawn_sleeper() -> Child {
+        Command::new("sleep").arg("30").spawn().unwrap()
+    }
+
+    fn record(pid: u32, started_at: u64) -> TrackedProcess {
+        TrackedProcess {
+            pid,
+            server_id: "srv".into(),
+            command: "sleep 30".into(),
+            started_at,
+        }
+    }
+
+    fn start_time(sys: &mut System, pid: u32) -> u64 {
+        let pid = Pid::from_u32(pid);
+        sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        sys.process(pid).unwrap().start_time()
+    }
+
+    #[test]
+    fn orphans_are_running_processes_with_the_recorded_start_time() {
+        let mut child = spawn_sleeper();
+        let mut sys = System::new();
+        let started_at = start_time(&mut sys, child.id());
+        let children = ChildProcesses::new(vec![
+            record(child.id(), started_at),
+            // Same PID, different process: reused after the original exited
+            record(child.id(), started_at.saturating_sub(3600)),
+            record(u32::MAX - 1, started_at),
+        ]);
+
+        assert_eq!(children.orphans(&mut sys), [record(child.id(), started_at)]);
+        // Stale records are forgotten
+        assert_eq!(children.previous.lock().unwrap().len(), 1);
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn kill_orphans_only_signals_verified_orphans() {
+        let mut orphan = spawn_sleeper();
+        let mut bystander = spawn_sleeper();
+        let mut sys = System::new();
+        let started_at = start_time(&mut sys, orphan.id());
+        let children = ChildProcesses::new(vec![record(orphan.id(), started_at)]);
+
+        let killed = children.kill_orphans(&mut sys, &[orphan.id(), bystander.id()]);
+
+        assert_eq!(killed, [orphan.id()]);
+        assert!(orphan.wait().unwrap().code().is_none());
+        assert!(bystander.try_wait().unwrap().is_none());
+        assert!(children.orphans(&mut sys).is_empty());
+
+        bystander.kill().unwrap();
+        bystander.wait().unwrap();
+    }
+
+    #[test]
+    fn descends_from_follows_parent_links() {
+        let mut child = spawn_sleeper();
+        let mut sys = System::new();
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        let me = Pid::from_u32(std::process::id());
+        let pid = Pid::from_u32(child.id());
+
+        assert!(descends_from(&sys, pid, me));
+        assert!(!descends_from(&sys, me, pid));
+        assert!(!descends_from(&sys, Pid::from_u32(u32::MAX - 1), me));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
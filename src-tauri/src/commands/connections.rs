@@ -1,14 +1,19 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
+use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, State};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::error::AppError;
-use crate::mcp::client::{McpClient, SharedConnections};
+use crate::mcp::client::{ClientOptions, McpClient, SharedConnections};
 use crate::mcp::oauth;
+use crate::mcp::prewarm;
 use crate::mcp::proxy::ProxyState;
+use crate::mcp::types::{McpToolDef, ServerInfo};
 use crate::state::{
-    ConnectionState, McpTool, ServerStatus, ServerTransport, SharedOAuthStore, SharedState,
+    AppState, ConnectionState, McpTool, ServerConfig, ServerConfigInput, ServerStatus,
+    ServerTransport, SharedOAuthStore, SharedState,
 };
 
 #[tauri::command]
@@ -22,6 +27,7 @@ pub async fn connect_server(
     // Read config while holding the lock briefly
     let server_config = {
         let mut s = state.lock().unwrap();
+        let default_env = s.default_env.clone();
         let server = s
             .servers
             .iter_mut()
@@ -36,14 +42,7 @@ pub async fn connect_server(
 
         server.status = Some(ServerStatus::Connecting);
 
-        ServerConnectConfig {
-            transport: server.transport.clone(),
-            command: server.command.clone(),
-            args: server.args.clone().unwrap_or_default(),
-            env: server.env.clone().unwrap_or_default(),
-            url: server.url.clone(),
-            headers: server.headers.clone().unwrap_or_default(),
-        }
+        ServerConnectConfig::from_server(server, &default_env)
     };
 
     let _ = app.emit(
@@ -53,42 +52,13 @@ pub async fn connect_server(
 
     // For HTTP transport, check if we have existing OAuth tokens
     let access_token = if matches!(server_config.transport, ServerTransport::Http) {
-        resolve_access_token(&oauth_store, &id, &app).await
+        oauth::resolve_access_token(&oauth_store, &id, &app).await
     } else {
         None
     };
 
     // Do the async connection work WITHOUT holding either lock
-    let client_result = match server_config.transport {
-        ServerTransport::Stdio => {
-            let command = server_config
-                .command
-                .ok_or_else(|| AppError::ConnectionFailed("No command specified".into()))?;
-            McpClient::connect_stdio(&app, &id, &command, &server_config.args, &server_config.env)
-                .await
-        }
-        ServerTransport::Http => {
-            let url = server_config
-                .url
-                .ok_or_else(|| AppError::ConnectionFailed("No URL specified".into()))?;
-            emit_server_log(&app, &id, "info", &format!("Connecting to {url}"));
-            match McpClient::connect_http(&url, server_config.headers, access_token).await {
-                Ok(client) => {
-                    emit_server_log(
-                        &app,
-                        &id,
-                        "info",
-                        &format!("Connected — {} tools available", client.tools.len()),
-                    );
-                    Ok(client)
-                }
-                Err(e) => {
-                    emit_server_log(&app, &id, "error", &format!("Connection failed: {e}"));
-                    Err(e)
-                }
-            }
-        }
-    };
+    let client_result = connect_client(&app, &id, server_config, access_token).await;
 
     match client_result {
         Ok(client) => {
@@ -97,26 +67,21 @@ pub async fn connect_server(
         }
         Err(AppError::AuthRequired(_)) => {
             info!("Server {id} requires OAuth authentication");
-            mark_server_error(
-                &app,
-                &state,
-                &id,
-                "Authentication required. Click Authorize to sign in.",
-            );
+            let e = AppError::AuthRequired("Click Authorize to sign in.".into());
+            mark_server_error(&app, &state, &id, &e);
             let _ = app.emit("oauth-required", serde_json::json!({ "serverId": id }));
-            Err(AppError::AuthRequired(
-                "Authentication required. Click Authorize to sign in.".into(),
-            ))
+            Err(e)
         }
         Err(e) => {
             error!("Failed to connect to server {id}: {e}");
+            mark_server_error(&app, &state, &id, &e);
             let error_message = e.to_string();
-            mark_server_error(&app, &state, &id, &error_message);
             let _ = app.emit(
                 "server-error",
                 serde_json::json!({
                     "serverId": id,
                     "error": error_message,
+                    "errorCode": e.code(),
                     "details": format!("Connection to server {id} failed: {error_message}")
                 }),
             );
@@ -132,23 +97,106 @@ pub async fn disconnect_server(
     connections: State<'_, SharedConnections>,
     id: String,
 ) -> Result<(), AppError> {
-    // Remove and shut down the live MCP client
-    {
-        let mut conns = connections.lock().await;
-        if let Some(client) = conns.remove(&id) {
-            client.shutdown();
-        }
+    teardown_connection(&app, &state, &connections, &id).await?;
+    crate::tray::rebuild_tray_menu(&app);
+
+    // Notify SSE clients — tool list is now empty for this server
+    crate::mcp::proxy::notify_if_tools_changed(&app, &id, &[]).await;
+
+    // Update integration configs so AI tools no longer see this server
+    let proxy_state = app.state::<ProxyState>();
+    let port = proxy_state.port().await;
+    if let Err(e) = crate::commands::integrations::refresh_integration_configs(&app, port).await {
+        tracing::warn!("Failed to update integration configs after disconnect: {e}");
     }
 
-    // Update AppState
+    emit_server_log(&app, &id, "info", "Disconnected");
+    info!("Disconnected server {id}");
+
+    Ok(())
+}
+
+/// Disconnect a server and connect it again from its current config, e.g. to
+/// pick up an edit or recover a wedged server. Emits `disconnected`, then the
+/// usual connect events. A failed reconnect leaves the server in `Error` with
+/// its tools withdrawn, never half-connected.
+#[tauri::command]
+pub async fn restart_server(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+    oauth_store: State<'_, SharedOAuthStore>,
+    id: String,
+) -> Result<(), AppError> {
     {
-        let mut s = state.lock().unwrap();
+        let s = state.lock().unwrap();
         let server = s
             .servers
-            .iter_mut()
+            .iter()
             .find(|s| s.id == id)
             .ok_or_else(|| AppError::ServerNotFound(id.clone()))?;
-        server.status = Some(ServerStatus::Disconnected);
+        if server.status == Some(ServerStatus::Connecting) {
+            return Err(AppError::Validation(format!(
+                "Server {id} is still connecting; wait for it to finish before restarting"
+            )));
+        }
+    }
+
+    teardown_connection(&app, &state, &connections, &id).await?;
+    emit_server_log(&app, &id, "info", "Restarting");
+    info!("Restarting server {id}");
+
+    let result = connect_server(app.clone(), state, connections, oauth_store, id.clone()).await;
+
+    // On success the connect path already refreshed tools and integration
+    // configs; on failure nothing has, so withdraw the old tools here
+    if result.is_err() {
+        let _ = app.emit(
+            "tools-updated",
+            serde_json::json!({ "serverId": id, "tools": [] }),
+        );
+        crate::mcp::proxy::notify_if_tools_changed(&app, &id, &[]).await;
+        let port = app.state::<ProxyState>().port().await;
+        if let Err(e) = crate::commands::integrations::refresh_integration_configs(&app, port).await
+        {
+            tracing::warn!("Failed to update integration configs after restart: {e}");
+        }
+    }
+
+    result
+}
+
+/// Force a server back to a clean `Disconnected` state without touching its
+/// config. Unlike `disconnect_server` this never fails on inconsistent state —
+/// it's the "get unstuck" escape hatch when a server is wedged in `Connecting`
+/// or `Error` and a normal connect/disconnect is refused.
+#[tauri::command]
+pub async fn reset_server_state(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+    id: String,
+) -> Result<(), AppError> {
+    {
+        let s = state.lock().unwrap();
+        if !s.servers.iter().any(|s| s.id == id) {
+            return Err(AppError::ServerNotFound(id));
+        }
+    }
+
+    // Drop any live client, even if AppState thinks we're not connected
+    let client = connections.lock().await.remove(&id);
+    if let Some(client) = client {
+        client.shutdown();
+    }
+
+    {
+        let mut s = state.lock().unwrap();
+        if let Some(server) = s.servers.iter_mut().find(|s| s.id == id) {
+            server.status = Some(ServerStatus::Disconnected);
+            server.last_error = None;
+            server.last_error_at = None;
+        }
         s.connections.remove(&id);
     }
 
@@ -156,31 +204,123 @@ pub async fn disconnect_server(
         "server-status-changed",
         serde_json::json!({ "serverId": id, "status": "disconnected" }),
     );
+    let _ = app.emit(
+        "tools-updated",
+        serde_json::json!({ "serverId": id, "tools": [] }),
+    );
 
     crate::tray::rebuild_tray_menu(&app);
-
-    // Notify SSE clients — tool list is now empty for this server
     crate::mcp::proxy::notify_if_tools_changed(&app, &id, &[]).await;
 
-    // Update integration configs so AI tools no longer see this server
-    let proxy_state = app.state::<ProxyState>();
-    let port = proxy_state.port().await;
-    if let Err(e) = crate::commands::integrations::update_all_integration_configs(&app, port) {
-        tracing::warn!("Failed to update integration configs after disconnect: {e}");
+    let port = app.state::<ProxyState>().port().await;
+    if let Err(e) = crate::commands::integrations::refresh_integration_configs(&app, port).await {
+        tracing::warn!("Failed to update integration configs after reset: {e}");
     }
 
-    emit_server_log(&app, &id, "info", "Disconnected");
-    info!("Disconnected server {id}");
+    emit_server_log(&app, &id, "info", "State reset");
+    info!("Reset state for server {id}");
 
     Ok(())
 }
 
+/// What `reconcile_now` changed.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileSummary {
+    /// Managed skills that were missing and got reinstalled.
+    pub skills_installed: Vec<String>,
+    /// Servers marked connected without a live client, now disconnected.
+    pub statuses_reset: Vec<String>,
+    /// Live clients whose server config no longer exists, now shut down.
+    pub orphaned_clients_removed: Vec<String>,
+    /// Cached tool lists for servers without a live client, now cleared.
+    pub stale_connections_cleared: Vec<String>,
+}
+
+/// Re-run startup reconciliation on demand: reinstall missing managed skills
+/// and bring server statuses back in line with the live clients.
+#[tauri::command]
+pub async fn reconcile_now(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+) -> Result<ReconcileSummary, AppError> {
+    let mut summary = ReconcileSummary {
+        skills_installed: crate::commands::skills::reconcile_managed_skills(&app, &state),
+        ..Default::default()
+    };
+
+    // Shut down clients for servers that were removed from the config
+    let live_ids = {
+        let mut conns = connections.lock().await;
+        let configured: Vec<String> = {
+            let s = state.lock().unwrap();
+            s.servers.iter().map(|srv| srv.id.clone()).collect()
+        };
+        for id in conns.ids() {
+            if !configured.contains(&id) {
+                if let Some(client) = conns.remove(&id) {
+                    client.shutdown();
+                }
+                summary.orphaned_clients_removed.push(id);
+            }
+        }
+        conns.ids()
+    };
+
+    {
+        let mut s = state.lock().unwrap();
+        for server in &mut s.servers {
+            if server.status == Some(ServerStatus::Connected) && !live_ids.contains(&server.id) {
+                server.status = Some(ServerStatus::Disconnected);
+                summary.statuses_reset.push(server.id.clone());
+            }
+        }
+        let stale: Vec<String> = s
+            .connections
+            .keys()
+            .filter(|id| !live_ids.contains(id))
+            .cloned()
+            .collect();
+        for id in &stale {
+            s.connections.remove(id);
+        }
+        summary.stale_connections_cleared = stale;
+    }
+
+    for id in &summary.statuses_reset {
+        let _ = app.emit(
+            "server-status-changed",
+            serde_json::json!({ "serverId": id, "status": "disconnected" }),
+        );
+    }
+    for id in &summary.stale_connections_cleared {
+        crate::mcp::proxy::notify_if_tools_changed(&app, id, &[]).await;
+    }
+
+    let changed = !summary.statuses_reset.is_empty()
+        || !summary.orphaned_clients_removed.is_empty()
+        || !summary.stale_connections_cleared.is_empty();
+    if changed {
+        crate::tray::rebuild_tray_menu(&app);
+        let port = app.state::<ProxyState>().port().await;
+        if let Err(e) = crate::commands::integrations::refresh_integration_configs(&app, port).await
+        {
+            tracing::warn!("Failed to update integration configs after reconcile: {e}");
+        }
+    }
+
+    info!("Reconciled state: {summary:?}");
+    Ok(summary)
+}
+
 /// Reconnect servers that were previously connected (called on app startup).
 /// Resets all statuses to Disconnected first, then attempts to reconnect each.
 pub async fn reconnect_on_startup(app: AppHandle) {
     let servers_to_reconnect: Vec<(String, ServerConnectConfig)> = {
         let state = app.state::<SharedState>();
         let mut s = state.lock().unwrap();
+        let default_env = s.default_env.clone();
 
         let mut to_reconnect = Vec::new();
         for server in &mut s.servers {
@@ -189,14 +329,7 @@ pub async fn reconnect_on_startup(app: AppHandle) {
             {
                 to_reconnect.push((
                     server.id.clone(),
-                    ServerConnectConfig {
-                        transport: server.transport.clone(),
-                        command: server.command.clone(),
-                        args: server.args.clone().unwrap_or_default(),
-                        env: server.env.clone().unwrap_or_default(),
-                        url: server.url.clone(),
-                        headers: server.headers.clone().unwrap_or_default(),
-                    },
+                    ServerConnectConfig::from_server(server, &default_env),
                 ));
             }
             // Reset all to disconnected — real status comes from actual connections
@@ -247,42 +380,12 @@ pub async fn reconnect_on_startup(app: AppHandle) {
         );
 
         let access_token = if matches!(config.transport, ServerTransport::Http) {
-            resolve_access_token(&oauth_store, &id, &app).await
+            oauth::resolve_access_token(&oauth_store, &id, &app).await
         } else {
             None
         };
 
-        let client_result = match config.transport {
-            ServerTransport::Stdio => {
-                let Some(command) = config.command else {
-                    error!("Server {id} has no command, skipping reconnect");
-                    continue;
-                };
-                McpClient::connect_stdio(&app, &id, &command, &config.args, &config.env).await
-            }
-            ServerTransport::Http => {
-                let Some(url) = config.url else {
-                    error!("Server {id} has no URL, skipping reconnect");
-                    continue;
-                };
-                emit_server_log(&app, &id, "info", &format!("Connecting to {url}"));
-                match McpClient::connect_http(&url, config.headers, access_token).await {
-                    Ok(client) => {
-                        emit_server_log(
-                            &app,
-                            &id,
-                            "info",
-                            &format!("Connected — {} tools available", client.tools.len()),
-                        );
-                        Ok(client)
-                    }
-                    Err(e) => {
-                        emit_server_log(&app, &id, "error", &format!("Connection failed: {e}"));
-                        Err(e)
-                    }
-                }
-            }
-        };
+        let client_result = connect_client(&app, &id, config, access_token).await;
 
         match client_result {
             Ok(client) => {
@@ -292,7 +395,7 @@ pub async fn reconnect_on_startup(app: AppHandle) {
             }
             Err(e) => {
                 error!("Failed to reconnect server {id}: {e}");
-                mark_server_error(&app, &state, &id, &e.to_string());
+                mark_server_error(&app, &state, &id, &e);
             }
         }
     }
@@ -301,56 +404,541 @@ pub async fn reconnect_on_startup(app: AppHandle) {
 // --- Private helpers ---
 
 /// Temporary struct to hold server config data extracted from the lock.
-struct ServerConnectConfig {
+pub(crate) struct ServerConnectConfig {
+    name: String,
     transport: ServerTransport,
     command: Option<String>,
     args: Vec<String>,
+    default_env: HashMap<String, String>,
     env: HashMap<String, String>,
+    /// Read when spawning, so file I/O and its errors stay outside the lock.
+    env_file: Option<String>,
+    prewarm: bool,
     url: Option<String>,
     headers: HashMap<String, String>,
+    options: ClientOptions,
 }
 
-/// Try to get a valid access token from stored OAuth state, refreshing if needed.
-async fn resolve_access_token(
-    oauth_store: &SharedOAuthStore,
-    id: &str,
-    app: &AppHandle,
-) -> Option<String> {
-    let store = oauth_store.lock().await;
-    let oauth_state = store.get(id)?;
-    let tokens = oauth_state.tokens.as_ref()?;
+impl ServerConnectConfig {
+    pub(crate) fn from_server(
+        server: &ServerConfig,
+        default_env: &HashMap<String, String>,
+    ) -> Self {
+        Self {
+            name: server.name.clone(),
+            transport: server.transport.clone(),
+            command: server.command.clone(),
+            args: server.args.clone().unwrap_or_default(),
+            default_env: default_env.clone(),
+            env: server.env.clone().unwrap_or_default(),
+            env_file: server.env_file.clone(),
+            prewarm: server.prewarm,
+            url: server.url.clone(),
+            headers: server.headers.clone().unwrap_or_default(),
+            options: ClientOptions::from_server(server),
+        }
+    }
 
-    if !oauth::is_token_expired(tokens) {
-        return Some(tokens.access_token.clone());
+    /// The child's environment: the default env, then the env file, then the
+    /// server's own env, each layer overriding keys that differ only in case.
+    /// Remaining case-variant keys are collapsed so the child sees one
+    /// unambiguous value.
+    fn resolve_env(&self) -> Result<HashMap<String, String>, AppError> {
+        let mut env = self.default_env.clone();
+        if let Some(path) = &self.env_file {
+            env = layer_env(&env, crate::env_file::load(path)?);
+        }
+        let mut env = layer_env(&env, self.env.clone());
+        for note in crate::commands::servers::normalize_env_case(&mut env) {
+            warn!("Server '{}': {note}", self.name);
+        }
+        Ok(env)
     }
 
-    if tokens.refresh_token.is_some() {
-        drop(store);
-        match oauth::try_refresh_token(oauth_store, id, app).await {
-            Ok(new_token) => Some(new_token),
-            Err(e) => {
-                tracing::warn!("Token refresh failed: {e}, will try without token");
-                None
+    /// Header values with `$VAR` and `${VAR}` expanded from the server's env
+    /// layers, falling back to the app's environment. Unknown variables and
+    /// the OAuth token placeholder are left for the transport as written.
+    pub(crate) fn resolve_headers(&self) -> Result<HashMap<String, String>, AppError> {
+        let env = self.resolve_env()?;
+        let lookup = |name: &str| env.get(name).cloned().or_else(|| std::env::var(name).ok());
+        Ok(self
+            .headers
+            .iter()
+            .map(|(k, v)| {
+                let value = crate::commands::servers::expand_env_references(v, lookup);
+                (k.clone(), value)
+            })
+            .collect())
+    }
+
+    /// Dial the HTTP server with `access_token`, initialize it and list its
+    /// tools, without the connect-phase events `connect_client` emits.
+    pub(crate) async fn connect_http(
+        &self,
+        access_token: Option<String>,
+    ) -> Result<McpClient, AppError> {
+        let url = self
+            .url
+            .as_deref()
+            .ok_or_else(|| AppError::ConnectionFailed("No URL specified".into()))?;
+        let headers = self.resolve_headers()?;
+        McpClient::connect_http(url, headers, access_token, &self.options).await
+    }
+}
+
+/// `over` wins over `under`, including keys that differ only in case.
+fn layer_env(
+    under: &HashMap<String, String>,
+    over: HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut env: HashMap<String, String> = under
+        .iter()
+        .filter(|(k, _)| !over.keys().any(|o| o.eq_ignore_ascii_case(k)))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    env.extend(over);
+    env
+}
+
+/// Spawn or dial the server described by `config` and run the MCP handshake.
+/// Holds no locks — callers extract the config first.
+async fn connect_client(
+    app: &AppHandle,
+    id: &str,
+    config: ServerConnectConfig,
+    access_token: Option<String>,
+) -> Result<McpClient, AppError> {
+    match config.transport {
+        ServerTransport::Stdio => {
+            let env = config.resolve_env()?;
+            let command = config
+                .command
+                .ok_or_else(|| AppError::ConnectionFailed("No command specified".into()))?;
+            if config.prewarm {
+                emit_connect_phase(app, id, "prewarming");
+                let program = crate::commands::resolve_binary(&command)?;
+                prewarm::prewarm(app, id, &program, &command, &config.args, &env).await;
+            }
+            let mut client =
+                McpClient::spawn_stdio(app, id, &command, &config.args, &env, &config.options)?;
+            complete_handshake(app, id, &mut client, &config.options).await?;
+            Ok(client)
+        }
+        ServerTransport::Http => {
+            let url = config
+                .url
+                .ok_or_else(|| AppError::ConnectionFailed("No URL specified".into()))?;
+            if config.options.allow_insecure_tls {
+                emit_server_log(
+                    app,
+                    id,
+                    "warn",
+                    "TLS certificate verification is disabled for this server — \
+                     only use this for trusted development servers",
+                );
+            }
+            emit_server_log(app, id, "info", &format!("Connecting to {url}"));
+            let connected = async {
+                let headers = config.resolve_headers()?;
+                let mut client =
+                    McpClient::dial_http(&url, headers, access_token, &config.options).await?;
+                client.refresh_tokens_from(app, id);
+                complete_handshake(app, id, &mut client, &config.options).await?;
+                Ok::<_, AppError>(client)
+            };
+            match connected.await {
+                Ok(client) => {
+                    emit_server_log(
+                        app,
+                        id,
+                        "info",
+                        &format!("Connected — {} tools available", client.tools.len()),
+                    );
+                    Ok(client)
+                }
+                Err(e) => {
+                    emit_server_log(app, id, "error", &format!("Connection failed: {e}"));
+                    Err(e)
+                }
             }
         }
+    }
+}
+
+/// Run `initialize` then `tools/list` on a freshly spawned or dialed client,
+/// announcing each phase so a slow server can be told apart from a hung one.
+async fn complete_handshake(
+    app: &AppHandle,
+    id: &str,
+    client: &mut McpClient,
+    options: &ClientOptions,
+) -> Result<(), AppError> {
+    emit_connect_phase(app, id, "initializing");
+    client.handshake(options).await?;
+    emit_connect_phase(app, id, "discovering-tools");
+    client.discover_tools().await
+}
+
+fn emit_connect_phase(app: &AppHandle, server_id: &str, phase: &str) {
+    let _ = app.emit(
+        "server-connect-phase",
+        serde_json::json!({ "serverId": server_id, "phase": phase }),
+    );
+}
+
+/// Open a short-lived client for `id` that has completed the initialize
+/// handshake but not tool discovery. Used for diagnostics on servers that
+/// aren't connected (or can't be); callers must `shutdown()` it when done.
+pub(crate) async fn open_transient_client(
+    app: &AppHandle,
+    state: &SharedState,
+    oauth_store: &SharedOAuthStore,
+    id: &str,
+) -> Result<McpClient, AppError> {
+    let (mut client, options) =
+        spawn_transient_client(app, state, oauth_store, id, "diagnostics").await?;
+    client.handshake(&options).await?;
+    Ok(client)
+}
+
+/// Id for a throwaway client of server `id`, unique per call so its child
+/// process, logs and traces never collide with the live connection's or with
+/// another throwaway client's.
+fn transient_client_id(id: &str, purpose: &str) -> String {
+    format!("{id}#{purpose}-{}", uuid::Uuid::new_v4())
+}
+
+/// Spawn or dial `id` under a [`transient_client_id`] without running the
+/// handshake, returning the options [`McpClient::handshake`] needs. Refreshed
+/// OAuth tokens are still looked up under `id`.
+async fn spawn_transient_client(
+    app: &AppHandle,
+    state: &SharedState,
+    oauth_store: &SharedOAuthStore,
+    id: &str,
+    purpose: &str,
+) -> Result<(McpClient, ClientOptions), AppError> {
+    let config = {
+        let s = state.lock().unwrap();
+        let server = s
+            .servers
+            .iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| AppError::ServerNotFound(id.to_string()))?;
+        ServerConnectConfig::from_server(server, &s.default_env)
+    };
+    let access_token = if matches!(config.transport, ServerTransport::Http) {
+        oauth::resolve_access_token(oauth_store, id, app).await
     } else {
         None
+    };
+    let client_id = transient_client_id(id, purpose);
+    let (mut client, options) =
+        spawn_unlisted_client(app, &client_id, config, access_token).await?;
+    client.refresh_tokens_from(app, id);
+    Ok((client, options))
+}
+
+/// Spawn or dial `config` without the handshake and without emitting any
+/// connection events, returning the options [`McpClient::handshake`] needs.
+async fn spawn_unlisted_client(
+    app: &AppHandle,
+    id: &str,
+    config: ServerConnectConfig,
+    access_token: Option<String>,
+) -> Result<(McpClient, ClientOptions), AppError> {
+    match config.transport {
+        ServerTransport::Stdio => {
+            let env = config.resolve_env()?;
+            let command = config
+                .command
+                .ok_or_else(|| AppError::ConnectionFailed("No command specified".into()))?;
+            let client =
+                McpClient::spawn_stdio(app, id, &command, &config.args, &env, &config.options)?;
+            Ok((client, config.options))
+        }
+        ServerTransport::Http => {
+            let url = config
+                .url
+                .ok_or_else(|| AppError::ConnectionFailed("No URL specified".into()))?;
+            let headers = config.resolve_headers()?;
+            let client = McpClient::dial_http(&url, headers, access_token, &config.options).await?;
+            Ok((client, config.options))
+        }
     }
 }
 
-/// Mark a server as errored: update state, emit events, rebuild tray.
-fn mark_server_error(app: &AppHandle, state: &SharedState, id: &str, error: &str) {
+/// Prefix of the per-call server id used for logs and traces of
+/// [`test_server_config`] clients. Each call gets its own id so concurrent
+/// tests don't tear down each other's client.
+const CONFIG_TEST_SERVER_ID_PREFIX: &str = "config-test";
+
+/// Outcome of connecting to an unsaved server config.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerConfigTest {
+    pub ok: bool,
+    pub server_info: Option<ServerInfo>,
+    pub tool_count: usize,
+    pub error: Option<String>,
+}
+
+impl ServerConfigTest {
+    fn failed(error: AppError) -> Self {
+        info!("Server config test failed: {error}");
+        Self {
+            ok: false,
+            server_info: None,
+            tool_count: 0,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Connect to a server config that hasn't been saved, run initialize and
+/// `tools/list`, then tear the client down. Nothing is added to the server
+/// list or persisted; connection failures are reported in the result.
+#[tauri::command]
+pub async fn test_server_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    input: ServerConfigInput,
+) -> Result<ServerConfigTest, AppError> {
+    let server = crate::commands::servers::server_from_input(input, None, None);
+    let config = {
+        let s = state.lock().unwrap();
+        ServerConnectConfig::from_server(&server, &s.default_env)
+    };
+
+    let test_id = format!("{CONFIG_TEST_SERVER_ID_PREFIX}-{}", uuid::Uuid::new_v4());
+    let (mut client, options) = match spawn_unlisted_client(&app, &test_id, config, None).await {
+        Ok(spawned) => spawned,
+        Err(e) => return Ok(ServerConfigTest::failed(e)),
+    };
+    let result = async {
+        client.handshake(&options).await?;
+        client.discover_tools().await
+    }
+    .await;
+    client.shutdown();
+
+    Ok(match result {
+        Ok(()) => ServerConfigTest {
+            ok: true,
+            server_info: client.server_info.take(),
+            tool_count: client.tools.len(),
+            error: None,
+        },
+        Err(e) => ServerConfigTest::failed(e),
+    })
+}
+
+/// How long each phase of one connect took, in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectPhaseTimings {
+    /// Starting the process (stdio) or setting up the HTTP transport.
+    pub spawn_ms: u64,
+    /// The initialize request and `notifications/initialized`.
+    pub initialize_ms: u64,
+    /// `tools/list` and parsing the result.
+    pub discovery_ms: u64,
+    pub total_ms: u64,
+    pub tool_count: usize,
+}
+
+/// Phase timings for two back-to-back connects: the first pays for cold
+/// caches (package downloads, JIT, DNS), the second shows the steady state.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTiming {
+    pub server_id: String,
+    pub cold: ConnectPhaseTimings,
+    pub warm: ConnectPhaseTimings,
+}
+
+/// Connect to a server twice with throwaway clients and report how long
+/// spawn, initialize, and tool discovery took each time. The live connection,
+/// if any, is left alone.
+#[tauri::command]
+pub async fn measure_connection_timing(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    oauth_store: State<'_, SharedOAuthStore>,
+    server_id: String,
+) -> Result<ConnectionTiming, AppError> {
+    let cold = time_transient_connect(&app, &state, &oauth_store, &server_id).await?;
+    let warm = time_transient_connect(&app, &state, &oauth_store, &server_id).await?;
+    info!(
+        "Connection timing for {server_id}: cold {}ms, warm {}ms",
+        cold.total_ms, warm.total_ms
+    );
+    Ok(ConnectionTiming {
+        server_id,
+        cold,
+        warm,
+    })
+}
+
+async fn time_transient_connect(
+    app: &AppHandle,
+    state: &SharedState,
+    oauth_store: &SharedOAuthStore,
+    id: &str,
+) -> Result<ConnectPhaseTimings, AppError> {
+    let started = Instant::now();
+    let (mut client, options) =
+        spawn_transient_client(app, state, oauth_store, id, "timing").await?;
+    let spawned = Instant::now();
+
+    let result = async {
+        client.handshake(&options).await?;
+        let initialized = Instant::now();
+        client.discover_tools().await?;
+        Ok::<_, AppError>(initialized)
+    }
+    .await;
+    let discovered = Instant::now();
+    client.shutdown();
+    let initialized = result?;
+
+    Ok(ConnectPhaseTimings {
+        spawn_ms: millis(spawned - started),
+        initialize_ms: millis(initialized - spawned),
+        discovery_ms: millis(discovered - initialized),
+        total_ms: millis(discovered - started),
+        tool_count: client.tools.len(),
+    })
+}
+
+fn millis(d: Duration) -> u64 {
+    d.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// Shut down the live client and mark the server `Disconnected`. The
+/// connections lock is held across both updates so a concurrent connect can't
+/// slip a client in between.
+async fn teardown_connection(
+    app: &AppHandle,
+    state: &SharedState,
+    connections: &SharedConnections,
+    id: &str,
+) -> Result<(), AppError> {
     {
+        let mut conns = connections.lock().await;
+        if let Some(client) = conns.remove(id) {
+            client.shutdown();
+        }
         let mut s = state.lock().unwrap();
-        if let Some(server) = s.servers.iter_mut().find(|s| s.id == id) {
-            server.status = Some(ServerStatus::Error);
+        if !clear_connection(&mut s, id) {
+            return Err(AppError::ServerNotFound(id.to_string()));
         }
     }
     let _ = app.emit(
         "server-status-changed",
-        serde_json::json!({ "serverId": id, "status": "error", "error": error }),
+        serde_json::json!({ "serverId": id, "status": "disconnected" }),
+    );
+    Ok(())
+}
+
+/// Drop the connection state for `id` and mark it `Disconnected`. Returns
+/// false if there is no such server.
+fn clear_connection(s: &mut AppState, id: &str) -> bool {
+    s.connections.remove(id);
+    match s.servers.iter_mut().find(|s| s.id == id) {
+        Some(server) => {
+            server.status = Some(ServerStatus::Disconnected);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Drop the connection state for `id` and mark it `Error` with `message`.
+fn record_server_error(s: &mut AppState, id: &str, message: &str) {
+    s.connections.remove(id);
+    if let Some(server) = s.servers.iter_mut().find(|s| s.id == id) {
+        server.status = Some(ServerStatus::Error);
+        server.last_error = Some(message.to_string());
+        server.last_error_at = Some(crate::stats::unix_now());
+    }
+}
+
+/// Mark a server as errored: update state, emit events, rebuild tray.
+fn mark_server_error(app: &AppHandle, state: &SharedState, id: &str, error: &AppError) {
+    let message = error.to_string();
+    record_server_error(&mut state.lock().unwrap(), id, &message);
+    let _ = app.emit(
+        "server-status-changed",
+        serde_json::json!({
+            "serverId": id,
+            "status": "error",
+            "error": message,
+            "errorCode": error.code(),
+        }),
+    );
+    crate::tray::rebuild_tray_menu(app);
+}
+
+fn to_mcp_tools(defs: &[McpToolDef], server_id: &str, server_name: &str) -> Vec<McpTool> {
+    defs.iter()
+        .map(|t| McpTool {
+            name: t.name.clone(),
+            title: t.title.clone(),
+            description: t.description.clone(),
+            input_schema: t.input_schema.clone(),
+            server_id: server_id.to_string(),
+            server_name: server_name.to_string(),
+        })
+        .collect()
+}
+
+/// Re-list a connected server's tools after it sent
+/// `notifications/tools/list_changed`, and pass the change on to proxy clients.
+pub async fn refresh_server_tools(app: &AppHandle, id: &str) {
+    let client = {
+        let connections = app.state::<SharedConnections>();
+        let conns = connections.lock().await;
+        conns.get(id)
+    };
+    // Notifications can arrive mid-handshake or from an unlisted client;
+    // the tools found at connect time are already current
+    let Some(client) = client else {
+        return;
+    };
+
+    let defs = match client.list_tools().await {
+        Ok(defs) => defs,
+        Err(e) => {
+            warn!("Failed to refresh tools for {id} after list_changed: {e}");
+            return;
+        }
+    };
+
+    let tools = {
+        let state = app.state::<SharedState>();
+        let mut s = state.lock().unwrap();
+        let server_name = s
+            .servers
+            .iter()
+            .find(|srv| srv.id == id)
+            .map(|srv| srv.name.clone())
+            .unwrap_or_default();
+        let tools = to_mcp_tools(&defs, id, &server_name);
+        let Some(conn) = s.connections.get_mut(id) else {
+            return;
+        };
+        conn.tools = tools.clone();
+        tools
+    };
+
+    info!("Server {id} changed its tools; now {} tools", tools.len());
+    let _ = app.emit(
+        "tools-updated",
+        serde_json::json!({ "serverId": id, "tools": tools }),
     );
     crate::tray::rebuild_tray_menu(app);
+    crate::mcp::proxy::notify_if_tools_changed(app, id, &tools).await;
 }
 
 /// Finalize a successful connection: store tools, update state, emit events, sync integrations.
@@ -368,19 +956,7 @@ async fn finalize_connection(
         let s = state.lock().unwrap();
         let srv = s.servers.iter().find(|s| s.id == id);
         server_name = srv.map(|s| s.name.clone()).unwrap_or_default();
-
-        client
-            .tools
-            .iter()
-            .map(|t| McpTool {
-                name: t.name.clone(),
-                title: t.title.clone(),
-                description: t.description.clone(),
-                input_schema: t.input_schema.clone(),
-                server_id: id.to_string(),
-                server_name: server_name.clone(),
-            })
-            .collect()
+        to_mcp_tools(&client.tools, id, &server_name)
     };
 
     info!("Connected to server {id} with {} tools", tools.len());
@@ -391,11 +967,16 @@ async fn finalize_connection(
         if let Some(server) = s.servers.iter_mut().find(|s| s.id == id) {
             server.status = Some(ServerStatus::Connected);
             server.last_connected = Some(chrono_now());
+            server.last_error = None;
+            server.last_error_at = None;
         }
         s.connections.insert(
             id.to_string(),
             ConnectionState {
                 tools: tools.clone(),
+                server_name: client.server_info.as_ref().map(|i| i.name.clone()),
+                server_version: client.server_info.as_ref().map(|i| i.version.clone()),
+                capabilities: client.capability_flags(),
             },
         );
     }
@@ -423,7 +1004,7 @@ async fn finalize_connection(
     // Update integration configs so AI tools see this server
     let proxy_state = app.state::<ProxyState>();
     let port = proxy_state.port().await;
-    if let Err(e) = crate::commands::integrations::update_all_integration_configs(app, port) {
+    if let Err(e) = crate::commands::integrations::refresh_integration_configs(app, port).await {
         tracing::warn!("Failed to update integration configs after connect: {e}");
     }
 
@@ -462,6 +1043,34 @@ pub async fn drain_log_buffer(
     Ok(std::mem::take(&mut s.log_buffer))
 }
 
+/// Point-in-time status of one server, for views that open after events were emitted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatusSnapshot {
+    pub server_id: String,
+    pub status: ServerStatus,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<u64>,
+}
+
+/// Current status of every server — called by a newly opened window before it
+/// subscribes to `server-status-changed`, so it starts from an accurate view.
+#[tauri::command]
+pub async fn get_all_server_statuses(
+    state: State<'_, SharedState>,
+) -> Result<Vec<ServerStatusSnapshot>, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.servers
+        .iter()
+        .map(|srv| ServerStatusSnapshot {
+            server_id: srv.id.clone(),
+            status: srv.status.clone().unwrap_or(ServerStatus::Disconnected),
+            last_error: srv.last_error.clone(),
+            last_error_at: srv.last_error_at,
+        })
+        .collect())
+}
+
 fn chrono_now() -> String {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -469,3 +1078,57 @@ fn chrono_now() -> String {
         .as_secs();
     format!("{now}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connected_state() -> AppState {
+        let mut state = AppState::new();
+        state.servers.push(ServerConfig {
+            id: "a".into(),
+            status: Some(ServerStatus::Connected),
+            ..Default::default()
+        });
+        state.connections.insert(
+            "a".into(),
+            ConnectionState {
+                tools: Vec::new(),
+                server_name: None,
+                server_version: None,
+                capabilities: Default::default(),
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn failed_reconnect_ends_in_error_without_connection() {
+        let mut state = connected_state();
+
+        assert!(clear_connection(&mut state, "a"));
+        assert_eq!(state.servers[0].status, Some(ServerStatus::Disconnected));
+        assert!(state.connections.is_empty());
+
+        record_server_error(&mut state, "a", "spawn failed");
+        let server = &state.servers[0];
+        assert_eq!(server.status, Some(ServerStatus::Error));
+        assert_eq!(server.last_error.as_deref(), Some("spawn failed"));
+        assert!(server.last_error_at.is_some());
+        assert!(state.connections.is_empty());
+    }
+
+    #[test]
+    fn teardown_of_unknown_server_is_reported() {
+        let mut state = connected_state();
+        assert!(!clear_connection(&mut state, "missing"));
+        assert_eq!(state.servers[0].status, Some(ServerStatus::Connected));
+    }
+
+    #[test]
+    fn transient_client_ids_are_unique_per_call() {
+        let first = transient_client_id("srv", "timing");
+        assert!(first.starts_with("srv#timing-"));
+        assert_ne!(first, transient_client_id("srv", "timing"));
+    }
+}
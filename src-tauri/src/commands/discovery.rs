@@ -1,7 +1,7 @@
 use serde::Serialize;
 use tauri::{AppHandle, State};
 
-use crate::commands::integrations::update_all_integration_configs;
+use crate::commands::integrations::refresh_integration_configs;
 use crate::commands::skills::{install_managed_skill, uninstall_managed_skill};
 use crate::error::AppError;
 use crate::mcp::proxy::ProxyState;
@@ -55,7 +55,7 @@ pub async fn set_discovery_mode(
     }
 
     let port = proxy_state.port().await;
-    if let Err(e) = update_all_integration_configs(&app, port) {
+    if let Err(e) = refresh_integration_configs(&app, port).await {
         tracing::warn!("Failed to update integration configs after discovery toggle: {e}");
     }
 
@@ -0,0 +1,135 @@
+use tauri::{AppHandle, State};
+use tracing::info;
+
+use crate::error::AppError;
+use crate::persistence::save_fallback_groups;
+use crate::state::{FallbackCandidate, FallbackGroup, SharedState};
+
+#[tauri::command]
+pub async fn list_fallback_groups(
+    state: State<'_, SharedState>,
+) -> Result<Vec<FallbackGroup>, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.fallback_groups.clone())
+}
+
+/// Create or replace the fallback group named `group.alias`. Candidates are
+/// tried in the given order; duplicates are dropped.
+#[tauri::command]
+pub async fn set_fallback_group(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    group: FallbackGroup,
+) -> Result<FallbackGroup, AppError> {
+    let alias = group.alias.trim().to_string();
+    if alias.is_empty() {
+        return Err(AppError::Validation(
+            "Fallback alias cannot be empty".into(),
+        ));
+    }
+    let mut candidates: Vec<FallbackCandidate> = Vec::new();
+    for candidate in group.candidates {
+        let candidate = FallbackCandidate {
+            server_id: candidate.server_id,
+            tool: candidate.tool.trim().to_string(),
+        };
+        if candidate.tool.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Fallback '{alias}' has a candidate without a tool name"
+            )));
+        }
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    if candidates.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Fallback '{alias}' needs at least one candidate"
+        )));
+    }
+    let group = FallbackGroup { alias, candidates };
+
+    let previous = {
+        let mut s = state.lock().unwrap();
+        if let Some(missing) = group
+            .candidates
+            .iter()
+            .find(|c| !s.servers.iter().any(|srv| srv.id == c.server_id))
+        {
+            return Err(AppError::ServerNotFound(missing.server_id.clone()));
+        }
+        let previous = match s
+            .fallback_groups
+            .iter()
+            .position(|g| g.alias == group.alias)
+        {
+            Some(idx) => Some(std::mem::replace(
+                &mut s.fallback_groups[idx],
+                group.clone(),
+            )),
+            None => {
+                s.fallback_groups.push(group.clone());
+                None
+            }
+        };
+        save_fallback_groups(&app, &s.fallback_groups);
+        previous
+    };
+
+    info!(
+        "Saved fallback group '{}' with {} candidate(s)",
+        group.alias,
+        group.candidates.len()
+    );
+    notify_endpoints(&app, &state, previous.iter().chain([&group])).await;
+    Ok(group)
+}
+
+#[tauri::command]
+pub async fn remove_fallback_group(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    alias: String,
+) -> Result<(), AppError> {
+    let removed = {
+        let mut s = state.lock().unwrap();
+        let idx = s
+            .fallback_groups
+            .iter()
+            .position(|g| g.alias == alias)
+            .ok_or_else(|| AppError::Validation(format!("Fallback group not found: {alias}")))?;
+        let removed = s.fallback_groups.remove(idx);
+        save_fallback_groups(&app, &s.fallback_groups);
+        removed
+    };
+
+    info!("Removed fallback group '{alias}'");
+    notify_endpoints(&app, &state, [&removed]).await;
+    Ok(())
+}
+
+/// Aliases show up in the tool lists of their candidates' endpoints, so
+/// those endpoints may need a `tools/list_changed`.
+async fn notify_endpoints<'a>(
+    app: &AppHandle,
+    state: &SharedState,
+    groups: impl IntoIterator<Item = &'a FallbackGroup>,
+) {
+    let mut server_ids: Vec<&str> = groups
+        .into_iter()
+        .flat_map(|g| g.candidates.iter().map(|c| c.server_id.as_str()))
+        .collect();
+    server_ids.sort_unstable();
+    server_ids.dedup();
+
+    for server_id in server_ids {
+        let tools = {
+            let s = state.lock().unwrap();
+            s.connections
+                .get(server_id)
+                .map(|c| c.tools.clone())
+                .unwrap_or_default()
+        };
+        crate::mcp::proxy::notify_if_tools_changed(app, server_id, &tools).await;
+    }
+}
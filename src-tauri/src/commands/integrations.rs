@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::Serialize;
 use tauri::{AppHandle, Manager, State};
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::commands::servers::{
+    import_server_inputs, server_warnings, ImportedServer, ServerImportReport,
+};
 use crate::error::AppError;
-use crate::mcp::proxy::ProxyState;
-use crate::persistence::{save_enabled_integrations, save_servers};
-use crate::state::{ServerConfig, ServerStatus, ServerTransport, SharedState};
+use crate::mcp::proxy::{ProxyState, PROXY_SERVICE};
+use crate::persistence::{save_enabled_integrations, save_servers, write_atomic};
+use crate::state::{ServerConfig, ServerConfigInput, ServerStatus, ServerTransport, SharedState};
 
 /// How to parse a tool's config file.
 #[derive(Debug, Clone)]
@@ -70,6 +74,92 @@ pub struct AiToolInfo {
     pub configured_port: u16,
     /// Existing MCP servers in this tool's config that could be imported.
     pub existing_servers: Vec<ExistingMcpServer>,
+    /// Servers imported by `enable_integration`, with any config warnings.
+    pub imported_servers: Vec<ImportedServer>,
+    /// Entries written under a name used by older versions; see `migrate_integration_keys`.
+    pub legacy_keys: Vec<String>,
+    /// Set when the config on disk disagrees with whether the integration is enabled.
+    pub drift: Option<IntegrationDrift>,
+}
+
+/// How a tool's config has drifted from the persisted enabled state, e.g.
+/// after the user edited it by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrationDrift {
+    /// Enabled, but our entries are gone from the config;
+    /// `overwrite_integration_port` writes them back.
+    EntriesMissing,
+    /// Not enabled, but the config still has our entries.
+    EntriesPresent,
+}
+
+/// Compare the persisted enabled state with what's on disk. An enabled tool
+/// with no entries is only drift if there's something to write.
+fn integration_drift(
+    enabled: bool,
+    has_entry: bool,
+    expects_entries: bool,
+) -> Option<IntegrationDrift> {
+    match (enabled, has_entry) {
+        (true, false) if expects_entries => Some(IntegrationDrift::EntriesMissing),
+        (false, true) => Some(IntegrationDrift::EntriesPresent),
+        _ => None,
+    }
+}
+
+/// Legacy entries renamed in one tool's config by `migrate_integration_keys`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationKeyMigration {
+    pub id: String,
+    pub config_path: String,
+    pub migrated_keys: Vec<String>,
+}
+
+/// A tool whose proxy entry points at another live proxy, typically a second
+/// Agent Hub instance (e.g. a dev build next to the installed app).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortConflict {
+    pub id: String,
+    pub name: String,
+    pub config_path: String,
+    /// Port in the tool's config, which something is still listening on.
+    pub configured_port: u16,
+    /// Port this instance's proxy is listening on.
+    pub current_port: u16,
+}
+
+/// How long to wait when probing whether a configured proxy port is live.
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Whether a tool's config still points at the running proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrationHealthStatus {
+    /// Config parses and our entry targets the current proxy port.
+    Healthy,
+    /// Our entry exists but targets a different port (e.g. after a restart).
+    PortMismatch,
+    /// The config file or our entry in it is missing.
+    Missing,
+    /// The config file exists but could not be parsed.
+    ParseError,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationHealth {
+    pub id: String,
+    pub status: IntegrationHealthStatus,
+    pub config_path: String,
+    /// Port found in the config entry, or 0 if none.
+    pub configured_port: u16,
+    /// Port the proxy is currently listening on.
+    pub expected_port: u16,
+    /// Parser error message when `status` is `parseError`.
+    pub error: Option<String>,
 }
 
 fn get_tool_definitions(home: &Path) -> Vec<ToolDef> {
@@ -190,6 +280,28 @@ fn is_proxy_url(url: &str) -> bool {
 // Config parsing — format-specific
 // ---------------------------------------------------------------------------
 
+/// Check that a config file is syntactically valid for its format.
+/// The `parse_*` functions treat unparseable files as empty, so health
+/// checks need this to tell a corrupt file apart from a missing entry.
+fn check_config_syntax(content: &str, format: &ConfigFormat) -> Result<(), String> {
+    match format {
+        ConfigFormat::McpServers | ConfigFormat::OpenCode => {
+            serde_json::from_str::<serde_json::Value>(content)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        ConfigFormat::Zed => {
+            serde_json::from_str::<serde_json::Value>(&strip_json_comments(content))
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        ConfigFormat::CodexToml => content
+            .parse::<toml::Value>()
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    }
+}
+
 /// Parse a tool's config file and return (enabled, port, existing_servers).
 fn parse_config(path: &Path, format: &ConfigFormat) -> (bool, u16, Vec<ExistingMcpServer>) {
     match format {
@@ -224,7 +336,7 @@ fn parse_mcp_servers(path: &Path) -> (bool, u16, Vec<ExistingMcpServer>) {
         let entry_url = value.get("url").and_then(|u| u.as_str()).unwrap_or("");
 
         // Detect our proxy entries
-        if key == DISCOVERY_SERVER_NAME || is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             enabled = true;
             if port == 0 {
                 port = extract_port_from_url(entry_url);
@@ -284,7 +396,7 @@ fn parse_opencode(path: &Path) -> (bool, u16, Vec<ExistingMcpServer>) {
     for (key, value) in servers_obj {
         let entry_url = value.get("url").and_then(|u| u.as_str()).unwrap_or("");
 
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             enabled = true;
             if port == 0 {
                 port = extract_port_from_url(entry_url);
@@ -361,7 +473,7 @@ fn parse_zed(path: &Path) -> (bool, u16, Vec<ExistingMcpServer>) {
     for (key, value) in servers_obj {
         let entry_url = value.get("url").and_then(|u| u.as_str()).unwrap_or("");
 
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             enabled = true;
             if port == 0 {
                 port = extract_port_from_url(entry_url);
@@ -422,7 +534,7 @@ fn parse_codex_toml(path: &Path) -> (bool, u16, Vec<ExistingMcpServer>) {
     for (key, value) in servers_table {
         let entry_url = value.get("url").and_then(|v| v.as_str()).unwrap_or("");
 
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             enabled = true;
             if port == 0 {
                 port = extract_port_from_url(entry_url);
@@ -488,18 +600,23 @@ fn import_mcp_servers(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
     let content = std::fs::read_to_string(path)?;
     let config: serde_json::Value = serde_json::from_str(&content)
         .map_err(|e| AppError::Protocol(format!("Invalid JSON: {e}")))?;
-    let servers_obj = match config.get("mcpServers").and_then(|v| v.as_object()) {
-        Some(obj) => obj,
-        None => return Ok(Vec::new()),
-    };
+    Ok(config
+        .get("mcpServers")
+        .and_then(|v| v.as_object())
+        .map(servers_from_mcp_servers)
+        .unwrap_or_default())
+}
+
+/// Convert the entries of an `mcpServers` object into new server configs,
+/// skipping our own discovery entry and anything pointing at the proxy.
+fn servers_from_mcp_servers(
+    servers_obj: &serde_json::Map<String, serde_json::Value>,
+) -> Vec<ServerConfig> {
     let mut result = Vec::new();
     for (key, value) in servers_obj {
-        // Skip discovery entry and proxy URLs
-        if key == DISCOVERY_SERVER_NAME {
-            continue;
-        }
+        // Skip discovery entry (current or legacy name) and proxy URLs
         let entry_url = value.get("url").and_then(|u| u.as_str()).unwrap_or("");
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             continue;
         }
 
@@ -535,9 +652,10 @@ fn import_mcp_servers(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
             managed: None,
             managed_by: None,
             registry_name: None,
+            ..Default::default()
         });
     }
-    Ok(result)
+    result
 }
 
 fn import_opencode(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
@@ -551,7 +669,7 @@ fn import_opencode(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
     let mut result = Vec::new();
     for (key, value) in servers_obj {
         let entry_url = value.get("url").and_then(|u| u.as_str()).unwrap_or("");
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             continue;
         }
 
@@ -604,6 +722,7 @@ fn import_opencode(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
             managed: None,
             managed_by: None,
             registry_name: None,
+            ..Default::default()
         });
     }
     Ok(result)
@@ -621,7 +740,7 @@ fn import_zed(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
     let mut result = Vec::new();
     for (key, value) in servers_obj {
         let entry_url = value.get("url").and_then(|u| u.as_str()).unwrap_or("");
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             continue;
         }
 
@@ -657,6 +776,7 @@ fn import_zed(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
             managed: None,
             managed_by: None,
             registry_name: None,
+            ..Default::default()
         });
     }
     Ok(result)
@@ -674,7 +794,7 @@ fn import_codex_toml(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
     let mut result = Vec::new();
     for (key, value) in servers_table {
         let entry_url = value.get("url").and_then(|v| v.as_str()).unwrap_or("");
-        if is_proxy_url(entry_url) {
+        if is_managed_key(key) || is_proxy_url(entry_url) {
             continue;
         }
 
@@ -717,6 +837,7 @@ fn import_codex_toml(path: &Path) -> Result<Vec<ServerConfig>, AppError> {
             managed: None,
             managed_by: None,
             registry_name: None,
+            ..Default::default()
         });
     }
     Ok(result)
@@ -832,8 +953,10 @@ fn run_claude_mcp(args: &[&str]) -> Result<String, AppError> {
 fn write_cli_config(app: &AppHandle, port: u16, tool_id: &str) -> Result<(), AppError> {
     let entries = connected_proxy_urls(app, port, tool_id);
 
-    // Remove existing proxy entry first (ignore errors — may not exist)
-    let _ = run_claude_mcp(&["remove", "--scope", "user", DISCOVERY_SERVER_NAME]);
+    // Remove existing proxy entries first (ignore errors — may not exist)
+    for name in managed_key_names() {
+        let _ = run_claude_mcp(&["remove", "--scope", "user", name]);
+    }
 
     // Remove non-proxy entries that were imported into Agent Hub.
     // Reading .claude.json to find them (same McpServers format).
@@ -865,8 +988,10 @@ fn write_cli_config(app: &AppHandle, port: u16, tool_id: &str) -> Result<(), App
 
 /// Remove proxy entries from Claude Code via `claude mcp remove`.
 fn remove_cli_entries(app: &AppHandle, port: u16, tool_id: &str) -> Result<(), AppError> {
-    // Remove the discovery entry
-    let _ = run_claude_mcp(&["remove", "--scope", "user", DISCOVERY_SERVER_NAME]);
+    // Remove the discovery entry, including names written by older versions
+    for name in managed_key_names() {
+        let _ = run_claude_mcp(&["remove", "--scope", "user", name]);
+    }
 
     // Also remove any per-server entries that look like ours
     let entries = connected_proxy_urls(app, port, tool_id);
@@ -880,6 +1005,207 @@ fn remove_cli_entries(app: &AppHandle, port: u16, tool_id: &str) -> Result<(), A
 /// The name used for the discovery endpoint in AI tool configs.
 const DISCOVERY_SERVER_NAME: &str = "agent-hub-discovery-mode";
 
+/// Names earlier versions (as MCP Manager) used for the discovery entry.
+const LEGACY_DISCOVERY_SERVER_NAMES: &[&str] = &["mcp-manager", "mcp_manager", "user-mcp-manager"];
+
+fn is_legacy_key(key: &str) -> bool {
+    LEGACY_DISCOVERY_SERVER_NAMES.contains(&key)
+}
+
+/// Whether a config entry name is one we write, under its current or a legacy name.
+fn is_managed_key(key: &str) -> bool {
+    key == DISCOVERY_SERVER_NAME || is_legacy_key(key)
+}
+
+fn managed_key_names() -> impl Iterator<Item = &'static str> {
+    std::iter::once(DISCOVERY_SERVER_NAME).chain(LEGACY_DISCOVERY_SERVER_NAMES.iter().copied())
+}
+
+/// Whether an entry is a proxy entry written under a legacy name. A server of
+/// the user's own that happens to share an old name isn't, and is left alone.
+fn is_legacy_proxy_entry(key: &str, url: Option<&str>) -> bool {
+    is_legacy_key(key) && url.is_some_and(is_proxy_url)
+}
+
+/// The URL a legacy proxy entry should have once migrated: its proxy URL moved
+/// to `port`. `None` for entries that aren't legacy proxy entries.
+fn migrated_proxy_url(key: &str, url: Option<&str>, port: u16) -> Option<String> {
+    if !is_legacy_proxy_entry(key, url) {
+        return None;
+    }
+    let mut parsed = url::Url::parse(url?).ok()?;
+    parsed.set_port(Some(port)).ok()?;
+    Some(parsed.to_string())
+}
+
+/// The object/table in a tool's config that holds its MCP server entries.
+fn servers_section(format: &ConfigFormat) -> &'static str {
+    match format {
+        ConfigFormat::McpServers => "mcpServers",
+        ConfigFormat::OpenCode => "mcp",
+        ConfigFormat::Zed => "context_servers",
+        ConfigFormat::CodexToml => "mcp_servers",
+    }
+}
+
+/// Legacy-named proxy entries present in a tool's config file.
+fn find_legacy_keys(path: &Path, format: &ConfigFormat) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let section = servers_section(format);
+    let entries: Vec<(String, Option<String>)> = match format {
+        ConfigFormat::CodexToml => content
+            .parse::<toml::Value>()
+            .ok()
+            .and_then(|v| {
+                v.get(section).and_then(|t| t.as_table()).map(|t| {
+                    t.iter()
+                        .map(|(k, v)| {
+                            let url = v.get("url").and_then(|u| u.as_str()).map(String::from);
+                            (k.clone(), url)
+                        })
+                        .collect()
+                })
+            })
+            .unwrap_or_default(),
+        _ => serde_json::from_str::<serde_json::Value>(&strip_json_comments(&content))
+            .ok()
+            .and_then(|v| {
+                v.get(section).and_then(|o| o.as_object()).map(|o| {
+                    o.iter()
+                        .map(|(k, v)| {
+                            let url = v.get("url").and_then(|u| u.as_str()).map(String::from);
+                            (k.clone(), url)
+                        })
+                        .collect()
+                })
+            })
+            .unwrap_or_default(),
+    };
+    entries
+        .into_iter()
+        .filter(|(k, url)| is_legacy_proxy_entry(k, url.as_deref()))
+        .map(|(k, _)| k)
+        .collect()
+}
+
+/// Move legacy-named proxy entries to `DISCOVERY_SERVER_NAME`, pointing them
+/// at `port`. If the current name already exists it wins and the legacy
+/// entries are dropped. Returns the legacy names that were migrated.
+fn rename_legacy_entries(
+    servers: &mut serde_json::Map<String, serde_json::Value>,
+    port: u16,
+) -> Vec<String> {
+    let legacy: Vec<(String, String)> = servers
+        .iter()
+        .filter_map(|(k, v)| {
+            let url = migrated_proxy_url(k, v.get("url").and_then(|u| u.as_str()), port)?;
+            Some((k.clone(), url))
+        })
+        .collect();
+    for (key, url) in &legacy {
+        if let Some(mut entry) = servers.remove(key) {
+            entry["url"] = serde_json::Value::String(url.clone());
+            servers.entry(DISCOVERY_SERVER_NAME).or_insert(entry);
+        }
+    }
+    legacy.into_iter().map(|(k, _)| k).collect()
+}
+
+/// [`rename_legacy_entries`] for a Codex `mcp_servers` table, keeping each
+/// entry's other keys and formatting.
+fn rename_legacy_toml_entries(servers: &mut dyn toml_edit::TableLike, port: u16) -> Vec<String> {
+    let legacy: Vec<(String, String)> = servers
+        .iter()
+        .filter_map(|(k, v)| {
+            let url = migrated_proxy_url(k, v.get("url").and_then(|u| u.as_str()), port)?;
+            Some((k.to_string(), url))
+        })
+        .collect();
+    for (key, url) in &legacy {
+        if let Some(mut entry) = servers.remove(key) {
+            entry["url"] = toml_edit::value(url.as_str());
+            if !servers.contains_key(DISCOVERY_SERVER_NAME) {
+                servers.insert(DISCOVERY_SERVER_NAME, entry);
+            }
+        }
+    }
+    legacy.into_iter().map(|(k, _)| k).collect()
+}
+
+/// Rename legacy proxy entries in a config file in place, pointing them at
+/// `port`. Returns the migrated names.
+fn migrate_config_file(
+    path: &Path,
+    format: &ConfigFormat,
+    port: u16,
+) -> Result<Vec<String>, AppError> {
+    let content = std::fs::read_to_string(path)?;
+    let section = servers_section(format);
+
+    if let ConfigFormat::CodexToml = format {
+        // Edited in place so the rest of the user's file, including values
+        // JSON can't represent (e.g. datetimes), comes back untouched
+        let mut config: toml_edit::DocumentMut = content
+            .parse()
+            .map_err(|e| AppError::Protocol(format!("Invalid TOML: {e}")))?;
+        let Some(servers) = config.get_mut(section).and_then(|v| v.as_table_like_mut()) else {
+            return Ok(Vec::new());
+        };
+        let legacy = rename_legacy_toml_entries(servers, port);
+        if !legacy.is_empty() {
+            write_atomic(path, config.to_string())?;
+        }
+        return Ok(legacy);
+    }
+
+    let mut config: serde_json::Value = serde_json::from_str(&strip_json_comments(&content))?;
+    let Some(servers) = config.get_mut(section).and_then(|v| v.as_object_mut()) else {
+        return Ok(Vec::new());
+    };
+    let legacy = rename_legacy_entries(servers, port);
+    if !legacy.is_empty() {
+        write_atomic(path, serde_json::to_string_pretty(&config)?)?;
+    }
+    Ok(legacy)
+}
+
+/// Rename legacy proxy entries in Claude Code's user config via the CLI, since
+/// `.claude.json` is owned by `claude` and not written directly.
+fn migrate_cli_entries(path: &Path, port: u16) -> Result<Vec<String>, AppError> {
+    let content = std::fs::read_to_string(path)?;
+    let config: serde_json::Value = serde_json::from_str(&content)?;
+    let Some(servers) = config.get("mcpServers").and_then(|v| v.as_object()) else {
+        return Ok(Vec::new());
+    };
+    let mut has_current = servers.contains_key(DISCOVERY_SERVER_NAME);
+
+    let mut migrated = Vec::new();
+    for (key, entry) in servers {
+        let Some(url) = migrated_proxy_url(key, entry.get("url").and_then(|u| u.as_str()), port)
+        else {
+            continue;
+        };
+        if !has_current {
+            let mut entry = entry.clone();
+            entry["url"] = serde_json::Value::String(url);
+            let json_str = serde_json::to_string(&entry)?;
+            run_claude_mcp(&[
+                "add-json",
+                "--scope",
+                "user",
+                DISCOVERY_SERVER_NAME,
+                &json_str,
+            ])?;
+            has_current = true;
+        }
+        run_claude_mcp(&["remove", "--scope", "user", key])?;
+        migrated.push(key.clone());
+    }
+    Ok(migrated)
+}
+
 /// Build a single discovery endpoint URL entry.
 fn discovery_proxy_url(port: u16, tool_id: &str) -> (String, String) {
     (
@@ -936,14 +1262,17 @@ fn connected_proxy_urls(app: &AppHandle, port: u16, tool_id: &str) -> Vec<(Strin
 // Tauri commands
 // ---------------------------------------------------------------------------
 
+/// Installed tools and their integration state. `enabled` comes from the
+/// persisted list; `drift` reports configs that disagree with it.
 #[tauri::command]
 pub async fn detect_integrations(
+    app: AppHandle,
     state: State<'_, SharedState>,
     proxy_state: State<'_, ProxyState>,
 ) -> Result<Vec<AiToolInfo>, AppError> {
     let home = home_dir()?;
     let tools = get_tool_definitions(&home);
-    let _port = proxy_state.port().await;
+    let port = proxy_state.port().await;
 
     let enabled_ids: Vec<String> = {
         let s = state.lock().unwrap();
@@ -955,11 +1284,22 @@ pub async fn detect_integrations(
         let installed = tool.detection_paths.iter().any(|p| p.exists());
         let enabled = enabled_ids.contains(&tool.id);
 
-        let (_, configured_port, existing_servers) = if installed {
+        let (has_entry, configured_port, existing_servers) = if installed {
             parse_config(&tool.config_path, &tool.config_format)
         } else {
             (false, 0, Vec::new())
         };
+        let drift = if installed {
+            let expects_entries = !connected_proxy_urls(&app, port, &tool.id).is_empty();
+            integration_drift(enabled, has_entry, expects_entries)
+        } else {
+            None
+        };
+        let legacy_keys = if installed {
+            find_legacy_keys(&tool.config_path, &tool.config_format)
+        } else {
+            Vec::new()
+        };
 
         results.push(AiToolInfo {
             id: tool.id,
@@ -969,12 +1309,382 @@ pub async fn detect_integrations(
             config_path: tool.config_path.display().to_string(),
             configured_port,
             existing_servers,
+            imported_servers: Vec::new(),
+            legacy_keys,
+            drift,
         });
     }
 
     Ok(results)
 }
 
+/// Rename proxy entries written under legacy names (e.g. `mcp-manager`) to the
+/// current name across all tool configs, so they aren't left orphaned, and
+/// point them at the port the proxy is listening on now. Entries under those
+/// names that aren't proxy entries belong to the user and are left alone.
+#[tauri::command]
+pub async fn migrate_integration_keys(
+    proxy_state: State<'_, ProxyState>,
+) -> Result<Vec<IntegrationKeyMigration>, AppError> {
+    let home = home_dir()?;
+    let port = proxy_state.port().await;
+    let mut results = Vec::new();
+    for tool in get_tool_definitions(&home) {
+        if find_legacy_keys(&tool.config_path, &tool.config_format).is_empty() {
+            continue;
+        }
+        let migrated_keys = match tool.config_strategy {
+            ConfigStrategy::ClaudeCli => migrate_cli_entries(&tool.config_path, port)?,
+            ConfigStrategy::ManagedFile => {
+                migrate_config_file(&tool.config_path, &tool.config_format, port)?
+            }
+        };
+        info!(
+            "Migrated legacy integration keys {:?} in {}",
+            migrated_keys, tool.name
+        );
+        results.push(IntegrationKeyMigration {
+            id: tool.id,
+            config_path: tool.config_path.display().to_string(),
+            migrated_keys,
+        });
+    }
+    Ok(results)
+}
+
+/// Check that a tool's config exists, parses, and contains a proxy entry
+/// pointing at the port the proxy is currently listening on.
+#[tauri::command]
+pub async fn check_integration_health(
+    proxy_state: State<'_, ProxyState>,
+    id: String,
+) -> Result<IntegrationHealth, AppError> {
+    let home = home_dir()?;
+    let tool = find_tool_def(&home, &id)?;
+    let expected_port = proxy_state.port().await;
+
+    let mut health = IntegrationHealth {
+        id,
+        status: IntegrationHealthStatus::Missing,
+        config_path: tool.config_path.display().to_string(),
+        configured_port: 0,
+        expected_port,
+        error: None,
+    };
+
+    let content = match std::fs::read_to_string(&tool.config_path) {
+        Ok(c) => c,
+        Err(_) => return Ok(health),
+    };
+    if let Err(e) = check_config_syntax(&content, &tool.config_format) {
+        health.status = IntegrationHealthStatus::ParseError;
+        health.error = Some(e);
+        return Ok(health);
+    }
+
+    let (has_entry, configured_port, _) = parse_config(&tool.config_path, &tool.config_format);
+    health.configured_port = configured_port;
+    health.status = if !has_entry {
+        IntegrationHealthStatus::Missing
+    } else if configured_port != expected_port {
+        IntegrationHealthStatus::PortMismatch
+    } else {
+        IntegrationHealthStatus::Healthy
+    };
+    Ok(health)
+}
+
+/// The port another instance's proxy serves this tool's entry on, if the
+/// entry points somewhere other than `port` and a proxy still answers there.
+/// A stale port left by a previous run, or one since taken by an unrelated
+/// process, is not a conflict.
+async fn conflicting_port(tool: &ToolDef, port: u16) -> Option<u16> {
+    let (has_entry, configured_port, _) = parse_config(&tool.config_path, &tool.config_format);
+    if !has_entry || configured_port == 0 || configured_port == port {
+        return None;
+    }
+    is_proxy_listening(configured_port)
+        .await
+        .then_some(configured_port)
+}
+
+/// Whether an Agent Hub proxy answers its health route on `port`.
+async fn is_proxy_listening(port: u16) -> bool {
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(PORT_PROBE_TIMEOUT)
+        .build()
+    else {
+        return false;
+    };
+    let Ok(response) = client
+        .get(format!("http://127.0.0.1:{port}/health"))
+        .send()
+        .await
+    else {
+        return false;
+    };
+    response
+        .json::<serde_json::Value>()
+        .await
+        .is_ok_and(|health| health["service"] == PROXY_SERVICE)
+}
+
+/// Enabled integrations whose config points at another running proxy.
+/// `update_all_integration_configs` leaves these alone; use
+/// `overwrite_integration_port` to claim one for this instance.
+#[tauri::command]
+pub async fn detect_port_conflicts(
+    state: State<'_, SharedState>,
+    proxy_state: State<'_, ProxyState>,
+) -> Result<Vec<PortConflict>, AppError> {
+    let home = home_dir()?;
+    let enabled_ids = state.lock().unwrap().enabled_integrations.clone();
+    let port = proxy_state.port().await;
+
+    let mut conflicts = Vec::new();
+    for tool in get_tool_definitions(&home) {
+        if !enabled_ids.contains(&tool.id) || !tool.config_path.exists() {
+            continue;
+        }
+        if let Some(configured_port) = conflicting_port(&tool, port).await {
+            conflicts.push(PortConflict {
+                config_path: tool.config_path.display().to_string(),
+                id: tool.id,
+                name: tool.name,
+                configured_port,
+                current_port: port,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Point a tool's config at this instance's proxy even if another proxy is
+/// live on the port it currently names. Also writes back entries that were
+/// removed by hand (`drift` of `entriesMissing`); unlike `enable_integration`,
+/// nothing is imported from the config.
+#[tauri::command]
+pub async fn overwrite_integration_port(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    proxy_state: State<'_, ProxyState>,
+    id: String,
+) -> Result<(), AppError> {
+    let port = proxy_state.port().await;
+    let tool = rewrite_enabled_config(&app, &state, &id, port)?;
+    info!(
+        "Pointed {} at this instance's proxy on port {port}",
+        tool.name
+    );
+    Ok(())
+}
+
+fn rewrite_enabled_config(
+    app: &AppHandle,
+    state: &SharedState,
+    id: &str,
+    port: u16,
+) -> Result<ToolDef, AppError> {
+    {
+        let s = state.lock().unwrap();
+        if !s.enabled_integrations.iter().any(|i| i == id) {
+            return Err(AppError::Validation(format!(
+                "Integration '{id}' is not enabled"
+            )));
+        }
+    }
+    let home = home_dir()?;
+    let tool = find_tool_def(&home, id)?;
+    write_managed_config(
+        app,
+        &tool.config_path,
+        port,
+        &tool.id,
+        &tool.config_format,
+        &tool.config_strategy,
+    )?;
+    Ok(tool)
+}
+
+/// What `enable_integration` does with a server found in the tool's config.
+enum ImportTarget {
+    /// Left alone, for the given reason.
+    Skip(String),
+    /// Merged into the existing server at this index.
+    Update(usize),
+    /// Added as a new server.
+    New,
+}
+
+fn import_target(servers: &[ServerConfig], server: &ServerConfig) -> ImportTarget {
+    // Never overwrite a managed server (owned by Memory, plugins, etc.)
+    if servers
+        .iter()
+        .any(|srv| srv.name == server.name && srv.managed_by.is_some())
+    {
+        return ImportTarget::Skip("managed by existing subsystem".to_string());
+    }
+    // Match on endpoint first so servers renamed in Agent Hub aren't duplicated
+    let existing = servers
+        .iter()
+        .position(|srv| srv.same_endpoint(server))
+        .or_else(|| servers.iter().position(|srv| srv.name == server.name));
+    match existing {
+        Some(idx) if servers[idx].managed_by.is_some() => {
+            ImportTarget::Skip(format!("matches managed server '{}'", servers[idx].name))
+        }
+        Some(idx) => ImportTarget::Update(idx),
+        None => ImportTarget::New,
+    }
+}
+
+/// One server from a tool's config after `apply_imports`.
+struct AppliedImport {
+    name: String,
+    action: PlannedImportAction,
+    /// For `update`, the existing server's name; for `skip`, why.
+    detail: Option<String>,
+    /// The server as it now stands in the list, unless skipped.
+    server: Option<ServerConfig>,
+}
+
+/// Import `candidates` into `servers` the way `enable_integration` does, one
+/// at a time, so a later duplicate in the same config updates the server an
+/// earlier one added.
+fn apply_imports(
+    servers: &mut Vec<ServerConfig>,
+    candidates: Vec<ServerConfig>,
+) -> Vec<AppliedImport> {
+    candidates
+        .into_iter()
+        .map(|server| {
+            let name = server.name.clone();
+            match import_target(servers, &server) {
+                ImportTarget::Skip(reason) => AppliedImport {
+                    name,
+                    action: PlannedImportAction::Skip,
+                    detail: Some(reason),
+                    server: None,
+                },
+                ImportTarget::Update(idx) => {
+                    let existing = servers[idx].name.clone();
+                    servers[idx].apply_import(server);
+                    AppliedImport {
+                        name,
+                        action: PlannedImportAction::Update,
+                        detail: Some(existing),
+                        server: Some(servers[idx].clone()),
+                    }
+                }
+                ImportTarget::New => {
+                    servers.push(server.clone());
+                    AppliedImport {
+                        name,
+                        action: PlannedImportAction::New,
+                        detail: None,
+                        server: Some(server),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// A server `enable_integration` would import, from `preview_enable_integration`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedImport {
+    pub name: String,
+    pub action: PlannedImportAction,
+    /// For `update`, the existing server that would change; for `skip`, why.
+    pub detail: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlannedImportAction {
+    New,
+    Update,
+    Skip,
+}
+
+/// What enabling an integration would change, from `preview_enable_integration`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnableIntegrationPreview {
+    pub id: String,
+    pub name: String,
+    pub config_path: String,
+    /// `"file"`, or `"cli"` when entries are registered through `claude mcp`.
+    pub strategy: String,
+    /// The config file as it is now; `None` if it doesn't exist, or for the
+    /// CLI strategy, which doesn't rewrite the file.
+    pub current_config: Option<String>,
+    /// The whole config file after enabling, or for the CLI strategy the
+    /// entries that would be registered.
+    pub proposed_config: String,
+    pub imports: Vec<PlannedImport>,
+}
+
+/// Dry run of `enable_integration`: the config before and after, and what
+/// would happen to each server in it. Nothing is written.
+#[tauri::command]
+pub async fn preview_enable_integration(
+    app: AppHandle,
+    proxy_state: State<'_, ProxyState>,
+    state: State<'_, SharedState>,
+    id: String,
+) -> Result<EnableIntegrationPreview, AppError> {
+    let home = home_dir()?;
+    let tool = find_tool_def(&home, &id)?;
+    let port = proxy_state.port().await;
+    let candidates = read_importable_servers(&tool)?;
+
+    // Simulate on a copy so the plan matches what enabling would do
+    let (mut servers, default_env) = {
+        let s = state.lock().unwrap();
+        (s.servers.clone(), s.default_env.clone())
+    };
+    let imports = apply_imports(&mut servers, candidates)
+        .into_iter()
+        .map(|applied| PlannedImport {
+            name: applied.name,
+            action: applied.action,
+            detail: applied.detail,
+            warnings: applied
+                .server
+                .map(|srv| server_warnings(&srv, &default_env))
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    // Imported servers start disconnected, so the entries are today's
+    let entries = connected_proxy_urls(&app, port, &tool.id);
+    let (strategy, current_config, proposed_config) = match tool.config_strategy {
+        ConfigStrategy::ManagedFile => (
+            "file",
+            std::fs::read_to_string(&tool.config_path).ok(),
+            render_managed_config(&tool.config_path, &tool.config_format, entries)?,
+        ),
+        ConfigStrategy::ClaudeCli => (
+            "cli",
+            None,
+            preview_for_format(&entries, &tool.config_format),
+        ),
+    };
+
+    Ok(EnableIntegrationPreview {
+        id: tool.id,
+        name: tool.name,
+        config_path: tool.config_path.display().to_string(),
+        strategy: strategy.to_string(),
+        current_config,
+        proposed_config,
+        imports,
+    })
+}
+
 #[tauri::command]
 pub async fn enable_integration(
     app: AppHandle,
@@ -989,30 +1699,27 @@ pub async fn enable_integration(
     // Import existing servers from the config file (format-agnostic)
     let candidates = read_importable_servers(&tool)?;
 
-    let imported_count = {
+    let (imported, default_env) = {
         let mut s = state.lock().unwrap();
 
-        let mut imported = 0;
-        for server in candidates {
-            // Never overwrite a managed server (owned by Memory, plugins, etc.)
-            if s.servers.iter().any(|srv| srv.name == server.name && srv.managed_by.is_some()) {
-                info!(
-                    "Skipping import of '{}' — managed by existing subsystem",
-                    server.name
-                );
-                continue;
+        let mut imported = Vec::new();
+        for applied in apply_imports(&mut s.servers, candidates) {
+            let detail = applied.detail.unwrap_or_default();
+            match applied.action {
+                PlannedImportAction::Skip => {
+                    info!("Skipping import of '{}' — {detail}", applied.name);
+                }
+                PlannedImportAction::Update => info!(
+                    "Updating existing server '{detail}' with import of '{}' from {}",
+                    applied.name, tool.name
+                ),
+                PlannedImportAction::New => {
+                    info!("Imported MCP server '{}' from {}", applied.name, tool.name);
+                }
             }
-            if let Some(idx) = s.servers.iter().position(|srv| srv.name == server.name) {
-                info!(
-                    "Replacing existing server '{}' with import from {}",
-                    server.name, tool.name
-                );
-                s.servers[idx] = server;
-            } else {
-                info!("Imported MCP server '{}' from {}", server.name, tool.name);
-                s.servers.push(server);
+            if let Some(server) = applied.server {
+                imported.push(server);
             }
-            imported += 1;
         }
 
         // Mark this tool as managed
@@ -1023,11 +1730,21 @@ pub async fn enable_integration(
         save_servers(&app, &s.servers);
         save_enabled_integrations(&app, &s.enabled_integrations);
 
-        imported
+        (imported, s.default_env.clone())
     }; // lock dropped here
 
-    if imported_count > 0 {
-        info!("Imported {imported_count} MCP server(s) from {}", tool.name);
+    // Warnings read env files, so they're checked once the lock is released
+    let imported_servers: Vec<ImportedServer> = imported
+        .into_iter()
+        .map(|server| ImportedServer::new(server, &default_env))
+        .collect();
+
+    if !imported_servers.is_empty() {
+        info!(
+            "Imported {} MCP server(s) from {}",
+            imported_servers.len(),
+            tool.name
+        );
         crate::tray::rebuild_tray_menu(&app);
     }
 
@@ -1047,9 +1764,44 @@ pub async fn enable_integration(
         config_path: tool.config_path.display().to_string(),
         configured_port: port,
         existing_servers: Vec::new(),
+        imported_servers,
+        legacy_keys: Vec::new(),
+        drift: None,
     })
 }
 
+/// Add the servers from a pasted `{"mcpServers": {...}}` blob. Entries whose
+/// name or endpoint matches an existing server are skipped and reported
+/// rather than overwritten.
+#[tauri::command]
+pub async fn import_servers_from_json(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    content: String,
+) -> Result<ServerImportReport, AppError> {
+    let config: serde_json::Value = serde_json::from_str(&strip_json_comments(&content))
+        .map_err(|e| AppError::Validation(format!("Invalid JSON: {e}")))?;
+    let servers_obj = config
+        .get("mcpServers")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| AppError::Validation("Expected an \"mcpServers\" object".into()))?;
+    let inputs = servers_from_mcp_servers(servers_obj)
+        .into_iter()
+        .map(|server| ServerConfigInput {
+            name: server.name,
+            enabled: server.enabled,
+            transport: server.transport,
+            command: server.command,
+            args: server.args,
+            env: server.env,
+            url: server.url,
+            headers: server.headers,
+            ..Default::default()
+        })
+        .collect();
+    import_server_inputs(&app, &state, inputs)
+}
+
 #[tauri::command]
 pub async fn disable_integration(
     app: AppHandle,
@@ -1077,6 +1829,9 @@ pub async fn disable_integration(
             config_path: tool.config_path.display().to_string(),
             configured_port: 0,
             existing_servers: Vec::new(),
+            imported_servers: Vec::new(),
+            legacy_keys: Vec::new(),
+            drift: None,
         });
     }
 
@@ -1095,6 +1850,9 @@ pub async fn disable_integration(
         config_path: tool.config_path.display().to_string(),
         configured_port: 0,
         existing_servers,
+        imported_servers: Vec::new(),
+        legacy_keys: Vec::new(),
+        drift: None,
     })
 }
 
@@ -1113,23 +1871,37 @@ fn write_managed_config(
 ) -> Result<(), AppError> {
     match strategy {
         ConfigStrategy::ClaudeCli => write_cli_config(app, port, tool_id),
-        ConfigStrategy::ManagedFile => match format {
-            ConfigFormat::McpServers => write_mcp_servers_config(app, path, port, tool_id),
-            ConfigFormat::OpenCode => write_opencode_config(app, path, port, tool_id),
-            ConfigFormat::Zed => write_zed_config(app, path, port, tool_id),
-            ConfigFormat::CodexToml => write_codex_config(app, path, port, tool_id),
-        },
+        ConfigStrategy::ManagedFile => {
+            let entries = connected_proxy_urls(app, port, tool_id);
+            let content = render_managed_config(path, format, entries)?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            write_atomic(path, content)?;
+            Ok(())
+        }
     }
 }
 
-fn write_mcp_servers_config(
-    app: &AppHandle,
+/// The full content of a tool's config file with its MCP section replaced by
+/// `entries`, keeping everything else in the file.
+fn render_managed_config(
     path: &Path,
-    port: u16,
-    tool_id: &str,
-) -> Result<(), AppError> {
-    let entries = connected_proxy_urls(app, port, tool_id);
+    format: &ConfigFormat,
+    entries: Vec<(String, String)>,
+) -> Result<String, AppError> {
+    match format {
+        ConfigFormat::McpServers => render_mcp_servers_config(path, entries),
+        ConfigFormat::OpenCode => render_opencode_config(path, entries),
+        ConfigFormat::Zed => render_zed_config(path, entries),
+        ConfigFormat::CodexToml => render_codex_config(path, entries),
+    }
+}
 
+fn render_mcp_servers_config(
+    path: &Path,
+    entries: Vec<(String, String)>,
+) -> Result<String, AppError> {
     // Read existing config to preserve other top-level keys (but replace mcpServers entirely)
     let mut config = if path.exists() {
         let content = std::fs::read_to_string(path)?;
@@ -1147,25 +1919,10 @@ fn write_mcp_servers_config(
     }
 
     config["mcpServers"] = serde_json::Value::Object(mcp_servers);
-
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
-
-    Ok(())
+    Ok(serde_json::to_string_pretty(&config)?)
 }
 
-fn write_opencode_config(
-    app: &AppHandle,
-    path: &Path,
-    port: u16,
-    tool_id: &str,
-) -> Result<(), AppError> {
-    let entries = connected_proxy_urls(app, port, tool_id);
-
+fn render_opencode_config(path: &Path, entries: Vec<(String, String)>) -> Result<String, AppError> {
     let mut mcp = serde_json::Map::new();
     for (name, url) in entries {
         mcp.insert(
@@ -1185,25 +1942,10 @@ fn write_opencode_config(
     };
 
     config["mcp"] = serde_json::Value::Object(mcp);
-
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
-
-    Ok(())
+    Ok(serde_json::to_string_pretty(&config)?)
 }
 
-fn write_zed_config(
-    app: &AppHandle,
-    path: &Path,
-    port: u16,
-    tool_id: &str,
-) -> Result<(), AppError> {
-    let entries = connected_proxy_urls(app, port, tool_id);
-
+fn render_zed_config(path: &Path, entries: Vec<(String, String)>) -> Result<String, AppError> {
     let mut context_servers = serde_json::Map::new();
     for (name, url) in entries {
         context_servers.insert(name, serde_json::json!({ "url": url }));
@@ -1219,25 +1961,10 @@ fn write_zed_config(
     };
 
     config["context_servers"] = serde_json::Value::Object(context_servers);
-
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
-
-    Ok(())
+    Ok(serde_json::to_string_pretty(&config)?)
 }
 
-fn write_codex_config(
-    app: &AppHandle,
-    path: &Path,
-    port: u16,
-    tool_id: &str,
-) -> Result<(), AppError> {
-    let entries = connected_proxy_urls(app, port, tool_id);
-
+fn render_codex_config(path: &Path, entries: Vec<(String, String)>) -> Result<String, AppError> {
     let mut mcp_servers = toml::map::Map::new();
     for (name, url) in entries {
         let mut entry = toml::map::Map::new();
@@ -1258,15 +1985,8 @@ fn write_codex_config(
         table.insert("mcp_servers".into(), toml::Value::Table(mcp_servers));
     }
 
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let content = toml::to_string_pretty(&config)
-        .map_err(|e| AppError::Protocol(format!("Failed to serialize TOML: {e}")))?;
-    std::fs::write(path, content)?;
-
-    Ok(())
+    toml::to_string_pretty(&config)
+        .map_err(|e| AppError::Protocol(format!("Failed to serialize TOML: {e}")))
 }
 
 // ---------------------------------------------------------------------------
@@ -1301,7 +2021,7 @@ fn remove_mcp_servers_entries(path: &Path) -> Result<(), AppError> {
         let proxy_keys: Vec<String> = servers
             .iter()
             .filter(|(k, v)| {
-                *k == DISCOVERY_SERVER_NAME
+                is_managed_key(k)
                     || v.get("url")
                         .and_then(|u| u.as_str())
                         .map(is_proxy_url)
@@ -1316,7 +2036,7 @@ fn remove_mcp_servers_entries(path: &Path) -> Result<(), AppError> {
     }
 
     let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
 
     Ok(())
 }
@@ -1328,11 +2048,12 @@ fn remove_opencode_entries(path: &Path) -> Result<(), AppError> {
     if let Some(mcp) = config.get_mut("mcp").and_then(|v| v.as_object_mut()) {
         let proxy_keys: Vec<String> = mcp
             .iter()
-            .filter(|(_, v)| {
-                v.get("url")
-                    .and_then(|u| u.as_str())
-                    .map(is_proxy_url)
-                    .unwrap_or(false)
+            .filter(|(k, v)| {
+                is_managed_key(k)
+                    || v.get("url")
+                        .and_then(|u| u.as_str())
+                        .map(is_proxy_url)
+                        .unwrap_or(false)
             })
             .map(|(k, _)| k.clone())
             .collect();
@@ -1343,7 +2064,7 @@ fn remove_opencode_entries(path: &Path) -> Result<(), AppError> {
     }
 
     let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
 
     Ok(())
 }
@@ -1359,11 +2080,12 @@ fn remove_zed_entries(path: &Path) -> Result<(), AppError> {
     {
         let proxy_keys: Vec<String> = servers
             .iter()
-            .filter(|(_, v)| {
-                v.get("url")
-                    .and_then(|u| u.as_str())
-                    .map(is_proxy_url)
-                    .unwrap_or(false)
+            .filter(|(k, v)| {
+                is_managed_key(k)
+                    || v.get("url")
+                        .and_then(|u| u.as_str())
+                        .map(is_proxy_url)
+                        .unwrap_or(false)
             })
             .map(|(k, _)| k.clone())
             .collect();
@@ -1374,7 +2096,7 @@ fn remove_zed_entries(path: &Path) -> Result<(), AppError> {
     }
 
     let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
 
     Ok(())
 }
@@ -1389,11 +2111,12 @@ fn remove_codex_entries(path: &Path) -> Result<(), AppError> {
         if let Some(servers) = table.get_mut("mcp_servers").and_then(|v| v.as_table_mut()) {
             let proxy_keys: Vec<String> = servers
                 .iter()
-                .filter(|(_, v)| {
-                    v.get("url")
-                        .and_then(|u| u.as_str())
-                        .map(is_proxy_url)
-                        .unwrap_or(false)
+                .filter(|(k, v)| {
+                    is_managed_key(k)
+                        || v.get("url")
+                            .and_then(|u| u.as_str())
+                            .map(is_proxy_url)
+                            .unwrap_or(false)
                 })
                 .map(|(k, _)| k.clone())
                 .collect();
@@ -1406,7 +2129,7 @@ fn remove_codex_entries(path: &Path) -> Result<(), AppError> {
 
     let content = toml::to_string_pretty(&config)
         .map_err(|e| AppError::Protocol(format!("Failed to serialize TOML: {e}")))?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
 
     Ok(())
 }
@@ -1477,7 +2200,7 @@ fn write_native_mcp_servers(servers: &[ServerConfig], path: &Path) -> Result<(),
     config["mcpServers"] = serde_json::Value::Object(mcp_servers);
 
     let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
     Ok(())
 }
 
@@ -1525,7 +2248,7 @@ fn write_native_opencode(servers: &[ServerConfig], path: &Path) -> Result<(), Ap
     config["mcp"] = serde_json::Value::Object(mcp);
 
     let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
     Ok(())
 }
 
@@ -1570,7 +2293,7 @@ fn write_native_zed(servers: &[ServerConfig], path: &Path) -> Result<(), AppErro
     config["context_servers"] = serde_json::Value::Object(context_servers);
 
     let content = serde_json::to_string_pretty(&config)?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
     Ok(())
 }
 
@@ -1624,7 +2347,7 @@ fn write_native_codex(servers: &[ServerConfig], path: &Path) -> Result<(), AppEr
 
     let content = toml::to_string_pretty(&config)
         .map_err(|e| AppError::Protocol(format!("Failed to serialize TOML: {e}")))?;
-    std::fs::write(path, content)?;
+    write_atomic(path, content)?;
     Ok(())
 }
 
@@ -1794,7 +2517,8 @@ pub fn restore_all_integration_configs(app: &AppHandle, port: u16) -> Result<(),
 
 /// Update all enabled integration configs with current connected servers.
 /// Called on proxy startup, server connect/disconnect, and enable/disable.
-pub fn update_all_integration_configs(app: &AppHandle, port: u16) -> Result<(), AppError> {
+/// Configs that point at another live proxy are skipped; see `detect_port_conflicts`.
+pub async fn update_all_integration_configs(app: &AppHandle, port: u16) -> Result<(), AppError> {
     let home = home_dir()?;
     let tools = get_tool_definitions(&home);
 
@@ -1809,6 +2533,15 @@ pub fn update_all_integration_configs(app: &AppHandle, port: u16) -> Result<(),
             continue;
         }
 
+        // Don't stomp on another running instance's entry
+        if let Some(other) = conflicting_port(&tool, port).await {
+            warn!(
+                "Not updating {} config: it points at another live proxy on port {other}",
+                tool.name
+            );
+            continue;
+        }
+
         if let Err(e) =
             write_managed_config(app, &tool.config_path, port, &tool.id, &tool.config_format, &tool.config_strategy)
         {
@@ -1820,3 +2553,298 @@ pub fn update_all_integration_configs(app: &AppHandle, port: u16) -> Result<(),
 
     Ok(())
 }
+
+/// Like `update_all_integration_configs`, but a no-op when the user has turned
+/// off `auto_update_integration_ports`. Use this for refreshes the user didn't
+/// ask for directly: launch, connect, disconnect and restart.
+pub async fn refresh_integration_configs(app: &AppHandle, port: u16) -> Result<(), AppError> {
+    let auto_update = {
+        let state = app.state::<SharedState>();
+        let s = state.lock().unwrap();
+        s.proxy_settings.auto_update_integration_ports
+    };
+    if_auto_update(auto_update, || update_all_integration_configs(app, port)).await
+}
+
+async fn if_auto_update<F, Fut>(auto_update: bool, rewrite: F) -> Result<(), AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<(), AppError>>,
+{
+    if !auto_update {
+        info!("Leaving integration configs as they are (auto-update disabled)");
+        return Ok(());
+    }
+    rewrite().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn disabled_auto_update_skips_config_rewrite() {
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let rewrite = || async {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        };
+
+        if_auto_update(false, rewrite).await.unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        if_auto_update(true, rewrite).await.unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn drift_when_entries_disagree_with_enabled_state() {
+        assert_eq!(
+            integration_drift(true, false, true),
+            Some(IntegrationDrift::EntriesMissing)
+        );
+        assert_eq!(
+            integration_drift(false, true, true),
+            Some(IntegrationDrift::EntriesPresent)
+        );
+        assert_eq!(
+            integration_drift(false, true, false),
+            Some(IntegrationDrift::EntriesPresent)
+        );
+    }
+
+    #[test]
+    fn no_drift_when_config_matches() {
+        assert_eq!(integration_drift(true, true, true), None);
+        assert_eq!(integration_drift(false, false, true), None);
+        // Nothing to write, so an enabled tool without entries is fine
+        assert_eq!(integration_drift(true, false, false), None);
+    }
+
+    fn server(name: &str, command: &str) -> ServerConfig {
+        ServerConfig {
+            id: Uuid::new_v4().to_string(),
+            name: name.into(),
+            command: Some(command.into()),
+            ..Default::default()
+        }
+    }
+
+    fn temp_config(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "agent-hub-integrations-{name}-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config");
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn entries() -> Vec<(String, String)> {
+        vec![("a".into(), "http://localhost:1/mcp/a".into())]
+    }
+
+    #[test]
+    fn import_target_matches_endpoint_before_name() {
+        let servers = vec![server("renamed", "run-a"), server("b", "run-b")];
+        assert!(matches!(
+            import_target(&servers, &server("a", "run-a")),
+            ImportTarget::Update(0)
+        ));
+        assert!(matches!(
+            import_target(&servers, &server("b", "run-other")),
+            ImportTarget::Update(1)
+        ));
+        assert!(matches!(
+            import_target(&servers, &server("c", "run-c")),
+            ImportTarget::New
+        ));
+    }
+
+    #[test]
+    fn import_target_never_touches_managed_servers() {
+        let mut memory = server("memory", "run-memory");
+        memory.managed_by = Some("memory".into());
+        let servers = vec![memory];
+        assert!(matches!(
+            import_target(&servers, &server("memory", "other")),
+            ImportTarget::Skip(_)
+        ));
+        assert!(matches!(
+            import_target(&servers, &server("mem", "run-memory")),
+            ImportTarget::Skip(_)
+        ));
+    }
+
+    #[test]
+    fn duplicates_within_one_config_update_the_first_import() {
+        let mut servers = vec![server("existing", "run-x")];
+        let applied = apply_imports(
+            &mut servers,
+            vec![
+                server("a", "run-a"),
+                server("a", "run-a2"),
+                server("x", "run-x"),
+            ],
+        );
+
+        let actions: Vec<_> = applied.iter().map(|a| a.action).collect();
+        assert_eq!(
+            actions,
+            [
+                PlannedImportAction::New,
+                PlannedImportAction::Update,
+                PlannedImportAction::Update
+            ]
+        );
+        assert_eq!(applied[2].detail.as_deref(), Some("existing"));
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[1].command.as_deref(), Some("run-a2"));
+    }
+
+    #[test]
+    fn render_mcp_servers_replaces_only_the_server_section() {
+        let path = temp_config(
+            "mcp-servers",
+            r#"{"theme": "dark", "mcpServers": {"old": {"command": "x"}}}"#,
+        );
+        let config: serde_json::Value =
+            serde_json::from_str(&render_mcp_servers_config(&path, entries()).unwrap()).unwrap();
+        assert_eq!(config["theme"], "dark");
+        assert_eq!(
+            config["mcpServers"],
+            serde_json::json!({ "a": { "type": "http", "url": "http://localhost:1/mcp/a" } })
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn render_zed_reads_commented_settings() {
+        let path = temp_config(
+            "zed",
+            "{\n  // editor\n  \"vim_mode\": true,\n  \"context_servers\": {}\n}",
+        );
+        let config: serde_json::Value =
+            serde_json::from_str(&render_zed_config(&path, entries()).unwrap()).unwrap();
+        assert_eq!(config["vim_mode"], true);
+        assert_eq!(
+            config["context_servers"]["a"]["url"],
+            "http://localhost:1/mcp/a"
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn render_opencode_and_codex_keep_other_settings() {
+        let path = temp_config("opencode", r#"{"model": "m"}"#);
+        let config: serde_json::Value =
+            serde_json::from_str(&render_opencode_config(&path, entries()).unwrap()).unwrap();
+        assert_eq!(config["model"], "m");
+        assert_eq!(config["mcp"]["a"]["type"], "remote");
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+
+        let path = temp_config(
+            "codex",
+            "model = \"m\"\n\n[mcp_servers.old]\ncommand = \"x\"\n",
+        );
+        let config: toml::Value = render_codex_config(&path, entries())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(config["model"].as_str(), Some("m"));
+        let servers = config["mcp_servers"].as_table().unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(
+            servers["a"]["url"].as_str(),
+            Some("http://localhost:1/mcp/a")
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn render_without_existing_config_writes_only_entries() {
+        let path = std::env::temp_dir().join("agent-hub-integrations-missing/config.json");
+        let config: serde_json::Value =
+            serde_json::from_str(&render_mcp_servers_config(&path, entries()).unwrap()).unwrap();
+        assert_eq!(config.as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn migration_moves_only_legacy_proxy_entries_to_the_current_port() {
+        let path = temp_config(
+            "legacy-json",
+            r#"{"mcpServers": {
+                "mcp-manager": {"url": "http://localhost:4000/mcp/discovery?client=cursor"},
+                "mcp_manager": {"command": "npx", "args": ["mcp-manager"]}
+            }}"#,
+        );
+        assert_eq!(
+            find_legacy_keys(&path, &ConfigFormat::McpServers),
+            vec!["mcp-manager"]
+        );
+        let migrated = migrate_config_file(&path, &ConfigFormat::McpServers, 5000).unwrap();
+        assert_eq!(migrated, vec!["mcp-manager"]);
+
+        let config: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let servers = config["mcpServers"].as_object().unwrap();
+        assert_eq!(
+            servers[DISCOVERY_SERVER_NAME]["url"],
+            "http://localhost:5000/mcp/discovery?client=cursor"
+        );
+        assert!(!servers.contains_key("mcp-manager"));
+        // Not a proxy entry, so it's the user's and stays put
+        assert_eq!(servers["mcp_manager"]["command"], "npx");
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn codex_migration_moves_only_legacy_proxy_entries() {
+        let path = temp_config(
+            "legacy-toml",
+            "[mcp_servers.mcp-manager]\nurl = \"http://127.0.0.1:4000/mcp/discovery\"\n\n\
+             [mcp_servers.user-mcp-manager]\nurl = \"https://example.com/mcp\"\n",
+        );
+        let migrated = migrate_config_file(&path, &ConfigFormat::CodexToml, 5000).unwrap();
+        assert_eq!(migrated, vec!["mcp-manager"]);
+
+        let config: toml::Value = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        let servers = config["mcp_servers"].as_table().unwrap();
+        assert_eq!(
+            servers[DISCOVERY_SERVER_NAME]["url"].as_str(),
+            Some("http://127.0.0.1:5000/mcp/discovery")
+        );
+        assert!(!servers.contains_key("mcp-manager"));
+        assert_eq!(
+            servers["user-mcp-manager"]["url"].as_str(),
+            Some("https://example.com/mcp")
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn codex_migration_keeps_unrelated_values_and_formatting() {
+        let path = temp_config(
+            "legacy-toml-datetime",
+            "# user settings\nmodel = \"o3\"\nlast_login = 1979-05-27T07:32:00Z\n\n\
+             [mcp_servers.mcp-manager]\nurl = \"http://127.0.0.1:4000/mcp/discovery\"\n\
+             startup_timeout_sec = 30\n",
+        );
+        let migrated = migrate_config_file(&path, &ConfigFormat::CodexToml, 5000).unwrap();
+        assert_eq!(migrated, vec!["mcp-manager"]);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# user settings\n"));
+        assert!(content.contains("last_login = 1979-05-27T07:32:00Z\n"));
+        let config: toml::Value = content.parse().unwrap();
+        assert!(config["last_login"].is_datetime());
+        let entry = &config["mcp_servers"][DISCOVERY_SERVER_NAME];
+        assert_eq!(
+            entry["url"].as_str(),
+            Some("http://127.0.0.1:5000/mcp/discovery")
+        );
+        assert_eq!(entry["startup_timeout_sec"].as_integer(), Some(30));
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
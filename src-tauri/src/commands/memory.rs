@@ -9,8 +9,8 @@ use crate::error::AppError;
 use crate::mcp::client::SharedConnections;
 use crate::mcp::proxy::ProxyState;
 use crate::persistence::{
-    load_openai_api_key, save_embedding_config, save_openai_api_key, save_redis_config,
-    save_servers,
+    load_cohere_api_key, load_openai_api_key, save_cohere_api_key, save_embedding_config,
+    save_openai_api_key, save_redis_config, save_servers,
 };
 use crate::state::{
     ConnectionState, EmbeddingConfig, EmbeddingProvider, McpTool, RedisConfig, RedisSource,
@@ -18,6 +18,8 @@ use crate::state::{
 };
 
 const NETWORK: &str = "agent-hub-net";
+/// Text embedded by `set_embedding_config` to verify the model.
+const EMBEDDING_PROBE: &str = "agent hub embedding probe";
 const REDIS_CONTAINER: &str = "agent-hub-redis";
 const OLLAMA_CONTAINER: &str = "agent-hub-ollama";
 const API_CONTAINER: &str = "agent-hub-api";
@@ -46,6 +48,7 @@ pub struct MemoryStatus {
 pub struct EmbeddingConfigStatus {
     pub config: EmbeddingConfig,
     pub has_openai_key: bool,
+    pub has_cohere_key: bool,
     pub pulled_ollama_models: Vec<String>,
     pub redis_config: RedisConfig,
 }
@@ -54,7 +57,10 @@ pub struct EmbeddingConfigStatus {
 #[serde(rename_all = "camelCase")]
 pub struct SaveEmbeddingConfigInput {
     pub config: EmbeddingConfig,
+    /// Used for both `openai` and `custom` (OpenAI-compatible) providers.
     pub openai_api_key: Option<String>,
+    #[serde(default)]
+    pub cohere_api_key: Option<String>,
 }
 
 async fn is_command_available(cmd: &str) -> bool {
@@ -586,6 +592,18 @@ fn uninstall_memory_skill() {
     info!("Removed memory skill from ~/.claude/skills/using-memory-mcp/");
 }
 
+/// Containers can't reach the host via `localhost`; rewrite it to
+/// `host.docker.internal` like the tunnel path does for Redis.
+fn container_reachable_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) if matches!(parsed.host_str(), Some("localhost") | Some("127.0.0.1")) => {
+            let _ = parsed.set_host(Some("host.docker.internal"));
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
 fn find_memory_server(servers: &[ServerConfig]) -> Option<&ServerConfig> {
     servers
         .iter()
@@ -608,11 +626,13 @@ pub async fn get_embedding_config(
     };
 
     let has_openai_key = load_openai_api_key(&app).is_some();
+    let has_cohere_key = load_cohere_api_key(&app).is_some();
     let pulled_ollama_models = list_pulled_ollama_models().await;
 
     Ok(EmbeddingConfigStatus {
         config,
         has_openai_key,
+        has_cohere_key,
         pulled_ollama_models,
         redis_config,
     })
@@ -629,6 +649,22 @@ pub async fn save_embedding_config_cmd(
             "Dimensions must be greater than 0".into(),
         ));
     }
+    if let Some(expected) = input.config.provider.known_dimensions(&input.config.model) {
+        if input.config.dimensions != expected {
+            return Err(AppError::Validation(format!(
+                "Model {} produces {expected}-dimensional embeddings, not {}",
+                input.config.model, input.config.dimensions
+            )));
+        }
+    }
+    if let EmbeddingProvider::Custom { base_url } = &input.config.provider {
+        let valid = url::Url::parse(base_url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"));
+        if !valid {
+            return Err(AppError::Validation(format!(
+                "Invalid embedding endpoint URL: {base_url}"
+            )));
+        }
+    }
 
     let previous_provider = {
         let s = state.lock().unwrap();
@@ -642,13 +678,19 @@ pub async fn save_embedding_config_cmd(
     }
     save_embedding_config(&app, &input.config);
 
-    // Save or clear OpenAI API key
-    if input.config.provider == EmbeddingProvider::Openai {
-        if let Some(key) = &input.openai_api_key {
-            if !key.is_empty() {
+    // Save API keys for the selected provider
+    match input.config.provider {
+        EmbeddingProvider::Openai | EmbeddingProvider::Custom { .. } => {
+            if let Some(key) = input.openai_api_key.as_deref().filter(|k| !k.is_empty()) {
                 save_openai_api_key(&app, key);
             }
         }
+        EmbeddingProvider::Cohere => {
+            if let Some(key) = input.cohere_api_key.as_deref().filter(|k| !k.is_empty()) {
+                save_cohere_api_key(&app, key);
+            }
+        }
+        EmbeddingProvider::Ollama => {}
     }
 
     // Stop Ollama container if switching away from Ollama
@@ -671,6 +713,123 @@ pub async fn save_embedding_config_cmd(
     Ok(())
 }
 
+/// Like `save_embedding_config_cmd`, but first asks the provider to embed a
+/// probe string so a missing model or wrong `dimensions` is caught now
+/// rather than when the memory server starts.
+#[tauri::command]
+pub async fn set_embedding_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    input: SaveEmbeddingConfigInput,
+) -> Result<(), AppError> {
+    let actual = probe_embedding_dimensions(&app, &input).await?;
+    if actual != input.config.dimensions {
+        return Err(AppError::Validation(format!(
+            "Model {} returned {actual}-dimensional embeddings, but {} were configured",
+            input.config.model, input.config.dimensions
+        )));
+    }
+    save_embedding_config_cmd(app, state, input).await
+}
+
+/// Embed a short probe string with the configured provider and return the
+/// length of the resulting vector.
+async fn probe_embedding_dimensions(
+    app: &AppHandle,
+    input: &SaveEmbeddingConfigInput,
+) -> Result<u32, AppError> {
+    let config = &input.config;
+    let given = |key: &Option<String>| key.clone().filter(|k| !k.is_empty());
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| AppError::Transport(format!("Failed to build HTTP client: {e}")))?;
+
+    let request = match &config.provider {
+        EmbeddingProvider::Ollama => {
+            if !is_container_running(OLLAMA_CONTAINER).await {
+                return Err(AppError::ConnectionFailed(
+                    "Ollama isn't running, so the model can't be verified".into(),
+                ));
+            }
+            client
+                .post("http://localhost:11434/api/embeddings")
+                .json(&serde_json::json!({ "model": config.model, "prompt": EMBEDDING_PROBE }))
+        }
+        EmbeddingProvider::Openai | EmbeddingProvider::Custom { .. } => {
+            let base = match &config.provider {
+                EmbeddingProvider::Custom { base_url } => base_url.trim_end_matches('/'),
+                _ => "https://api.openai.com/v1",
+            };
+            let key = given(&input.openai_api_key).or_else(|| load_openai_api_key(app));
+            if key.is_none() && config.provider == EmbeddingProvider::Openai {
+                return Err(AppError::Validation("OpenAI API key not configured".into()));
+            }
+            let req = client
+                .post(format!("{base}/embeddings"))
+                .json(&serde_json::json!({ "model": config.model, "input": EMBEDDING_PROBE }));
+            match key {
+                Some(key) => req.bearer_auth(key),
+                None => req,
+            }
+        }
+        EmbeddingProvider::Cohere => {
+            let key = given(&input.cohere_api_key)
+                .or_else(|| load_cohere_api_key(app))
+                .ok_or_else(|| AppError::Validation("Cohere API key not configured".into()))?;
+            client
+                .post("https://api.cohere.com/v1/embed")
+                .bearer_auth(key)
+                .json(&serde_json::json!({
+                    "model": config.model,
+                    "texts": [EMBEDDING_PROBE],
+                    "input_type": "search_document",
+                }))
+        }
+    };
+
+    let provider = config.provider.as_str();
+    let response = request.send().await.map_err(|e| {
+        AppError::ConnectionFailed(format!("Failed to reach {provider} embeddings API: {e}"))
+    })?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(if status.is_client_error() {
+            AppError::Validation(format!(
+                "{provider} rejected model {} ({status}): {body}",
+                config.model
+            ))
+        } else {
+            AppError::ConnectionFailed(format!(
+                "{provider} embeddings API returned {status}: {body}"
+            ))
+        });
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| AppError::Protocol(format!("Invalid {provider} embeddings response: {e}")))?;
+    let vector = match &config.provider {
+        EmbeddingProvider::Ollama => body.get("embedding"),
+        EmbeddingProvider::Openai | EmbeddingProvider::Custom { .. } => {
+            body.pointer("/data/0/embedding")
+        }
+        EmbeddingProvider::Cohere => body.pointer("/embeddings/0"),
+    };
+    vector
+        .and_then(|v| v.as_array())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.len() as u32)
+        .ok_or_else(|| {
+            AppError::Protocol(format!(
+                "{provider} returned no embedding for model {}",
+                config.model
+            ))
+        })
+}
+
 #[tauri::command]
 pub async fn save_redis_config_cmd(
     app: AppHandle,
@@ -854,7 +1013,7 @@ pub async fn enable_memory(
         embedding_config.dimensions.to_string(),
     );
 
-    match embedding_config.provider {
+    match &embedding_config.provider {
         EmbeddingProvider::Ollama => {
             // Start Ollama container on the same network
             step += 1;
@@ -906,6 +1065,31 @@ pub async fn enable_memory(
             env.insert("EMBEDDING_MODEL".into(), embedding_config.model.clone());
             env.insert("OPENAI_API_KEY".into(), api_key);
         }
+        EmbeddingProvider::Cohere => {
+            let api_key = load_cohere_api_key(&app).ok_or_else(|| {
+                AppError::Protocol(
+                    "Cohere API key not configured. Save your API key in embedding settings first."
+                        .into(),
+                )
+            })?;
+
+            env.insert(
+                "EMBEDDING_MODEL".into(),
+                format!("cohere/{}", embedding_config.model),
+            );
+            env.insert("COHERE_API_KEY".into(), api_key);
+        }
+        EmbeddingProvider::Custom { base_url } => {
+            // Gateways often run without auth; LiteLLM still wants a key set
+            let api_key = load_openai_api_key(&app).unwrap_or_else(|| "unused".into());
+
+            env.insert(
+                "EMBEDDING_MODEL".into(),
+                format!("openai/{}", embedding_config.model),
+            );
+            env.insert("OPENAI_API_BASE".into(), container_reachable_url(base_url));
+            env.insert("OPENAI_API_KEY".into(), api_key);
+        }
     }
 
     // Start the API container (port 8000)
@@ -973,6 +1157,7 @@ pub async fn enable_memory(
         managed: None,
         managed_by: Some("memory".into()),
         registry_name: None,
+        ..Default::default()
     };
 
     {
@@ -1039,7 +1224,7 @@ pub async fn disable_memory(
     // Update integration configs to remove this server's proxy entry
     let proxy_state = app.state::<ProxyState>();
     let port = proxy_state.port().await;
-    if let Err(e) = crate::commands::integrations::update_all_integration_configs(&app, port) {
+    if let Err(e) = crate::commands::integrations::refresh_integration_configs(&app, port).await {
         tracing::warn!("Failed to update integration configs after memory disable: {e}");
     }
 
@@ -1239,8 +1424,8 @@ pub async fn restart_memory(
             .unwrap_or_else(|| "http://localhost:9050/sse".into())
     };
 
-    use crate::mcp::client::McpClient;
-    match McpClient::connect_http(&url, HashMap::new(), None).await {
+    use crate::mcp::client::{ClientOptions, McpClient};
+    match McpClient::connect_http(&url, HashMap::new(), None, &ClientOptions::default()).await {
         Ok(client) => {
             let tools: Vec<McpTool> = client
                 .tools
@@ -1266,6 +1451,9 @@ pub async fn restart_memory(
                     server_id.clone(),
                     ConnectionState {
                         tools: tools.clone(),
+                        server_name: client.server_info.as_ref().map(|i| i.name.clone()),
+                        server_version: client.server_info.as_ref().map(|i| i.version.clone()),
+                        capabilities: client.capability_flags(),
                     },
                 );
                 if let Some(srv) = s.servers.iter_mut().find(|s| s.id == server_id) {
@@ -1350,10 +1538,7 @@ async fn get_memory_status_inner(state: &SharedState) -> Result<MemoryStatus, Ap
         (false, false, false, false)
     };
 
-    let provider_str = match embedding_config.provider {
-        EmbeddingProvider::Ollama => "ollama",
-        EmbeddingProvider::Openai => "openai",
-    };
+    let provider_str = embedding_config.provider.as_str();
 
     let redis_source = match redis_config.source {
         RedisSource::Local => "local",
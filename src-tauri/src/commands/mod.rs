@@ -1,10 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tracing::info;
 
+use crate::error::AppError;
+
 pub mod connections;
 pub mod data_management;
 pub mod discovery;
+pub mod fallback;
 pub mod integrations;
 pub mod memories;
 pub mod memory;
@@ -12,6 +15,7 @@ pub mod oauth;
 pub mod plugins;
 pub mod proxy;
 pub mod registry;
+pub mod sampling;
 pub mod servers;
 pub mod skills;
 pub mod skills_config;
@@ -23,28 +27,151 @@ pub mod tools;
 // Shared CLI helpers
 // ---------------------------------------------------------------------------
 
-/// Resolve the `claude` binary path.
-///
-/// macOS GUI apps (DMG installs) don't inherit the user's shell PATH, so a bare
-/// `"claude"` lookup fails even when the CLI is installed. Check well-known
-/// locations first, then fall back to a bare name for PATH resolution.
+/// Resolve the `claude` binary path, falling back to the bare name so a
+/// missing CLI surfaces as a spawn error from the caller.
 pub(crate) fn resolve_claude_binary() -> String {
-    let candidates: Vec<PathBuf> = [
-        dirs::home_dir().map(|h| h.join(".local/bin/claude")),
-        Some(PathBuf::from("/usr/local/bin/claude")),
-        Some(PathBuf::from("/opt/homebrew/bin/claude")),
-    ]
-    .into_iter()
-    .flatten()
-    .collect();
-
-    for path in &candidates {
-        if path.exists() {
+    match resolve_binary("claude") {
+        Ok(path) => {
             info!("Resolved claude CLI at {}", path.display());
-            return path.to_string_lossy().into_owned();
+            path.to_string_lossy().into_owned()
         }
+        Err(_) => "claude".to_string(),
+    }
+}
+
+/// Find the executable for a server `command` such as `npx`, `uvx` or `node`.
+///
+/// macOS GUI apps (DMG installs) don't inherit the user's shell PATH, so a bare
+/// lookup misses tools installed through Homebrew, nvm, asdf and friends. PATH
+/// is searched first, then the usual install locations. Commands containing a
+/// path separator are used as given. The error lists every place looked.
+pub(crate) fn resolve_binary(command: &str) -> Result<PathBuf, AppError> {
+    if command.contains('/') || command.contains('\\') {
+        let path = PathBuf::from(command);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(AppError::Transport(format!("Command not found: {command}")))
+        };
     }
 
-    // Fall back to bare name — works when PATH is inherited (e.g. `pnpm tauri dev`)
-    "claude".to_string()
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    for dir in fallback_bin_dirs() {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    find_in_dirs(command, &dirs).ok_or_else(|| {
+        let searched: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+        AppError::Transport(format!(
+            "Command '{command}' was not found. Looked in: {}",
+            searched.join(", ")
+        ))
+    })
+}
+
+fn find_in_dirs(command: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", "exe", "cmd", "bat"]
+    } else {
+        &[""]
+    };
+    dirs.iter().find_map(|dir| {
+        extensions.iter().find_map(|ext| {
+            let candidate = dir.join(command);
+            let candidate = if ext.is_empty() {
+                candidate
+            } else {
+                candidate.with_extension(ext)
+            };
+            candidate.is_file().then_some(candidate)
+        })
+    })
+}
+
+/// Where package managers and version managers put binaries, for when PATH
+/// comes from a GUI launch rather than the user's shell.
+fn fallback_bin_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/opt/homebrew/bin"),
+        PathBuf::from("/usr/local/bin"),
+        PathBuf::from("/usr/bin"),
+        PathBuf::from("/bin"),
+    ];
+    let Some(home) = dirs::home_dir() else {
+        return dirs;
+    };
+    // Newest nvm-installed node first, so `npx` matches the default install
+    dirs.extend(nvm_bin_dirs(&home.join(".nvm/versions/node")));
+    for relative in [
+        ".local/bin",
+        ".cargo/bin",
+        ".bun/bin",
+        ".deno/bin",
+        ".volta/bin",
+        ".asdf/shims",
+        ".local/share/mise/shims",
+        ".local/share/pnpm",
+        "Library/pnpm",
+        ".npm-global/bin",
+    ] {
+        dirs.push(home.join(relative));
+    }
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        dirs.push(PathBuf::from(app_data).join("npm"));
+    }
+    dirs
+}
+
+/// `bin` dirs of nvm's node installs under `versions_dir`, newest version first.
+fn nvm_bin_dirs(versions_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(versions_dir) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<(Vec<u64>, PathBuf)> = entries
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            (node_version_key(&name), entry.path().join("bin"))
+        })
+        .collect();
+    versions.sort_by(|a, b| b.0.cmp(&a.0));
+    versions.into_iter().map(|(_, dir)| dir).collect()
+}
+
+/// Numeric components of a version directory name like `v20.11.1`.
+fn node_version_key(name: &str) -> Vec<u64> {
+    name.trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_versions_compare_numerically() {
+        assert!(node_version_key("v20.11.1") > node_version_key("v9.11.2"));
+        assert!(node_version_key("v18.10.0") > node_version_key("v18.2.0"));
+    }
+
+    #[test]
+    fn finds_command_in_later_dir() {
+        let root = std::env::temp_dir().join(format!("agent-hub-bin-{}", std::process::id()));
+        let (empty, bin) = (root.join("empty"), root.join("bin"));
+        std::fs::create_dir_all(&empty).unwrap();
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("fake-mcp"), "").unwrap();
+
+        let found = find_in_dirs("fake-mcp", &[empty.clone(), bin.clone()]);
+        assert_eq!(found, Some(bin.join("fake-mcp")));
+        assert_eq!(find_in_dirs("missing-mcp", &[empty, bin]), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
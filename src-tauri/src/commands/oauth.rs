@@ -2,9 +2,11 @@ use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_opener::OpenerExt;
 use tracing::{error, info};
 
+use crate::commands::connections::ServerConnectConfig;
 use crate::error::AppError;
 use crate::mcp::client::SharedConnections;
-use crate::mcp::{oauth, oauth_callback};
+use crate::mcp::oauth;
+use crate::mcp::oauth_callback::OAuthCallbacks;
 use crate::state::{OAuthState, ServerTransport, SharedOAuthStore, SharedState};
 
 #[tauri::command]
@@ -13,10 +15,11 @@ pub async fn start_oauth_flow(
     state: State<'_, SharedState>,
     oauth_store: State<'_, SharedOAuthStore>,
     connections: State<'_, SharedConnections>,
+    callbacks: State<'_, OAuthCallbacks>,
     id: String,
 ) -> Result<(), AppError> {
     // 1. Read server URL from AppState
-    let server_url = {
+    let (server_url, configured_port) = {
         let s = state.lock().unwrap();
         let server = s
             .servers
@@ -28,10 +31,11 @@ pub async fn start_oauth_flow(
                 "OAuth is only supported for HTTP servers".into(),
             ));
         }
-        server
+        let url = server
             .url
             .clone()
-            .ok_or_else(|| AppError::OAuth("No URL configured for server".into()))?
+            .ok_or_else(|| AppError::OAuth("No URL configured for server".into()))?;
+        (url, s.oauth_callback_port)
     };
 
     let _ = app.emit(
@@ -42,65 +46,107 @@ pub async fn start_oauth_flow(
     // 2-3. Discover auth server metadata (tries RFC 9728 first, falls back to direct)
     let metadata = oauth::discover_metadata(&server_url).await?;
 
-    // 4. Start callback server to get the redirect URI
-    let (port, callback_rx) = oauth_callback::start_callback_server().await?;
+    // 4. Start callback server to get the redirect URI, preferring the
+    //    configured port, then the port a stored registration was made for
+    let stored = oauth_store.lock().await.get(&id).cloned();
+    let registered_port = stored
+        .as_ref()
+        .and_then(|os| os.registered_redirect_uri.as_deref())
+        .and_then(|uri| url::Url::parse(uri).ok())
+        .and_then(|uri| uri.port());
+    let state_nonce = oauth::generate_state_nonce();
+    let (port, callback_rx) = callbacks
+        .register(configured_port.or(registered_port), state_nonce.clone())
+        .await?;
     let redirect_uri = format!("http://127.0.0.1:{port}/oauth/callback");
 
-    // 5. Dynamic client registration if available and no stored client_id
-    let (client_id, client_secret) = {
-        let store = oauth_store.lock().await;
-        let existing = store.get(&id);
-        match existing {
-            Some(os) if os.client_id.is_some() => {
-                (os.client_id.clone().unwrap(), os.client_secret.clone())
-            }
-            _ => {
-                drop(store);
-                if let Some(ref reg_endpoint) = metadata.registration_endpoint {
-                    let (cid, csec) = oauth::dynamic_register(reg_endpoint, &redirect_uri).await?;
-                    (cid, csec)
-                } else {
+    // Until the browser is open, a failure must also stop the callback wait
+    let prepared = async {
+        // 5. Use the stored client_id, or register one (RFC 7591) if there is none
+        //    or it was registered for a redirect URI we can no longer serve
+        let reusable = stored.as_ref().filter(|os| {
+            os.client_id.is_some()
+                && os
+                    .registered_redirect_uri
+                    .as_ref()
+                    .is_none_or(|uri| *uri == redirect_uri)
+        });
+        let (client_id, client_secret) = match reusable {
+            Some(os) => (os.client_id.clone().unwrap(), os.client_secret.clone()),
+            None => {
+                let Some(reg_endpoint) = metadata.registration_endpoint.as_deref() else {
                     return Err(AppError::OAuth(
-                        "Server has no registration_endpoint and no client_id is stored. \
-                         Cannot authenticate without a client_id."
+                        "This server does not support dynamic client registration \
+                         (no registration_endpoint) and no client_id is configured for it."
                             .into(),
                     ));
-                }
+                };
+                let _ = app.emit(
+                    "oauth-status-changed",
+                    serde_json::json!({ "serverId": id, "status": "registering" }),
+                );
+                let (cid, csec) = oauth::dynamic_register(reg_endpoint, &redirect_uri).await?;
+
+                // Persist the registration before redirecting so an abandoned
+                // browser step doesn't force another one next time. Tokens
+                // issued to the previous client can't be refreshed by this
+                // one, so they're dropped with it
+                let mut store = oauth_store.lock().await;
+                store.set(
+                    id.clone(),
+                    OAuthState {
+                        auth_server_metadata: metadata.clone(),
+                        client_id: Some(cid.clone()),
+                        client_secret: csec.clone(),
+                        registered_redirect_uri: Some(redirect_uri.clone()),
+                        tokens: None,
+                    },
+                );
+                crate::persistence::save_oauth_store(&app, store.entries());
+                (cid, csec)
             }
-        }
-    };
+        };
 
-    // 6. Generate PKCE + state nonce
-    let pkce = oauth::generate_pkce();
-    let state_nonce = oauth::generate_state_nonce();
+        // 6. Generate PKCE (the state nonce was handed to the callback server)
+        let pkce = oauth::generate_pkce(&metadata.code_challenge_methods_supported);
 
-    // 7. Build authorization URL
-    let auth_url =
-        oauth::build_authorization_url(&metadata, &client_id, &redirect_uri, &pkce, &state_nonce)?;
+        // 7. Build authorization URL
+        let auth_url = oauth::build_authorization_url(
+            &metadata,
+            &client_id,
+            &redirect_uri,
+            &pkce,
+            &state_nonce,
+        )?;
 
-    // 8. Open browser
-    info!("Opening browser for OAuth authorization");
-    app.opener()
-        .open_url(&auth_url, None::<&str>)
-        .map_err(|e| AppError::OAuth(format!("Failed to open browser: {e}")))?;
+        // 8. Open browser
+        info!("Opening browser for OAuth authorization");
+        app.opener()
+            .open_url(&auth_url, None::<&str>)
+            .map_err(|e| AppError::OAuth(format!("Failed to open browser: {e}")))?;
+
+        Ok::<_, AppError>((client_id, client_secret, pkce))
+    }
+    .await;
+    let (client_id, client_secret, pkce) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            callbacks.deregister(&state_nonce).await;
+            return Err(e);
+        }
+    };
 
     let _ = app.emit(
         "oauth-status-changed",
         serde_json::json!({ "serverId": id, "status": "awaiting_browser" }),
     );
 
-    // 9. Await callback (2-min timeout is built into the callback server)
+    // 9. Await callback (2-min timeout is built into the callback server;
+    //    only a redirect echoing our nonce is routed to this flow)
     let callback_result = callback_rx
         .await
         .map_err(|_| AppError::OAuth("OAuth callback channel closed unexpectedly".into()))??;
 
-    // Verify state nonce
-    if callback_result.state != state_nonce {
-        return Err(AppError::OAuth(
-            "OAuth state mismatch — possible CSRF attack".into(),
-        ));
-    }
-
     let _ = app.emit(
         "oauth-status-changed",
         serde_json::json!({ "serverId": id, "status": "exchanging_code" }),
@@ -120,12 +166,16 @@ pub async fn start_oauth_flow(
     // 11. Store in OAuthStore and persist to disk
     {
         let mut store = oauth_store.lock().await;
+        let registered_redirect_uri = store
+            .get(&id)
+            .and_then(|os| os.registered_redirect_uri.clone());
         store.set(
             id.clone(),
             OAuthState {
                 auth_server_metadata: metadata,
                 client_id: Some(client_id),
                 client_secret,
+                registered_redirect_uri,
                 tokens: Some(tokens.clone()),
             },
         );
@@ -141,18 +191,16 @@ pub async fn start_oauth_flow(
 
     // 12. Auto-retry connection with token
     //     Re-read config and connect with the new access token.
-    let server_config = {
+    let connect_config = {
         let mut s = state.lock().unwrap();
+        let default_env = s.default_env.clone();
         let server = s
             .servers
             .iter_mut()
             .find(|s| s.id == id)
             .ok_or_else(|| AppError::ServerNotFound(id.clone()))?;
         server.status = Some(crate::state::ServerStatus::Connecting);
-        (
-            server.url.clone().unwrap_or_default(),
-            server.headers.clone().unwrap_or_default(),
-        )
+        ServerConnectConfig::from_server(server, &default_env)
     };
 
     let _ = app.emit(
@@ -160,15 +208,11 @@ pub async fn start_oauth_flow(
         serde_json::json!({ "serverId": id, "status": "connecting" }),
     );
 
-    let client = crate::mcp::client::McpClient::connect_http(
-        &server_config.0,
-        server_config.1,
-        Some(tokens.access_token),
-    )
-    .await;
+    let client = connect_config.connect_http(Some(tokens.access_token)).await;
 
     match client {
-        Ok(mcp_client) => {
+        Ok(mut mcp_client) => {
+            mcp_client.refresh_tokens_from(&app, &id);
             let tools: Vec<crate::state::McpTool> = {
                 let s = state.lock().unwrap();
                 let server_name = s
@@ -200,6 +244,9 @@ pub async fn start_oauth_flow(
                     id.clone(),
                     crate::state::ConnectionState {
                         tools: tools.clone(),
+                        server_name: mcp_client.server_info.as_ref().map(|i| i.name.clone()),
+                        server_version: mcp_client.server_info.as_ref().map(|i| i.version.clone()),
+                        capabilities: mcp_client.capability_flags(),
                     },
                 );
             }
@@ -230,13 +277,44 @@ pub async fn start_oauth_flow(
             }
             let _ = app.emit(
                 "server-status-changed",
-                serde_json::json!({ "serverId": id, "status": "error", "error": e.to_string() }),
+                serde_json::json!({
+                    "serverId": id,
+                    "status": "error",
+                    "error": e.to_string(),
+                    "errorCode": e.code(),
+                }),
             );
             Err(e)
         }
     }
 }
 
+#[tauri::command]
+pub async fn get_oauth_callback_port(
+    state: State<'_, SharedState>,
+) -> Result<Option<u16>, AppError> {
+    Ok(state.lock().unwrap().oauth_callback_port)
+}
+
+/// Fix the port the OAuth callback server listens on, so the redirect URI
+/// `http://127.0.0.1:<port>/oauth/callback` can be registered with a
+/// provider. `None` goes back to a random free port.
+#[tauri::command]
+pub async fn set_oauth_callback_port(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    port: Option<u16>,
+) -> Result<(), AppError> {
+    if port == Some(0) {
+        return Err(AppError::Validation(
+            "OAuth callback port must be between 1 and 65535".into(),
+        ));
+    }
+    state.lock().unwrap().oauth_callback_port = port;
+    crate::persistence::save_oauth_callback_port(&app, port);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn clear_oauth_tokens(
     app: AppHandle,
@@ -1,10 +1,70 @@
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::{oneshot, Mutex, RwLock};
+use tokio::task::JoinHandle;
 use tracing::info;
 
 use crate::error::AppError;
-use crate::state::plugin::{PluginInfo, PluginListOutput};
+use crate::persistence::save_plugin_cache_ttl;
+use crate::state::plugin::{MarketplaceInfo, MarketplaceListOutput, PluginInfo, PluginListOutput};
 
 use super::resolve_claude_binary;
 
+/// Long-running `claude plugin` operations, keyed by operation ID (the plugin
+/// key or marketplace name), so the frontend can cancel them.
+#[derive(Default)]
+pub struct PluginOperations {
+    running: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+/// How long a fetched plugin list is reused when no TTL has been configured.
+pub const DEFAULT_PLUGIN_CACHE_TTL_SECS: u64 = 60;
+
+/// Cached result of `claude plugin list --available --json`. Invalidated by
+/// any command that changes installed/enabled plugins or marketplaces.
+pub struct PluginListCache {
+    entry: RwLock<Option<(Instant, Vec<PluginInfo>)>>,
+    ttl_secs: AtomicU64,
+}
+
+impl PluginListCache {
+    pub fn new(ttl_secs: u64) -> Self {
+        Self {
+            entry: RwLock::new(None),
+            ttl_secs: AtomicU64::new(ttl_secs),
+        }
+    }
+
+    async fn get(&self) -> Option<Vec<PluginInfo>> {
+        let ttl = self.ttl_secs.load(Ordering::Relaxed);
+        let entry = self.entry.read().await;
+        entry
+            .as_ref()
+            .filter(|(fetched_at, _)| fetched_at.elapsed().as_secs() < ttl)
+            .map(|(_, plugins)| plugins.clone())
+    }
+
+    async fn store(&self, plugins: Vec<PluginInfo>) {
+        *self.entry.write().await = Some((Instant::now(), plugins));
+    }
+
+    pub async fn invalidate(&self) {
+        *self.entry.write().await = None;
+    }
+}
+
+fn cli_not_found(claude: &str, e: std::io::Error) -> AppError {
+    tracing::warn!("Failed to run claude CLI ('{claude}'): {e}");
+    AppError::DependencyNotFound(
+        "Claude CLI not found. Make sure `claude` is installed and in your PATH.".to_string(),
+    )
+}
+
 /// Run a `claude plugin <subcommand>` and return stdout.
 async fn run_claude_plugin(args: &[&str]) -> Result<String, AppError> {
     let claude = resolve_claude_binary();
@@ -14,13 +74,7 @@ async fn run_claude_plugin(args: &[&str]) -> Result<String, AppError> {
         .env_remove("CLAUDECODE")
         .output()
         .await
-        .map_err(|e| {
-            tracing::warn!("Failed to run claude CLI ('{claude}'): {e}");
-            AppError::DependencyNotFound(
-                "Claude CLI not found. Make sure `claude` is installed and in your PATH."
-                    .to_string(),
-            )
-        })?;
+        .map_err(|e| cli_not_found(&claude, e))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -31,20 +85,163 @@ async fn run_claude_plugin(args: &[&str]) -> Result<String, AppError> {
         } else {
             stderr
         };
-        return Err(AppError::Protocol(msg));
+        return Err(classify_cli_error(msg));
+    }
+
+    Ok(stdout)
+}
+
+/// Run a `claude plugin <subcommand>`, forwarding each output line as a
+/// `plugin-install-progress` event while it runs. Returns stdout on success.
+/// The operation can be stopped with `cancel_plugin_operation(operation_id)`.
+async fn run_claude_plugin_streaming(
+    app: &AppHandle,
+    ops: &PluginOperations,
+    operation_id: &str,
+    args: &[&str],
+) -> Result<String, AppError> {
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    {
+        let mut running = ops.running.lock().await;
+        if running.contains_key(operation_id) {
+            return Err(AppError::Validation(format!(
+                "Plugin operation {operation_id} is already running"
+            )));
+        }
+        running.insert(operation_id.to_string(), cancel_tx);
     }
 
+    let result = stream_claude_plugin(app, operation_id, args, cancel_rx).await;
+    ops.running.lock().await.remove(operation_id);
+    result
+}
+
+async fn stream_claude_plugin(
+    app: &AppHandle,
+    operation_id: &str,
+    args: &[&str],
+    cancel_rx: oneshot::Receiver<()>,
+) -> Result<String, AppError> {
+    let claude = resolve_claude_binary();
+    let mut child = tokio::process::Command::new(&claude)
+        .arg("plugin")
+        .args(args)
+        .env_remove("CLAUDECODE")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| cli_not_found(&claude, e))?;
+
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        let _ = child.kill().await;
+        return Err(AppError::Protocol(
+            "Failed to capture claude CLI output".into(),
+        ));
+    };
+    let stdout = forward_lines(app, operation_id, "stdout", stdout);
+    let stderr = forward_lines(app, operation_id, "stderr", stderr);
+
+    let status = tokio::select! {
+        status = child.wait() => status,
+        _ = cancel_rx => {
+            info!("Cancelling plugin operation {operation_id}");
+            let _ = child.kill().await;
+            // Grandchildren may keep the pipes open; don't wait on them
+            stdout.abort();
+            stderr.abort();
+            return Err(AppError::Protocol(format!(
+                "Plugin operation {operation_id} was cancelled"
+            )));
+        }
+    }
+    .map_err(|e| AppError::Protocol(format!("Failed to wait for claude CLI: {e}")))?;
+
+    let stdout = stdout.await.unwrap_or_default();
+    let stderr = stderr.await.unwrap_or_default();
+    if !status.success() {
+        return Err(classify_cli_error(if stderr.is_empty() {
+            stdout
+        } else {
+            stderr
+        }));
+    }
     Ok(stdout)
 }
 
+/// Map a failed `claude plugin` invocation's output to the matching
+/// `AppError` variant, keeping the raw CLI text as the message.
+fn classify_cli_error(raw: String) -> AppError {
+    let lower = raw.to_lowercase();
+    let raw = raw.trim().to_string();
+    if lower.contains("marketplace")
+        && (lower.contains("not found") || lower.contains("unknown marketplace"))
+    {
+        AppError::MarketplaceNotFound(raw)
+    } else if lower.contains("already installed") {
+        AppError::PluginAlreadyInstalled(raw)
+    } else if lower.contains("not logged in")
+        || lower.contains("please log in")
+        || lower.contains("please run /login")
+        || lower.contains("authentication required")
+        || lower.contains("unauthorized")
+    {
+        AppError::AuthRequired(raw)
+    } else if lower.contains("command not found") || lower.contains("no such file or directory") {
+        AppError::DependencyNotFound(raw)
+    } else {
+        AppError::Protocol(raw)
+    }
+}
+
+/// Emit each line of `reader` as a `plugin-install-progress` event and
+/// return everything read once the stream closes.
+fn forward_lines(
+    app: &AppHandle,
+    operation_id: &str,
+    stream: &'static str,
+    reader: impl AsyncRead + Unpin + Send + 'static,
+) -> JoinHandle<String> {
+    let app = app.clone();
+    let operation_id = operation_id.to_string();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        let mut collected = String::new();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = app.emit(
+                "plugin-install-progress",
+                serde_json::json!({
+                    "operationId": operation_id,
+                    "stream": stream,
+                    "line": line,
+                }),
+            );
+            collected.push_str(&line);
+            collected.push('\n');
+        }
+        collected
+    })
+}
+
 /// Fetch the full available+installed list from `claude plugin list --available --json`,
-/// merging both into a unified `Vec<PluginInfo>`.
-async fn fetch_all_plugins() -> Result<Vec<PluginInfo>, AppError> {
+/// merging both into a unified `Vec<PluginInfo>`. Served from `cache` unless
+/// it has expired or `force` is set.
+async fn fetch_all_plugins(
+    cache: &PluginListCache,
+    force: bool,
+) -> Result<Vec<PluginInfo>, AppError> {
+    if !force {
+        if let Some(plugins) = cache.get().await {
+            return Ok(plugins);
+        }
+    }
     let json = run_claude_plugin(&["list", "--available", "--json"]).await?;
     let output: PluginListOutput = serde_json::from_str(&json).map_err(|e| {
         AppError::Protocol(format!("Failed to parse plugin list output: {e}"))
     })?;
-    Ok(output.into_plugin_list())
+    let plugins = output.into_plugin_list();
+    cache.store(plugins.clone()).await;
+    Ok(plugins)
 }
 
 // ---------------------------------------------------------------------------
@@ -53,9 +250,11 @@ async fn fetch_all_plugins() -> Result<Vec<PluginInfo>, AppError> {
 
 #[tauri::command]
 pub async fn list_available_plugins(
+    cache: State<'_, PluginListCache>,
     search: Option<String>,
+    force: Option<bool>,
 ) -> Result<Vec<PluginInfo>, AppError> {
-    let mut all = fetch_all_plugins().await?;
+    let mut all = fetch_all_plugins(&cache, force.unwrap_or(false)).await?;
 
     // Client-side search filter
     if let Some(ref query) = search {
@@ -76,8 +275,11 @@ pub async fn list_available_plugins(
 }
 
 #[tauri::command]
-pub async fn list_installed_plugins() -> Result<Vec<PluginInfo>, AppError> {
-    let all = fetch_all_plugins().await?;
+pub async fn list_installed_plugins(
+    cache: State<'_, PluginListCache>,
+    force: Option<bool>,
+) -> Result<Vec<PluginInfo>, AppError> {
+    let all = fetch_all_plugins(&cache, force.unwrap_or(false)).await?;
     Ok(all.into_iter().filter(|p| p.installed).collect())
 }
 
@@ -87,30 +289,41 @@ pub async fn list_installed_plugins() -> Result<Vec<PluginInfo>, AppError> {
 
 #[tauri::command]
 pub async fn install_plugin(
+    app: AppHandle,
+    ops: State<'_, PluginOperations>,
+    cache: State<'_, PluginListCache>,
     plugin_name: String,
     marketplace: String,
 ) -> Result<String, AppError> {
     let key = format!("{plugin_name}@{marketplace}");
     info!("Installing plugin via CLI: {key}");
-    run_claude_plugin(&["install", &key]).await?;
+    let result = run_claude_plugin_streaming(&app, &ops, &key, &["install", &key]).await;
+    cache.invalidate().await;
+    result?;
     info!("Installed plugin: {key}");
     Ok(key)
 }
 
 #[tauri::command]
 pub async fn uninstall_plugin(
+    app: AppHandle,
+    ops: State<'_, PluginOperations>,
+    cache: State<'_, PluginListCache>,
     plugin_name: String,
     marketplace: String,
 ) -> Result<(), AppError> {
     let key = format!("{plugin_name}@{marketplace}");
     info!("Uninstalling plugin via CLI: {key}");
-    run_claude_plugin(&["uninstall", &key]).await?;
+    let result = run_claude_plugin_streaming(&app, &ops, &key, &["uninstall", &key]).await;
+    cache.invalidate().await;
+    result?;
     info!("Uninstalled plugin: {key}");
     Ok(())
 }
 
 #[tauri::command]
 pub async fn toggle_plugin(
+    cache: State<'_, PluginListCache>,
     plugin_name: String,
     marketplace: String,
     enabled: bool,
@@ -118,7 +331,9 @@ pub async fn toggle_plugin(
     let key = format!("{plugin_name}@{marketplace}");
     let subcmd = if enabled { "enable" } else { "disable" };
     info!("Toggling plugin via CLI: {subcmd} {key}");
-    run_claude_plugin(&[subcmd, &key]).await?;
+    let result = run_claude_plugin(&[subcmd, &key]).await;
+    cache.invalidate().await;
+    result?;
     info!("Toggled plugin {key} -> {enabled}");
     Ok(())
 }
@@ -128,9 +343,129 @@ pub async fn toggle_plugin(
 // ---------------------------------------------------------------------------
 
 #[tauri::command]
-pub async fn update_marketplace(name: String) -> Result<String, AppError> {
+pub async fn update_marketplace(
+    app: AppHandle,
+    ops: State<'_, PluginOperations>,
+    cache: State<'_, PluginListCache>,
+    name: String,
+) -> Result<String, AppError> {
     info!("Updating marketplace: {name}");
-    let result = run_claude_plugin(&["marketplace", "update", &name]).await?;
+    let result =
+        run_claude_plugin_streaming(&app, &ops, &name, &["marketplace", "update", &name]).await;
+    cache.invalidate().await;
+    let result = result?;
     info!("Marketplace {name} updated successfully");
     Ok(result)
 }
+
+#[tauri::command]
+pub async fn list_marketplaces() -> Result<Vec<MarketplaceInfo>, AppError> {
+    let json = run_claude_plugin(&["marketplace", "list", "--json"]).await?;
+    let output: MarketplaceListOutput = serde_json::from_str(&json)
+        .map_err(|e| AppError::Protocol(format!("Failed to parse marketplace list output: {e}")))?;
+    Ok(output.into_marketplaces())
+}
+
+/// Add a marketplace from `url` (a git URL, `owner/repo`, or local path).
+/// The CLI names the marketplace from its manifest, so `name` is only used to
+/// confirm the result and as the operation ID for progress/cancellation.
+#[tauri::command]
+pub async fn add_marketplace(
+    app: AppHandle,
+    ops: State<'_, PluginOperations>,
+    cache: State<'_, PluginListCache>,
+    name: String,
+    url: String,
+) -> Result<Vec<MarketplaceInfo>, AppError> {
+    info!("Adding marketplace {name} from {url}");
+    let result =
+        run_claude_plugin_streaming(&app, &ops, &name, &["marketplace", "add", &url]).await;
+    cache.invalidate().await;
+    result?;
+
+    let marketplaces = list_marketplaces().await?;
+    if !marketplaces.iter().any(|m| m.name == name) {
+        tracing::warn!("Marketplace added from {url} is not named '{name}'");
+    }
+    Ok(marketplaces)
+}
+
+#[tauri::command]
+pub async fn remove_marketplace(
+    cache: State<'_, PluginListCache>,
+    name: String,
+) -> Result<Vec<MarketplaceInfo>, AppError> {
+    info!("Removing marketplace {name}");
+    let result = run_claude_plugin(&["marketplace", "remove", &name]).await;
+    cache.invalidate().await;
+    result?;
+    list_marketplaces().await
+}
+
+/// Kill a running install/uninstall/marketplace update. `operation_id` is the
+/// `plugin@marketplace` key, or the marketplace name for updates.
+#[tauri::command]
+pub async fn cancel_plugin_operation(
+    ops: State<'_, PluginOperations>,
+    operation_id: String,
+) -> Result<(), AppError> {
+    let cancel = ops.running.lock().await.remove(&operation_id);
+    match cancel {
+        Some(tx) => {
+            let _ = tx.send(());
+            Ok(())
+        }
+        None => Err(AppError::Validation(format!(
+            "No plugin operation {operation_id} is running"
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_plugin_cache_ttl(cache: State<'_, PluginListCache>) -> Result<u64, AppError> {
+    Ok(cache.ttl_secs.load(Ordering::Relaxed))
+}
+
+/// Set how long the plugin list is cached, in seconds. `0` disables caching.
+#[tauri::command]
+pub async fn set_plugin_cache_ttl(
+    app: AppHandle,
+    cache: State<'_, PluginListCache>,
+    ttl_secs: u64,
+) -> Result<(), AppError> {
+    cache.ttl_secs.store(ttl_secs, Ordering::Relaxed);
+    save_plugin_cache_ttl(&app, ttl_secs);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_common_cli_failures() {
+        let cases = [
+            ("error: unknown marketplace 'foo'", "marketplace_not_found"),
+            ("Marketplace \"foo\" not found", "marketplace_not_found"),
+            (
+                "Plugin x@y is already installed",
+                "plugin_already_installed",
+            ),
+            ("Not logged in. Please run /login", "auth_required"),
+            ("sh: claude: command not found", "dependency_not_found"),
+            ("error: something else broke", "protocol"),
+        ];
+        for (raw, code) in cases {
+            assert_eq!(classify_cli_error(raw.to_string()).code(), code, "{raw}");
+        }
+    }
+
+    #[test]
+    fn classification_keeps_raw_text() {
+        let err = classify_cli_error("error: unknown marketplace 'foo'\n".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Marketplace not found: error: unknown marketplace 'foo'"
+        );
+    }
+}
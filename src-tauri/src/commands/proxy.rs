@@ -1,13 +1,21 @@
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, State};
 
+use crate::audit::AuditLog;
 use crate::error::AppError;
-use crate::mcp::proxy::ProxyState;
+use crate::mcp::proxy::{self, ProxyState};
+use crate::persistence::save_proxy_settings;
+use crate::state::{AuditConfig, ProxyIdentity, ProxySettings, SharedState};
+
+/// Smallest accepted audit log cap — below this rotation would churn constantly.
+const MIN_AUDIT_LOG_BYTES: u64 = 64 * 1024;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProxyStatus {
     pub running: bool,
     pub port: u16,
+    /// Startup failure, e.g. the pinned port was already taken.
+    pub error: Option<String>,
 }
 
 #[tauri::command]
@@ -15,5 +23,360 @@ pub async fn get_proxy_status(proxy_state: State<'_, ProxyState>) -> Result<Prox
     Ok(ProxyStatus {
         running: proxy_state.is_running().await,
         port: proxy_state.port().await,
+        error: proxy_state.error().await,
+    })
+}
+
+/// Where MCP clients reach the proxy, for configuring clients by hand.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyEndpoint {
+    pub running: bool,
+    pub port: u16,
+    /// Base URL of the proxy; each server is served at `{url}/{server_id}` and
+    /// the discovery endpoint at `{url}/discovery`. `None` while not running.
+    pub url: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_proxy_endpoint(
+    proxy_state: State<'_, ProxyState>,
+) -> Result<ProxyEndpoint, AppError> {
+    let running = proxy_state.is_running().await;
+    let port = proxy_state.port().await;
+    Ok(ProxyEndpoint {
+        running,
+        port,
+        url: running.then(|| format!("http://localhost:{port}/mcp")),
     })
 }
+
+/// All persisted proxy settings together with the proxy's runtime status.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    #[serde(flatten)]
+    pub settings: ProxySettings,
+    pub running: bool,
+    pub port: u16,
+    pub error: Option<String>,
+}
+
+async fn proxy_config(state: &SharedState, proxy_state: &ProxyState) -> ProxyConfig {
+    let settings = state.lock().unwrap().proxy_settings.clone();
+    ProxyConfig {
+        settings,
+        running: proxy_state.is_running().await,
+        port: proxy_state.port().await,
+        error: proxy_state.error().await,
+    }
+}
+
+#[tauri::command]
+pub async fn get_proxy_config(
+    state: State<'_, SharedState>,
+    proxy_state: State<'_, ProxyState>,
+) -> Result<ProxyConfig, AppError> {
+    Ok(proxy_config(&state, &proxy_state).await)
+}
+
+/// Validate and save every proxy setting at once. Unlike
+/// `set_preferred_proxy_port`, a changed port is applied immediately by
+/// restarting the proxy; everything else applies to the next request. If the
+/// new port can't be bound, nothing is saved and the proxy keeps running.
+#[tauri::command]
+pub async fn set_proxy_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    proxy_state: State<'_, ProxyState>,
+    settings: ProxySettings,
+) -> Result<ProxyConfig, AppError> {
+    check_preferred_port(settings.preferred_port)?;
+    check_max_argument_bytes(settings.max_argument_bytes)?;
+    check_audit_config(&settings.audit)?;
+    let settings = ProxySettings {
+        allowed_origins: normalize_origins(&settings.allowed_origins)?,
+        identity: normalize_identity(settings.identity),
+        ..settings
+    };
+
+    let port_changed = {
+        let s = state.lock().unwrap();
+        s.proxy_settings.preferred_port != settings.preferred_port
+    };
+    if port_changed {
+        proxy::restart_proxy(&app, &proxy_state, settings.preferred_port).await?;
+    }
+
+    {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings = settings.clone();
+    }
+    save_proxy_settings(&app, &settings);
+
+    Ok(proxy_config(&state, &proxy_state).await)
+}
+
+/// Whether empty server endpoints explain themselves in `initialize`.
+#[tauri::command]
+pub async fn get_empty_tools_notice(state: State<'_, SharedState>) -> Result<bool, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.empty_tools_notice)
+}
+
+#[tauri::command]
+pub async fn set_empty_tools_notice(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    enabled: bool,
+) -> Result<bool, AppError> {
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.empty_tools_notice = enabled;
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+    Ok(enabled)
+}
+
+/// Whether enabled integration configs are rewritten on launch and on server changes.
+#[tauri::command]
+pub async fn get_auto_update_integration_ports(
+    state: State<'_, SharedState>,
+) -> Result<bool, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.auto_update_integration_ports)
+}
+
+#[tauri::command]
+pub async fn set_auto_update_integration_ports(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    enabled: bool,
+) -> Result<bool, AppError> {
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.auto_update_integration_ports = enabled;
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+    Ok(enabled)
+}
+
+/// Proxy-wide cap on tool-call argument size; `None` means the built-in default.
+#[tauri::command]
+pub async fn get_max_argument_bytes(
+    state: State<'_, SharedState>,
+) -> Result<Option<u64>, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.max_argument_bytes)
+}
+
+#[tauri::command]
+pub async fn set_max_argument_bytes(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    max_bytes: Option<u64>,
+) -> Result<Option<u64>, AppError> {
+    check_max_argument_bytes(max_bytes)?;
+
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.max_argument_bytes = max_bytes;
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+    Ok(max_bytes)
+}
+
+#[tauri::command]
+pub async fn get_audit_config(state: State<'_, SharedState>) -> Result<AuditConfig, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.audit.clone())
+}
+
+#[tauri::command]
+pub async fn set_audit_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: AuditConfig,
+) -> Result<AuditConfig, AppError> {
+    check_audit_config(&config)?;
+
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.audit = config.clone();
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+    Ok(config)
+}
+
+/// Write the whole audit log (including the rotated file) to `path` as JSONL.
+/// Returns the number of bytes exported.
+#[tauri::command]
+pub async fn export_audit_log(
+    audit_log: State<'_, AuditLog>,
+    path: String,
+) -> Result<u64, AppError> {
+    let audit_log = audit_log.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || audit_log.export(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_proxy_identity(state: State<'_, SharedState>) -> Result<ProxyIdentity, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.identity.clone())
+}
+
+/// Override the name/version advertised in `initialize`. Blank values revert
+/// to the defaults. Applies to the next `initialize`; no restart needed.
+#[tauri::command]
+pub async fn set_proxy_identity(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    identity: ProxyIdentity,
+) -> Result<ProxyIdentity, AppError> {
+    let identity = normalize_identity(identity);
+
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.identity = identity.clone();
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+    Ok(identity)
+}
+
+/// The pinned proxy port, if any.
+#[tauri::command]
+pub async fn get_preferred_proxy_port(
+    state: State<'_, SharedState>,
+) -> Result<Option<u16>, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.preferred_port)
+}
+
+/// Pin the proxy to `port`, or clear the pin with `None`. Takes effect on the
+/// next launch; a pinned port that is taken at startup is reported as an error
+/// instead of silently moving to another port.
+#[tauri::command]
+pub async fn set_preferred_proxy_port(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    port: Option<u16>,
+) -> Result<Option<u16>, AppError> {
+    check_preferred_port(port)?;
+
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.preferred_port = port;
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+
+    Ok(port)
+}
+
+/// Extra origins the proxy accepts beyond localhost and the Tauri webview.
+#[tauri::command]
+pub async fn get_allowed_origins(state: State<'_, SharedState>) -> Result<Vec<String>, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.proxy_settings.allowed_origins.clone())
+}
+
+/// Replace the origin allow-list. Each entry must be a bare `http(s)://host[:port]`
+/// origin; entries are normalized and de-duplicated before saving.
+#[tauri::command]
+pub async fn set_allowed_origins(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    origins: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    let normalized = normalize_origins(&origins)?;
+
+    let settings = {
+        let mut s = state.lock().unwrap();
+        s.proxy_settings.allowed_origins = normalized.clone();
+        s.proxy_settings.clone()
+    };
+    save_proxy_settings(&app, &settings);
+
+    Ok(normalized)
+}
+
+fn check_preferred_port(port: Option<u16>) -> Result<(), AppError> {
+    if port == Some(0) {
+        return Err(AppError::Validation(
+            "Port 0 is not a fixed port; clear the setting instead".into(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_max_argument_bytes(max_bytes: Option<u64>) -> Result<(), AppError> {
+    if max_bytes == Some(0) {
+        return Err(AppError::Validation(
+            "Argument size limit must be greater than zero".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_audit_config(config: &AuditConfig) -> Result<(), AppError> {
+    if config.max_bytes < MIN_AUDIT_LOG_BYTES {
+        return Err(AppError::Validation(format!(
+            "Audit log size must be at least {MIN_AUDIT_LOG_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+/// Trim the identity overrides; blank values revert to the defaults.
+fn normalize_identity(identity: ProxyIdentity) -> ProxyIdentity {
+    let non_blank = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    ProxyIdentity {
+        name: non_blank(identity.name),
+        version: non_blank(identity.version),
+    }
+}
+
+/// Normalize each origin and drop duplicates, keeping the first occurrence.
+fn normalize_origins(origins: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for raw in origins {
+        let origin = normalize_origin(raw)?;
+        if !normalized.contains(&origin) {
+            normalized.push(origin);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Parse a user-entered origin and return its canonical serialization
+/// (lowercased host, default port dropped, no trailing slash).
+fn normalize_origin(raw: &str) -> Result<String, AppError> {
+    let trimmed = raw.trim();
+    let url = url::Url::parse(trimmed)
+        .map_err(|e| AppError::Validation(format!("Invalid origin '{trimmed}': {e}")))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::Validation(format!(
+            "Origin must use http or https: {trimmed}"
+        )));
+    }
+    if url.host_str().is_none()
+        || url.path() != "/"
+        || url.query().is_some()
+        || url.fragment().is_some()
+    {
+        return Err(AppError::Validation(format!(
+            "Origin must be scheme://host[:port] with no path: {trimmed}"
+        )));
+    }
+
+    Ok(url.origin().ascii_serialization())
+}
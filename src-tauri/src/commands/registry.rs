@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use tauri::{AppHandle, State};
 
+use crate::commands::servers::{add_server_inner, ImportedServer};
 use crate::error::AppError;
 use crate::state::registry::{
     MarketplaceCache, MarketplaceServerDetail, RegistrySearchResult, RuntimeDeps,
 };
-use crate::state::{ServerConfig, ServerConfigInput, SharedState};
+use crate::state::{ServerConfigInput, SharedState};
 
 #[tauri::command]
 pub async fn search_registry(
@@ -68,7 +69,7 @@ pub async fn install_registry_server(
     cache: State<'_, MarketplaceCache>,
     id: String,
     env_vars: Option<HashMap<String, String>>,
-) -> Result<ServerConfig, AppError> {
+) -> Result<ImportedServer, AppError> {
     if !cache.ensure_loaded().await {
         return Err(AppError::Protocol(
             "Failed to load marketplace data. Check your network connection.".into(),
@@ -98,9 +99,12 @@ pub async fn install_registry_server(
         url: None,
         headers: None,
         tags: None,
+        ..Default::default()
     };
 
-    crate::commands::servers::add_server_inner(&app, &state, input, Some(id), None)
+    let server = add_server_inner(&app, &state, input, Some(id), None)?;
+    let default_env = state.lock().unwrap().default_env.clone();
+    Ok(ImportedServer::new(server, &default_env))
 }
 
 #[tauri::command]
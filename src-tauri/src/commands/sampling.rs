@@ -0,0 +1,23 @@
+use tauri::State;
+
+use crate::error::AppError;
+use crate::mcp::sampling::SamplingRequests;
+
+/// Answer a `sampling-request` event. Pass the `CreateMessageResult` object as
+/// `result` to approve, or omit it (optionally with a `reason`) to decline.
+#[tauri::command]
+pub async fn respond_to_sampling(
+    requests: State<'_, SamplingRequests>,
+    request_id: String,
+    result: Option<serde_json::Value>,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    let outcome =
+        result.ok_or_else(|| reason.unwrap_or_else(|| "User rejected sampling request".into()));
+    if !requests.resolve(&request_id, outcome).await {
+        return Err(AppError::Validation(format!(
+            "Sampling request {request_id} is no longer pending"
+        )));
+    }
+    Ok(())
+}
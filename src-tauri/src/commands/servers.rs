@@ -1,10 +1,20 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::commands::proxy::check_max_argument_bytes;
 use crate::error::AppError;
-use crate::persistence::save_servers;
+use crate::persistence::{save_default_env, save_servers};
 use crate::state::registry::detect_http_proxy;
-use crate::state::{ServerConfig, ServerConfigInput, ServerStatus, ServerTransport, SharedState};
+use crate::state::{
+    duplicate_advertised_name, CapabilityFlags, ServerConfig, ServerConfigInput, ServerLoadError,
+    ServerStatus, ServerTransport, SharedState, ToolOverride,
+};
 
 /// If the input is a stdio config wrapping an HTTP proxy (e.g. `npx mcp-remote`),
 /// rewrite it to use HTTP transport directly.
@@ -28,6 +38,217 @@ fn maybe_rewrite_proxy(mut input: ServerConfigInput) -> ServerConfigInput {
     input
 }
 
+/// A server added by an import path (integration, JSON/file, registry) along
+/// with non-fatal problems spotted in its config.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedServer {
+    #[serde(flatten)]
+    pub server: ServerConfig,
+    pub warnings: Vec<String>,
+}
+
+impl ImportedServer {
+    pub fn new(server: ServerConfig, default_env: &HashMap<String, String>) -> Self {
+        let warnings = server_warnings(&server, default_env);
+        Self { server, warnings }
+    }
+}
+
+/// Non-fatal problems with a server config: a stdio command that isn't on
+/// PATH, an unreadable env file, env/args referencing variables that aren't
+/// defined anywhere, env keys that differ only in case, an unparseable URL, or
+/// an unknown output encoding. Checked on every save and reported back from imports.
+pub fn server_warnings(
+    server: &ServerConfig,
+    default_env: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    match server.transport {
+        ServerTransport::Stdio => match server.command.as_deref() {
+            Some(cmd) if !cmd.is_empty() => {
+                if !command_exists(cmd) {
+                    warnings.push(format!(
+                        "Command '{cmd}' was not found on PATH or in common install locations"
+                    ));
+                }
+            }
+            _ => warnings.push("No command specified".into()),
+        },
+        ServerTransport::Http => match server.url.as_deref().map(url::Url::parse) {
+            Some(Ok(u)) if matches!(u.scheme(), "http" | "https") => {}
+            Some(_) => warnings.push(format!(
+                "URL '{}' is not a valid http(s) URL",
+                server.url.as_deref().unwrap_or_default()
+            )),
+            None => warnings.push("No URL specified".into()),
+        },
+    }
+
+    if let Some(label) = server.output_encoding.as_deref() {
+        if encoding_rs::Encoding::for_label(label.trim().as_bytes()).is_none() {
+            warnings.push(format!("Unknown output encoding '{label}'; using UTF-8"));
+        }
+    }
+
+    let file_env = match server.env_file.as_deref().map(crate::env_file::load) {
+        Some(Ok(vars)) => vars,
+        Some(Err(e)) => {
+            warnings.push(e.to_string());
+            HashMap::new()
+        }
+        None => HashMap::new(),
+    };
+
+    let mut env = server.env.clone().unwrap_or_default();
+    warnings.extend(normalize_env_case(&mut env));
+    for var in undefined_env_references(server, &env, &file_env, default_env) {
+        warnings.push(format!("References undefined environment variable ${var}"));
+    }
+    warnings
+}
+
+/// Variables referenced from `env` values, args or headers that none of
+/// `env`, the env file, the default env or the app's own environment define.
+fn undefined_env_references(
+    server: &ServerConfig,
+    env: &HashMap<String, String>,
+    file_env: &HashMap<String, String>,
+    default_env: &HashMap<String, String>,
+) -> Vec<String> {
+    let is_defined = |var: &str| {
+        env.contains_key(var)
+            || file_env.contains_key(var)
+            || default_env.contains_key(var)
+            || std::env::var_os(var).is_some()
+    };
+    let values = env
+        .values()
+        .chain(server.args.iter().flatten())
+        .chain(server.headers.iter().flat_map(|h| h.values()));
+    let mut undefined: Vec<String> = values
+        .flat_map(|v| env_references(v))
+        .filter(|var| !is_defined(var))
+        .collect();
+    undefined.sort();
+    undefined.dedup();
+    undefined
+}
+
+/// On Windows, collapse env keys that differ only in case (e.g. `Path` and
+/// `PATH`), which collide there. Elsewhere they are distinct variables and
+/// are left alone. Returns a description of each decision.
+pub fn normalize_env_case(env: &mut HashMap<String, String>) -> Vec<String> {
+    if !cfg!(windows) {
+        return Vec::new();
+    }
+    collapse_env_case(env)
+}
+
+/// Each group of keys that differ only in case keeps its all-uppercase
+/// spelling if present, otherwise the lexicographically first, so the outcome
+/// doesn't depend on map order.
+fn collapse_env_case(env: &mut HashMap<String, String>) -> Vec<String> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in env.keys() {
+        groups
+            .entry(key.to_uppercase())
+            .or_default()
+            .push(key.clone());
+    }
+
+    let mut notes = Vec::new();
+    for (upper, mut keys) in groups {
+        if keys.len() < 2 {
+            continue;
+        }
+        keys.sort();
+        let keep = if keys.contains(&upper) {
+            upper
+        } else {
+            keys[0].clone()
+        };
+        for key in keys.iter().filter(|k| **k != keep) {
+            env.remove(key);
+        }
+        notes.push(format!(
+            "Environment keys {} differ only in case; using {keep}",
+            keys.join(", ")
+        ));
+    }
+    notes
+}
+
+/// Whether `cmd` is an existing path or resolves the way a stdio spawn would.
+fn command_exists(cmd: &str) -> bool {
+    crate::commands::resolve_binary(cmd).is_ok()
+}
+
+/// The `$VAR` and `${VAR}` references in `value`, as each one's byte range
+/// and variable name. A `$` not followed by a variable name, including an
+/// unterminated `${` or a `${...}` placeholder that isn't a name, is plain text.
+fn env_reference_spans(value: &str) -> Vec<(Range<usize>, &str)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    while let Some(pos) = value[start..].find('$').map(|p| start + p) {
+        let after = &value[pos + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(inner) => match inner.find('}') {
+                Some(end) => (&inner[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        start = pos + 1;
+        if is_env_name(name) {
+            start += len;
+            spans.push((pos..start, name));
+        }
+    }
+    spans
+}
+
+/// Replace `$VAR` and `${VAR}` references in `value` with what `lookup`
+/// returns. References it can't resolve, and `${...}` forms that aren't
+/// variable names, are kept as written.
+pub(crate) fn expand_env_references(
+    value: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut copied = 0;
+    for (span, name) in env_reference_spans(value) {
+        if let Some(resolved) = lookup(name) {
+            out.push_str(&value[copied..span.start]);
+            out.push_str(&resolved);
+            copied = span.end;
+        }
+    }
+    out.push_str(&value[copied..]);
+    out
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names of variables referenced as `$VAR` or `${VAR}` in `value`.
+fn env_references(value: &str) -> Vec<String> {
+    env_reference_spans(value)
+        .into_iter()
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
 /// Core server-creation logic, reusable by both the `add_server` command and registry install.
 pub fn add_server_inner(
     app: &AppHandle,
@@ -36,8 +257,59 @@ pub fn add_server_inner(
     registry_name: Option<String>,
     managed_by: Option<String>,
 ) -> Result<ServerConfig, AppError> {
+    let server = new_server(input, registry_name, managed_by)?;
+
+    let default_env = {
+        let mut state = state.lock().unwrap();
+        state.servers.push(server.clone());
+        save_servers(app, &state.servers);
+        state.default_env.clone()
+    };
+    crate::tray::rebuild_tray_menu(app);
+    for warning in server_warnings(&server, &default_env) {
+        warn!("Server '{}': {warning}", server.name);
+    }
+    Ok(server)
+}
+
+/// Build and validate a server for [`add_server_inner`] and [`import_server_inputs`].
+fn new_server(
+    input: ServerConfigInput,
+    registry_name: Option<String>,
+    managed_by: Option<String>,
+) -> Result<ServerConfig, AppError> {
+    let server = server_from_input(input, registry_name, managed_by);
+    check_tool_overrides(server.tool_overrides.as_ref(), &[])?;
+    check_max_argument_bytes(server.max_argument_bytes)?;
+    Ok(server)
+}
+
+/// Reject overrides that would advertise two tools under one name, since the
+/// proxy could only route calls to one of them. `tool_names` are the server's
+/// tools from its live connection, if any.
+fn check_tool_overrides(
+    overrides: Option<&HashMap<String, ToolOverride>>,
+    tool_names: &[String],
+) -> Result<(), AppError> {
+    let Some(overrides) = overrides else {
+        return Ok(());
+    };
+    match duplicate_advertised_name(overrides, tool_names.iter().map(String::as_str)) {
+        Some(name) => Err(AppError::Validation(format!(
+            "Tool name '{name}' would be advertised for more than one tool"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Build a new, disconnected server from user input under a fresh id.
+pub(crate) fn server_from_input(
+    input: ServerConfigInput,
+    registry_name: Option<String>,
+    managed_by: Option<String>,
+) -> ServerConfig {
     let input = maybe_rewrite_proxy(input);
-    let server = ServerConfig {
+    ServerConfig {
         id: Uuid::new_v4().to_string(),
         name: input.name,
         enabled: input.enabled,
@@ -45,6 +317,7 @@ pub fn add_server_inner(
         command: input.command,
         args: input.args,
         env: input.env,
+        env_file: input.env_file,
         url: input.url,
         headers: input.headers,
         tags: input.tags,
@@ -53,21 +326,433 @@ pub fn add_server_inner(
         managed: None,
         managed_by,
         registry_name,
+        id_format: input.id_format,
+        allow_insecure_tls: input.allow_insecure_tls,
+        max_concurrent_calls: input.max_concurrent_calls,
+        max_argument_bytes: input.max_argument_bytes,
+        max_frame_bytes: input.max_frame_bytes,
+        output_encoding: input.output_encoding,
+        validate_arguments: input.validate_arguments,
+        exposed_tools: input.exposed_tools,
+        blocked_tools: input.blocked_tools,
+        tool_overrides: input.tool_overrides,
+        exclude_from_discovery: input.exclude_from_discovery,
+        discovery_excluded_tools: input.discovery_excluded_tools,
+        roots: input.roots,
+        enable_sampling: input.enable_sampling,
+        prewarm: input.prewarm,
+    }
+}
+
+/// An import entry that was left alone, and why.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedImport {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Result of a bulk import: the servers added and the entries skipped.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerImportReport {
+    pub imported: Vec<ImportedServer>,
+    pub skipped: Vec<SkippedImport>,
+}
+
+/// Add servers parsed from pasted JSON or an imported file. Each entry is
+/// saved even if it has warnings, so the UI can flag it without blocking.
+#[tauri::command]
+pub async fn import_servers(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    inputs: Vec<ServerConfigInput>,
+) -> Result<ServerImportReport, AppError> {
+    import_server_inputs(&app, &state, inputs)
+}
+
+/// Add `inputs` the way [`add_server_inner`] does, saving once. Entries that
+/// [`plan_import`] rejects are skipped and reported; the rest are added.
+pub(crate) fn import_server_inputs(
+    app: &AppHandle,
+    state: &SharedState,
+    inputs: Vec<ServerConfigInput>,
+) -> Result<ServerImportReport, AppError> {
+    let (added, skipped, default_env) = {
+        let mut s = state.lock().unwrap();
+        let (added, skipped) = plan_import(&s.servers, inputs);
+        if !added.is_empty() {
+            s.servers.extend(added.iter().cloned());
+            save_servers(app, &s.servers);
+        }
+        (added, skipped, s.default_env.clone())
     };
 
-    {
-        let mut state = state.lock().unwrap();
-        state.servers.push(server.clone());
-        save_servers(app, &state.servers);
+    if !added.is_empty() {
+        crate::tray::rebuild_tray_menu(app);
     }
-    crate::tray::rebuild_tray_menu(app);
-    Ok(server)
+    // Warnings probe the filesystem, so they're gathered after unlocking
+    let imported = added
+        .into_iter()
+        .map(|server| ImportedServer::new(server, &default_env))
+        .collect();
+    Ok(ServerImportReport { imported, skipped })
+}
+
+/// Decide every import entry before anything is saved: invalid entries, and
+/// ones whose name or endpoint matches an existing server or an earlier
+/// entry, are skipped with a reason. Returns the servers to add.
+fn plan_import(
+    existing: &[ServerConfig],
+    inputs: Vec<ServerConfigInput>,
+) -> (Vec<ServerConfig>, Vec<SkippedImport>) {
+    let mut added: Vec<ServerConfig> = Vec::new();
+    let mut skipped = Vec::new();
+    for input in inputs {
+        let name = input.name.clone();
+        let outcome = match new_server(input, None, None) {
+            Err(e) => Err(e.to_string()),
+            Ok(server) => {
+                let mut known = existing.iter().chain(&added);
+                if known.clone().any(|srv| srv.name == server.name) {
+                    Err("A server with this name already exists".to_string())
+                } else if let Some(srv) = known.find(|srv| srv.same_endpoint(&server)) {
+                    Err(format!("Same endpoint as existing server '{}'", srv.name))
+                } else {
+                    Ok(server)
+                }
+            }
+        };
+        match outcome {
+            Ok(server) => {
+                info!("Importing MCP server '{}'", server.name);
+                added.push(server);
+            }
+            Err(reason) => {
+                info!("Skipping import of '{name}': {reason}");
+                skipped.push(SkippedImport { name, reason });
+            }
+        }
+    }
+    (added, skipped)
 }
 
 #[tauri::command]
-pub async fn list_servers(state: State<'_, SharedState>) -> Result<Vec<ServerConfig>, AppError> {
+pub async fn list_servers(state: State<'_, SharedState>) -> Result<Vec<ServerSummary>, AppError> {
     let state = state.lock().unwrap();
-    Ok(state.servers.clone())
+    Ok(state
+        .servers
+        .iter()
+        .map(|server| {
+            let conn = state.connections.get(&server.id);
+            ServerSummary {
+                config: server.clone(),
+                tool_count: conn.map(|c| c.tools.len()),
+                server_name: conn.and_then(|c| c.server_name.clone()),
+                server_version: conn.and_then(|c| c.server_version.clone()),
+                capabilities: conn.map(|c| c.capabilities),
+            }
+        })
+        .collect())
+}
+
+/// Stored server entries that couldn't be read in full when the app started.
+#[tauri::command]
+pub async fn get_load_errors(
+    state: State<'_, SharedState>,
+) -> Result<Vec<ServerLoadError>, AppError> {
+    Ok(state.lock().unwrap().server_load_errors.clone())
+}
+
+/// How long `diagnose_server` waits for a TCP connection to an HTTP server.
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of one `diagnose_server` check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Not run because an earlier check it depends on failed.
+    Skip,
+}
+
+/// One step of a connection diagnosis, with guidance when it fails.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl DiagnosticCheck {
+    fn new(
+        id: &'static str,
+        label: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            id,
+            label,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Check the usual reasons a server fails to connect, in order, without
+/// starting it: for stdio, that the command resolves and every referenced
+/// environment variable is defined; for HTTP, that the URL is valid and its
+/// host accepts TCP connections. Every applicable check is reported.
+#[tauri::command]
+pub async fn diagnose_server(
+    state: State<'_, SharedState>,
+    id: String,
+) -> Result<Vec<DiagnosticCheck>, AppError> {
+    let (server, default_env) = {
+        let s = state.lock().unwrap();
+        let server = s
+            .servers
+            .iter()
+            .find(|srv| srv.id == id)
+            .cloned()
+            .ok_or_else(|| AppError::ServerNotFound(id.clone()))?;
+        (server, s.default_env.clone())
+    };
+
+    let checks = match server.transport {
+        ServerTransport::Stdio => diagnose_stdio(&server, &default_env),
+        ServerTransport::Http => diagnose_http(&server).await,
+    };
+    Ok(checks)
+}
+
+fn diagnose_stdio(
+    server: &ServerConfig,
+    default_env: &HashMap<String, String>,
+) -> Vec<DiagnosticCheck> {
+    let mut checks = Vec::new();
+
+    let command = server.command.as_deref().unwrap_or("").trim();
+    checks.push(if command.is_empty() {
+        DiagnosticCheck::new(
+            "command",
+            "Command",
+            CheckStatus::Fail,
+            "No command is set. Enter the program that starts the server, e.g. npx.",
+        )
+    } else if command_exists(command) {
+        DiagnosticCheck::new(
+            "command",
+            "Command",
+            CheckStatus::Pass,
+            format!("'{command}' was found"),
+        )
+    } else {
+        DiagnosticCheck::new(
+            "command",
+            "Command",
+            CheckStatus::Fail,
+            format!(
+                "'{command}' was not found on PATH or in common install locations. \
+                 Install it, or enter its full path."
+            ),
+        )
+    });
+
+    let mut file_env = HashMap::new();
+    if let Some(path) = server.env_file.as_deref() {
+        checks.push(match crate::env_file::load(path) {
+            Ok(vars) => {
+                let detail = format!("Read {} variable(s) from {path}", vars.len());
+                file_env = vars;
+                DiagnosticCheck::new("env-file", "Env file", CheckStatus::Pass, detail)
+            }
+            Err(e) => {
+                DiagnosticCheck::new("env-file", "Env file", CheckStatus::Fail, e.to_string())
+            }
+        });
+    }
+
+    let mut env = server.env.clone().unwrap_or_default();
+    normalize_env_case(&mut env);
+    let undefined = undefined_env_references(server, &env, &file_env, default_env);
+    checks.push(if undefined.is_empty() {
+        DiagnosticCheck::new(
+            "env",
+            "Environment variables",
+            CheckStatus::Pass,
+            "Every referenced variable is defined",
+        )
+    } else {
+        let names: Vec<String> = undefined.iter().map(|v| format!("${v}")).collect();
+        DiagnosticCheck::new(
+            "env",
+            "Environment variables",
+            CheckStatus::Fail,
+            format!(
+                "{} referenced but not defined. Add them to the server's environment, \
+                 its env file, or the default environment.",
+                names.join(", ")
+            ),
+        )
+    });
+
+    checks
+}
+
+async fn diagnose_http(server: &ServerConfig) -> Vec<DiagnosticCheck> {
+    let parsed = server
+        .url
+        .as_deref()
+        .map(url::Url::parse)
+        .and_then(Result::ok)
+        .filter(|u| matches!(u.scheme(), "http" | "https"));
+    let target = parsed.as_ref().and_then(|u| {
+        let host = u.host_str()?;
+        Some((host.to_string(), u.port_or_known_default()?))
+    });
+
+    let Some((host, port)) = target else {
+        let detail = match server.url.as_deref() {
+            Some(url) => format!("'{url}' is not a valid http(s) URL"),
+            None => "No URL is set".to_string(),
+        };
+        return vec![
+            DiagnosticCheck::new("url", "URL", CheckStatus::Fail, detail),
+            DiagnosticCheck::new(
+                "reachable",
+                "Server reachable",
+                CheckStatus::Skip,
+                "Needs a valid URL",
+            ),
+        ];
+    };
+
+    let url_check = DiagnosticCheck::new(
+        "url",
+        "URL",
+        CheckStatus::Pass,
+        format!("Connecting to {host} on port {port}"),
+    );
+    let connect = tokio::net::TcpStream::connect((host.as_str(), port));
+    let reachable = match tokio::time::timeout(REACHABILITY_TIMEOUT, connect).await {
+        Ok(Ok(_)) => DiagnosticCheck::new(
+            "reachable",
+            "Server reachable",
+            CheckStatus::Pass,
+            format!("{host}:{port} accepted a connection"),
+        ),
+        Ok(Err(e)) => DiagnosticCheck::new(
+            "reachable",
+            "Server reachable",
+            CheckStatus::Fail,
+            format!(
+                "Couldn't connect to {host}:{port} ({e}). Check the server is running \
+                 and the address is right."
+            ),
+        ),
+        Err(_) => DiagnosticCheck::new(
+            "reachable",
+            "Server reachable",
+            CheckStatus::Fail,
+            format!(
+                "{host}:{port} didn't answer within {}s. A firewall, VPN or proxy may \
+                 be blocking it.",
+                REACHABILITY_TIMEOUT.as_secs()
+            ),
+        ),
+    };
+    vec![url_check, reachable]
+}
+
+/// A server's config plus what its live connection reported, if connected.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerSummary {
+    #[serde(flatten)]
+    pub config: ServerConfig,
+    pub tool_count: Option<usize>,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub capabilities: Option<CapabilityFlags>,
+}
+
+/// Servers sharing one connection status.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerGroup {
+    pub count: usize,
+    /// Total tools exposed by the servers in this group (only connected
+    /// servers have tools).
+    pub tool_count: usize,
+    pub servers: Vec<ServerConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupedServers {
+    pub connected: ServerGroup,
+    pub connecting: ServerGroup,
+    pub disconnected: ServerGroup,
+    pub error: ServerGroup,
+}
+
+/// All servers bucketed by connection status, in list order within each group.
+#[tauri::command]
+pub async fn list_servers_grouped(
+    state: State<'_, SharedState>,
+) -> Result<GroupedServers, AppError> {
+    let s = state.lock().unwrap();
+    let mut grouped = GroupedServers::default();
+    for server in &s.servers {
+        let group = match server
+            .status
+            .as_ref()
+            .unwrap_or(&ServerStatus::Disconnected)
+        {
+            ServerStatus::Connected => &mut grouped.connected,
+            ServerStatus::Connecting => &mut grouped.connecting,
+            ServerStatus::Disconnected => &mut grouped.disconnected,
+            ServerStatus::Error => &mut grouped.error,
+        };
+        group.count += 1;
+        group.tool_count += s.connections.get(&server.id).map_or(0, |c| c.tools.len());
+        group.servers.push(server.clone());
+    }
+    Ok(grouped)
+}
+
+#[tauri::command]
+pub async fn get_default_env(
+    state: State<'_, SharedState>,
+) -> Result<HashMap<String, String>, AppError> {
+    let s = state.lock().unwrap();
+    Ok(s.default_env.clone())
+}
+
+/// Replace the env vars applied to every stdio server on connect. Takes
+/// effect on the next connect; running servers keep their environment.
+#[tauri::command]
+pub async fn set_default_env(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    env: HashMap<String, String>,
+) -> Result<(), AppError> {
+    if let Some(key) = env.keys().find(|k| k.is_empty() || k.contains('=')) {
+        return Err(AppError::Validation(format!(
+            "Invalid environment variable name: '{key}'"
+        )));
+    }
+    {
+        let mut s = state.lock().unwrap();
+        s.default_env = env.clone();
+    }
+    save_default_env(&app, &env);
+    Ok(())
 }
 
 #[tauri::command]
@@ -118,8 +803,15 @@ pub async fn update_server(
     input: ServerConfigInput,
 ) -> Result<ServerConfig, AppError> {
     let input = maybe_rewrite_proxy(input);
-    let updated = {
+    let (updated, default_env) = {
         let mut s = state.lock().unwrap();
+        let tool_names: Vec<String> = s
+            .connections
+            .get(&id)
+            .map(|c| c.tools.iter().map(|t| t.name.clone()).collect())
+            .unwrap_or_default();
+        check_tool_overrides(input.tool_overrides.as_ref(), &tool_names)?;
+        check_max_argument_bytes(input.max_argument_bytes)?;
         let server = s
             .servers
             .iter_mut()
@@ -131,16 +823,418 @@ pub async fn update_server(
         server.command = input.command;
         server.args = input.args;
         server.env = input.env;
+        server.env_file = input.env_file;
         server.url = input.url;
         server.headers = input.headers;
         server.enabled = input.enabled;
         server.tags = input.tags;
+        server.id_format = input.id_format;
+        server.allow_insecure_tls = input.allow_insecure_tls;
+        server.max_concurrent_calls = input.max_concurrent_calls;
+        server.max_argument_bytes = input.max_argument_bytes;
+        server.max_frame_bytes = input.max_frame_bytes;
+        server.output_encoding = input.output_encoding;
+        server.validate_arguments = input.validate_arguments;
+        server.exposed_tools = input.exposed_tools;
+        server.blocked_tools = input.blocked_tools;
+        server.tool_overrides = input.tool_overrides;
+        server.exclude_from_discovery = input.exclude_from_discovery;
+        server.discovery_excluded_tools = input.discovery_excluded_tools;
+        server.roots = input.roots;
+        server.enable_sampling = input.enable_sampling;
+        server.prewarm = input.prewarm;
         // Preserve registry_name — don't overwrite from input
 
         let updated = server.clone();
+        save_servers(&app, &s.servers);
+        (updated, s.default_env.clone())
+    };
+    crate::tray::rebuild_tray_menu(&app);
+    // Checking warnings reads the env file, so it waits for the lock to drop
+    for warning in server_warnings(&updated, &default_env) {
+        warn!("Server '{}': {warning}", updated.name);
+    }
+
+    // Exposed/blocked tools and overrides alter what proxy clients see
+    let tools = {
+        let s = state.lock().unwrap();
+        s.connections.get(&id).map(|c| c.tools.clone())
+    };
+    if let Some(tools) = tools {
+        crate::mcp::proxy::notify_if_tools_changed(&app, &id, &tools).await;
+    }
+
+    Ok(updated)
+}
+
+/// How `set_tags_bulk` combines the given tags with each server's existing tags.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMode {
+    Add,
+    Remove,
+    Replace,
+}
+
+/// Apply one tag change to many servers in a single persisted update.
+#[tauri::command]
+pub async fn set_tags_bulk(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    server_ids: Vec<String>,
+    tags: Vec<String>,
+    mode: TagMode,
+) -> Result<Vec<ServerConfig>, AppError> {
+    let updated = {
+        let mut s = state.lock().unwrap();
+
+        // Validate every ID up front so a typo doesn't leave a partial update
+        if let Some(missing) = server_ids
+            .iter()
+            .find(|id| !s.servers.iter().any(|srv| &srv.id == *id))
+        {
+            return Err(AppError::ServerNotFound(missing.clone()));
+        }
+
+        let mut updated = Vec::new();
+        for server in s
+            .servers
+            .iter_mut()
+            .filter(|srv| server_ids.contains(&srv.id))
+        {
+            let mut current = server.tags.take().unwrap_or_default();
+            match mode {
+                TagMode::Add => {
+                    for tag in &tags {
+                        if !current.contains(tag) {
+                            current.push(tag.clone());
+                        }
+                    }
+                }
+                TagMode::Remove => current.retain(|t| !tags.contains(t)),
+                TagMode::Replace => current = tags.clone(),
+            }
+            server.tags = if current.is_empty() {
+                None
+            } else {
+                Some(current)
+            };
+            updated.push(server.clone());
+        }
+
         save_servers(&app, &s.servers);
         updated
     };
     crate::tray::rebuild_tray_menu(&app);
     Ok(updated)
 }
+
+/// Copy a server's configuration under a new id and a unique "(copy)" name.
+/// The copy is placed right after the original and starts disconnected.
+#[tauri::command]
+pub async fn duplicate_server(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    id: String,
+) -> Result<ServerConfig, AppError> {
+    let copy = {
+        let mut s = state.lock().unwrap();
+        let index = s
+            .servers
+            .iter()
+            .position(|srv| srv.id == id)
+            .ok_or_else(|| AppError::ServerNotFound(id.clone()))?;
+
+        let mut copy = s.servers[index].clone();
+        copy.id = Uuid::new_v4().to_string();
+        copy.name = copy_name(&copy.name, s.servers.iter().map(|srv| srv.name.as_str()));
+        copy.status = Some(ServerStatus::Disconnected);
+        copy.last_connected = None;
+        copy.last_error = None;
+        copy.last_error_at = None;
+        // The copy belongs to the user, not to whatever manages the original
+        copy.managed = None;
+        copy.managed_by = None;
+
+        s.servers.insert(index + 1, copy.clone());
+        save_servers(&app, &s.servers);
+        copy
+    };
+    crate::tray::rebuild_tray_menu(&app);
+    Ok(copy)
+}
+
+/// `"{name} (copy)"`, or `"{name} (copy N)"` for the first N that is free.
+fn copy_name<'a>(name: &str, existing: impl Iterator<Item = &'a str> + Clone) -> String {
+    let base = format!("{name} (copy)");
+    let taken = |candidate: &str| existing.clone().any(|n| n == candidate);
+    if !taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{name} (copy {n})"))
+        .find(|candidate| !taken(candidate))
+        .expect("unbounded range yields a free name")
+}
+
+/// Rearrange the server list to match `ids`, which must name every server
+/// exactly once. The order is what `list_servers` and the tray show.
+#[tauri::command]
+pub async fn reorder_servers(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    ids: Vec<String>,
+) -> Result<(), AppError> {
+    {
+        let mut s = state.lock().unwrap();
+        let positions = order_positions(&s.servers, &ids)?;
+        s.servers
+            .sort_by_key(|server| positions[server.id.as_str()]);
+        save_servers(&app, &s.servers);
+    }
+    crate::tray::rebuild_tray_menu(&app);
+    Ok(())
+}
+
+/// Map each server ID to its position in `ids`, checking that `ids` is a
+/// permutation of the servers' IDs.
+fn order_positions<'a>(
+    servers: &[ServerConfig],
+    ids: &'a [String],
+) -> Result<HashMap<&'a str, usize>, AppError> {
+    let mut positions = HashMap::new();
+    for (index, id) in ids.iter().enumerate() {
+        if !servers.iter().any(|s| &s.id == id) {
+            return Err(AppError::ServerNotFound(id.clone()));
+        }
+        if positions.insert(id.as_str(), index).is_some() {
+            return Err(AppError::Validation(format!(
+                "Server ID listed more than once: {id}"
+            )));
+        }
+    }
+    if let Some(missing) = servers
+        .iter()
+        .find(|s| !positions.contains_key(s.id.as_str()))
+    {
+        return Err(AppError::Validation(format!(
+            "Server order is missing '{}'",
+            missing.name
+        )));
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_references_finds_plain_and_braced_vars() {
+        assert_eq!(
+            env_references("Bearer ${API_TOKEN} for $USER_1/x"),
+            vec!["API_TOKEN", "USER_1"]
+        );
+    }
+
+    #[test]
+    fn normalizes_case_variant_env_keys() {
+        let mut env: HashMap<String, String> = [
+            ("Path", "/a"),
+            ("PATH", "/b"),
+            ("path", "/c"),
+            ("home", "/h1"),
+            ("Home", "/h2"),
+            ("TERM", "xterm"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let notes = collapse_env_case(&mut env);
+        assert_eq!(env.len(), 3);
+        assert_eq!(env["PATH"], "/b");
+        assert_eq!(env["Home"], "/h2");
+        assert_eq!(env["TERM"], "xterm");
+        assert_eq!(
+            notes,
+            vec![
+                "Environment keys Home, home differ only in case; using Home",
+                "Environment keys PATH, Path, path differ only in case; using PATH",
+            ]
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn keeps_case_variant_env_keys_off_windows() {
+        let mut env = HashMap::from([
+            ("Path".to_string(), "/a".to_string()),
+            ("PATH".to_string(), "/b".to_string()),
+        ]);
+        assert!(normalize_env_case(&mut env).is_empty());
+        assert_eq!(env.len(), 2);
+    }
+
+    fn stdio_input(name: &str, command: &str) -> ServerConfigInput {
+        ServerConfigInput {
+            name: name.into(),
+            enabled: true,
+            command: Some(command.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn zero_argument_limit_is_rejected() {
+        let input = ServerConfigInput {
+            max_argument_bytes: Some(0),
+            ..stdio_input("fs", "fs-server")
+        };
+        assert!(matches!(
+            new_server(input, None, None),
+            Err(AppError::Validation(_))
+        ));
+
+        let input = ServerConfigInput {
+            max_argument_bytes: Some(1),
+            ..stdio_input("fs", "fs-server")
+        };
+        assert!(new_server(input, None, None).is_ok());
+    }
+
+    #[test]
+    fn import_skips_invalid_and_duplicate_entries_without_aborting() {
+        let existing = vec![server_from_input(
+            stdio_input("fs", "fs-server"),
+            None,
+            None,
+        )];
+        let invalid = ServerConfigInput {
+            tool_overrides: Some(HashMap::from([
+                (
+                    "a".to_string(),
+                    ToolOverride {
+                        display_name: Some("b".into()),
+                        description: None,
+                    },
+                ),
+                (
+                    "b".to_string(),
+                    ToolOverride {
+                        display_name: None,
+                        description: None,
+                    },
+                ),
+            ])),
+            ..stdio_input("invalid", "invalid-server")
+        };
+
+        let (added, skipped) = plan_import(
+            &existing,
+            vec![
+                stdio_input("fs", "other-server"),
+                invalid,
+                stdio_input("git", "git-server"),
+                stdio_input("fs-copy", "fs-server"),
+                stdio_input("git", "git-server-2"),
+                stdio_input("git-copy", "git-server"),
+            ],
+        );
+
+        let added: Vec<_> = added.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(added, ["git"]);
+        let skipped: Vec<_> = skipped.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(skipped, ["fs", "invalid", "fs-copy", "git", "git-copy"]);
+    }
+
+    #[test]
+    fn colliding_tool_overrides_are_rejected() {
+        let overrides = HashMap::from([(
+            "fs_read".to_string(),
+            ToolOverride {
+                display_name: Some("read_file".into()),
+                description: None,
+            },
+        )]);
+        assert!(check_tool_overrides(Some(&overrides), &["fs_read".into()]).is_ok());
+
+        let err = check_tool_overrides(Some(&overrides), &["fs_read".into(), "read_file".into()])
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(msg) if msg.contains("'read_file'")));
+    }
+
+    #[test]
+    fn env_references_ignores_non_variables() {
+        assert!(env_references("costs $5, ends with $").is_empty());
+        assert!(env_references("${unterminated").is_empty());
+        assert!(env_references("Bearer ${oauth.access_token}").is_empty());
+    }
+
+    #[test]
+    fn expand_env_references_keeps_unknowns_and_placeholders() {
+        let lookup = |name: &str| (name == "TOKEN").then(|| "abc".to_string());
+        assert_eq!(
+            expand_env_references("Bearer ${TOKEN} $TOKEN/$MISSING", lookup),
+            "Bearer abc abc/$MISSING"
+        );
+        assert_eq!(
+            expand_env_references("Bearer ${oauth.access_token} $", lookup),
+            "Bearer ${oauth.access_token} $"
+        );
+    }
+
+    #[test]
+    fn expansion_and_warnings_agree_past_an_unterminated_brace() {
+        let lookup = |name: &str| (name == "TOKEN").then(|| "abc".to_string());
+        assert_eq!(
+            expand_env_references("${unterminated $TOKEN", lookup),
+            "${unterminated abc"
+        );
+        assert_eq!(env_references("${unterminated $TOKEN"), vec!["TOKEN"]);
+    }
+
+    #[test]
+    fn warnings_flag_missing_command_and_undefined_vars() {
+        let server = ServerConfig {
+            name: "test".into(),
+            transport: ServerTransport::Stdio,
+            command: Some("/definitely/not/a/real/binary".into()),
+            args: Some(vec!["--token=${AGENT_HUB_TEST_UNDEFINED}".into()]),
+            env: Some(HashMap::from([("KNOWN".into(), "1".into())])),
+            ..Default::default()
+        };
+        let warnings = server_warnings(&server, &HashMap::new());
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings[0].contains("not found"));
+        assert!(warnings[1].contains("AGENT_HUB_TEST_UNDEFINED"));
+    }
+
+    #[test]
+    fn order_positions_requires_a_permutation() {
+        let servers: Vec<ServerConfig> = ["a", "b", "c"]
+            .iter()
+            .map(|id| ServerConfig {
+                id: id.to_string(),
+                name: id.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let order = ids(&["c", "a", "b"]);
+        let positions = order_positions(&servers, &order).unwrap();
+        assert_eq!((positions["c"], positions["a"], positions["b"]), (0, 1, 2));
+
+        assert!(order_positions(&servers, &ids(&["a", "b"])).is_err());
+        assert!(order_positions(&servers, &ids(&["a", "a", "b"])).is_err());
+        assert!(order_positions(&servers, &ids(&["a", "b", "x"])).is_err());
+    }
+
+    #[test]
+    fn copy_name_skips_taken_names() {
+        let existing = ["Git", "Git (copy)", "Git (copy 2)"];
+        assert_eq!(copy_name("Files", existing.iter().copied()), "Files (copy)");
+        assert_eq!(copy_name("Git", existing.iter().copied()), "Git (copy 3)");
+    }
+}
@@ -1,14 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tauri::{AppHandle, State};
 use tracing::{info, warn};
 
-use crate::commands::skills_config;
+use crate::commands::skills_config::{self, SkillTargets};
 use crate::error::AppError;
 use crate::persistence;
-use crate::state::skill::InstalledSkill;
+use crate::state::skill::{InstalledSkill, SkillAsset, SkillAssetStore};
 use crate::state::skills_registry::{
     MarketplaceSkillDetail, SkillsMarketplaceCache, SkillsSearchResult,
 };
@@ -22,12 +23,44 @@ use crate::state::SharedState;
 struct SkillFrontmatter {
     name: Option<String>,
     description: Option<String>,
+    category: Option<String>,
+    /// A YAML list or a comma-separated string; see `SkillFrontmatter::tags`.
+    tags: Option<serde_yaml::Value>,
 }
 
-fn parse_frontmatter(content: &str) -> (SkillFrontmatter, String) {
+impl SkillFrontmatter {
+    fn tags(&self) -> Vec<String> {
+        let items: Vec<String> = match &self.tags {
+            Some(serde_yaml::Value::Sequence(seq)) => seq
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect(),
+            Some(serde_yaml::Value::String(s)) => s.split(',').map(String::from).collect(),
+            _ => Vec::new(),
+        };
+        items
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+}
+
+/// Outcome of reading a SKILL.md frontmatter block.
+enum Frontmatter {
+    /// No `---` block at the top of the file.
+    Missing,
+    /// A `---` block is present but isn't valid YAML (or is never closed).
+    Invalid(String),
+    Parsed(SkillFrontmatter),
+}
+
+/// Split SKILL.md into its frontmatter and body. The body is the whole
+/// content unless the frontmatter parsed.
+fn read_frontmatter(content: &str) -> (Frontmatter, String) {
     let trimmed = content.trim_start();
     if !trimmed.starts_with("---") {
-        return (SkillFrontmatter::default(), content.to_string());
+        return (Frontmatter::Missing, content.to_string());
     }
 
     let after_first = &trimmed[3..];
@@ -39,17 +72,109 @@ fn parse_frontmatter(content: &str) -> (SkillFrontmatter, String) {
             .to_string();
 
         match serde_yaml::from_str::<SkillFrontmatter>(yaml_str) {
-            Ok(fm) => (fm, body),
-            Err(e) => {
-                warn!("Failed to parse SKILL.md frontmatter: {e}");
-                (SkillFrontmatter::default(), content.to_string())
-            }
+            Ok(fm) => (Frontmatter::Parsed(fm), body),
+            Err(e) => (Frontmatter::Invalid(e.to_string()), content.to_string()),
         }
     } else {
-        (SkillFrontmatter::default(), content.to_string())
+        (
+            Frontmatter::Invalid("frontmatter is missing its closing `---`".into()),
+            content.to_string(),
+        )
+    }
+}
+
+fn parse_frontmatter(content: &str) -> (SkillFrontmatter, String) {
+    match read_frontmatter(content) {
+        (Frontmatter::Parsed(fm), body) => (fm, body),
+        (Frontmatter::Invalid(e), body) => {
+            warn!("Failed to parse SKILL.md frontmatter: {e}");
+            (SkillFrontmatter::default(), body)
+        }
+        (Frontmatter::Missing, body) => (SkillFrontmatter::default(), body),
     }
 }
 
+// ---------------------------------------------------------------------------
+// SKILL.md lint
+// ---------------------------------------------------------------------------
+
+/// Bodies shorter than this are unlikely to give an agent useful guidance.
+const MIN_SKILL_BODY_CHARS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkillLintKind {
+    MissingFrontmatter,
+    InvalidYaml,
+    MissingName,
+    EmptyDescription,
+    ShortBody,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillLintWarning {
+    pub kind: SkillLintKind,
+    pub message: String,
+}
+
+fn lint_skill(content: &str) -> Vec<SkillLintWarning> {
+    let lint = |kind, message: String| SkillLintWarning { kind, message };
+    let (frontmatter, body) = read_frontmatter(content);
+    let mut warnings = Vec::new();
+    let fm = match frontmatter {
+        Frontmatter::Missing => {
+            warnings.push(lint(
+                SkillLintKind::MissingFrontmatter,
+                "No frontmatter; name and description will be guessed".into(),
+            ));
+            SkillFrontmatter::default()
+        }
+        Frontmatter::Invalid(e) => {
+            warnings.push(lint(
+                SkillLintKind::InvalidYaml,
+                format!("Frontmatter is not valid YAML: {e}"),
+            ));
+            SkillFrontmatter::default()
+        }
+        Frontmatter::Parsed(fm) => fm,
+    };
+
+    if fm.name.as_deref().is_none_or(|n| n.trim().is_empty()) {
+        warnings.push(lint(
+            SkillLintKind::MissingName,
+            "Frontmatter has no `name`".into(),
+        ));
+    }
+    if fm
+        .description
+        .as_deref()
+        .is_none_or(|d| d.trim().is_empty())
+    {
+        warnings.push(lint(
+            SkillLintKind::EmptyDescription,
+            "Frontmatter `description` is empty; agents use it to decide when to load the skill"
+                .into(),
+        ));
+    }
+    let body_chars = body.trim().chars().count();
+    if body_chars < MIN_SKILL_BODY_CHARS {
+        warnings.push(lint(
+            SkillLintKind::ShortBody,
+            format!(
+                "Body is only {body_chars} characters (expected at least {MIN_SKILL_BODY_CHARS})"
+            ),
+        ));
+    }
+    warnings
+}
+
+/// Lint SKILL.md content without installing it.
+#[tauri::command]
+pub async fn validate_skill(content: String) -> Result<Vec<SkillLintWarning>, AppError> {
+    Ok(lint_skill(&content))
+}
+
 // ---------------------------------------------------------------------------
 // Shared directory scanner
 // ---------------------------------------------------------------------------
@@ -138,6 +263,7 @@ pub async fn search_skills_marketplace(
     cache: State<'_, SkillsMarketplaceCache>,
     state: State<'_, SharedState>,
     search: String,
+    category: Option<String>,
     limit: Option<u32>,
 ) -> Result<SkillsSearchResult, AppError> {
     let (installed_ids, installed_skill_ids): (Vec<String>, Vec<String>) = {
@@ -148,7 +274,13 @@ pub async fn search_skills_marketplace(
     };
 
     let result = cache
-        .search(&search, limit.unwrap_or(30), &installed_ids, &installed_skill_ids)
+        .search(
+            &search,
+            category.as_deref(),
+            limit.unwrap_or(30),
+            &installed_ids,
+            &installed_skill_ids,
+        )
         .await;
     Ok(result)
 }
@@ -171,6 +303,7 @@ pub async fn get_skills_marketplace_detail(
         })?;
 
     let (fm, _body) = parse_frontmatter(&content);
+    let tags = fm.tags();
 
     Ok(MarketplaceSkillDetail {
         id: format!("{source}/{skill_id}"),
@@ -180,6 +313,8 @@ pub async fn get_skills_marketplace_detail(
         installs,
         description: fm.description.unwrap_or_default(),
         content,
+        category: fm.category,
+        tags,
     })
 }
 
@@ -188,7 +323,7 @@ pub async fn get_skills_marketplace_detail(
 // ---------------------------------------------------------------------------
 
 /// Install a managed skill into state, persist it, and write to enabled tool directories.
-/// Returns the created `InstalledSkill`. Skips if a skill with the same `skill_id` already exists.
+/// Skips if a skill with the same `skill_id` already exists; returns whether it was installed.
 pub fn install_managed_skill(
     app: &AppHandle,
     state: &SharedState,
@@ -197,11 +332,11 @@ pub fn install_managed_skill(
     description: &str,
     content: &str,
     managed_by: &str,
-) {
+) -> bool {
     let integrations = {
         let mut s = state.lock().unwrap();
         if s.installed_skills.iter().any(|sk| sk.skill_id == skill_id) {
-            return;
+            return false;
         }
         let skill = InstalledSkill {
             id: format!("agent-hub/{skill_id}"),
@@ -214,16 +349,19 @@ pub fn install_managed_skill(
             installs: None,
             managed: None,
             managed_by: Some(managed_by.to_string()),
+            content_hash: Some(content_hash(content)),
+            assets: Vec::new(),
         };
         s.installed_skills.push(skill);
         persistence::save_installed_skills(app, &s.installed_skills);
-        s.enabled_skill_integrations.clone()
+        SkillTargets::from_state(&s)
     };
 
-    if let Err(e) = skills_config::write_skill(skill_id, content, &integrations) {
+    if let Err(e) = skills_config::write_skill(skill_id, content, &[], &integrations) {
         warn!("Failed to write managed skill {skill_id}: {e}");
     }
     info!("Installed managed skill: {skill_id} (managed_by={managed_by})");
+    true
 }
 
 /// Uninstall a managed skill from state, persist, and remove from tool directories.
@@ -233,7 +371,7 @@ pub fn uninstall_managed_skill(
     skill_id: &str,
     managed_by: &str,
 ) {
-    let integrations = {
+    let (assets, integrations) = {
         let mut s = state.lock().unwrap();
         let idx = match s.installed_skills.iter().position(|sk| {
             sk.skill_id == skill_id && sk.managed_by.as_deref() == Some(managed_by)
@@ -241,12 +379,12 @@ pub fn uninstall_managed_skill(
             Some(i) => i,
             None => return,
         };
-        s.installed_skills.remove(idx);
+        let skill = s.installed_skills.remove(idx);
         persistence::save_installed_skills(app, &s.installed_skills);
-        s.enabled_skill_integrations.clone()
+        (skill.assets, SkillTargets::from_state(&s))
     };
 
-    if let Err(e) = skills_config::remove_skill(skill_id, &integrations) {
+    if let Err(e) = skills_config::remove_skill(skill_id, &assets, &integrations) {
         warn!("Failed to remove managed skill {skill_id}: {e}");
     }
     info!("Uninstalled managed skill: {skill_id} (managed_by={managed_by})");
@@ -259,11 +397,12 @@ pub fn uninstall_managed_skill(
 /// Reconcile managed skills on startup: if a feature (memory, discovery) is
 /// enabled in state but the corresponding managed skill entry is missing,
 /// install it. This handles users who enabled features before managed skills
-/// were introduced.
-pub fn reconcile_managed_skills(app: &AppHandle, state: &SharedState) {
+/// were introduced. Returns the IDs of skills that were (re)installed.
+pub fn reconcile_managed_skills(app: &AppHandle, state: &SharedState) -> Vec<String> {
     use crate::commands::discovery::{DISCOVERY_SKILL_CONTENT, DISCOVERY_SKILL_ID};
     use crate::commands::memory::{MEMORY_MANAGED_SKILL_CONTENT, MEMORY_SKILL_ID};
 
+    let mut installed = Vec::new();
     let (memory_enabled, discovery_enabled) = {
         let s = state.lock().unwrap();
         let mem = s
@@ -279,7 +418,7 @@ pub fn reconcile_managed_skills(app: &AppHandle, state: &SharedState) {
         crate::commands::memory::install_memory_skill();
 
         // Ensure the managed skill entry exists in state
-        install_managed_skill(
+        if install_managed_skill(
             app,
             state,
             MEMORY_SKILL_ID,
@@ -287,12 +426,14 @@ pub fn reconcile_managed_skills(app: &AppHandle, state: &SharedState) {
             "Search and store persistent memories using the agent-memory MCP server",
             MEMORY_MANAGED_SKILL_CONTENT,
             "memory",
-        );
+        ) {
+            installed.push(MEMORY_SKILL_ID.to_string());
+        }
         info!("Reconciled memory managed skill");
     }
 
     if discovery_enabled {
-        install_managed_skill(
+        if install_managed_skill(
             app,
             state,
             DISCOVERY_SKILL_ID,
@@ -300,9 +441,13 @@ pub fn reconcile_managed_skills(app: &AppHandle, state: &SharedState) {
             "Find and use MCP tools through the discovery endpoint",
             DISCOVERY_SKILL_CONTENT,
             "discovery",
-        );
+        ) {
+            installed.push(DISCOVERY_SKILL_ID.to_string());
+        }
         info!("Reconciled discovery managed skill");
     }
+
+    installed
 }
 
 // ---------------------------------------------------------------------------
@@ -322,6 +467,8 @@ pub struct InstalledSkillInfo {
     pub installs: Option<u64>,
     pub managed: bool,
     pub managed_by: Option<String>,
+    /// Relative paths of the supporting files shipped with SKILL.md.
+    pub assets: Vec<String>,
 }
 
 impl From<&InstalledSkill> for InstalledSkillInfo {
@@ -337,6 +484,7 @@ impl From<&InstalledSkill> for InstalledSkillInfo {
             installs: s.installs,
             managed: is_managed,
             managed_by: s.managed_by.clone(),
+            assets: s.assets.iter().map(|a| a.path.clone()).collect(),
         }
     }
 }
@@ -360,13 +508,19 @@ pub async fn install_skill(
     skill_id: String,
     installs: Option<u64>,
 ) -> Result<InstalledSkillInfo, AppError> {
+    if !is_safe_skill_dir(&skill_id) {
+        return Err(AppError::Validation(format!(
+            "Invalid skill name: {skill_id}"
+        )));
+    }
     // Check if already installed
-    {
+    let store = {
         let s = state.lock().unwrap();
         if s.installed_skills.iter().any(|sk| sk.id == id) {
             return Err(AppError::Validation(format!("Skill already installed: {id}")));
         }
-    }
+        s.skill_assets.clone()
+    };
 
     // Fetch SKILL.md content
     let content = cache
@@ -378,6 +532,14 @@ pub async fn install_skill(
             ))
         })?;
 
+    let assets = cache
+        .fetch_skill_assets(&source, &skill_id, &store)
+        .await
+        .unwrap_or_else(|| {
+            warn!("Could not list files for {source}/{skill_id}; installing SKILL.md only");
+            Vec::new()
+        });
+
     let (fm, _body) = parse_frontmatter(&content);
 
     let skill = InstalledSkill {
@@ -391,18 +553,22 @@ pub async fn install_skill(
         installs,
         managed: None,
         managed_by: None,
+        content_hash: Some(content_hash(&content)),
+        assets,
     };
 
-    let enabled_integrations: Vec<String>;
+    let enabled_integrations: SkillTargets;
     {
         let mut s = state.lock().unwrap();
         s.installed_skills.push(skill.clone());
-        enabled_integrations = s.enabled_skill_integrations.clone();
+        enabled_integrations = SkillTargets::from_state(&s);
         persistence::save_installed_skills(&app, &s.installed_skills);
     }
 
-    // Write SKILL.md to all enabled tool directories
-    if let Err(e) = skills_config::write_skill(&skill_id, &content, &enabled_integrations) {
+    // Write SKILL.md and assets to all enabled tool directories
+    if let Err(e) =
+        skills_config::write_skill(&skill_id, &content, &skill.assets, &enabled_integrations)
+    {
         warn!("Failed to write skill files: {e}");
     }
 
@@ -426,7 +592,7 @@ pub async fn uninstall_skill(
         }
     }
 
-    let (skill_id, enabled_integrations) = {
+    let (skill, enabled_integrations) = {
         let mut s = state.lock().unwrap();
         let idx = s
             .installed_skills
@@ -435,13 +601,15 @@ pub async fn uninstall_skill(
             .ok_or_else(|| AppError::Validation(format!("Skill not found: {id}")))?;
 
         let skill = s.installed_skills.remove(idx);
-        let integrations = s.enabled_skill_integrations.clone();
+        let integrations = SkillTargets::from_state(&s);
         persistence::save_installed_skills(&app, &s.installed_skills);
-        (skill.skill_id, integrations)
+        (skill, integrations)
     };
 
-    // Remove SKILL.md from all enabled tool directories
-    if let Err(e) = skills_config::remove_skill(&skill_id, &enabled_integrations) {
+    // Remove SKILL.md and assets from all enabled tool directories
+    if let Err(e) =
+        skills_config::remove_skill(&skill.skill_id, &skill.assets, &enabled_integrations)
+    {
         warn!("Failed to remove skill files: {e}");
     }
 
@@ -456,7 +624,7 @@ pub async fn toggle_skill(
     id: String,
     enabled: bool,
 ) -> Result<InstalledSkillInfo, AppError> {
-    let (skill_id, content, enabled_integrations) = {
+    let (skill_id, content, assets, enabled_integrations) = {
         let mut s = state.lock().unwrap();
         let skill = s
             .installed_skills
@@ -467,17 +635,20 @@ pub async fn toggle_skill(
         skill.enabled = enabled;
         let skill_id = skill.skill_id.clone();
         let content = skill.content.clone();
-        let integrations = s.enabled_skill_integrations.clone();
+        let assets = skill.assets.clone();
+        let integrations = SkillTargets::from_state(&s);
         persistence::save_installed_skills(&app, &s.installed_skills);
-        (skill_id, content, integrations)
+        (skill_id, content, assets, integrations)
     };
 
     if enabled {
-        if let Err(e) = skills_config::write_skill(&skill_id, &content, &enabled_integrations) {
+        if let Err(e) =
+            skills_config::write_skill(&skill_id, &content, &assets, &enabled_integrations)
+        {
             warn!("Failed to write skill files on enable: {e}");
         }
     } else {
-        if let Err(e) = skills_config::remove_skill(&skill_id, &enabled_integrations) {
+        if let Err(e) = skills_config::remove_skill(&skill_id, &assets, &enabled_integrations) {
             warn!("Failed to remove skill files on disable: {e}");
         }
     }
@@ -516,6 +687,210 @@ pub struct SkillContentResponse {
     pub content: String,
 }
 
+// ---------------------------------------------------------------------------
+// Skill updates
+// ---------------------------------------------------------------------------
+
+/// An installed marketplace skill whose upstream SKILL.md has changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdateInfo {
+    pub id: String,
+    pub name: String,
+    pub source: String,
+    pub skill_id: String,
+    pub installed_hash: String,
+    pub latest_hash: String,
+}
+
+/// Skills fetched from a marketplace repo; local imports and managed skills
+/// have no upstream to compare against.
+fn is_marketplace_skill(skill: &InstalledSkill) -> bool {
+    skill.managed_by.is_none()
+        && skill.managed != Some(true)
+        && skill.source != "local"
+        && skill.source != "agent-hub"
+}
+
+/// Hash recorded at install time, falling back to the stored content for
+/// skills installed before hashes were tracked.
+fn installed_hash(skill: &InstalledSkill) -> String {
+    skill
+        .content_hash
+        .clone()
+        .unwrap_or_else(|| content_hash(&skill.content))
+}
+
+#[tauri::command]
+pub async fn check_skill_updates(
+    state: State<'_, SharedState>,
+    cache: State<'_, SkillsMarketplaceCache>,
+) -> Result<Vec<SkillUpdateInfo>, AppError> {
+    let candidates: Vec<InstalledSkill> = {
+        let s = state.lock().unwrap();
+        s.installed_skills
+            .iter()
+            .filter(|sk| is_marketplace_skill(sk))
+            .cloned()
+            .collect()
+    };
+
+    let mut updates = Vec::new();
+    for skill in candidates {
+        let Some(latest) = cache
+            .fetch_skill_content(&skill.source, &skill.skill_id)
+            .await
+        else {
+            warn!(
+                "Could not fetch SKILL.md for {} while checking updates",
+                skill.id
+            );
+            continue;
+        };
+        let installed_hash = installed_hash(&skill);
+        let latest_hash = content_hash(&latest);
+        if installed_hash != latest_hash {
+            updates.push(SkillUpdateInfo {
+                id: skill.id,
+                name: skill.name,
+                source: skill.source,
+                skill_id: skill.skill_id,
+                installed_hash,
+                latest_hash,
+            });
+        }
+    }
+    Ok(updates)
+}
+
+#[tauri::command]
+pub async fn update_skill(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    cache: State<'_, SkillsMarketplaceCache>,
+    id: String,
+) -> Result<SkillUpdateResult, AppError> {
+    let (previous, store) = {
+        let s = state.lock().unwrap();
+        let skill = s
+            .installed_skills
+            .iter()
+            .find(|sk| sk.id == id)
+            .ok_or_else(|| AppError::Validation(format!("Skill not found: {id}")))?;
+        if !is_marketplace_skill(skill) {
+            return Err(AppError::Validation(format!(
+                "Skill has no marketplace source: {id}"
+            )));
+        }
+        (skill.clone(), s.skill_assets.clone())
+    };
+    let (source, skill_id) = (previous.source.clone(), previous.skill_id.clone());
+
+    let content = cache
+        .fetch_skill_content(&source, &skill_id)
+        .await
+        .ok_or_else(|| {
+            AppError::Protocol(format!("Could not fetch SKILL.md for {source}/{skill_id}"))
+        })?;
+    let latest_assets = cache.fetch_skill_assets(&source, &skill_id, &store).await;
+    if latest_assets.is_none() {
+        warn!("Could not list files for {source}/{skill_id}; keeping existing assets");
+    }
+    let (fm, _body) = parse_frontmatter(&content);
+
+    let (info, enabled, stale, assets, enabled_integrations, synced) = {
+        let mut s = state.lock().unwrap();
+        let skill = s
+            .installed_skills
+            .iter_mut()
+            .find(|sk| sk.id == id)
+            .ok_or_else(|| AppError::Validation(format!("Skill not found: {id}")))?;
+        skill.content = content.clone();
+        skill.content_hash = Some(content_hash(&content));
+        // Files dropped upstream are removed from the tool directories below
+        let mut stale = Vec::new();
+        if let Some(latest) = latest_assets {
+            stale = std::mem::replace(&mut skill.assets, latest);
+            stale.retain(|old| !skill.assets.iter().any(|a| a.path == old.path));
+        }
+        if let Some(name) = fm.name {
+            skill.name = name;
+        }
+        if let Some(description) = fm.description {
+            skill.description = description;
+        }
+        let info = InstalledSkillInfo::from(&*skill);
+        let enabled = skill.enabled;
+        let assets = skill.assets.clone();
+        persistence::save_installed_skills(&app, &s.installed_skills);
+        let synced = s.skill_sync_hashes.clone();
+        (
+            info,
+            enabled,
+            stale,
+            assets,
+            SkillTargets::from_state(&s),
+            synced,
+        )
+    };
+
+    let mut conflicts = Vec::new();
+    if enabled {
+        // Leave copies edited on disk for the user to resolve
+        // An error here is reported by the write below
+        for tool in enabled_integrations.tools().unwrap_or_default() {
+            let synced_hash = synced
+                .get(tool.id)
+                .and_then(|hashes| hashes.get(&skill_id))
+                .map(String::as_str);
+            if let Some(conflict) = local_edit(&tool.skills_dir, &previous, synced_hash) {
+                warn!(
+                    "Skill {skill_id} in {} was edited on disk; not updating it",
+                    tool.name
+                );
+                conflicts.push((tool.id, conflict));
+            }
+        }
+        let skip: Vec<&str> = conflicts.iter().map(|(tool_id, _)| *tool_id).collect();
+        match skills_config::write_skill_update(
+            &skill_id,
+            &content,
+            &assets,
+            &stale,
+            &enabled_integrations,
+            &skip,
+        ) {
+            Ok(written) => {
+                let hash = skills_config::skill_files_hash(&content, &assets);
+                let mut s = state.lock().unwrap();
+                for tool_id in written {
+                    s.skill_sync_hashes
+                        .entry(tool_id.to_string())
+                        .or_default()
+                        .insert(skill_id.clone(), hash.clone());
+                }
+                persistence::save_skill_sync_hashes(&app, &s.skill_sync_hashes);
+            }
+            Err(e) => warn!("Failed to write updated skill files: {e}"),
+        }
+    }
+
+    info!("Updated skill: {id}");
+    Ok(SkillUpdateResult {
+        skill: info,
+        conflicts: conflicts.into_iter().map(|(_, c)| c).collect(),
+    })
+}
+
+/// Result of `update_skill`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdateResult {
+    pub skill: InstalledSkillInfo,
+    /// Tools whose copy was edited on disk and so kept its local version.
+    pub conflicts: Vec<SkillContentConflict>,
+}
+
 // ---------------------------------------------------------------------------
 // Skill integration commands (Settings > Skills)
 // ---------------------------------------------------------------------------
@@ -529,6 +904,168 @@ pub struct SkillToolInfo {
     pub enabled: bool,
     pub skills_path: String,
     pub existing_skills: Vec<ExistingSkillInfo>,
+    /// Tracked skills whose SKILL.md in this tool was edited on disk. These
+    /// are left untouched by sync until resolved with `resolve_skill_conflict`.
+    pub conflicts: Vec<SkillContentConflict>,
+    /// Untracked skills in this tool whose `skill_id` also exists in another
+    /// tool with different content. Enabling imports only one of them.
+    pub collisions: Vec<SkillIdCollision>,
+}
+
+/// An untracked `skill_id` found in several tools' skills directories with
+/// differing content.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillIdCollision {
+    pub skill_id: String,
+    pub copies: Vec<SkillCopy>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillCopy {
+    pub tool_id: String,
+    pub path: String,
+    pub sha256: String,
+}
+
+/// A tracked skill whose SKILL.md on disk differs from the stored content.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillContentConflict {
+    pub skill_id: String,
+    /// ID of the tracked `InstalledSkill`.
+    pub installed_id: String,
+    pub path: String,
+    pub tracked_sha256: String,
+    pub disk_sha256: String,
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Compare each tracked skill with its SKILL.md under `skills_dir`.
+fn find_content_conflicts(
+    skills_dir: &Path,
+    installed: &[InstalledSkill],
+) -> Vec<SkillContentConflict> {
+    installed
+        .iter()
+        .filter_map(|skill| {
+            let path = skills_dir.join(&skill.skill_id).join("SKILL.md");
+            let disk = std::fs::read_to_string(&path).ok()?;
+            let disk_sha256 = content_hash(&disk);
+            let tracked_sha256 = content_hash(&skill.content);
+            (disk_sha256 != tracked_sha256).then(|| SkillContentConflict {
+                skill_id: skill.skill_id.clone(),
+                installed_id: skill.id.clone(),
+                path: path.display().to_string(),
+                tracked_sha256,
+                disk_sha256,
+            })
+        })
+        .collect()
+}
+
+/// The conflict if `skill`'s SKILL.md under `skills_dir` was edited since it
+/// was last written: it matches neither the tracked content nor the files
+/// hash recorded when the tool was last synced.
+fn local_edit(
+    skills_dir: &Path,
+    skill: &InstalledSkill,
+    synced_hash: Option<&str>,
+) -> Option<SkillContentConflict> {
+    let conflict = find_content_conflicts(skills_dir, std::slice::from_ref(skill)).pop()?;
+    let disk = std::fs::read_to_string(&conflict.path).ok()?;
+    let last_written = synced_hash
+        .is_some_and(|hash| hash == skills_config::skill_files_hash(&disk, &skill.assets));
+    (!last_written).then_some(conflict)
+}
+
+/// Make the on-disk `disk_content` the tracked content of `skill`, taking its
+/// name and description from the frontmatter when present.
+fn adopt_disk_content(skill: &mut InstalledSkill, disk_content: &str) {
+    let (fm, _body) = parse_frontmatter(disk_content);
+    skill.content = disk_content.to_string();
+    skill.content_hash = Some(content_hash(disk_content));
+    if let Some(name) = fm.name {
+        skill.name = name;
+    }
+    if let Some(description) = fm.description {
+        skill.description = description;
+    }
+}
+
+/// Split `installed` into the skills safe to write under `skills_dir` and the
+/// conflicts: tracked skills whose SKILL.md there was edited on disk, which
+/// sync leaves for the user to resolve.
+fn without_conflicts(
+    skills_dir: &Path,
+    installed: Vec<InstalledSkill>,
+    tool_name: &str,
+) -> (Vec<InstalledSkill>, Vec<SkillContentConflict>) {
+    let conflicts = find_content_conflicts(skills_dir, &installed);
+    for conflict in &conflicts {
+        warn!(
+            "Skill {} in {tool_name} differs from tracked content; leaving it for the user to resolve",
+            conflict.skill_id
+        );
+    }
+    let to_sync = installed
+        .into_iter()
+        .filter(|sk| !conflicts.iter().any(|c| c.skill_id == sk.skill_id))
+        .collect();
+    (to_sync, conflicts)
+}
+
+/// Path of the skill file for `skill_id` under `skills_dir`: `<id>/SKILL.md`,
+/// or a standalone `<id>.md`.
+fn skill_file_on_disk(skills_dir: &Path, skill_id: &str) -> Option<std::path::PathBuf> {
+    [
+        skills_dir.join(skill_id).join("SKILL.md"),
+        skills_dir.join(format!("{skill_id}.md")),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+/// Group untracked skills across all tools by `skill_id` and keep the ids
+/// whose copies don't all have the same content.
+fn find_skill_id_collisions(
+    tools: &[skills_config::SkillToolDef],
+    exclude_ids: &HashSet<String>,
+) -> Vec<SkillIdCollision> {
+    let mut by_id: BTreeMap<String, Vec<SkillCopy>> = BTreeMap::new();
+    for tool in tools {
+        for found in scan_skills_in_dir(&tool.skills_dir, exclude_ids) {
+            let Some(path) = skill_file_on_disk(&tool.skills_dir, &found.skill_id) else {
+                continue;
+            };
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            by_id.entry(found.skill_id).or_default().push(SkillCopy {
+                tool_id: tool.id.to_string(),
+                path: path.display().to_string(),
+                sha256: content_hash(&content),
+            });
+        }
+    }
+
+    by_id
+        .into_iter()
+        .filter(|(_, copies)| copies.iter().any(|c| c.sha256 != copies[0].sha256))
+        .map(|(skill_id, copies)| SkillIdCollision { skill_id, copies })
+        .collect()
+}
+
+fn collisions_for_tool(collisions: &[SkillIdCollision], tool_id: &str) -> Vec<SkillIdCollision> {
+    collisions
+        .iter()
+        .filter(|c| c.copies.iter().any(|copy| copy.tool_id == tool_id))
+        .cloned()
+        .collect()
 }
 
 /// Detect which tools support skills, whether they're installed, and whether
@@ -537,13 +1074,21 @@ pub struct SkillToolInfo {
 pub async fn detect_skill_integrations(
     state: State<'_, SharedState>,
 ) -> Result<Vec<SkillToolInfo>, AppError> {
-    let tools = skills_config::get_skill_tool_definitions()?;
-    let (enabled_ids, installed_skill_ids) = {
+    let (tools, enabled_ids, installed_skill_ids, installed_skills) = {
         let s = state.lock().unwrap();
-        let enabled = s.enabled_skill_integrations.clone();
+        let tools = skills_config::get_skill_tool_definitions(&s.skill_dir_overrides)?;
+        let enabled = SkillTargets::from_state(&s);
         let ids: HashSet<String> = s.installed_skills.iter().map(|sk| sk.skill_id.clone()).collect();
-        (enabled, ids)
+        (tools, enabled, ids, s.installed_skills.clone())
     };
+    let collisions = find_skill_id_collisions(&tools, &installed_skill_ids);
+    for collision in &collisions {
+        warn!(
+            "Skill {} exists with different content in {} tools",
+            collision.skill_id,
+            collision.copies.len()
+        );
+    }
 
     let results = tools
         .into_iter()
@@ -553,14 +1098,22 @@ pub async fn detect_skill_integrations(
             let installed = parent.map(|p| p.exists()).unwrap_or(false);
 
             let existing_skills = scan_skills_in_dir(&tool.skills_dir, &installed_skill_ids);
+            let enabled = enabled_ids.contains(&tool.id.to_string());
+            let conflicts = if enabled {
+                find_content_conflicts(&tool.skills_dir, &installed_skills)
+            } else {
+                Vec::new()
+            };
 
             SkillToolInfo {
                 id: tool.id.to_string(),
                 name: tool.name.to_string(),
                 installed,
-                enabled: enabled_ids.contains(&tool.id.to_string()),
+                enabled,
                 skills_path: tool.skills_dir.display().to_string(),
                 existing_skills,
+                conflicts,
+                collisions: collisions_for_tool(&collisions, tool.id),
             }
         })
         .collect();
@@ -568,19 +1121,41 @@ pub async fn detect_skill_integrations(
     Ok(results)
 }
 
-/// Read full content of a SKILL.md file (for import). Returns `(skill_id, name, description, content)`.
-fn read_skill_for_import(file_path: &Path, skill_id: &str) -> Option<(String, String, String, String)> {
+/// Read full content of a SKILL.md file (for import).
+fn read_skill_for_import(
+    file_path: &Path,
+    skill_id: &str,
+    assets: Vec<SkillAsset>,
+) -> Option<ImportableSkill> {
     let content = std::fs::read_to_string(file_path)
         .map_err(|e| warn!("Failed to read {}: {e}", file_path.display()))
         .ok()?;
     let (fm, _body) = parse_frontmatter(&content);
-    let name = fm.name.unwrap_or_else(|| skill_id.to_string());
-    let description = fm.description.unwrap_or_default();
-    Some((skill_id.to_string(), name, description, content))
+    Some(ImportableSkill {
+        skill_id: skill_id.to_string(),
+        name: fm.name.unwrap_or_else(|| skill_id.to_string()),
+        description: fm.description.unwrap_or_default(),
+        content,
+        assets,
+    })
 }
 
-/// Scan a tool's skills directory and return importable skills (full content) not already installed.
-fn find_importable_skills(skills_dir: &Path, installed_ids: &HashSet<String>) -> Vec<(String, String, String, String)> {
+/// A skill found on disk in a tool's skills directory.
+struct ImportableSkill {
+    skill_id: String,
+    name: String,
+    description: String,
+    content: String,
+    assets: Vec<SkillAsset>,
+}
+
+/// Scan a tool's skills directory and return importable skills (full content
+/// and supporting files, stored in `store`) not already installed.
+fn find_importable_skills(
+    skills_dir: &Path,
+    installed_ids: &HashSet<String>,
+    store: &SkillAssetStore,
+) -> Vec<ImportableSkill> {
     if !skills_dir.exists() {
         return vec![];
     }
@@ -602,7 +1177,8 @@ fn find_importable_skills(skills_dir: &Path, installed_ids: &HashSet<String>) ->
             }
             let skill_md = path.join("SKILL.md");
             if skill_md.exists() {
-                if let Some(info) = read_skill_for_import(&skill_md, &skill_id) {
+                let assets = skills_config::read_skill_assets(&path, store);
+                if let Some(info) = read_skill_for_import(&skill_md, &skill_id, assets) {
                     results.push(info);
                 }
             }
@@ -614,7 +1190,7 @@ fn find_importable_skills(skills_dir: &Path, installed_ids: &HashSet<String>) ->
             if installed_ids.contains(&skill_id) {
                 continue;
             }
-            if let Some(info) = read_skill_for_import(&path, &skill_id) {
+            if let Some(info) = read_skill_for_import(&path, &skill_id, Vec::new()) {
                 results.push(info);
             }
         }
@@ -635,7 +1211,8 @@ pub async fn enable_skill_integration(
         )));
     }
 
-    let tools = skills_config::get_skill_tool_definitions()?;
+    let dir_overrides = state.lock().unwrap().skill_dir_overrides.clone();
+    let tools = skills_config::get_skill_tool_definitions(&dir_overrides)?;
     let tool = tools.iter().find(|t| t.id == id).ok_or_else(|| {
         AppError::Validation(format!("Unknown skill tool: {id}"))
     })?;
@@ -644,27 +1221,40 @@ pub async fn enable_skill_integration(
     let importable = {
         let s = state.lock().unwrap();
         let installed_ids: HashSet<String> = s.installed_skills.iter().map(|sk| sk.skill_id.clone()).collect();
-        find_importable_skills(&tool.skills_dir, &installed_ids)
+        find_importable_skills(&tool.skills_dir, &installed_ids, &s.skill_assets)
     };
 
     if !importable.is_empty() {
         let mut s = state.lock().unwrap();
-        for (skill_id, name, description, content) in &importable {
+        for found in importable {
             // Double-check not already added (another tool may share the same skill_id)
-            if s.installed_skills.iter().any(|sk| sk.skill_id == *skill_id) {
+            if let Some(existing) = s
+                .installed_skills
+                .iter()
+                .find(|sk| sk.skill_id == found.skill_id)
+            {
+                if existing.content != found.content {
+                    warn!(
+                        "Not importing {} from {}: already installed from another tool with different content",
+                        found.skill_id, tool.name
+                    );
+                }
                 continue;
             }
+            let skill_id = found.skill_id;
             let skill = InstalledSkill {
                 id: format!("local:{id}/{skill_id}"),
-                name: name.clone(),
+                name: found.name,
                 skill_id: skill_id.clone(),
                 source: "local".to_string(),
-                description: description.clone(),
-                content: content.clone(),
+                description: found.description,
+                content_hash: Some(content_hash(&found.content)),
+                content: found.content,
                 enabled: true,
                 installs: None,
                 managed: None,
                 managed_by: None,
+                assets: found.assets,
             };
             info!("Imported existing skill from {}: {skill_id}", tool.name);
             s.installed_skills.push(skill);
@@ -672,18 +1262,32 @@ pub async fn enable_skill_integration(
         persistence::save_installed_skills(&app, &s.installed_skills);
     }
 
-    let installed_skills = {
+    let (installed_skills, mut synced, store) = {
         let mut s = state.lock().unwrap();
         if !s.enabled_skill_integrations.contains(&id) {
             s.enabled_skill_integrations.push(id.clone());
             persistence::save_enabled_skill_integrations(&app, &s.enabled_skill_integrations);
         }
-        s.installed_skills.clone()
+        let synced = s.skill_sync_hashes.get(&id).cloned().unwrap_or_default();
+        (s.installed_skills.clone(), synced, s.skill_assets.clone())
     };
 
-    // Sync all enabled skills to this tool
-    if let Err(e) = skills_config::sync_skills_for_tool(&id, &installed_skills) {
-        warn!("Failed to sync skills for {id}: {e}");
+    // Don't clobber skills that were edited on disk — report them instead
+    let (to_sync, conflicts) = without_conflicts(&tool.skills_dir, installed_skills, tool.name);
+
+    // Sync all enabled skills to this tool, skipping ones already up to date
+    match skills_config::sync_skills_for_tool(&id, &to_sync, &mut synced, &dir_overrides, &store) {
+        Ok(written) => info!(
+            "Wrote {written} of {} skill(s) to {}",
+            to_sync.len(),
+            tool.name
+        ),
+        Err(e) => warn!("Failed to sync skills for {id}: {e}"),
+    }
+    {
+        let mut s = state.lock().unwrap();
+        s.skill_sync_hashes.insert(id.clone(), synced);
+        persistence::save_skill_sync_hashes(&app, &s.skill_sync_hashes);
     }
 
     let tool = tools.iter().find(|t| t.id == id).unwrap();
@@ -705,6 +1309,11 @@ pub async fn enable_skill_integration(
         enabled: true,
         skills_path: tool.skills_dir.display().to_string(),
         existing_skills: scan_skills_in_dir(&tool.skills_dir, &installed_skill_ids),
+        conflicts,
+        collisions: collisions_for_tool(
+            &find_skill_id_collisions(&tools, &installed_skill_ids),
+            tool.id,
+        ),
     })
 }
 
@@ -715,15 +1324,25 @@ pub async fn disable_skill_integration(
     state: State<'_, SharedState>,
     id: String,
 ) -> Result<SkillToolInfo, AppError> {
-    let (installed_skills, tools) = {
+    let (installed_skills, tools, dir_overrides) = {
         let mut s = state.lock().unwrap();
         s.enabled_skill_integrations.retain(|i| i != &id);
         persistence::save_enabled_skill_integrations(&app, &s.enabled_skill_integrations);
-        (s.installed_skills.clone(), skills_config::get_skill_tool_definitions()?)
+        if s.skill_sync_hashes.remove(&id).is_some() {
+            persistence::save_skill_sync_hashes(&app, &s.skill_sync_hashes);
+        }
+        let tools = skills_config::get_skill_tool_definitions(&s.skill_dir_overrides)?;
+        (
+            s.installed_skills.clone(),
+            tools,
+            s.skill_dir_overrides.clone(),
+        )
     };
 
     // Remove all managed skill files from this tool
-    if let Err(e) = skills_config::remove_all_skills_for_tool(&id, &installed_skills) {
+    if let Err(e) =
+        skills_config::remove_all_skills_for_tool(&id, &installed_skills, &dir_overrides)
+    {
         warn!("Failed to remove skills for {id}: {e}");
     }
 
@@ -747,5 +1366,759 @@ pub async fn disable_skill_integration(
         enabled: false,
         skills_path: tool.skills_dir.display().to_string(),
         existing_skills: scan_skills_in_dir(&tool.skills_dir, &installed_skill_ids),
+        conflicts: Vec::new(),
+        collisions: collisions_for_tool(
+            &find_skill_id_collisions(&tools, &installed_skill_ids),
+            tool.id,
+        ),
     })
 }
+
+/// Skills directories the user has moved away from a tool's default, keyed by tool ID.
+#[tauri::command]
+pub async fn get_skill_dir_overrides(
+    state: State<'_, SharedState>,
+) -> Result<HashMap<String, String>, AppError> {
+    Ok(state.lock().unwrap().skill_dir_overrides.clone())
+}
+
+/// Point a tool's skills at a different directory, or restore the default
+/// with `None`. If the tool's skill integration is enabled, managed skills
+/// are moved from the old directory to the new one, except those the new
+/// directory already holds with edited content; those are returned as
+/// conflicts, as `enable_skill_integration` does.
+#[tauri::command]
+pub async fn set_skill_dir_override(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    tool_id: String,
+    path: Option<String>,
+) -> Result<Vec<SkillContentConflict>, AppError> {
+    if !skills_config::supports_skills(&tool_id) {
+        return Err(AppError::Validation(format!(
+            "Tool {tool_id} does not support skills"
+        )));
+    }
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(dir) = &path {
+        let dir_path = Path::new(dir);
+        if !dir_path.is_absolute() {
+            return Err(AppError::Validation(format!(
+                "Skills directory must be an absolute path: {dir}"
+            )));
+        }
+        if !dir_path.is_dir() {
+            return Err(AppError::Validation(format!(
+                "Skills directory does not exist: {dir}"
+            )));
+        }
+    }
+
+    let (enabled, installed_skills, old_overrides, new_overrides, store) = {
+        let mut s = state.lock().unwrap();
+        let old_overrides = s.skill_dir_overrides.clone();
+        match &path {
+            Some(dir) => s.skill_dir_overrides.insert(tool_id.clone(), dir.clone()),
+            None => s.skill_dir_overrides.remove(&tool_id),
+        };
+        persistence::save_skill_dir_overrides(&app, &s.skill_dir_overrides);
+        let enabled = s.enabled_skill_integrations.contains(&tool_id);
+        if enabled && s.skill_sync_hashes.remove(&tool_id).is_some() {
+            persistence::save_skill_sync_hashes(&app, &s.skill_sync_hashes);
+        }
+        (
+            enabled,
+            s.installed_skills.clone(),
+            old_overrides,
+            s.skill_dir_overrides.clone(),
+            s.skill_assets.clone(),
+        )
+    };
+
+    match &path {
+        Some(dir) => info!("Skills directory for {tool_id} set to {dir}"),
+        None => info!("Skills directory for {tool_id} reset to default"),
+    }
+    if !enabled || old_overrides.get(&tool_id) == new_overrides.get(&tool_id) {
+        return Ok(Vec::new());
+    }
+
+    if let Err(e) =
+        skills_config::remove_all_skills_for_tool(&tool_id, &installed_skills, &old_overrides)
+    {
+        warn!("Failed to remove skills from previous directory for {tool_id}: {e}");
+    }
+    let tools = skills_config::get_skill_tool_definitions(&new_overrides)?;
+    let tool = tools
+        .iter()
+        .find(|t| t.id == tool_id)
+        .ok_or_else(|| AppError::Validation(format!("Unknown skill tool: {tool_id}")))?;
+    let (to_sync, conflicts) = without_conflicts(&tool.skills_dir, installed_skills, tool.name);
+    let mut synced = HashMap::new();
+    match skills_config::sync_skills_for_tool(
+        &tool_id,
+        &to_sync,
+        &mut synced,
+        &new_overrides,
+        &store,
+    ) {
+        Ok(written) => info!("Moved {written} skill(s) for {tool_id} to the new directory"),
+        Err(e) => warn!("Failed to sync skills for {tool_id}: {e}"),
+    }
+    let mut s = state.lock().unwrap();
+    s.skill_sync_hashes.insert(tool_id, synced);
+    persistence::save_skill_sync_hashes(&app, &s.skill_sync_hashes);
+    Ok(conflicts)
+}
+
+/// How to settle a [`SkillContentConflict`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum SkillConflictResolution {
+    /// Adopt the on-disk SKILL.md as the tracked content everywhere.
+    KeepTheirs,
+    /// Replace the on-disk SKILL.md with the tracked content.
+    Overwrite,
+    /// Import the on-disk version as a new local skill, then restore the
+    /// tracked content under the original name.
+    #[serde(rename_all = "camelCase")]
+    Rename { new_skill_id: String },
+}
+
+#[tauri::command]
+pub async fn resolve_skill_conflict(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    tool_id: String,
+    skill_id: String,
+    resolution: SkillConflictResolution,
+) -> Result<(), AppError> {
+    if !is_safe_skill_dir(&skill_id) {
+        return Err(AppError::Validation(format!(
+            "Invalid skill name: {skill_id}"
+        )));
+    }
+    let dir_overrides = state.lock().unwrap().skill_dir_overrides.clone();
+    let tools = skills_config::get_skill_tool_definitions(&dir_overrides)?;
+    let tool = tools
+        .iter()
+        .find(|t| t.id == tool_id)
+        .ok_or_else(|| AppError::Validation(format!("Unknown skill tool: {tool_id}")))?;
+    let disk_path = tool.skills_dir.join(&skill_id).join("SKILL.md");
+    let disk_content = std::fs::read_to_string(&disk_path)?;
+
+    let (tracked, integrations) = {
+        let s = state.lock().unwrap();
+        let skill = s
+            .installed_skills
+            .iter()
+            .find(|sk| sk.skill_id == skill_id)
+            .cloned()
+            .ok_or_else(|| AppError::Validation(format!("Skill not found: {skill_id}")))?;
+        (skill, SkillTargets::from_state(&s))
+    };
+
+    // Tools now holding the resolved copy, keyed by the skill ID written
+    let mut written: Vec<(&str, String, String)> = Vec::new();
+    let enabled_tools: Vec<&'static str> = if tracked.enabled {
+        integrations.tools()?.iter().map(|t| t.id).collect()
+    } else {
+        Vec::new()
+    };
+
+    match resolution {
+        SkillConflictResolution::KeepTheirs => {
+            {
+                let mut s = state.lock().unwrap();
+                if let Some(skill) = s.installed_skills.iter_mut().find(|sk| sk.id == tracked.id) {
+                    adopt_disk_content(skill, &disk_content);
+                }
+                persistence::save_installed_skills(&app, &s.installed_skills);
+            }
+            if tracked.enabled {
+                skills_config::write_skill(
+                    &skill_id,
+                    &disk_content,
+                    &tracked.assets,
+                    &integrations,
+                )?;
+            }
+            let hash = skills_config::skill_files_hash(&disk_content, &tracked.assets);
+            for id in enabled_tools.iter().copied().chain([tool.id]) {
+                written.push((id, skill_id.clone(), hash.clone()));
+            }
+        }
+        SkillConflictResolution::Overwrite => {
+            std::fs::write(&disk_path, &tracked.content)?;
+            if tracked.enabled {
+                skills_config::write_skill(
+                    &skill_id,
+                    &tracked.content,
+                    &tracked.assets,
+                    &integrations,
+                )?;
+            }
+            let hash = skills_config::skill_files_hash(&tracked.content, &tracked.assets);
+            for id in enabled_tools.iter().copied().chain([tool.id]) {
+                written.push((id, skill_id.clone(), hash.clone()));
+            }
+        }
+        SkillConflictResolution::Rename { new_skill_id } => {
+            if !is_safe_skill_dir(&new_skill_id) {
+                return Err(AppError::Validation(format!(
+                    "Invalid skill name: {new_skill_id}"
+                )));
+            }
+            let (fm, _body) = parse_frontmatter(&disk_content);
+            let disk_assets = skills_config::read_skill_assets(
+                &tool.skills_dir.join(&skill_id),
+                &integrations.assets,
+            );
+            let renamed = InstalledSkill {
+                id: format!("local:{tool_id}/{new_skill_id}"),
+                name: fm.name.unwrap_or_else(|| new_skill_id.clone()),
+                skill_id: new_skill_id.clone(),
+                source: "local".to_string(),
+                description: fm.description.unwrap_or_default(),
+                content: disk_content.clone(),
+                enabled: true,
+                installs: None,
+                managed: None,
+                managed_by: None,
+                content_hash: Some(content_hash(&disk_content)),
+                assets: disk_assets.clone(),
+            };
+            {
+                let mut s = state.lock().unwrap();
+                if s.installed_skills
+                    .iter()
+                    .any(|sk| sk.skill_id == new_skill_id)
+                {
+                    return Err(AppError::Validation(format!(
+                        "Skill already installed: {new_skill_id}"
+                    )));
+                }
+                s.installed_skills.push(renamed);
+                persistence::save_installed_skills(&app, &s.installed_skills);
+            }
+            skills_config::write_skill(&new_skill_id, &disk_content, &disk_assets, &integrations)?;
+            std::fs::write(&disk_path, &tracked.content)?;
+            let renamed_hash = skills_config::skill_files_hash(&disk_content, &disk_assets);
+            for id in integrations.tools()?.iter().map(|t| t.id) {
+                written.push((id, new_skill_id.clone(), renamed_hash.clone()));
+            }
+            let hash = skills_config::skill_files_hash(&tracked.content, &tracked.assets);
+            written.push((tool.id, skill_id.clone(), hash));
+        }
+    }
+
+    // Record what was written so the next sync doesn't see a local edit
+    {
+        let mut s = state.lock().unwrap();
+        for (id, skill, hash) in written {
+            s.skill_sync_hashes
+                .entry(id.to_string())
+                .or_default()
+                .insert(skill, hash);
+        }
+        persistence::save_skill_sync_hashes(&app, &s.skill_sync_hashes);
+    }
+
+    info!("Resolved skill conflict for {skill_id} in {}", tool.name);
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Skill bundles — export/import the whole library as a directory
+// ---------------------------------------------------------------------------
+
+const BUNDLE_MANIFEST: &str = "manifest.json";
+const BUNDLE_VERSION: u32 = 1;
+
+/// `manifest.json` at the root of a skills bundle. Each skill's content lives
+/// in `<skillId>/SKILL.md` next to it, with its assets alongside.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SkillsBundleManifest {
+    version: u32,
+    skills: Vec<BundledSkill>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledSkill {
+    id: String,
+    name: String,
+    skill_id: String,
+    source: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "default_true")]
+    enabled: bool,
+    #[serde(default)]
+    installs: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// What to do when a bundled skill is already installed.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillConflict {
+    #[default]
+    Skip,
+    Replace,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillsBundleImport {
+    pub installed: Vec<String>,
+    pub replaced: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// A skill directory name must be a single path component, since it is
+/// joined onto a tool's skills directory.
+fn is_safe_skill_dir(skill_id: &str) -> bool {
+    !skill_id.is_empty() && skill_id != "." && skill_id != ".." && !skill_id.contains(['/', '\\'])
+}
+
+/// Write user-installed skills into `path` as a bundle directory — all of
+/// them, or only those in `ids`. Managed skills are left out — their features
+/// reinstall them.
+#[tauri::command]
+pub async fn export_skills_bundle(
+    state: State<'_, SharedState>,
+    path: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, AppError> {
+    let (skills, store): (Vec<InstalledSkill>, _) = {
+        let s = state.lock().unwrap();
+        let skills = s
+            .installed_skills
+            .iter()
+            .filter(|sk| sk.managed_by.is_none() && sk.managed != Some(true))
+            .filter(|sk| ids.as_ref().is_none_or(|ids| ids.contains(&sk.id)))
+            .cloned()
+            .collect();
+        (skills, s.skill_assets.clone())
+    };
+
+    let root = Path::new(&path);
+    std::fs::create_dir_all(root)?;
+    let mut manifest = SkillsBundleManifest {
+        version: BUNDLE_VERSION,
+        skills: Vec::with_capacity(skills.len()),
+    };
+    for skill in skills {
+        if !is_safe_skill_dir(&skill.skill_id) {
+            warn!("Skipping skill {} with unsafe directory name", skill.id);
+            continue;
+        }
+        skills_config::write_skill_files(
+            &root.join(&skill.skill_id),
+            &skill.content,
+            &skill.assets,
+            &store,
+        )?;
+        manifest.skills.push(BundledSkill {
+            id: skill.id,
+            name: skill.name,
+            skill_id: skill.skill_id,
+            source: skill.source,
+            description: skill.description,
+            enabled: skill.enabled,
+            installs: skill.installs,
+        });
+    }
+    std::fs::write(
+        root.join(BUNDLE_MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    info!("Exported {} skill(s) to {path}", manifest.skills.len());
+    Ok(manifest.skills.len())
+}
+
+/// Install the skills listed in a bundle's manifest. Skills that conflict
+/// with an installed one are skipped or replaced per `conflict`; managed
+/// skills are never replaced.
+#[tauri::command]
+pub async fn import_skills_bundle(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    path: String,
+    conflict: Option<SkillConflict>,
+) -> Result<SkillsBundleImport, AppError> {
+    let conflict = conflict.unwrap_or_default();
+    let root = Path::new(&path);
+    let manifest: SkillsBundleManifest =
+        serde_json::from_str(&std::fs::read_to_string(root.join(BUNDLE_MANIFEST))?)?;
+    if manifest.version > BUNDLE_VERSION {
+        return Err(AppError::Validation(format!(
+            "Skills bundle version {} is newer than this app supports",
+            manifest.version
+        )));
+    }
+
+    // Read everything from the bundle before taking the state lock
+    let store = state.lock().unwrap().skill_assets.clone();
+    let mut result = SkillsBundleImport::default();
+    let mut incoming = Vec::with_capacity(manifest.skills.len());
+    for bundled in manifest.skills {
+        if !is_safe_skill_dir(&bundled.skill_id) {
+            warn!(
+                "Skipping bundled skill {} with unsafe directory name",
+                bundled.id
+            );
+            result.skipped.push(bundled.id);
+            continue;
+        }
+        let skill_dir = root.join(&bundled.skill_id);
+        let content = match std::fs::read_to_string(skill_dir.join("SKILL.md")) {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("Skipping bundled skill {}: {e}", bundled.id);
+                result.skipped.push(bundled.id);
+                continue;
+            }
+        };
+        incoming.push(InstalledSkill {
+            id: bundled.id,
+            name: bundled.name,
+            skill_id: bundled.skill_id,
+            source: bundled.source,
+            description: bundled.description,
+            content_hash: Some(content_hash(&content)),
+            content,
+            enabled: bundled.enabled,
+            installs: bundled.installs,
+            managed: None,
+            managed_by: None,
+            assets: skills_config::read_skill_assets(&skill_dir, &store),
+        });
+    }
+
+    let (to_write, to_remove, integrations) = {
+        let mut s = state.lock().unwrap();
+        let (to_write, to_remove) =
+            merge_bundled_skills(&mut s.installed_skills, incoming, conflict, &mut result);
+        persistence::save_installed_skills(&app, &s.installed_skills);
+        (to_write, to_remove, SkillTargets::from_state(&s))
+    };
+
+    // Clear out replaced skills first so files they no longer ship don't linger
+    for old in to_remove {
+        if let Err(e) = skills_config::remove_skill(&old.skill_id, &old.assets, &integrations) {
+            warn!(
+                "Failed to remove replaced skill files for {}: {e}",
+                old.skill_id
+            );
+        }
+    }
+    for skill in to_write {
+        if let Err(e) = skills_config::write_skill(
+            &skill.skill_id,
+            &skill.content,
+            &skill.assets,
+            &integrations,
+        ) {
+            warn!("Failed to write skill files for {}: {e}", skill.skill_id);
+        }
+    }
+
+    info!(
+        "Imported skills bundle from {path}: {} installed, {} replaced, {} skipped",
+        result.installed.len(),
+        result.replaced.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
+/// Merge bundled skills into `installed`, recording each outcome in `result`.
+/// A bundled skill conflicts with every installed skill that shares its ID or
+/// its directory name, since both would write the same files. Returns the
+/// enabled skills to write and the replaced skills whose files to remove.
+fn merge_bundled_skills(
+    installed: &mut Vec<InstalledSkill>,
+    incoming: Vec<InstalledSkill>,
+    conflict: SkillConflict,
+    result: &mut SkillsBundleImport,
+) -> (Vec<InstalledSkill>, Vec<InstalledSkill>) {
+    let mut to_write = Vec::new();
+    let mut to_remove = Vec::new();
+    let mut imported: Vec<(String, String)> = Vec::new();
+    for skill in incoming {
+        if imported
+            .iter()
+            .any(|(id, dir)| *id == skill.id || *dir == skill.skill_id)
+        {
+            warn!(
+                "Skipping bundled skill {}: the bundle lists its ID or directory twice",
+                skill.id
+            );
+            result.skipped.push(skill.id);
+            continue;
+        }
+
+        let matched: Vec<usize> = installed
+            .iter()
+            .enumerate()
+            .filter(|(_, sk)| sk.id == skill.id || sk.skill_id == skill.skill_id)
+            .map(|(i, _)| i)
+            .collect();
+        let managed = matched
+            .iter()
+            .any(|&i| installed[i].managed_by.is_some() || installed[i].managed == Some(true));
+        if !matched.is_empty() && (managed || matches!(conflict, SkillConflict::Skip)) {
+            result.skipped.push(skill.id);
+            continue;
+        }
+
+        imported.push((skill.id.clone(), skill.skill_id.clone()));
+        if skill.enabled {
+            to_write.push(skill.clone());
+        }
+        let id = skill.id.clone();
+        // Replace the first conflicting skill in place and drop the rest
+        match matched.split_first() {
+            Some((&first, rest)) => {
+                for &i in rest.iter().rev() {
+                    to_remove.push(installed.remove(i));
+                }
+                to_remove.push(std::mem::replace(&mut installed[first], skill));
+                result.replaced.push(id);
+            }
+            None => {
+                installed.push(skill);
+                result.installed.push(id);
+            }
+        }
+    }
+    (to_write, to_remove)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(content: &str) -> Vec<SkillLintKind> {
+        lint_skill(content).into_iter().map(|w| w.kind).collect()
+    }
+
+    #[test]
+    fn accepts_complete_skill() {
+        let body = "Use this skill when reviewing pull requests. ".repeat(4);
+        let content = format!("---\nname: review\ndescription: Review PRs\n---\n{body}");
+        assert!(kinds(&content).is_empty());
+    }
+
+    #[test]
+    fn distinguishes_missing_and_invalid_frontmatter() {
+        assert_eq!(
+            kinds("just a body")[..2],
+            [
+                SkillLintKind::MissingFrontmatter,
+                SkillLintKind::MissingName
+            ]
+        );
+        assert_eq!(
+            kinds("---\nname: [unclosed\n---\nbody")[0],
+            SkillLintKind::InvalidYaml
+        );
+        assert_eq!(kinds("---\nname: x\nbody")[0], SkillLintKind::InvalidYaml);
+    }
+
+    #[test]
+    fn flags_empty_description_and_short_body() {
+        assert_eq!(
+            kinds("---\nname: x\ndescription: \"\"\n---\nshort"),
+            vec![SkillLintKind::EmptyDescription, SkillLintKind::ShortBody]
+        );
+    }
+
+    fn skill(skill_id: &str, content: &str) -> InstalledSkill {
+        InstalledSkill {
+            id: format!("owner/repo/{skill_id}"),
+            name: skill_id.into(),
+            skill_id: skill_id.into(),
+            source: "owner/repo".into(),
+            description: String::new(),
+            content: content.into(),
+            enabled: true,
+            installs: None,
+            managed: None,
+            managed_by: None,
+            content_hash: None,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn skill_dirs_must_be_one_path_component() {
+        assert!(is_safe_skill_dir("find-skills"));
+        for id in ["", ".", "..", "../etc", "a/b", "a\\b", "/abs"] {
+            assert!(!is_safe_skill_dir(id), "{id:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn edited_skills_are_reported_and_not_synced() {
+        let dir = std::env::temp_dir().join(format!("agent-hub-skill-dir-{}", std::process::id()));
+        for (id, content) in [("edited", "changed on disk"), ("same", "tracked")] {
+            std::fs::create_dir_all(dir.join(id)).unwrap();
+            std::fs::write(dir.join(id).join("SKILL.md"), content).unwrap();
+        }
+        let installed = vec![
+            skill("edited", "tracked"),
+            skill("same", "tracked"),
+            skill("absent", "tracked"),
+        ];
+
+        let (to_sync, conflicts) = without_conflicts(&dir, installed, "Test");
+        std::fs::remove_dir_all(&dir).ok();
+
+        let synced: Vec<_> = to_sync.iter().map(|s| s.skill_id.as_str()).collect();
+        assert_eq!(synced, ["same", "absent"]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].skill_id, "edited");
+        assert_eq!(conflicts[0].installed_id, "owner/repo/edited");
+    }
+
+    #[test]
+    fn update_leaves_local_edits_alone() {
+        let dir = std::env::temp_dir().join(format!("agent-hub-skill-edit-{}", std::process::id()));
+        let tracked = skill("s", "tracked");
+        std::fs::create_dir_all(dir.join("s")).unwrap();
+
+        std::fs::write(dir.join("s/SKILL.md"), "tracked").unwrap();
+        assert!(local_edit(&dir, &tracked, None).is_none());
+
+        std::fs::write(dir.join("s/SKILL.md"), "edited").unwrap();
+        let conflict = local_edit(&dir, &tracked, None).expect("edit reported");
+        assert_eq!(conflict.skill_id, "s");
+        let other = skills_config::skill_files_hash("older", &[]);
+        assert!(local_edit(&dir, &tracked, Some(&other)).is_some());
+
+        // What was last synced there isn't a local edit
+        let synced = skills_config::skill_files_hash("edited", &[]);
+        assert!(local_edit(&dir, &tracked, Some(&synced)).is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(local_edit(&dir, &tracked, None).is_none());
+    }
+
+    #[test]
+    fn resolved_conflict_is_not_reported_again() {
+        let dir =
+            std::env::temp_dir().join(format!("agent-hub-skill-resolve-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("s")).unwrap();
+        std::fs::write(dir.join("s/SKILL.md"), "edited").unwrap();
+
+        // Keep theirs: the disk copy becomes the tracked content
+        let mut kept = skill("s", "tracked");
+        assert!(local_edit(&dir, &kept, None).is_some());
+        adopt_disk_content(&mut kept, "edited");
+        assert_eq!(
+            kept.content_hash.as_deref(),
+            Some(content_hash("edited").as_str())
+        );
+        let synced = skills_config::skill_files_hash(&kept.content, &kept.assets);
+        assert!(local_edit(&dir, &kept, Some(&synced)).is_none());
+        let (_, conflicts) = without_conflicts(&dir, vec![kept], "Test");
+        assert!(conflicts.is_empty());
+
+        // Overwrite: the tracked content goes back on disk
+        let overwritten = skill("s", "tracked");
+        std::fs::write(dir.join("s/SKILL.md"), &overwritten.content).unwrap();
+        let synced = skills_config::skill_files_hash(&overwritten.content, &overwritten.assets);
+        assert!(local_edit(&dir, &overwritten, Some(&synced)).is_none());
+        let (_, conflicts) = without_conflicts(&dir, vec![overwritten], "Test");
+        assert!(conflicts.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn bundle_import(
+        installed: &mut Vec<InstalledSkill>,
+        incoming: Vec<InstalledSkill>,
+        conflict: SkillConflict,
+    ) -> (SkillsBundleImport, Vec<InstalledSkill>, Vec<InstalledSkill>) {
+        let mut result = SkillsBundleImport::default();
+        let (to_write, to_remove) =
+            merge_bundled_skills(installed, incoming, conflict, &mut result);
+        (result, to_write, to_remove)
+    }
+
+    #[test]
+    fn bundle_skips_conflicts_by_id_or_directory() {
+        let mut other_dir = skill("other", "bundled");
+        other_dir.id = "owner/repo/tools".into();
+        let mut same_dir = skill("tools", "bundled");
+        same_dir.id = "someone/else/tools".into();
+        let mut installed = vec![skill("tools", "installed")];
+
+        let (result, to_write, to_remove) = bundle_import(
+            &mut installed,
+            vec![other_dir, same_dir, skill("fresh", "bundled")],
+            SkillConflict::Skip,
+        );
+
+        assert_eq!(result.skipped, ["owner/repo/tools", "someone/else/tools"]);
+        assert_eq!(result.installed, ["owner/repo/fresh"]);
+        assert!(result.replaced.is_empty());
+        assert_eq!(installed.len(), 2);
+        assert_eq!(installed[0].content, "installed");
+        assert_eq!(to_write.len(), 1);
+        assert!(to_remove.is_empty());
+    }
+
+    #[test]
+    fn bundle_replaces_every_skill_sharing_id_or_directory() {
+        let mut by_dir = skill("docs", "installed");
+        by_dir.id = "someone/else/docs".into();
+        let mut installed = vec![skill("tools", "installed"), by_dir];
+        let mut bundled = skill("docs", "bundled");
+        bundled.id = "owner/repo/tools".into();
+
+        let (result, to_write, to_remove) =
+            bundle_import(&mut installed, vec![bundled], SkillConflict::Replace);
+
+        assert_eq!(result.replaced, ["owner/repo/tools"]);
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].skill_id, "docs");
+        assert_eq!(installed[0].content, "bundled");
+        assert_eq!(to_write.len(), 1);
+        let mut removed: Vec<_> = to_remove.iter().map(|s| s.id.as_str()).collect();
+        removed.sort();
+        assert_eq!(removed, ["owner/repo/tools", "someone/else/docs"]);
+    }
+
+    #[test]
+    fn bundle_never_replaces_managed_skills_or_repeats_a_directory() {
+        let mut managed = skill("memory", "installed");
+        managed.managed_by = Some("memory".into());
+        let mut installed = vec![managed];
+        let mut disabled = skill("fresh", "bundled");
+        disabled.enabled = false;
+        let mut repeat = skill("fresh", "again");
+        repeat.id = "someone/else/fresh".into();
+
+        let (result, to_write, to_remove) = bundle_import(
+            &mut installed,
+            vec![skill("memory", "bundled"), disabled, repeat],
+            SkillConflict::Replace,
+        );
+
+        assert_eq!(result.skipped, ["owner/repo/memory", "someone/else/fresh"]);
+        assert_eq!(result.installed, ["owner/repo/fresh"]);
+        assert_eq!(installed[0].content, "installed");
+        assert_eq!(installed.len(), 2);
+        assert!(to_write.is_empty());
+        assert!(to_remove.is_empty());
+    }
+}
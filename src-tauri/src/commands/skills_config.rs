@@ -1,9 +1,14 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use sha2::{Digest, Sha256};
 use tracing::{info, warn};
 
 use crate::error::AppError;
-use crate::state::skill::InstalledSkill;
+use crate::state::skill::{
+    InstalledSkill, SkillAsset, SkillAssetStore, MAX_SKILL_ASSETS, MAX_SKILL_ASSET_BYTES,
+};
+use crate::state::AppState;
 
 // ---------------------------------------------------------------------------
 // Tool definitions — which AI tools support skills and where they go
@@ -24,7 +29,21 @@ fn home_dir() -> Result<PathBuf, AppError> {
     })
 }
 
-pub fn get_skill_tool_definitions() -> Result<Vec<SkillToolDef>, AppError> {
+/// All skill-capable tools. `dir_overrides` maps a tool ID to a skills
+/// directory that replaces the tool's default location.
+pub fn get_skill_tool_definitions(
+    dir_overrides: &HashMap<String, String>,
+) -> Result<Vec<SkillToolDef>, AppError> {
+    let mut tools = default_skill_tool_definitions()?;
+    for tool in &mut tools {
+        if let Some(dir) = dir_overrides.get(tool.id) {
+            tool.skills_dir = PathBuf::from(dir);
+        }
+    }
+    Ok(tools)
+}
+
+fn default_skill_tool_definitions() -> Result<Vec<SkillToolDef>, AppError> {
     let home = home_dir()?;
     Ok(vec![
         SkillToolDef {
@@ -55,6 +74,33 @@ pub fn get_skill_tool_definitions() -> Result<Vec<SkillToolDef>, AppError> {
     ])
 }
 
+/// Where skill files get written: the tools with skills enabled and any
+/// skills directory overrides, plus the store their assets are read from.
+/// Snapshot it from `AppState` under the lock.
+#[derive(Debug, Clone)]
+pub struct SkillTargets {
+    pub integrations: Vec<String>,
+    pub dir_overrides: HashMap<String, String>,
+    pub assets: SkillAssetStore,
+}
+
+impl SkillTargets {
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            integrations: state.enabled_skill_integrations.clone(),
+            dir_overrides: state.skill_dir_overrides.clone(),
+            assets: state.skill_assets.clone(),
+        }
+    }
+
+    /// The enabled tools, with overridden directories applied.
+    pub fn tools(&self) -> Result<Vec<SkillToolDef>, AppError> {
+        let mut tools = get_skill_tool_definitions(&self.dir_overrides)?;
+        tools.retain(|t| self.integrations.iter().any(|i| i == t.id));
+        Ok(tools)
+    }
+}
+
 /// Returns whether a given integration ID supports skills.
 pub fn supports_skills(integration_id: &str) -> bool {
     matches!(
@@ -64,101 +110,391 @@ pub fn supports_skills(integration_id: &str) -> bool {
 }
 
 // ---------------------------------------------------------------------------
-// Write / remove SKILL.md files
+// Skill assets — supporting files next to SKILL.md
 // ---------------------------------------------------------------------------
 
-/// Write a skill's SKILL.md to all enabled tool directories.
-pub fn write_skill(
-    skill_id: &str,
+/// Collect every file under `skill_dir` except the top-level SKILL.md into
+/// `store`. Hidden entries and symlinks are skipped; collection stops at
+/// [`MAX_SKILL_ASSETS`] files or [`MAX_SKILL_ASSET_BYTES`] in total.
+pub fn read_skill_assets(skill_dir: &Path, store: &SkillAssetStore) -> Vec<SkillAsset> {
+    let mut assets = Vec::new();
+    let mut total = 0u64;
+    let mut pending = vec![(skill_dir.to_path_buf(), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(e) => e,
+            Err(e) => {
+                warn!("Failed to read {}: {e}", dir.display());
+                continue;
+            }
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let Some(name) = entry.file_name().to_str().map(String::from) else {
+                continue;
+            };
+            if name.starts_with('.') {
+                continue;
+            }
+            let rel = format!("{prefix}{name}");
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push((entry.path(), format!("{rel}/")));
+                continue;
+            }
+            if !file_type.is_file() || !SkillAsset::is_safe_path(&rel) {
+                continue;
+            }
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if assets.len() >= MAX_SKILL_ASSETS || total + size > MAX_SKILL_ASSET_BYTES {
+                warn!(
+                    "Skill directory {} exceeds asset limits; ignoring {rel} and the rest",
+                    skill_dir.display()
+                );
+                return assets;
+            }
+            match std::fs::read(entry.path()).and_then(|bytes| store.put(rel, &bytes)) {
+                Ok(asset) => {
+                    total += size;
+                    assets.push(asset);
+                }
+                Err(e) => warn!("Failed to store {}: {e}", entry.path().display()),
+            }
+        }
+    }
+
+    assets
+}
+
+/// Write SKILL.md and all assets, read from `store`, into `skill_dir`.
+pub fn write_skill_files(
+    skill_dir: &Path,
     content: &str,
-    enabled_skill_integrations: &[String],
+    assets: &[SkillAsset],
+    store: &SkillAssetStore,
 ) -> Result<(), AppError> {
-    let tools = get_skill_tool_definitions()?;
+    std::fs::create_dir_all(skill_dir)?;
+    std::fs::write(skill_dir.join("SKILL.md"), content)?;
 
-    for tool in &tools {
-        if !enabled_skill_integrations.contains(&tool.id.to_string()) {
+    for asset in assets {
+        if !SkillAsset::is_safe_path(&asset.path) {
+            warn!("Skipping unsafe skill asset path: {}", asset.path);
             continue;
         }
+        let bytes = store.read(asset).map_err(|e| {
+            AppError::Validation(format!("Missing data for skill asset {}: {e}", asset.path))
+        })?;
+        let path = skill_dir.join(&asset.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, bytes)?;
+    }
 
-        let skill_dir = tool.skills_dir.join(skill_id);
-        std::fs::create_dir_all(&skill_dir)?;
+    Ok(())
+}
+
+/// Remove SKILL.md and the tracked assets from `skill_dir`, then any
+/// directories left empty. Files the user added themselves are kept.
+fn remove_skill_files(skill_dir: &Path, assets: &[SkillAsset]) -> Result<(), AppError> {
+    remove_tracked_files(
+        skill_dir,
+        std::iter::once("SKILL.md").chain(asset_paths(assets)),
+    )
+}
+
+fn asset_paths(assets: &[SkillAsset]) -> impl Iterator<Item = &str> {
+    assets
+        .iter()
+        .map(|a| a.path.as_str())
+        .filter(|p| SkillAsset::is_safe_path(p))
+}
+
+fn remove_tracked_files<'a>(
+    skill_dir: &Path,
+    files: impl Iterator<Item = &'a str>,
+) -> Result<(), AppError> {
+    if !skill_dir.exists() {
+        return Ok(());
+    }
+
+    let mut dirs = vec![skill_dir.to_path_buf()];
+    for rel in files {
+        let path = skill_dir.join(rel);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        let mut parent = path.parent();
+        while let Some(dir) = parent.filter(|d| *d != skill_dir && d.starts_with(skill_dir)) {
+            dirs.push(dir.to_path_buf());
+            parent = dir.parent();
+        }
+    }
 
-        let skill_path = skill_dir.join("SKILL.md");
-        std::fs::write(&skill_path, content)?;
-        info!("Wrote SKILL.md to {} for {}", skill_path.display(), tool.name);
+    // Deepest first so parents are empty by the time we reach them
+    dirs.sort_by(|a, b| {
+        b.components()
+            .count()
+            .cmp(&a.components().count())
+            .then_with(|| a.cmp(b))
+    });
+    dirs.dedup();
+    for dir in dirs {
+        // Fails (and is left alone) when the directory still has other files
+        let _ = std::fs::remove_dir(&dir);
     }
 
     Ok(())
 }
 
-/// Remove a skill's directory from all enabled tool directories.
-pub fn remove_skill(
+// ---------------------------------------------------------------------------
+// Write / remove skill files
+// ---------------------------------------------------------------------------
+
+/// Write a skill's SKILL.md and assets to all enabled tool directories.
+pub fn write_skill(
     skill_id: &str,
-    enabled_skill_integrations: &[String],
+    content: &str,
+    assets: &[SkillAsset],
+    targets: &SkillTargets,
 ) -> Result<(), AppError> {
-    let tools = get_skill_tool_definitions()?;
+    for tool in &targets.tools()? {
+        let skill_dir = tool.skills_dir.join(skill_id);
+        write_skill_files(&skill_dir, content, assets, &targets.assets)?;
+        info!(
+            "Wrote skill {skill_id} ({} asset(s)) to {} for {}",
+            assets.len(),
+            skill_dir.display(),
+            tool.name
+        );
+    }
 
-    for tool in &tools {
-        if !enabled_skill_integrations.contains(&tool.id.to_string()) {
-            continue;
-        }
+    Ok(())
+}
 
+/// Remove a skill's SKILL.md and tracked assets from all enabled tool directories.
+pub fn remove_skill(
+    skill_id: &str,
+    assets: &[SkillAsset],
+    targets: &SkillTargets,
+) -> Result<(), AppError> {
+    for tool in &targets.tools()? {
         let skill_dir = tool.skills_dir.join(skill_id);
         if skill_dir.exists() {
-            std::fs::remove_dir_all(&skill_dir)?;
-            info!("Removed skill dir {} for {}", skill_dir.display(), tool.name);
+            remove_skill_files(&skill_dir, assets)?;
+            info!(
+                "Removed skill files from {} for {}",
+                skill_dir.display(),
+                tool.name
+            );
         }
     }
 
     Ok(())
 }
 
-/// Sync all installed skills for a specific tool.
-/// Writes enabled skills and removes disabled ones.
+/// Write an updated skill to each enabled tool not in `skip_tools`, first
+/// removing the `stale` assets the update dropped. Returns the IDs of the
+/// tools written.
+pub fn write_skill_update(
+    skill_id: &str,
+    content: &str,
+    assets: &[SkillAsset],
+    stale: &[SkillAsset],
+    targets: &SkillTargets,
+    skip_tools: &[&str],
+) -> Result<Vec<&'static str>, AppError> {
+    let mut written = Vec::new();
+    for tool in targets.tools()? {
+        if skip_tools.contains(&tool.id) {
+            continue;
+        }
+        let skill_dir = tool.skills_dir.join(skill_id);
+        remove_tracked_files(&skill_dir, asset_paths(stale))?;
+        write_skill_files(&skill_dir, content, assets, &targets.assets)?;
+        info!(
+            "Updated skill {skill_id} in {} for {}",
+            skill_dir.display(),
+            tool.name
+        );
+        written.push(tool.id);
+    }
+
+    Ok(written)
+}
+
+/// Hash of everything [`write_skill_files`] writes for a skill.
+pub fn skill_files_hash(content: &str, assets: &[SkillAsset]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    for asset in assets {
+        // Separators keep ("a", "bc") and ("ab", "c") from hashing the same
+        hasher.update([0]);
+        hasher.update(asset.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(asset.sha256.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Sync all installed skills for a specific tool, reading assets from `store`.
+/// Writes enabled skills and removes disabled ones. `synced` holds the
+/// [`skill_files_hash`] last written per skill ID for this tool; skills whose
+/// hash is unchanged and whose SKILL.md is still intact on disk are left
+/// alone so their mtimes don't change. Returns how many skills were written.
 pub fn sync_skills_for_tool(
     tool_id: &str,
     installed_skills: &[InstalledSkill],
-) -> Result<(), AppError> {
-    let tools = get_skill_tool_definitions()?;
+    synced: &mut HashMap<String, String>,
+    dir_overrides: &HashMap<String, String>,
+    store: &SkillAssetStore,
+) -> Result<usize, AppError> {
+    let tools = get_skill_tool_definitions(dir_overrides)?;
     let tool = tools.iter().find(|t| t.id == tool_id).ok_or_else(|| {
         AppError::Validation(format!("Unknown skill tool: {tool_id}"))
     })?;
 
+    let mut written = 0;
     for skill in installed_skills {
         let skill_dir = tool.skills_dir.join(&skill.skill_id);
 
         if skill.enabled {
-            std::fs::create_dir_all(&skill_dir)?;
-            let skill_path = skill_dir.join("SKILL.md");
-            std::fs::write(&skill_path, &skill.content)?;
+            let hash = skill_files_hash(&skill.content, &skill.assets);
+            let unchanged = synced.get(&skill.skill_id) == Some(&hash)
+                && std::fs::read_to_string(skill_dir.join("SKILL.md"))
+                    .is_ok_and(|disk| disk == skill.content);
+            if unchanged {
+                continue;
+            }
+            write_skill_files(&skill_dir, &skill.content, &skill.assets, store)?;
+            synced.insert(skill.skill_id.clone(), hash);
+            written += 1;
             info!("Synced skill {} to {}", skill.skill_id, tool.name);
-        } else if skill_dir.exists() {
-            std::fs::remove_dir_all(&skill_dir)?;
-            info!("Removed disabled skill {} from {}", skill.skill_id, tool.name);
+        } else {
+            synced.remove(&skill.skill_id);
+            if skill_dir.exists() {
+                remove_skill_files(&skill_dir, &skill.assets)?;
+                info!("Removed disabled skill {} from {}", skill.skill_id, tool.name);
+            }
         }
     }
 
-    Ok(())
+    Ok(written)
 }
 
 /// Remove all managed skill files from a specific tool.
 pub fn remove_all_skills_for_tool(
     tool_id: &str,
     installed_skills: &[InstalledSkill],
+    dir_overrides: &HashMap<String, String>,
 ) -> Result<(), AppError> {
-    let tools = get_skill_tool_definitions()?;
+    let tools = get_skill_tool_definitions(dir_overrides)?;
     let tool = tools.iter().find(|t| t.id == tool_id).ok_or_else(|| {
         AppError::Validation(format!("Unknown skill tool: {tool_id}"))
     })?;
 
     for skill in installed_skills {
         let skill_dir = tool.skills_dir.join(&skill.skill_id);
-        if skill_dir.exists() {
-            if let Err(e) = std::fs::remove_dir_all(&skill_dir) {
-                warn!("Failed to remove {} from {}: {e}", skill.skill_id, tool.name);
-            }
+        if let Err(e) = remove_skill_files(&skill_dir, &skill.assets) {
+            warn!("Failed to remove {} from {}: {e}", skill.skill_id, tool.name);
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("agent-hub-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn round_trips_skill_tree_and_keeps_user_files() {
+        let dir = temp_dir("skill");
+        let store_dir = temp_dir("skill-store");
+        let store = SkillAssetStore::new(&store_dir);
+        let assets = vec![
+            store.put("scripts/run.sh".into(), b"echo hi").unwrap(),
+            store
+                .put("references/deep/notes.md".into(), &[0u8, 159, 146, 150])
+                .unwrap(),
+        ];
+
+        write_skill_files(&dir, "---\nname: x\n---\n", &assets, &store).unwrap();
+        assert_eq!(
+            std::fs::read(dir.join("scripts/run.sh")).unwrap(),
+            b"echo hi"
+        );
+        std::fs::write(dir.join("scripts/mine.txt"), "keep").unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+
+        let mut read = read_skill_assets(&dir, &store);
+        read.retain(|a| a.path != "scripts/mine.txt");
+        read.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(read, vec![assets[1].clone(), assets[0].clone()]);
+
+        remove_skill_files(&dir, &assets).unwrap();
+        assert!(dir.join("scripts/mine.txt").exists());
+        assert!(!dir.join("SKILL.md").exists());
+        assert!(!dir.join("references").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&store_dir);
+    }
+
+    #[test]
+    fn removes_only_tracked_files_and_emptied_dirs() {
+        let dir = temp_dir("skill-remove");
+        std::fs::create_dir_all(dir.join("a/b")).unwrap();
+        std::fs::create_dir_all(dir.join("c")).unwrap();
+        std::fs::write(dir.join("a/b/tracked.txt"), "").unwrap();
+        std::fs::write(dir.join("c/tracked.txt"), "").unwrap();
+        std::fs::write(dir.join("c/mine.txt"), "").unwrap();
+
+        remove_tracked_files(
+            &dir,
+            ["a/b/tracked.txt", "c/tracked.txt", "never/existed.txt"].into_iter(),
+        )
+        .unwrap();
+
+        assert!(!dir.join("a").exists());
+        assert!(!dir.join("c/tracked.txt").exists());
+        assert!(dir.join("c/mine.txt").exists());
+        assert!(dir.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unsafe_asset_paths_are_never_removed() {
+        let root = temp_dir("skill-escape");
+        let dir = root.join("skill");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(root.join("outside.txt"), "").unwrap();
+        let outside = root.join("outside.txt").display().to_string();
+        let assets: Vec<SkillAsset> = ["../outside.txt", outside.as_str(), "..\\outside.txt"]
+            .into_iter()
+            .map(|path| SkillAsset {
+                path: path.into(),
+                sha256: String::new(),
+            })
+            .collect();
+
+        remove_tracked_files(&dir, asset_paths(&assets)).unwrap();
+
+        assert!(root.join("outside.txt").exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
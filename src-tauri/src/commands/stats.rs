@@ -1,8 +1,29 @@
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
 
 use crate::error::AppError;
 use crate::persistence::save_stats;
-use crate::stats::{ServerStats, StatsStore};
+use crate::state::SharedState;
+use crate::stats::{CallMetrics, ServerStats, StatsStore, ToolCallEntry};
+
+/// Tool-call metrics across all servers, plus a breakdown per server.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStats {
+    #[serde(flatten)]
+    pub overall: CallMetrics,
+    pub servers: Vec<ServerCallMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCallMetrics {
+    pub server_id: String,
+    #[serde(flatten)]
+    pub metrics: CallMetrics,
+}
 
 #[tauri::command]
 pub async fn get_server_stats(
@@ -13,6 +34,133 @@ pub async fn get_server_stats(
     Ok(store.get(&server_id).cloned().unwrap_or_default())
 }
 
+/// Call counts and p50/p95 latency for the dashboard. Percentiles cover each
+/// server's recent-call window rather than all-time history.
+#[tauri::command]
+pub async fn get_proxy_stats(stats_store: State<'_, StatsStore>) -> Result<ProxyStats, AppError> {
+    let store = stats_store.read().await;
+
+    let mut servers: Vec<ServerCallMetrics> = store
+        .iter()
+        .map(|(id, stats)| ServerCallMetrics {
+            server_id: id.clone(),
+            metrics: CallMetrics::from_calls(
+                stats.total_calls,
+                stats.errors,
+                &stats.recent_calls.iter().collect::<Vec<_>>(),
+            ),
+        })
+        .collect();
+    servers.sort_by(|a, b| a.server_id.cmp(&b.server_id));
+
+    let all_recent: Vec<_> = store.values().flat_map(|s| s.recent_calls.iter()).collect();
+    let overall = CallMetrics::from_calls(
+        store.values().map(|s| s.total_calls).sum(),
+        store.values().map(|s| s.errors).sum(),
+        &all_recent,
+    );
+
+    Ok(ProxyStats { overall, servers })
+}
+
+/// Optional filters for the proxy call log. Unset fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CallLogFilter {
+    /// Server ID or name.
+    pub server: Option<String>,
+    pub tool: Option<String>,
+    /// Inclusive Unix-seconds bounds.
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub errors_only: bool,
+}
+
+impl CallLogFilter {
+    fn matches(&self, server_id: &str, server_name: &str, call: &ToolCallEntry) -> bool {
+        self.server
+            .as_deref()
+            .is_none_or(|s| s == server_id || s.eq_ignore_ascii_case(server_name))
+            && self.tool.as_deref().is_none_or(|t| t == call.tool)
+            && self.since.is_none_or(|since| call.timestamp >= since)
+            && self.until.is_none_or(|until| call.timestamp <= until)
+            && (!self.errors_only || call.is_error)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallLogEntry {
+    pub server_id: String,
+    pub server_name: String,
+    #[serde(flatten)]
+    pub call: ToolCallEntry,
+}
+
+/// Recent proxy calls across all servers matching `filter`, oldest first.
+/// Covers each server's in-memory recent-call window.
+async fn filtered_call_log(
+    state: &SharedState,
+    stats_store: &StatsStore,
+    filter: &CallLogFilter,
+) -> Vec<CallLogEntry> {
+    let names: std::collections::HashMap<String, String> = {
+        let s = state.lock().unwrap();
+        s.servers
+            .iter()
+            .map(|srv| (srv.id.clone(), srv.name.clone()))
+            .collect()
+    };
+
+    let store = stats_store.read().await;
+    let mut entries: Vec<CallLogEntry> = store
+        .iter()
+        .flat_map(|(server_id, stats)| {
+            let server_name = names.get(server_id).cloned().unwrap_or_default();
+            stats
+                .recent_calls
+                .iter()
+                .filter(|call| filter.matches(server_id, &server_name, call))
+                .map(|call| CallLogEntry {
+                    server_id: server_id.clone(),
+                    server_name: server_name.clone(),
+                    call: call.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    entries.sort_by_key(|e| e.call.timestamp);
+    entries
+}
+
+#[tauri::command]
+pub async fn get_proxy_call_log(
+    state: State<'_, SharedState>,
+    stats_store: State<'_, StatsStore>,
+    filter: Option<CallLogFilter>,
+) -> Result<Vec<CallLogEntry>, AppError> {
+    Ok(filtered_call_log(&state, &stats_store, &filter.unwrap_or_default()).await)
+}
+
+/// Write the filtered call log to `path` as JSONL. Returns the number of entries.
+#[tauri::command]
+pub async fn export_proxy_log(
+    state: State<'_, SharedState>,
+    stats_store: State<'_, StatsStore>,
+    path: String,
+    filter: Option<CallLogFilter>,
+) -> Result<usize, AppError> {
+    let entries = filtered_call_log(&state, &stats_store, &filter.unwrap_or_default()).await;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+    for entry in &entries {
+        serde_json::to_writer(&mut file, entry)?;
+        file.write_all(b"\n")?;
+    }
+    file.flush()?;
+    Ok(entries.len())
+}
+
 #[tauri::command]
 pub async fn reset_server_stats(
     app: AppHandle,
@@ -3,11 +3,15 @@ use std::sync::Mutex;
 use std::time::Instant;
 
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tracing::info;
 
+use crate::child_processes::ChildProcesses;
 use crate::error::AppError;
+use crate::logging::{parse_level, Logging};
 use crate::mcp::client::SharedConnections;
 use crate::mcp::proxy::ProxyState;
+use crate::persistence::save_log_level;
 use crate::state::SharedState;
 
 #[derive(Debug, Serialize)]
@@ -50,6 +54,27 @@ pub struct SystemStatusResponse {
     pub checked_at: u64,
 }
 
+/// The server whose process tree contains a given PID.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PidOwner {
+    pub server_id: String,
+    pub server_name: String,
+    /// PID of the server's own child process (an ancestor of, or equal to, the queried PID).
+    pub server_pid: u32,
+}
+
+/// A server process from a previous session that is still running.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedProcess {
+    pub pid: u32,
+    pub server_id: String,
+    /// Current name of the server, or its ID if it has since been removed.
+    pub server_name: String,
+    pub command: String,
+}
+
 async fn check_redis_health() -> RedisHealth {
     let start = Instant::now();
 
@@ -227,3 +252,142 @@ pub async fn get_system_status(
         checked_at,
     })
 }
+
+/// How many parent links to follow when looking for a managed ancestor.
+const MAX_PROCESS_DEPTH: usize = 32;
+
+/// Find the server that owns `pid` — either its direct child process or a
+/// descendant of it (e.g. the `node` process behind an `npx` wrapper).
+#[tauri::command]
+pub async fn find_server_by_pid(
+    app_state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+    system: State<'_, SharedSystem>,
+    pid: u32,
+) -> Result<Option<PidOwner>, AppError> {
+    let managed_pids: HashMap<u32, String> = {
+        let conns = connections.lock().await;
+        conns
+            .pids()
+            .into_iter()
+            .map(|(id, pid)| (pid, id))
+            .collect()
+    };
+
+    let owner = {
+        let mut sys = system
+            .lock()
+            .map_err(|e| AppError::ConnectionFailed(format!("Failed to lock sysinfo: {e}")))?;
+
+        let mut current = sysinfo::Pid::from_u32(pid);
+        let mut found = None;
+        for _ in 0..MAX_PROCESS_DEPTH {
+            if let Some(server_id) = managed_pids.get(&current.as_u32()) {
+                found = Some((server_id.clone(), current.as_u32()));
+                break;
+            }
+            sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[current]), true);
+            match sys.process(current).and_then(|p| p.parent()) {
+                Some(parent) if parent != current => current = parent,
+                _ => break,
+            }
+        }
+        found
+    };
+
+    let Some((server_id, server_pid)) = owner else {
+        return Ok(None);
+    };
+    let server_name = {
+        let s = app_state.lock().unwrap();
+        s.servers
+            .iter()
+            .find(|srv| srv.id == server_id)
+            .map(|srv| srv.name.clone())
+            .unwrap_or_else(|| server_id.clone())
+    };
+    Ok(Some(PidOwner {
+        server_id,
+        server_name,
+        server_pid,
+    }))
+}
+
+/// Stdio server processes left running by a previous session, e.g. after a crash.
+#[tauri::command]
+pub async fn find_orphaned_processes(
+    app_state: State<'_, SharedState>,
+    children: State<'_, ChildProcesses>,
+    system: State<'_, SharedSystem>,
+) -> Result<Vec<OrphanedProcess>, AppError> {
+    let orphans = {
+        let mut sys = system
+            .lock()
+            .map_err(|e| AppError::ConnectionFailed(format!("Failed to lock sysinfo: {e}")))?;
+        children.orphans(&mut sys)
+    };
+
+    let s = app_state.lock().unwrap();
+    Ok(orphans
+        .into_iter()
+        .map(|record| OrphanedProcess {
+            server_name: s
+                .servers
+                .iter()
+                .find(|srv| srv.id == record.server_id)
+                .map(|srv| srv.name.clone())
+                .unwrap_or_else(|| record.server_id.clone()),
+            pid: record.pid,
+            server_id: record.server_id,
+            command: record.command,
+        })
+        .collect())
+}
+
+/// Kill orphans reported by `find_orphaned_processes`, along with their
+/// descendants. Other PIDs are ignored. Returns the PIDs that were signalled.
+#[tauri::command]
+pub async fn kill_orphaned(
+    app: AppHandle,
+    children: State<'_, ChildProcesses>,
+    system: State<'_, SharedSystem>,
+    pids: Vec<u32>,
+) -> Result<Vec<u32>, AppError> {
+    let mut sys = system
+        .lock()
+        .map_err(|e| AppError::ConnectionFailed(format!("Failed to lock sysinfo: {e}")))?;
+    let killed = children.kill_orphans(&mut sys, &pids);
+    children.persist(&app);
+    Ok(killed)
+}
+
+/// Current log level: `error`, `warn`, `info`, `debug`, `trace` or `off`.
+#[tauri::command]
+pub async fn get_log_level(logging: State<'_, Logging>) -> Result<String, AppError> {
+    Ok(logging.level().to_string().to_lowercase())
+}
+
+/// Change the log level for the console and the log file, effective
+/// immediately and kept across restarts.
+#[tauri::command]
+pub async fn set_log_level(
+    app: AppHandle,
+    logging: State<'_, Logging>,
+    level: String,
+) -> Result<(), AppError> {
+    let filter = parse_level(&level)?;
+    logging.set_level(filter)?;
+    let level = filter.to_string().to_lowercase();
+    save_log_level(&app, &level);
+    info!("Log level set to {level}");
+    Ok(())
+}
+
+/// Path of the log file being written, for attaching to bug reports.
+#[tauri::command]
+pub async fn get_log_file_path(logging: State<'_, Logging>) -> Result<String, AppError> {
+    logging
+        .file_path()
+        .map(|path| path.to_string_lossy().into_owned())
+        .ok_or_else(|| AppError::Validation("Logging to a file is unavailable".into()))
+}
@@ -1,10 +1,16 @@
 use std::sync::Arc;
 
-use tauri::State;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tracing::warn;
 
+use crate::commands::connections::{connect_server, disconnect_server, open_transient_client};
 use crate::error::AppError;
 use crate::mcp::client::{CallToolResult, McpClient, SharedConnections};
-use crate::state::{McpTool, SharedState};
+use crate::mcp::trace::{ServerTraces, TraceEntry};
+use crate::mcp::types::{JsonRpcResponse, ServerCapabilities};
+use crate::mcp::validation;
+use crate::state::{McpTool, SharedOAuthStore, SharedState};
 
 #[tauri::command]
 pub async fn list_tools(
@@ -33,20 +39,348 @@ pub async fn list_all_tools(state: State<'_, SharedState>) -> Result<Vec<McpTool
     Ok(all_tools)
 }
 
+/// Results returned by `search_tools` when no limit is given.
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Rank the tools of every connected server against `query`, best match first.
+/// Every whitespace-separated term has to match the tool's name, title or
+/// description; terms that only match as a subsequence of the name still count.
+#[tauri::command]
+pub async fn search_tools(
+    state: State<'_, SharedState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<McpTool>, AppError> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let s = state.lock().unwrap();
+    let mut ranked: Vec<(u32, &McpTool)> = s
+        .connections
+        .values()
+        .flat_map(|conn| &conn.tools)
+        .filter_map(|tool| tool_match_score(tool, &terms).map(|score| (score, tool)))
+        .collect();
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.server_name.cmp(&b.server_name))
+    });
+    Ok(ranked
+        .into_iter()
+        .take(limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .map(|(_, tool)| tool.clone())
+        .collect())
+}
+
+/// Score `tool` against lowercase `terms`, or `None` if any term misses.
+fn tool_match_score(tool: &McpTool, terms: &[String]) -> Option<u32> {
+    let name = tool.name.to_lowercase();
+    let title = tool.title.as_deref().unwrap_or("").to_lowercase();
+    let description = tool.description.as_deref().unwrap_or("").to_lowercase();
+
+    terms.iter().try_fold(0, |total, term| {
+        let score = if name == *term {
+            100
+        } else if name.starts_with(term.as_str()) {
+            60
+        } else if name.contains(term.as_str()) {
+            40
+        } else if title.contains(term.as_str()) {
+            30
+        } else if description.contains(term.as_str()) {
+            10
+        } else if is_subsequence(term, &name) {
+            5
+        } else {
+            return None;
+        };
+        Some(total + score)
+    })
+}
+
+/// Whether the characters of `needle` appear in `haystack` in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|c| chars.any(|h| h == c))
+}
+
+/// Call a tool on a connected server. With `validate`, arguments are first
+/// checked against the tool's `inputSchema`; violations come back as an error
+/// result listing them in `validationErrors`, and the call isn't sent.
 #[tauri::command]
 pub async fn call_tool(
+    state: State<'_, SharedState>,
     connections: State<'_, SharedConnections>,
     server_id: String,
     tool_name: String,
     arguments: serde_json::Value,
+    validate: Option<bool>,
 ) -> Result<CallToolResult, AppError> {
+    {
+        let s = state.lock().unwrap();
+        let checked = if validate.unwrap_or(false) {
+            let errors = validation::argument_errors(&s, &server_id, &tool_name, &arguments);
+            if !errors.is_empty() {
+                return Ok(CallToolResult::invalid_arguments(&tool_name, errors));
+            }
+            // The schema is already checked; only the size limit remains
+            let max_bytes = validation::max_argument_bytes(&s, &server_id);
+            validation::check_argument_size(&arguments, max_bytes)
+        } else {
+            validation::check_tool_call(&s, &server_id, &tool_name, &arguments)
+        };
+        checked.map_err(AppError::Validation)?;
+    }
+
     // Clone the Arc handle and drop the lock before async I/O
     let client: Arc<McpClient> = {
         let conns = connections.lock().await;
         conns
             .get(&server_id)
-            .cloned()
             .ok_or_else(|| AppError::ServerNotFound(server_id.clone()))?
     };
     client.call_tool(&tool_name, arguments).await
 }
+
+/// One entry of a `call_tools_batch` request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchToolCall {
+    pub server_id: String,
+    pub tool_name: String,
+    #[serde(default = "empty_arguments")]
+    pub arguments: serde_json::Value,
+}
+
+fn empty_arguments() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+/// Outcome of one batched call: `result` on success, `error` otherwise.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchToolCallResult {
+    pub result: Option<CallToolResult>,
+    pub error: Option<String>,
+}
+
+/// Most calls accepted in one `call_tools_batch` request.
+const MAX_BATCH_CALLS: usize = 100;
+
+/// Run several tool calls in one invocation, each through the server's live
+/// client exactly like `call_tool`. Results come back in request order; a
+/// failed call doesn't stop the others. With `concurrent`, all calls are in
+/// flight at once (still bounded by each server's concurrency limit).
+#[tauri::command]
+pub async fn call_tools_batch(
+    state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+    calls: Vec<BatchToolCall>,
+    concurrent: Option<bool>,
+) -> Result<Vec<BatchToolCallResult>, AppError> {
+    if calls.len() > MAX_BATCH_CALLS {
+        return Err(AppError::Validation(format!(
+            "Batch has {} calls; at most {MAX_BATCH_CALLS} are allowed",
+            calls.len()
+        )));
+    }
+
+    let run = |call: BatchToolCall| {
+        let state = state.clone();
+        let connections = connections.clone();
+        async move {
+            let outcome = call_tool(
+                state,
+                connections,
+                call.server_id,
+                call.tool_name,
+                call.arguments,
+                None,
+            )
+            .await;
+            match outcome {
+                Ok(result) => BatchToolCallResult {
+                    result: Some(result),
+                    error: None,
+                },
+                Err(e) => BatchToolCallResult {
+                    result: None,
+                    error: Some(e.to_string()),
+                },
+            }
+        }
+    };
+
+    if concurrent.unwrap_or(false) {
+        return Ok(futures::future::join_all(calls.into_iter().map(run)).await);
+    }
+    let mut results = Vec::with_capacity(calls.len());
+    for call in calls {
+        results.push(run(call).await);
+    }
+    Ok(results)
+}
+
+/// Connect `server_id` if it isn't already, call `tool_name`, and return the
+/// result. With `disconnect_after`, a server this call connected is
+/// disconnected again afterwards; servers that were already up stay up.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn connect_and_call(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+    oauth_store: State<'_, SharedOAuthStore>,
+    server_id: String,
+    tool_name: String,
+    arguments: serde_json::Value,
+    disconnect_after: Option<bool>,
+) -> Result<CallToolResult, AppError> {
+    let was_connected = connections.lock().await.get(&server_id).is_some();
+    if !was_connected {
+        connect_server(
+            app.clone(),
+            state.clone(),
+            connections.clone(),
+            oauth_store,
+            server_id.clone(),
+        )
+        .await?;
+    }
+
+    let result = call_tool(
+        state.clone(),
+        connections.clone(),
+        server_id.clone(),
+        tool_name,
+        arguments,
+        None,
+    )
+    .await;
+
+    if !was_connected && disconnect_after.unwrap_or(false) {
+        if let Err(e) = disconnect_server(app, state, connections, server_id.clone()).await {
+            warn!("Failed to disconnect {server_id} after connect_and_call: {e}");
+        }
+    }
+    result
+}
+
+/// Send `tools/list` to a server and return the result unparsed, for
+/// debugging schemas that fail to deserialize. Uses the live connection if
+/// there is one; otherwise opens a temporary one just for this request.
+#[tauri::command]
+pub async fn get_raw_tools_list(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    connections: State<'_, SharedConnections>,
+    oauth_store: State<'_, SharedOAuthStore>,
+    server_id: String,
+) -> Result<serde_json::Value, AppError> {
+    let live = connections.lock().await.get(&server_id);
+    if let Some(client) = live {
+        return client.raw_tools_list().await;
+    }
+
+    let client = open_transient_client(&app, &state, &oauth_store, &server_id).await?;
+    let result = client.raw_tools_list().await;
+    client.shutdown();
+    result
+}
+
+/// Send an arbitrary JSON-RPC request over a server's live connection and
+/// return the response exactly as received, error included. Only available in
+/// debug builds — it bypasses tool allow-lists and argument validation.
+#[tauri::command]
+pub async fn send_raw_request(
+    connections: State<'_, SharedConnections>,
+    server_id: String,
+    method: String,
+    params: Option<serde_json::Value>,
+) -> Result<JsonRpcResponse, AppError> {
+    if !cfg!(debug_assertions) {
+        return Err(AppError::Validation(
+            "Raw JSON-RPC requests are only available in debug builds".into(),
+        ));
+    }
+
+    let client = connections
+        .lock()
+        .await
+        .get(&server_id)
+        .ok_or_else(|| AppError::ServerNotFound(server_id.clone()))?;
+    warn!("Raw JSON-RPC request to {server_id}: {method}");
+    client.send_raw_request(&method, params).await
+}
+
+/// The capabilities a connected server declared in its `initialize` result.
+#[tauri::command]
+pub async fn get_server_capabilities(
+    connections: State<'_, SharedConnections>,
+    id: String,
+) -> Result<ServerCapabilities, AppError> {
+    let client = connections
+        .lock()
+        .await
+        .get(&id)
+        .ok_or_else(|| AppError::ServerNotFound(id.clone()))?;
+    client
+        .server_capabilities
+        .clone()
+        .ok_or_else(|| AppError::Protocol(format!("Server {id} has not completed initialize")))
+}
+
+/// Recent JSON-RPC traffic over a server's stdio transport, oldest first.
+#[tauri::command]
+pub fn get_server_trace(traces: State<'_, ServerTraces>, id: String) -> Vec<TraceEntry> {
+    traces.entries(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, title: Option<&str>, description: Option<&str>) -> McpTool {
+        McpTool {
+            name: name.to_string(),
+            title: title.map(String::from),
+            description: description.map(String::from),
+            input_schema: None,
+            server_id: "srv".to_string(),
+            server_name: "Server".to_string(),
+        }
+    }
+
+    fn terms(query: &str) -> Vec<String> {
+        query.split_whitespace().map(str::to_lowercase).collect()
+    }
+
+    #[test]
+    fn name_matches_outrank_description_matches() {
+        let by_name = tool("create_issue", None, None);
+        let by_description = tool("open_ticket", None, Some("Create an issue"));
+        let query = terms("create");
+
+        let name_score = tool_match_score(&by_name, &query).unwrap();
+        let description_score = tool_match_score(&by_description, &query).unwrap();
+        assert!(name_score > description_score);
+    }
+
+    #[test]
+    fn every_term_must_match() {
+        let t = tool("send_message", Some("Send Slack message"), None);
+        assert!(tool_match_score(&t, &terms("slack message")).is_some());
+        assert!(tool_match_score(&t, &terms("slack email")).is_none());
+    }
+
+    #[test]
+    fn subsequence_of_name_is_a_weak_match() {
+        let t = tool("list_pull_requests", None, None);
+        assert_eq!(tool_match_score(&t, &terms("lpr")), Some(5));
+        assert_eq!(tool_match_score(&t, &terms("rpl")), None);
+    }
+}
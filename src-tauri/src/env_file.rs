@@ -0,0 +1,140 @@
+//! Dotenv-style files referenced by a server's `envFile`.
+//!
+//! Each non-blank line is `KEY=VALUE`, optionally prefixed with `export`.
+//! Lines starting with `#` are comments. Values may be double-quoted (with
+//! `\n`, `\t`, `\"` and `\\` escapes), single-quoted (taken literally), or
+//! bare, in which case a ` #` starts a trailing comment.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::AppError;
+
+/// Read and parse the env file at `path`. Missing files and malformed lines
+/// are reported as validation errors naming the file.
+pub fn load(path: &str) -> Result<HashMap<String, String>, AppError> {
+    let file = Path::new(path);
+    if !file.is_absolute() {
+        return Err(AppError::Validation(format!(
+            "Env file must be an absolute path: {path}"
+        )));
+    }
+    if !file.is_file() {
+        return Err(AppError::Validation(format!("Env file not found: {path}")));
+    }
+    let contents = std::fs::read_to_string(file)?;
+    parse(&contents).map_err(|e| AppError::Validation(format!("Env file {path}: {e}")))
+}
+
+/// Parse env file contents. Later assignments to the same key win.
+pub fn parse(contents: &str) -> Result<HashMap<String, String>, String> {
+    let mut vars = HashMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+        let (key, value) = parse_line(line).map_err(|e| format!("line {}: {e}", index + 1))?;
+        vars.insert(key, value);
+    }
+    Ok(vars)
+}
+
+fn parse_line(line: &str) -> Result<(String, String), String> {
+    let (key, raw) = line
+        .split_once('=')
+        .ok_or_else(|| "expected KEY=VALUE".to_string())?;
+    let key = key.trim();
+    if !is_valid_key(key) {
+        return Err(format!("invalid variable name '{key}'"));
+    }
+    Ok((key.to_string(), parse_value(raw.trim())?))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_value(raw: &str) -> Result<String, String> {
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return trailing_comment_only(chars.as_str()).map(|()| value),
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => break,
+                },
+                other => value.push(other),
+            }
+        }
+        return Err("unterminated double quote".to_string());
+    }
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let (value, after) = rest
+            .split_once('\'')
+            .ok_or_else(|| "unterminated single quote".to_string())?;
+        trailing_comment_only(after)?;
+        return Ok(value.to_string());
+    }
+    let value = raw.split_once(" #").map_or(raw, |(value, _)| value);
+    Ok(value.trim_end().to_string())
+}
+
+/// After a closing quote only whitespace or a comment may follow.
+fn trailing_comment_only(rest: &str) -> Result<(), String> {
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(())
+    } else {
+        Err(format!("unexpected text after closing quote: '{rest}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bare_quoted_and_exported_values() {
+        let vars = parse(
+            "# comment\n\
+             API_KEY=abc123\n\
+             export REGION = us-east-1 # trailing\n\
+             GREETING=\"hello \\\"world\\\"\\n\"\n\
+             RAW='$HOME \\n stays'\n\
+             EMPTY=\n",
+        )
+        .unwrap();
+
+        assert_eq!(vars["API_KEY"], "abc123");
+        assert_eq!(vars["REGION"], "us-east-1");
+        assert_eq!(vars["GREETING"], "hello \"world\"\n");
+        assert_eq!(vars["RAW"], "$HOME \\n stays");
+        assert_eq!(vars["EMPTY"], "");
+    }
+
+    #[test]
+    fn reports_malformed_lines_with_their_number() {
+        assert_eq!(
+            parse("OK=1\nnot a pair\n").unwrap_err(),
+            "line 2: expected KEY=VALUE"
+        );
+        assert_eq!(
+            parse("1BAD=x").unwrap_err(),
+            "line 1: invalid variable name '1BAD'"
+        );
+        assert_eq!(
+            parse("A=\"open").unwrap_err(),
+            "line 1: unterminated double quote"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use serde::ser::SerializeStruct;
 use serde::Serialize;
 
 #[derive(Debug, thiserror::Error)]
@@ -17,6 +18,10 @@ pub enum AppError {
     #[error("Transport error: {0}")]
     Transport(String),
 
+    /// A request was sent but no response arrived in time.
+    #[error("Timed out {0}")]
+    Timeout(String),
+
     #[error("Authentication required: {0}")]
     AuthRequired(String),
 
@@ -32,6 +37,12 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Marketplace not found: {0}")]
+    MarketplaceNotFound(String),
+
+    #[error("Plugin already installed: {0}")]
+    PluginAlreadyInstalled(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -39,11 +50,57 @@ pub enum AppError {
     Json(#[from] serde_json::Error),
 }
 
+impl AppError {
+    /// Stable identifier for the error class, for callers that need to branch
+    /// on the kind of failure rather than its message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::ServerNotFound(_) => "server_not_found",
+            AppError::AlreadyConnected(_) => "already_connected",
+            AppError::ConnectionFailed(_) => "connection_failed",
+            AppError::Protocol(_) => "protocol",
+            AppError::Transport(_) => "transport",
+            AppError::Timeout(_) => "timeout",
+            AppError::AuthRequired(_) => "auth_required",
+            AppError::OAuth(_) => "oauth",
+            AppError::IntegrationNotFound(_) => "integration_not_found",
+            AppError::DependencyNotFound(_) => "dependency_not_found",
+            AppError::Validation(_) => "validation",
+            AppError::MarketplaceNotFound(_) => "marketplace_not_found",
+            AppError::PluginAlreadyInstalled(_) => "plugin_already_installed",
+            AppError::Io(_) => "io",
+            AppError::Json(_) => "json",
+        }
+    }
+}
+
+/// Commands reject with `{ "code": ..., "message": ... }` so the frontend can
+/// branch on `code` and show `message`.
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        let mut error = serializer.serialize_struct("AppError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let error = AppError::AuthRequired("Click Authorize to sign in.".into());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "auth_required",
+                "message": "Authentication required: Click Authorize to sign in.",
+            })
+        );
     }
 }
@@ -1,5 +1,9 @@
+mod audit;
+mod child_processes;
 mod commands;
+mod env_file;
 mod error;
+mod logging;
 mod mcp;
 mod memory_client;
 mod persistence;
@@ -11,7 +15,7 @@ use commands::status::SharedSystem;
 use mcp::client::McpConnections;
 use state::registry::MarketplaceCache;
 use state::skills_registry::SkillsMarketplaceCache;
-use state::{AppState, OAuthStore};
+use state::{AppState, OAuthStore, SkillAssetStore};
 use stats::StatsStore;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
@@ -20,8 +24,6 @@ use tracing::info;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tracing_subscriber::fmt::init();
-
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
@@ -29,6 +31,8 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
+            let logging = logging::init(app.handle());
+
             // TODO(2026-03-12): Remove migration after 2 weeks
             // Migrate store from old bundle ID (com.mcpmanager.app → com.agenthub.app)
             let old_store_dir = dirs::home_dir()
@@ -45,8 +49,12 @@ pub fn run() {
                 }
             }
 
+            // Only now open the store: it caches config.json on first access
+            logging.apply_persisted_level(app.handle());
+            app.manage(logging);
+
             // Load persisted server configs, enabled integrations, and stats
-            let servers = persistence::load_servers(app.handle());
+            let (servers, server_load_errors) = persistence::load_servers(app.handle());
             let enabled_integrations = persistence::load_enabled_integrations(app.handle());
             let stats = persistence::load_stats(app.handle());
             let embedding_config = persistence::load_embedding_config(app.handle());
@@ -61,9 +69,31 @@ pub fn run() {
             );
 
             let tool_discovery_enabled = persistence::load_tool_discovery(app.handle());
-            let installed_skills = persistence::load_installed_skills(app.handle());
+            let skill_assets = match app.path().app_data_dir() {
+                Ok(dir) => SkillAssetStore::new(&dir),
+                Err(e) => {
+                    tracing::error!("Skill assets unavailable, can't resolve the app data dir: {e}");
+                    SkillAssetStore::unavailable()
+                }
+            };
+            let loaded_skills = persistence::load_installed_skills(app.handle());
+            match persistence::prune_skill_assets(&skill_assets, &loaded_skills) {
+                Ok(Some(0)) => {}
+                Ok(Some(n)) => info!("Removed {n} unused skill asset(s)"),
+                Ok(None) => tracing::warn!(
+                    "Not pruning skill assets: some stored skills couldn't be read"
+                ),
+                Err(e) => tracing::warn!("Failed to prune skill assets: {e}"),
+            }
+            let installed_skills = loaded_skills.skills;
             let enabled_skill_integrations =
                 persistence::load_enabled_skill_integrations(app.handle());
+            let skill_sync_hashes = persistence::load_skill_sync_hashes(app.handle());
+            let skill_dir_overrides = persistence::load_skill_dir_overrides(app.handle());
+            let proxy_settings = persistence::load_proxy_settings(app.handle());
+            let default_env = persistence::load_default_env(app.handle());
+            let fallback_groups = persistence::load_fallback_groups(app.handle());
+            let oauth_callback_port = persistence::load_oauth_callback_port(app.handle());
             info!(
                 "Loaded {} installed skills, {} skill integrations",
                 installed_skills.len(),
@@ -72,12 +102,20 @@ pub fn run() {
 
             let mut app_state = AppState::new();
             app_state.servers = servers;
+            app_state.server_load_errors = server_load_errors;
             app_state.enabled_integrations = enabled_integrations;
             app_state.embedding_config = embedding_config;
             app_state.redis_config = redis_config;
             app_state.tool_discovery_enabled = tool_discovery_enabled;
             app_state.installed_skills = installed_skills;
+            app_state.skill_assets = skill_assets;
             app_state.enabled_skill_integrations = enabled_skill_integrations;
+            app_state.skill_sync_hashes = skill_sync_hashes;
+            app_state.skill_dir_overrides = skill_dir_overrides;
+            app_state.proxy_settings = proxy_settings;
+            app_state.default_env = default_env;
+            app_state.fallback_groups = fallback_groups;
+            app_state.oauth_callback_port = oauth_callback_port;
             let app_state = Mutex::new(app_state);
 
             // Reconcile managed skills for features enabled before managed skills existed
@@ -87,10 +125,28 @@ pub fn run() {
             app.manage(tokio::sync::Mutex::new(McpConnections::new()));
             app.manage(tokio::sync::Mutex::new(OAuthStore::from_entries(oauth_entries)));
             app.manage(Mutex::new(sysinfo::System::new()) as SharedSystem);
+            app.manage(child_processes::ChildProcesses::new(
+                persistence::load_child_processes(app.handle()),
+            ));
 
             let stats_store: StatsStore = Arc::new(RwLock::new(stats));
             app.manage(stats_store);
             app.manage(MarketplaceCache::new());
+            app.manage(mcp::sampling::SamplingRequests::default());
+            app.manage(persistence::PendingSave::default());
+            app.manage(mcp::trace::ServerTraces::default());
+            app.manage(mcp::oauth_callback::OAuthCallbacks::default());
+            app.manage(commands::plugins::PluginOperations::default());
+            app.manage(commands::plugins::PluginListCache::new(
+                persistence::load_plugin_cache_ttl(app.handle()),
+            ));
+            app.manage(match app.path().app_data_dir() {
+                Ok(dir) => audit::AuditLog::new(dir),
+                Err(e) => {
+                    tracing::error!("Audit log unavailable, can't resolve the app data dir: {e}");
+                    audit::AuditLog::unavailable()
+                }
+            });
             app.manage(SkillsMarketplaceCache::new());
 
             // Start the MCP proxy server
@@ -117,42 +173,112 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::servers::list_servers,
+            commands::servers::get_load_errors,
+            commands::servers::diagnose_server,
+            commands::servers::list_servers_grouped,
+            commands::servers::get_default_env,
+            commands::fallback::list_fallback_groups,
+            commands::fallback::set_fallback_group,
+            commands::fallback::remove_fallback_group,
+            commands::servers::set_default_env,
+            commands::servers::import_servers,
             commands::servers::add_server,
             commands::servers::remove_server,
             commands::servers::update_server,
+            commands::servers::set_tags_bulk,
+            commands::servers::duplicate_server,
+            commands::servers::reorder_servers,
             commands::connections::connect_server,
             commands::connections::disconnect_server,
+            commands::connections::restart_server,
             commands::connections::drain_log_buffer,
+            commands::connections::get_all_server_statuses,
+            commands::connections::reset_server_state,
+            commands::connections::reconcile_now,
+            commands::connections::measure_connection_timing,
+            commands::connections::test_server_config,
             commands::tools::list_tools,
             commands::tools::list_all_tools,
             commands::tools::call_tool,
+            commands::tools::connect_and_call,
+            commands::tools::get_raw_tools_list,
+            commands::tools::send_raw_request,
+            commands::tools::get_server_trace,
+            commands::tools::get_server_capabilities,
+            commands::tools::search_tools,
+            commands::tools::call_tools_batch,
+            commands::sampling::respond_to_sampling,
             commands::proxy::get_proxy_status,
+            commands::proxy::get_proxy_endpoint,
+            commands::proxy::get_proxy_config,
+            commands::proxy::set_proxy_config,
+            commands::proxy::get_allowed_origins,
+            commands::proxy::set_allowed_origins,
+            commands::proxy::get_preferred_proxy_port,
+            commands::proxy::set_preferred_proxy_port,
+            commands::proxy::get_empty_tools_notice,
+            commands::proxy::set_empty_tools_notice,
+            commands::proxy::get_auto_update_integration_ports,
+            commands::proxy::set_auto_update_integration_ports,
+            commands::proxy::get_max_argument_bytes,
+            commands::proxy::set_max_argument_bytes,
+            commands::proxy::get_proxy_identity,
+            commands::proxy::set_proxy_identity,
+            commands::proxy::get_audit_config,
+            commands::proxy::set_audit_config,
+            commands::proxy::export_audit_log,
             commands::integrations::detect_integrations,
+            commands::integrations::check_integration_health,
+            commands::integrations::detect_port_conflicts,
+            commands::integrations::overwrite_integration_port,
+            commands::integrations::preview_enable_integration,
             commands::integrations::enable_integration,
+            commands::integrations::import_servers_from_json,
             commands::integrations::disable_integration,
+            commands::integrations::migrate_integration_keys,
             commands::oauth::start_oauth_flow,
             commands::oauth::clear_oauth_tokens,
+            commands::oauth::get_oauth_callback_port,
+            commands::oauth::set_oauth_callback_port,
             commands::skills::search_skills_marketplace,
             commands::skills::get_skills_marketplace_detail,
             commands::skills::list_installed_skills,
             commands::skills::install_skill,
+            commands::skills::export_skills_bundle,
+            commands::skills::import_skills_bundle,
+            commands::skills::resolve_skill_conflict,
             commands::skills::uninstall_skill,
             commands::skills::toggle_skill,
             commands::skills::get_skill_content,
+            commands::skills::validate_skill,
+            commands::skills::check_skill_updates,
+            commands::skills::update_skill,
             commands::skills::detect_skill_integrations,
             commands::skills::enable_skill_integration,
             commands::skills::disable_skill_integration,
+            commands::skills::get_skill_dir_overrides,
+            commands::skills::set_skill_dir_override,
             commands::memory::get_memory_status,
             commands::memory::enable_memory,
             commands::memory::disable_memory,
             commands::memory::restart_memory,
             commands::memory::get_embedding_config,
             commands::memory::save_embedding_config_cmd,
+            commands::memory::set_embedding_config,
             commands::memory::save_redis_config_cmd,
             commands::memory::delete_ollama_model,
             commands::stats::get_server_stats,
             commands::stats::reset_server_stats,
+            commands::stats::get_proxy_stats,
+            commands::stats::get_proxy_call_log,
+            commands::stats::export_proxy_log,
             commands::status::get_system_status,
+            commands::status::find_server_by_pid,
+            commands::status::find_orphaned_processes,
+            commands::status::kill_orphaned,
+            commands::status::get_log_level,
+            commands::status::set_log_level,
+            commands::status::get_log_file_path,
             commands::memories::search_memories,
             commands::memories::get_memory,
             commands::memories::check_memory_health,
@@ -173,6 +299,12 @@ pub fn run() {
             commands::plugins::toggle_plugin,
             commands::plugins::list_installed_plugins,
             commands::plugins::update_marketplace,
+            commands::plugins::list_marketplaces,
+            commands::plugins::add_marketplace,
+            commands::plugins::remove_marketplace,
+            commands::plugins::cancel_plugin_operation,
+            commands::plugins::get_plugin_cache_ttl,
+            commands::plugins::set_plugin_cache_ttl,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
@@ -195,6 +327,9 @@ pub fn run() {
             } else {
                 info!("Restored integration configs to native mode on exit");
             }
+
+            // Write out settings changed within the save debounce window
+            persistence::flush_store(app_handle);
         }
     });
 }
@@ -0,0 +1,220 @@
+//! Tracing setup: console output plus a daily-rotated log file in the app
+//! data dir, behind a level filter that can be changed at runtime.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::error::AppError;
+use crate::persistence::load_log_level;
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "agent-hub";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Handle to the installed subscriber, managed as app state.
+pub struct Logging {
+    level: reload::Handle<LevelFilter, Registry>,
+    /// `None` when logging to the console only.
+    dir: Option<PathBuf>,
+}
+
+impl Logging {
+    pub fn level(&self) -> LevelFilter {
+        self.level.clone_current().unwrap_or(LevelFilter::INFO)
+    }
+
+    pub fn set_level(&self, level: LevelFilter) -> Result<(), AppError> {
+        self.level
+            .reload(level)
+            .map_err(|e| std::io::Error::other(e.to_string()).into())
+    }
+
+    /// Switch to the level saved by `set_log_level`. Separate from `init` so
+    /// setup can run the store migration before the store is first opened.
+    pub fn apply_persisted_level(&self, app: &AppHandle) {
+        let persisted = load_log_level(app);
+        match parse_level(&persisted) {
+            Ok(level) => {
+                if let Err(e) = self.set_level(level) {
+                    warn!("Failed to apply log level '{persisted}': {e}");
+                }
+            }
+            Err(e) => warn!("Ignoring saved log level: {e}"),
+        }
+    }
+
+    /// The file currently being written, or the log directory if no file
+    /// has been created yet. `None` when logging to the console only.
+    pub fn file_path(&self) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(log_files(dir).pop().unwrap_or_else(|| dir.clone()))
+    }
+}
+
+/// Install the global subscriber at the default level. Call once, at setup.
+pub fn init(app: &AppHandle) -> Logging {
+    let (filter, level) = reload::Layer::new(LevelFilter::INFO);
+
+    let file = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| std::io::Error::other(e.to_string()))
+        .and_then(|data_dir| DailyFile::open(data_dir.join(LOG_DIR)));
+    let (file_layer, dir, file_error) = match file {
+        Ok(file) => {
+            let dir = file.dir.clone();
+            let layer = fmt::layer().with_ansi(false).with_writer(Mutex::new(file));
+            (Some(layer), Some(dir), None)
+        }
+        Err(e) => (None, None, Some(e)),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .init();
+    if let Some(e) = file_error {
+        warn!("Logging to console only; can't open the log file: {e}");
+    }
+
+    Logging { level, dir }
+}
+
+/// Parse `error`, `warn`, `info`, `debug`, `trace` or `off`, in any case.
+pub fn parse_level(level: &str) -> Result<LevelFilter, AppError> {
+    level.trim().parse().map_err(|_| {
+        AppError::Validation(format!(
+            "Invalid log level '{level}': expected error, warn, info, debug, trace or off"
+        ))
+    })
+}
+
+/// Appends to `agent-hub.YYYY-MM-DD.log` (UTC date), starting a new file when
+/// the date changes and deleting all but the newest `MAX_LOG_FILES`.
+struct DailyFile {
+    dir: PathBuf,
+    date: String,
+    file: File,
+}
+
+impl DailyFile {
+    fn open(dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let date = utc_date(crate::stats::unix_now());
+        let file = open_log_file(&dir, &date)?;
+        prune_log_files(&dir);
+        Ok(Self { dir, date, file })
+    }
+}
+
+impl Write for DailyFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let today = utc_date(crate::stats::unix_now());
+        if today != self.date {
+            self.file = open_log_file(&self.dir, &today)?;
+            self.date = today;
+            prune_log_files(&self.dir);
+        }
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_log_file(dir: &Path, date: &str) -> std::io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(format!("{LOG_FILE_PREFIX}.{date}.{LOG_FILE_SUFFIX}")))
+}
+
+fn prune_log_files(dir: &Path) {
+    let files = log_files(dir);
+    let excess = files.len().saturating_sub(MAX_LOG_FILES);
+    for old in &files[..excess] {
+        let _ = std::fs::remove_file(old);
+    }
+}
+
+/// Log files in `dir`, oldest first. Daily files are named
+/// `agent-hub.YYYY-MM-DD.log`, so name order is date order.
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.starts_with(&format!("{LOG_FILE_PREFIX}."))
+                && name.ends_with(&format!(".{LOG_FILE_SUFFIX}"))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// `YYYY-MM-DD` for a unix timestamp, in UTC.
+fn utc_date(unix_secs: u64) -> String {
+    // Days-to-civil conversion from Howard Hinnant's date algorithms
+    let z = (unix_secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_dates() {
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(951_782_400), "2000-02-29");
+        assert_eq!(utc_date(1_700_000_000), "2023-11-14");
+    }
+
+    #[test]
+    fn keeps_newest_log_files() {
+        let dir = std::env::temp_dir().join(format!("agent-hub-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(log_files(&dir).is_empty());
+
+        for day in 1..=9 {
+            std::fs::write(dir.join(format!("agent-hub.2026-01-0{day}.log")), "").unwrap();
+        }
+        std::fs::write(dir.join("other.2026-02-01.log"), "").unwrap();
+        prune_log_files(&dir);
+
+        let files = log_files(&dir);
+        assert_eq!(files.len(), MAX_LOG_FILES);
+        assert_eq!(files[0], dir.join("agent-hub.2026-01-03.log"));
+        assert_eq!(files.last(), Some(&dir.join("agent-hub.2026-01-09.log")));
+        assert!(dir.join("other.2026-02-01.log").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
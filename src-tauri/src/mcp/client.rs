@@ -1,14 +1,100 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tauri::AppHandle;
-use tokio::sync::Mutex;
-use tracing::info;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tracing::{info, warn};
 
 use crate::error::AppError;
 use crate::mcp::http_transport::HttpTransport;
 use crate::mcp::transport::StdioTransport;
 use crate::mcp::types::*;
+use crate::state::{CapabilityFlags, JsonRpcIdFormat, ServerConfig};
+
+/// Concurrent tool calls allowed per server when not configured.
+pub const DEFAULT_MAX_CONCURRENT_CALLS: u32 = 8;
+
+/// Upper bound on `tools/list` pages fetched in one discovery, in case a
+/// server keeps handing out cursors.
+const MAX_TOOLS_LIST_PAGES: usize = 100;
+
+/// Per-server options that shape how a client talks to its backend.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptions {
+    /// Wire format for JSON-RPC request ids.
+    pub id_format: JsonRpcIdFormat,
+    /// Disable TLS certificate verification (HTTP transport only).
+    pub allow_insecure_tls: bool,
+    /// Cap on in-flight tool calls; `None` uses [`DEFAULT_MAX_CONCURRENT_CALLS`].
+    pub max_concurrent_calls: Option<u32>,
+    /// Workspace root URIs answered to `roots/list` (stdio transport only).
+    pub roots: Vec<String>,
+    /// Advertise sampling and forward `sampling/createMessage` to the user
+    /// (stdio transport only).
+    pub sampling: bool,
+    /// Largest stdout message accepted; `None` uses
+    /// [`DEFAULT_MAX_FRAME_BYTES`](crate::mcp::transport::DEFAULT_MAX_FRAME_BYTES)
+    /// (stdio transport only).
+    pub max_frame_bytes: Option<u64>,
+    /// Charset for decoding stderr; `None` means UTF-8 (stdio transport only).
+    pub output_encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+impl ClientOptions {
+    pub fn from_server(server: &ServerConfig) -> Self {
+        Self {
+            id_format: server.id_format.unwrap_or_default(),
+            allow_insecure_tls: server.allow_insecure_tls,
+            max_concurrent_calls: server.max_concurrent_calls,
+            roots: server.roots.iter().flatten().map(|r| root_uri(r)).collect(),
+            sampling: server.enable_sampling,
+            max_frame_bytes: server.max_frame_bytes,
+            output_encoding: server
+                .output_encoding
+                .as_deref()
+                .and_then(|label| encoding_rs::Encoding::for_label(label.trim().as_bytes())),
+        }
+    }
+
+    fn call_slots(&self) -> Semaphore {
+        let permits = self
+            .max_concurrent_calls
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_CALLS)
+            .max(1);
+        Semaphore::new(permits as usize)
+    }
+}
+
+/// Normalize a configured root to a URI: values that already have a scheme are
+/// kept, absolute paths become `file://` URIs.
+fn root_uri(raw: &str) -> String {
+    let raw = raw.trim();
+    if url::Url::parse(raw).is_ok() {
+        return raw.to_string();
+    }
+    url::Url::from_file_path(raw)
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| raw.to_string())
+}
+
+/// The cursor to request after a `tools/list` page that returned `next`, or
+/// `None` if that was the last page. A repeated cursor would loop forever, so
+/// it's treated as the last page.
+fn next_page_cursor(current: Option<&String>, next: Option<String>) -> Option<String> {
+    next.filter(|next| !next.is_empty() && current != Some(next))
+}
+
+/// Append a later `tools/list` page's `tools` to the first page's result.
+fn append_raw_tools(merged: &mut serde_json::Value, mut page: serde_json::Value) {
+    let Some(serde_json::Value::Array(more)) = page.get_mut("tools").map(serde_json::Value::take)
+    else {
+        return;
+    };
+    if let Some(tools) = merged.get_mut("tools").and_then(|t| t.as_array_mut()) {
+        tools.extend(more);
+    }
+}
 
 /// Transport abstraction — either stdio (local process) or HTTP (remote server).
 enum Transport {
@@ -28,6 +114,17 @@ impl Transport {
         }
     }
 
+    async fn send_request_raw(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<JsonRpcResponse, AppError> {
+        match self {
+            Transport::Stdio(t) => t.send_request_raw(method, params).await,
+            Transport::Http(t) => t.send_request_raw(method, params).await,
+        }
+    }
+
     async fn send_notification(
         &self,
         method: &str,
@@ -58,60 +155,96 @@ pub struct McpClient {
     pub server_capabilities: Option<ServerCapabilities>,
     pub server_info: Option<ServerInfo>,
     pub tools: Vec<McpToolDef>,
+    /// Limits concurrent tool calls so a flood of requests queues instead of
+    /// overwhelming the backend.
+    call_slots: Semaphore,
 }
 
 impl McpClient {
-    /// Spawn an MCP server, perform the initialization handshake, and discover tools.
-    pub async fn connect_stdio(
+    /// Spawn an MCP server without talking to it yet. Call [`Self::handshake`]
+    /// before sending anything else.
+    pub fn spawn_stdio(
         app: &AppHandle,
         server_id: &str,
         command: &str,
         args: &[String],
         env: &HashMap<String, String>,
+        options: &ClientOptions,
     ) -> Result<Self, AppError> {
-        let transport = StdioTransport::spawn(app, server_id, command, args, env)?;
-
-        let mut client = Self {
+        let transport = StdioTransport::spawn(app, server_id, command, args, env, options)?;
+        Ok(Self {
             transport: Transport::Stdio(transport),
             server_capabilities: None,
             server_info: None,
             tools: Vec::new(),
-        };
+            call_slots: options.call_slots(),
+        })
+    }
 
-        client.initialize().await?;
+    /// Connect to a remote MCP server via HTTP, perform initialization, and discover tools.
+    pub async fn connect_http(
+        url: &str,
+        headers: HashMap<String, String>,
+        access_token: Option<String>,
+        options: &ClientOptions,
+    ) -> Result<Self, AppError> {
+        let mut client = Self::open_http(url, headers, access_token, options).await?;
         client.discover_tools().await?;
-
         Ok(client)
     }
 
-    /// Connect to a remote MCP server via HTTP, perform initialization, and discover tools.
-    pub async fn connect_http(
+    /// Connect to a remote MCP server via HTTP and perform initialization only.
+    pub async fn open_http(
         url: &str,
         headers: HashMap<String, String>,
         access_token: Option<String>,
+        options: &ClientOptions,
     ) -> Result<Self, AppError> {
-        let transport = HttpTransport::connect(url, headers, access_token).await?;
+        let mut client = Self::dial_http(url, headers, access_token, options).await?;
+        client.handshake(options).await?;
+        Ok(client)
+    }
 
-        let mut client = Self {
+    /// Set up the HTTP transport without initializing. Call
+    /// [`Self::handshake`] before sending anything else.
+    pub async fn dial_http(
+        url: &str,
+        headers: HashMap<String, String>,
+        access_token: Option<String>,
+        options: &ClientOptions,
+    ) -> Result<Self, AppError> {
+        let transport = HttpTransport::connect(url, headers, access_token, options).await?;
+        Ok(Self {
             transport: Transport::Http(transport),
             server_capabilities: None,
             server_info: None,
             tools: Vec::new(),
-        };
-
-        client.initialize().await?;
-        client.discover_tools().await?;
+            call_slots: options.call_slots(),
+        })
+    }
 
-        Ok(client)
+    /// Run the initialize handshake on a freshly spawned or dialed client.
+    pub async fn handshake(&mut self, options: &ClientOptions) -> Result<(), AppError> {
+        // Roots and sampling are only answerable over stdio, where we read server requests
+        let over_stdio = matches!(self.transport, Transport::Stdio(_));
+        self.initialize(
+            over_stdio && !options.roots.is_empty(),
+            over_stdio && options.sampling,
+        )
+        .await
     }
 
     /// Send the MCP initialize request and notifications/initialized.
-    async fn initialize(&mut self) -> Result<(), AppError> {
+    async fn initialize(
+        &mut self,
+        advertise_roots: bool,
+        advertise_sampling: bool,
+    ) -> Result<(), AppError> {
         let params = InitializeParams {
             protocol_version: "2025-03-26".to_string(),
             capabilities: ClientCapabilities {
-                roots: None,
-                sampling: None,
+                roots: advertise_roots.then(|| serde_json::json!({ "listChanged": false })),
+                sampling: advertise_sampling.then(|| serde_json::json!({})),
             },
             client_info: ClientInfo {
                 name: "Agent Hub".to_string(),
@@ -150,29 +283,91 @@ impl McpClient {
         Ok(())
     }
 
-    /// Send tools/list and store the results.
-    async fn discover_tools(&mut self) -> Result<(), AppError> {
-        let response = self
-            .transport
-            .send_request("tools/list", Some(serde_json::json!({})))
-            .await?;
-
-        let result = response
-            .result
-            .ok_or_else(|| AppError::Protocol("No result in tools/list response".into()))?;
+    /// Send tools/list, following `nextCursor` across pages, and store the results.
+    pub async fn discover_tools(&mut self) -> Result<(), AppError> {
+        self.tools = self.list_tools().await?;
+        Ok(())
+    }
 
+    /// Fetch every page of `tools/list` without touching `self.tools`, so a
+    /// shared client can re-list after `notifications/tools/list_changed`.
+    pub async fn list_tools(&self) -> Result<Vec<McpToolDef>, AppError> {
         #[derive(serde::Deserialize)]
         struct ToolsListResult {
             tools: Vec<McpToolDef>,
         }
 
-        let tools_result: ToolsListResult = serde_json::from_value(result)
-            .map_err(|e| AppError::Protocol(format!("Failed to parse tools list: {e}")))?;
+        let mut tools = Vec::new();
+        for page in self.tools_list_pages().await? {
+            let page: ToolsListResult = serde_json::from_value(page)
+                .map_err(|e| AppError::Protocol(format!("Failed to parse tools list: {e}")))?;
+            tools.extend(page.tools);
+        }
 
-        info!("Discovered {} tools", tools_result.tools.len());
-        self.tools = tools_result.tools;
+        info!("Discovered {} tools", tools.len());
+        Ok(tools)
+    }
 
-        Ok(())
+    /// Send tools/list, following `nextCursor` across pages, and return the
+    /// result as the server sent it, without parsing it into [`McpToolDef`]s:
+    /// the first page's result, with every later page's `tools` appended.
+    pub async fn raw_tools_list(&self) -> Result<serde_json::Value, AppError> {
+        let mut pages = self.tools_list_pages().await?.into_iter();
+        let mut merged = pages.next().unwrap_or_default();
+        for page in pages {
+            append_raw_tools(&mut merged, page);
+        }
+        if let Some(result) = merged.as_object_mut() {
+            result.remove("nextCursor");
+        }
+        Ok(merged)
+    }
+
+    /// Send tools/list, following `nextCursor`, and return each page's result
+    /// unparsed.
+    async fn tools_list_pages(&self) -> Result<Vec<serde_json::Value>, AppError> {
+        let mut pages = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_TOOLS_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => serde_json::json!({ "cursor": cursor }),
+                None => serde_json::json!({}),
+            };
+            let response = self
+                .transport
+                .send_request("tools/list", Some(params))
+                .await?;
+
+            let page = response
+                .result
+                .ok_or_else(|| AppError::Protocol("No result in tools/list response".into()))?;
+            let next = page
+                .get("nextCursor")
+                .and_then(|c| c.as_str())
+                .map(String::from);
+            pages.push(page);
+
+            cursor = next_page_cursor(cursor.as_ref(), next);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        if cursor.is_some() {
+            warn!(
+                "Stopped tools/list after {MAX_TOOLS_LIST_PAGES} pages; results may be incomplete"
+            );
+        }
+        Ok(pages)
+    }
+
+    /// Send an arbitrary request and return the response untouched, including
+    /// JSON-RPC errors. For debugging methods the client doesn't model.
+    pub async fn send_raw_request(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<JsonRpcResponse, AppError> {
+        self.transport.send_request_raw(method, params).await
     }
 
     /// Call a tool by name with the given arguments.
@@ -201,6 +396,32 @@ impl McpClient {
         Ok(call_result)
     }
 
+    /// Wait up to `timeout` for a free tool-call slot on this server.
+    /// Returns `None` if the server stayed saturated for the whole wait.
+    pub async fn acquire_call_slot(&self, timeout: Duration) -> Option<SemaphorePermit<'_>> {
+        tokio::time::timeout(timeout, self.call_slots.acquire())
+            .await
+            .ok()?
+            .ok()
+    }
+
+    /// Summary of what the server declared in `initialize`; all false before
+    /// the handshake.
+    pub fn capability_flags(&self) -> CapabilityFlags {
+        self.server_capabilities
+            .as_ref()
+            .map(ServerCapabilities::flags)
+            .unwrap_or_default()
+    }
+
+    /// Keep an HTTP client's OAuth token current from the store; see
+    /// [`HttpTransport::refresh_tokens_from`]. No-op for stdio.
+    pub fn refresh_tokens_from(&mut self, app: &AppHandle, server_id: &str) {
+        if let Transport::Http(transport) = &mut self.transport {
+            transport.refresh_tokens_from(app, server_id);
+        }
+    }
+
     /// Return the PID of the underlying process, if using stdio transport.
     pub fn pid(&self) -> Option<u32> {
         match &self.transport {
@@ -222,6 +443,27 @@ pub struct CallToolResult {
     pub content: Vec<McpContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// Schema violations found before the call; when set, nothing was sent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation_errors: Vec<String>,
+}
+
+impl CallToolResult {
+    /// An error result for arguments that failed pre-flight validation.
+    pub fn invalid_arguments(tool_name: &str, errors: Vec<String>) -> Self {
+        Self {
+            content: vec![McpContent {
+                r#type: "text".to_string(),
+                text: Some(crate::mcp::validation::invalid_arguments_message(
+                    tool_name, &errors,
+                )),
+                data: None,
+                mime_type: None,
+            }],
+            is_error: Some(true),
+            validation_errors: errors,
+        }
+    }
 }
 
 /// Holds active MCP client connections, keyed by server ID.
@@ -246,8 +488,15 @@ impl McpConnections {
         self.clients.remove(id)
     }
 
-    pub fn get(&self, id: &str) -> Option<&Arc<McpClient>> {
-        self.clients.get(id)
+    /// A handle to the client for `id`. Returned by value so callers drop
+    /// the connections lock before awaiting anything on the client.
+    pub fn get(&self, id: &str) -> Option<Arc<McpClient>> {
+        self.clients.get(id).cloned()
+    }
+
+    /// IDs of all servers with a live client.
+    pub fn ids(&self) -> Vec<String> {
+        self.clients.keys().cloned().collect()
     }
 
     /// Return (server_id, pid) for all connected stdio clients.
@@ -260,3 +509,39 @@ impl McpConnections {
 }
 
 pub type SharedConnections = Mutex<McpConnections>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paging_stops_on_a_missing_empty_or_repeated_cursor() {
+        let current = Some("a".to_string());
+        assert_eq!(
+            next_page_cursor(current.as_ref(), Some("b".into())),
+            Some("b".into())
+        );
+        assert_eq!(next_page_cursor(current.as_ref(), Some("a".into())), None);
+        assert_eq!(
+            next_page_cursor(current.as_ref(), Some(String::new())),
+            None
+        );
+        assert_eq!(next_page_cursor(None, None), None);
+    }
+
+    #[test]
+    fn raw_pages_are_appended_unparsed() {
+        let mut merged = serde_json::json!({
+            "tools": [{"name": "a"}],
+            "nextCursor": "1"
+        });
+        append_raw_tools(
+            &mut merged,
+            serde_json::json!({ "tools": [{"name": "b", "inputSchema": 7}] }),
+        );
+        assert_eq!(
+            merged["tools"],
+            serde_json::json!([{"name": "a"}, {"name": "b", "inputSchema": 7}])
+        );
+    }
+}
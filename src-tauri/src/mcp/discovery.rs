@@ -1,70 +1,129 @@
 use std::collections::HashMap;
 
+use axum::body::Bytes;
 use axum::extract::{Query, State as AxumState};
 use axum::http::HeaderMap;
 use axum::response::IntoResponse;
-use axum::Json;
 use serde_json::Value;
 use tauri::Manager;
 use tokio::time::Instant;
 use tracing::{error, info};
 
+use crate::audit;
 use crate::mcp::client::SharedConnections;
-use crate::mcp::http_common::{
-    accepted_response, client_accepts_sse, json_response, mcp_response, negotiate_version,
-    new_session_id, validate_origin,
+use crate::mcp::http_common::{client_accepts_sse, json_response, mcp_response, negotiate_version};
+use crate::mcp::proxy::{
+    advertised_server_info, batch_opens_session, check_origin, classify_message, end_session,
+    make_error_response, parse_request_body, post_reply, record_tool_stats, require_session,
+    split_batch, Incoming, ProxyAppState, ProxyState, CALL_QUEUE_TIMEOUT,
 };
-use crate::mcp::proxy::{make_error_response, record_tool_stats, ProxyAppState};
+use crate::mcp::validation;
 use crate::state::SharedState;
 
+/// Session endpoint key for `/mcp/discovery` (server IDs are UUIDs, so no clash).
+const DISCOVERY_ENDPOINT: &str = "discovery";
+
+/// Handle DELETE requests to `/mcp/discovery` — the client is ending its session.
+pub(crate) async fn handle_discovery_delete(
+    AxumState(state): AxumState<ProxyAppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    end_session(&state.app_handle, &headers, DISCOVERY_ENDPOINT).await
+}
+
 /// Handle POST requests to `/mcp/discovery` — the single discovery endpoint.
+/// Accepts a single message or a JSON-RPC batch, like the per-server endpoints.
 pub(crate) async fn handle_discovery_post(
     AxumState(state): AxumState<ProxyAppState>,
     headers: HeaderMap,
     Query(query): Query<HashMap<String, String>>,
-    Json(body): Json<Value>,
+    body: Bytes,
 ) -> impl IntoResponse {
     // Origin validation (MCP Streamable HTTP spec)
-    if let Err((status, msg)) = validate_origin(&headers) {
+    if let Err((status, msg)) = check_origin(&state.app_handle, &headers) {
         return (status, HeaderMap::new(), msg);
     }
 
+    let use_sse = client_accepts_sse(&headers);
+    let body = match parse_request_body(&body, &headers) {
+        Ok(body) => body,
+        Err(rejection) => return rejection,
+    };
+
+    let (messages, is_batch) = match split_batch(body) {
+        Ok(split) => split,
+        Err(resp) => return mcp_response(&resp, None, use_sse),
+    };
+
     // Check if discovery mode is enabled
     {
         let app_state = state.app_handle.state::<SharedState>();
         let s = app_state.lock().unwrap();
         if !s.tool_discovery_enabled {
-            let resp = make_error_response(
-                body.get("id").cloned(),
-                -32001,
-                "Tool discovery mode is not enabled",
-            );
-            return json_response(&resp, None);
+            let mut responses: Vec<Value> = messages
+                .iter()
+                .map(|m| {
+                    make_error_response(
+                        m.get("id").cloned(),
+                        -32001,
+                        "Tool discovery mode is not enabled",
+                    )
+                })
+                .collect();
+            return if is_batch {
+                json_response(&Value::Array(responses), None)
+            } else {
+                json_response(&responses.swap_remove(0), None)
+            };
         }
     }
 
-    let method = body
-        .get("method")
-        .and_then(|m| m.as_str())
-        .unwrap_or_default();
-    let id = body.get("id").cloned();
-    let params = body.get("params").cloned();
     let client_id = query.get("client").cloned().unwrap_or_default();
 
-    let use_sse = client_accepts_sse(&headers);
-    let req_session: Option<String> = headers
-        .get("mcp-session-id")
-        .and_then(|v| v.to_str().ok())
-        .map(String::from);
-
-    // Notifications (no id) get 202 Accepted with no body
-    if id.is_none() {
-        return accepted_response(req_session.as_deref());
+    // Every message after `initialize` must carry the session it was issued.
+    // A batch led by `initialize` uses the session it opens.
+    let opens_session = match batch_opens_session(&messages) {
+        Ok(opens) => opens,
+        Err(resp) => return mcp_response(&resp, None, use_sse),
+    };
+    let mut session = if opens_session {
+        None
+    } else {
+        match require_session(&state.app_handle, &headers, DISCOVERY_ENDPOINT).await {
+            Ok(session_id) => Some(session_id),
+            Err(rejection) => return rejection,
+        }
+    };
+
+    let mut responses = Vec::new();
+    for message in messages {
+        if let Some(resp) =
+            handle_discovery_message(&state, &client_id, message, &mut session).await
+        {
+            responses.push(resp);
+        }
     }
+    post_reply(responses, is_batch, session.as_deref(), use_sse)
+}
+
+/// Answer one JSON-RPC message sent to the discovery endpoint. Returns `None`
+/// for notifications. `initialize` opens a new session and stores it in `session`.
+async fn handle_discovery_message(
+    state: &ProxyAppState,
+    client_id: &str,
+    body: Value,
+    session: &mut Option<String>,
+) -> Option<Value> {
+    let (id, method) = match classify_message(&body) {
+        Incoming::Invalid => return Some(make_error_response(None, -32600, "Invalid Request")),
+        Incoming::Notification => return None,
+        Incoming::Request { id, method } => (Some(id.clone()), method),
+    };
+    let params = body.get("params").cloned();
 
     info!("Discovery endpoint: {method}");
 
-    match method {
+    let response = match method {
         "initialize" => {
             // Negotiate protocol version from client's requested version
             let client_version = params
@@ -74,10 +133,17 @@ pub(crate) async fn handle_discovery_post(
                 .unwrap_or("");
             let negotiated = negotiate_version(client_version);
 
-            // Generate a session ID for this connection
-            let session_id = new_session_id();
-
-            let response = serde_json::json!({
+            // Issue and track a session ID for this connection
+            let session_id = state
+                .app_handle
+                .state::<ProxyState>()
+                .inner()
+                .clone()
+                .create_session(DISCOVERY_ENDPOINT)
+                .await;
+            *session = Some(session_id);
+
+            serde_json::json!({
                 "jsonrpc": "2.0",
                 "id": id,
                 "result": {
@@ -87,27 +153,21 @@ pub(crate) async fn handle_discovery_post(
                             "listChanged": false
                         }
                     },
-                    "serverInfo": {
-                        "name": "Agent Hub — Tool Discovery",
-                        "version": env!("CARGO_PKG_VERSION")
-                    }
+                    "serverInfo": advertised_server_info(&state.app_handle, "Tool Discovery")
                 }
-            });
-            mcp_response(&response, Some(&session_id), use_sse)
-        }
-        "tools/list" => {
-            let response = handle_tools_list(id);
-            mcp_response(&response, req_session.as_deref(), use_sse)
-        }
-        "tools/call" => {
-            let response = handle_tools_call(id, params, &client_id, &state).await;
-            mcp_response(&response, req_session.as_deref(), use_sse)
-        }
-        _ => {
-            let response =
-                make_error_response(id, -32601, &format!("Method not found: {method}"));
-            mcp_response(&response, req_session.as_deref(), use_sse)
+            })
         }
+        "tools/call" => handle_tools_call(id, params, client_id, session.as_deref(), state).await,
+        _ => stateless_response(id, method),
+    };
+    Some(response)
+}
+
+/// Answers that don't depend on app state: `tools/list` and unknown methods.
+pub(super) fn stateless_response(id: Option<Value>, method: &str) -> Value {
+    match method {
+        "tools/list" => handle_tools_list(id),
+        _ => make_error_response(id, -32601, &format!("Method not found: {method}")),
     }
 }
 
@@ -173,6 +233,7 @@ async fn handle_tools_call(
     id: Option<Value>,
     params: Option<Value>,
     client_id: &str,
+    session_id: Option<&str>,
     state: &ProxyAppState,
 ) -> Value {
     let params = match params {
@@ -193,7 +254,7 @@ async fn handle_tools_call(
     match tool_name {
         "discover_tools" => handle_discover_tools(id, &arguments, state),
         "list_servers" => handle_list_servers(id, state),
-        "call_tool" => handle_call_tool(id, &arguments, client_id, state).await,
+        "call_tool" => handle_call_tool(id, &arguments, client_id, session_id, state).await,
         _ => make_error_response(
             id,
             -32602,
@@ -265,6 +326,9 @@ fn handle_discover_tools(id: Option<Value>, arguments: &Value, state: &ProxyAppS
         };
 
         for tool in &conn.tools {
+            if !srv.discoverable_tool(&tool.name) {
+                continue;
+            }
             let name_lower = tool.name.to_lowercase();
             let desc_lower = tool
                 .description
@@ -333,14 +397,20 @@ fn handle_list_servers(id: Option<Value>, state: &ProxyAppState) -> Value {
         if srv.status != Some(crate::state::ServerStatus::Connected) {
             continue;
         }
-        if srv.managed_by.is_some() {
+        if srv.managed_by.is_some() || srv.exclude_from_discovery {
             continue;
         }
 
         let tool_names: Vec<String> = s
             .connections
             .get(&srv.id)
-            .map(|c| c.tools.iter().map(|t| t.name.clone()).collect())
+            .map(|c| {
+                c.tools
+                    .iter()
+                    .filter(|t| srv.discoverable_tool(&t.name))
+                    .map(|t| t.name.clone())
+                    .collect()
+            })
             .unwrap_or_default();
 
         servers.push(serde_json::json!({
@@ -413,16 +483,46 @@ async fn handle_call_tool(
     id: Option<Value>,
     arguments: &Value,
     client_id: &str,
+    session_id: Option<&str>,
     state: &ProxyAppState,
 ) -> Value {
-    let server_id = match arguments.get("server_id").and_then(|s| s.as_str()) {
-        Some(s) => s.to_string(),
-        None => return make_error_response(id, -32602, "Missing required argument: server_id"),
+    let requested_server = arguments.get("server_id").and_then(|s| s.as_str());
+    let requested_tool = arguments.get("tool_name").and_then(|s| s.as_str());
+
+    // Calls refused before reaching the backend are audited too
+    let refuse = |server_name: &str, response: Value| {
+        audit::record_tool_call(
+            &state.app_handle,
+            audit::ToolCallAudit {
+                server_id: requested_server.unwrap_or_default(),
+                server_name,
+                tool: requested_tool.unwrap_or_default(),
+                session_id,
+                client: client_id,
+                success: false,
+                duration_ms: 0,
+                arguments: arguments.get("arguments").unwrap_or(&Value::Null),
+                result: response
+                    .get("result")
+                    .or(response.get("error"))
+                    .unwrap_or(&Value::Null),
+            },
+        );
+        response
     };
 
-    let tool_name = match arguments.get("tool_name").and_then(|s| s.as_str()) {
-        Some(s) => s.to_string(),
-        None => return make_error_response(id, -32602, "Missing required argument: tool_name"),
+    let Some(server_id) = requested_server.map(str::to_string) else {
+        return refuse(
+            "",
+            make_error_response(id, -32602, "Missing required argument: server_id"),
+        );
+    };
+
+    let Some(tool_name) = requested_tool.map(str::to_string) else {
+        return refuse(
+            "",
+            make_error_response(id, -32602, "Missing required argument: tool_name"),
+        );
     };
 
     let tool_arguments = arguments
@@ -430,55 +530,95 @@ async fn handle_call_tool(
         .cloned()
         .unwrap_or(serde_json::json!({}));
 
-    // Look up server name and managed status
-    let (server_name, is_managed) = {
+    // Look up server name, managed status, and whether discovery may route the tool
+    let found = {
         let app_state = state.app_handle.state::<SharedState>();
         let s = app_state.lock().unwrap();
-        match s.servers.iter().find(|srv| srv.id == server_id) {
-            Some(srv) => (srv.name.clone(), srv.managed_by.is_some()),
-            None => {
-                return make_error_response(
-                    id,
-                    -32602,
-                    &format!("No server found with ID: {server_id}"),
-                );
-            }
-        }
+        s.servers.iter().find(|srv| srv.id == server_id).map(|srv| {
+            (
+                srv.name.clone(),
+                srv.managed_by.is_some(),
+                srv.discoverable_tool(&tool_name),
+            )
+        })
+    };
+    let Some((server_name, is_managed, is_exposed)) = found else {
+        return refuse(
+            &server_id,
+            make_error_response(id, -32602, &format!("No server found with ID: {server_id}")),
+        );
     };
 
     if is_managed {
-        return serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "result": {
-                "content": [{
-                    "type": "text",
-                    "text": format!(
-                        "Server '{}' is managed externally and its tools should be called directly through its own MCP connection, not via discovery.",
-                        server_name
-                    )
-                }],
-                "isError": true
-            }
-        });
+        return refuse(
+            &server_name,
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "content": [{
+                        "type": "text",
+                        "text": format!(
+                            "Server '{}' is managed externally and its tools should be called directly through its own MCP connection, not via discovery.",
+                            server_name
+                        )
+                    }],
+                    "isError": true
+                }
+            }),
+        );
+    }
+
+    if !is_exposed {
+        return refuse(
+            &server_name,
+            make_error_response(id, -32601, &format!("Tool not found: {tool_name}")),
+        );
+    }
+
+    let checked = {
+        let app_state = state.app_handle.state::<SharedState>();
+        let s = app_state.lock().unwrap();
+        validation::check_tool_call(&s, &server_id, &tool_name, &tool_arguments)
+    };
+    if let Err(msg) = checked {
+        return refuse(&server_name, make_error_response(id, -32602, &msg));
     }
 
     // Get the MCP client
     let connections = state.app_handle.state::<SharedConnections>();
     let client = {
         let conns = connections.lock().await;
-        match conns.get(&server_id).cloned() {
+        match conns.get(&server_id) {
             Some(c) => c,
             None => {
-                return make_error_response(
-                    id,
-                    -32602,
-                    &format!("Server '{server_name}' is not connected"),
+                return refuse(
+                    &server_name,
+                    make_error_response(
+                        id,
+                        -32602,
+                        &format!("Server '{server_name}' is not connected"),
+                    ),
                 );
             }
         }
     };
 
+    // Queue behind other in-flight calls to this server
+    let _slot = match client.acquire_call_slot(CALL_QUEUE_TIMEOUT).await {
+        Some(slot) => slot,
+        None => {
+            return refuse(
+                &server_name,
+                make_error_response(
+                    id,
+                    -32603,
+                    &format!("Server busy: '{server_name}' has too many tool calls in flight"),
+                ),
+            );
+        }
+    };
+
     info!("Discovery tool call: {server_name}.{tool_name}");
 
     let start = Instant::now();
@@ -548,5 +688,23 @@ async fn handle_call_tool(
     )
     .await;
 
+    audit::record_tool_call(
+        &state.app_handle,
+        audit::ToolCallAudit {
+            server_id: &server_id,
+            server_name: &server_name,
+            tool: &tool_name,
+            session_id,
+            client: client_id,
+            success: !is_error,
+            duration_ms,
+            arguments: arguments.get("arguments").unwrap_or(&Value::Null),
+            result: response
+                .get("result")
+                .or(response.get("error"))
+                .unwrap_or(&Value::Null),
+        },
+    );
+
     response
 }
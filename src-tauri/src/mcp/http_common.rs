@@ -34,6 +34,15 @@ pub(crate) fn new_session_id() -> String {
     Uuid::new_v4().to_string()
 }
 
+/// Read the `Mcp-Session-Id` header sent by the client, if any.
+pub(crate) fn request_session_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("mcp-session-id")
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+}
+
 // ---------------------------------------------------------------------------
 // Origin validation
 // ---------------------------------------------------------------------------
@@ -65,8 +74,12 @@ fn is_localhost_origin(origin: &str) -> bool {
 /// - No Origin header → allow (non-browser client).
 /// - Localhost variant → allow.
 /// - `tauri://` or `https://tauri.` scheme → allow (Tauri webview).
+/// - Listed in the user's `extra_allowed` origins → allow.
 /// - Anything else → 403 Forbidden.
-pub(crate) fn validate_origin(headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+pub(crate) fn validate_origin(
+    headers: &HeaderMap,
+    extra_allowed: &[String],
+) -> Result<(), (StatusCode, String)> {
     let origin = match headers.get("origin") {
         Some(v) => v.to_str().unwrap_or(""),
         None => return Ok(()), // non-browser client
@@ -84,6 +97,15 @@ pub(crate) fn validate_origin(headers: &HeaderMap) -> Result<(), (StatusCode, St
         return Ok(());
     }
 
+    let normalized = origin.trim_end_matches('/');
+    if extra_allowed.iter().any(|allowed| {
+        allowed
+            .trim_end_matches('/')
+            .eq_ignore_ascii_case(normalized)
+    }) {
+        return Ok(());
+    }
+
     Err((
         StatusCode::FORBIDDEN,
         format!("Origin not allowed: {origin}"),
@@ -203,7 +225,38 @@ mod tests {
     #[test]
     fn no_origin_allowed() {
         let headers = HeaderMap::new();
-        assert!(validate_origin(&headers).is_ok());
+        assert!(validate_origin(&headers, &[]).is_ok());
+    }
+
+    fn origin_headers(origin: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("origin", HeaderValue::from_str(origin).unwrap());
+        headers
+    }
+
+    #[test]
+    fn configured_origins_allowed() {
+        let allowed = vec!["https://app.example.com/".to_string()];
+        for origin in ["https://app.example.com", "https://APP.example.com/"] {
+            assert!(
+                validate_origin(&origin_headers(origin), &allowed).is_ok(),
+                "{origin} should be allowed"
+            );
+        }
+    }
+
+    #[test]
+    fn unlisted_origins_rejected() {
+        let allowed = vec!["https://app.example.com".to_string()];
+        for origin in [
+            "https://evil.example.com",
+            "http://app.example.com",
+            "https://app.example.com.evil.com",
+        ] {
+            let (status, _) = validate_origin(&origin_headers(origin), &allowed).unwrap_err();
+            assert_eq!(status, StatusCode::FORBIDDEN, "{origin} should be rejected");
+        }
+        assert!(validate_origin(&origin_headers("https://app.example.com"), &[]).is_err());
     }
 
     #[test]
@@ -219,7 +272,7 @@ mod tests {
             let mut headers = HeaderMap::new();
             headers.insert("origin", HeaderValue::from_str(origin).expect("valid header"));
             assert!(
-                validate_origin(&headers).is_ok(),
+                validate_origin(&headers, &[]).is_ok(),
                 "expected {origin} to be allowed"
             );
         }
@@ -231,7 +284,7 @@ mod tests {
             let mut headers = HeaderMap::new();
             headers.insert("origin", HeaderValue::from_str(origin).expect("valid header"));
             assert!(
-                validate_origin(&headers).is_ok(),
+                validate_origin(&headers, &[]).is_ok(),
                 "expected {origin} to be allowed"
             );
         }
@@ -244,7 +297,7 @@ mod tests {
             "origin",
             HeaderValue::from_static("https://evil.example.com"),
         );
-        let err = validate_origin(&headers).unwrap_err();
+        let err = validate_origin(&headers, &[]).unwrap_err();
         assert_eq!(err.0, StatusCode::FORBIDDEN);
     }
 
@@ -336,6 +389,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn request_session_id_reads_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_session_id(&headers), None);
+        headers.insert("mcp-session-id", HeaderValue::from_static(""));
+        assert_eq!(request_session_id(&headers), None);
+        headers.insert("mcp-session-id", HeaderValue::from_static("sess-789"));
+        assert_eq!(request_session_id(&headers).as_deref(), Some("sess-789"));
+    }
+
     #[test]
     fn new_session_id_is_valid_uuid() {
         let id = new_session_id();
@@ -3,13 +3,28 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use futures::StreamExt;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
+use tauri::{AppHandle, Manager};
 use tokio::sync::{oneshot, Mutex};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
 use crate::error::AppError;
+use crate::mcp::client::ClientOptions;
+use crate::mcp::oauth::resolve_access_token;
+use crate::mcp::transport::{decode_request_id, encode_request_id, server_request_reply};
 use crate::mcp::types::{JsonRpcRequest, JsonRpcResponse};
+use crate::state::{JsonRpcIdFormat, SharedOAuthStore};
+
+/// Header value placeholder replaced with the server's current OAuth access
+/// token on every request, e.g. `Authorization: Bearer ${oauth.access_token}`.
+pub const OAUTH_TOKEN_PLACEHOLDER: &str = "${oauth.access_token}";
+
+/// Where to look up a fresh OAuth token before each request.
+struct TokenRefresh {
+    app: AppHandle,
+    server_id: String,
+}
 
 /// Pending request senders, keyed by stringified JSON-RPC id.
 type PendingMap = Arc<Mutex<HashMap<String, oneshot::Sender<JsonRpcResponse>>>>;
@@ -24,6 +39,8 @@ type PendingMap = Arc<Mutex<HashMap<String, oneshot::Sender<JsonRpcResponse>>>>;
 ///   the POST response body.
 pub struct HttpTransport {
     next_id: AtomicU64,
+    /// Wire format for outgoing request ids.
+    id_format: JsonRpcIdFormat,
     client: Client,
     /// The URL to POST JSON-RPC requests to.
     post_url: String,
@@ -39,6 +56,14 @@ pub struct HttpTransport {
     pending: PendingMap,
     /// Background SSE reader task handle (legacy SSE only).
     _sse_reader: Option<JoinHandle<()>>,
+    /// Params of the last `initialize`, replayed when the server drops our session.
+    init_params: Mutex<Option<serde_json::Value>>,
+    /// Serializes re-initialization so concurrent calls that all hit an
+    /// expired session start only one new session.
+    reinit_lock: Mutex<()>,
+    /// Set by [`Self::refresh_tokens_from`]; otherwise the token from connect
+    /// time is used for the life of the transport.
+    token_refresh: Option<TokenRefresh>,
 }
 
 impl HttpTransport {
@@ -51,14 +76,24 @@ impl HttpTransport {
         url: &str,
         headers: HashMap<String, String>,
         access_token: Option<String>,
+        options: &ClientOptions,
     ) -> Result<Self, AppError> {
-        let client = Client::new();
+        let id_format = options.id_format;
+        let client = if options.allow_insecure_tls {
+            warn!("TLS certificate verification is DISABLED for {url}");
+            Client::builder()
+                .danger_accept_invalid_certs(true)
+                .build()
+                .map_err(|e| AppError::Transport(format!("Failed to build HTTP client: {e}")))?
+        } else {
+            Client::new()
+        };
         let token = Arc::new(Mutex::new(access_token));
 
         // Heuristic: if the URL ends with /sse, use legacy SSE mode
         if url.ends_with("/sse") {
             info!("URL ends with /sse, using legacy SSE transport for {url}");
-            return Self::connect_legacy_sse(url, headers, client, token).await;
+            return Self::connect_legacy_sse(url, headers, client, token, id_format).await;
         }
 
         // Default: streamable HTTP — just store the URL, no probing needed.
@@ -66,6 +101,7 @@ impl HttpTransport {
 
         Ok(Self {
             next_id: AtomicU64::new(1),
+            id_format,
             client,
             post_url: url.to_string(),
             headers,
@@ -74,6 +110,9 @@ impl HttpTransport {
             legacy_sse: false,
             pending: Arc::new(Mutex::new(HashMap::new())),
             _sse_reader: None,
+            init_params: Mutex::new(None),
+            reinit_lock: Mutex::new(()),
+            token_refresh: None,
         })
     }
 
@@ -85,20 +124,11 @@ impl HttpTransport {
         headers: HashMap<String, String>,
         client: Client,
         access_token: Arc<Mutex<Option<String>>>,
+        id_format: JsonRpcIdFormat,
     ) -> Result<Self, AppError> {
-        let mut req = client.get(url).header("Accept", "text/event-stream");
-
-        for (k, v) in &headers {
-            req = req.header(k.as_str(), v.as_str());
-        }
-
-        // Inject Bearer token if available
-        {
-            let tok = access_token.lock().await;
-            if let Some(ref token) = *tok {
-                req = req.header("Authorization", format!("Bearer {token}"));
-            }
-        }
+        let req = client.get(url).header("Accept", "text/event-stream");
+        let token = access_token.lock().await.clone();
+        let req = with_auth_headers(req, &headers, token.as_deref());
 
         let response = req
             .send()
@@ -203,6 +233,7 @@ impl HttpTransport {
 
         Ok(Self {
             next_id: AtomicU64::new(1),
+            id_format,
             client,
             post_url,
             headers,
@@ -211,6 +242,9 @@ impl HttpTransport {
             legacy_sse: true,
             pending,
             _sse_reader: Some(sse_reader),
+            init_params: Mutex::new(None),
+            reinit_lock: Mutex::new(()),
+            token_refresh: None,
         })
     }
 
@@ -219,12 +253,22 @@ impl HttpTransport {
         &self,
         method: &str,
         params: Option<serde_json::Value>,
+    ) -> Result<JsonRpcResponse, AppError> {
+        self.send_request_raw(method, params).await?.into_result()
+    }
+
+    /// Like [`Self::send_request`], but a JSON-RPC error response is returned
+    /// as-is rather than converted into an `Err`.
+    pub async fn send_request_raw(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
     ) -> Result<JsonRpcResponse, AppError> {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
 
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
-            id: Some(serde_json::Value::Number(id.into())),
+            id: Some(encode_request_id(id, self.id_format)),
             method: method.to_string(),
             params,
         };
@@ -241,33 +285,75 @@ impl HttpTransport {
             return self.send_request_legacy_sse(id, &body, method).await;
         }
 
+        if method == "initialize" {
+            *self.init_params.lock().await = request.params.clone();
+        }
+
         // Streamable HTTP: POST and read response from body
+        let (mut response, sent_session) = self.post(&body).await?;
+
+        // The server forgot our session (restart, expiry): start a new one
+        // and retry once before giving up
+        if response.status() == reqwest::StatusCode::NOT_FOUND && method != "initialize" {
+            if let Some(stale) = sent_session {
+                warn!(
+                    "Session expired on {}; re-initializing before retrying {method}",
+                    self.post_url
+                );
+                self.reinitialize(&stale).await?;
+                response = self.post(&body).await?.0;
+            }
+        }
+
+        self.read_response(response, id, method).await
+    }
+
+    /// Re-read the server's OAuth token from the store before each request,
+    /// refreshing it when expired, so rotated tokens are used without
+    /// reconnecting.
+    pub fn refresh_tokens_from(&mut self, app: &AppHandle, server_id: &str) {
+        self.token_refresh = Some(TokenRefresh {
+            app: app.clone(),
+            server_id: server_id.to_string(),
+        });
+    }
+
+    /// The token to send now, picking up a refreshed one if configured.
+    async fn current_token(&self) -> Option<String> {
+        if let Some(refresh) = &self.token_refresh {
+            let oauth_store = refresh.app.state::<SharedOAuthStore>();
+            if let Some(token) =
+                resolve_access_token(&oauth_store, &refresh.server_id, &refresh.app).await
+            {
+                *self.access_token.lock().await = Some(token);
+            }
+        }
+        self.access_token.lock().await.clone()
+    }
+
+    /// POST a JSON-RPC message over the pooled client with the configured
+    /// headers, token and session. Returns the response and the session id
+    /// that was sent, and remembers any session id the server assigns.
+    async fn post(
+        &self,
+        body: &serde_json::Value,
+    ) -> Result<(reqwest::Response, Option<String>), AppError> {
         let mut req = self
             .client
             .post(&self.post_url)
             .header("Content-Type", "application/json")
             .header("Accept", "application/json, text/event-stream");
 
-        for (k, v) in &self.headers {
-            req = req.header(k.as_str(), v.as_str());
-        }
-
-        {
-            let tok = self.access_token.lock().await;
-            if let Some(ref token) = *tok {
-                req = req.header("Authorization", format!("Bearer {token}"));
-            }
-        }
+        let token = self.current_token().await;
+        req = with_auth_headers(req, &self.headers, token.as_deref());
 
-        {
-            let sid = self.session_id.lock().await;
-            if let Some(ref s) = *sid {
-                req = req.header("Mcp-Session-Id", s.as_str());
-            }
+        let sent_session = self.session_id.lock().await.clone();
+        if let Some(ref s) = sent_session {
+            req = req.header("Mcp-Session-Id", s.as_str());
         }
 
         let response = req
-            .json(&body)
+            .json(body)
             .send()
             .await
             .map_err(|e| AppError::Transport(format!("HTTP request failed: {e}")))?;
@@ -281,6 +367,18 @@ impl HttpTransport {
             *sid = Some(new_sid.to_string());
         }
 
+        Ok((response, sent_session))
+    }
+
+    /// Check a streamable HTTP response's status and decode the JSON-RPC
+    /// response to request `id` from its body, which is either plain JSON or
+    /// an SSE stream.
+    async fn read_response(
+        &self,
+        response: reqwest::Response,
+        id: u64,
+        method: &str,
+    ) -> Result<JsonRpcResponse, AppError> {
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(AppError::AuthRequired(self.post_url.clone()));
         }
@@ -299,15 +397,13 @@ impl HttpTransport {
             .unwrap_or("")
             .to_string();
 
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| AppError::Transport(format!("Failed to read HTTP response: {e}")))?;
-
         let json_text = if content_type.contains("text/event-stream") {
-            extract_json_from_sse(&response_text)?
+            self.read_sse_response(response, id, method).await?
         } else {
-            response_text
+            response
+                .text()
+                .await
+                .map_err(|e| AppError::Transport(format!("Failed to read HTTP response: {e}")))?
         };
 
         let rpc_response: JsonRpcResponse = serde_json::from_str(&json_text).map_err(|e| {
@@ -316,11 +412,105 @@ impl HttpTransport {
             ))
         })?;
 
-        if let Some(err) = &rpc_response.error {
-            return Err(AppError::Protocol(format!("{}: {}", err.code, err.message)));
+        Ok(rpc_response)
+    }
+
+    /// Read an SSE response body (streamable HTTP mode) until the `message`
+    /// event carrying the response to request `id`, and return its data. The
+    /// server may send notifications or requests of its own first, and may keep
+    /// the stream open afterwards, so we stop as soon as our response arrives.
+    async fn read_sse_response(
+        &self,
+        response: reqwest::Response,
+        id: u64,
+        method: &str,
+    ) -> Result<String, AppError> {
+        let mut stream = response.bytes_stream();
+        let mut buffer = Vec::new();
+        let mut ended = false;
+
+        loop {
+            match take_sse_message(&mut buffer, id) {
+                Some(SseMessage::Response(data)) => return Ok(data),
+                Some(SseMessage::ServerRequest {
+                    id: request_id,
+                    method: request_method,
+                }) => {
+                    self.answer_server_request(request_id, &request_method)
+                        .await;
+                    continue;
+                }
+                None if ended => {
+                    return Err(AppError::Transport(format!(
+                        "SSE response for {method} ended without a JSON-RPC response"
+                    )));
+                }
+                None => {}
+            }
+            match stream.next().await {
+                Some(chunk) => {
+                    let chunk =
+                        chunk.map_err(|e| AppError::Transport(format!("SSE stream error: {e}")))?;
+                    push_sse_chunk(&mut buffer, &chunk);
+                }
+                None => {
+                    // The final event may not be followed by a blank line
+                    buffer.extend_from_slice(b"\n\n");
+                    ended = true;
+                }
+            }
         }
+    }
 
-        Ok(rpc_response)
+    /// Reply to a request the server sent on a response stream by POSTing
+    /// the answer, as the stdio reader does on stdin. Roots and sampling
+    /// aren't advertised over HTTP, so only `ping` gets a result.
+    async fn answer_server_request(&self, id: serde_json::Value, method: &str) {
+        debug!("Answering server request {method} on {}", self.post_url);
+        let reply = server_request_reply(id, method, &[]);
+        match self.post(&reply).await {
+            Ok((response, _)) if !response.status().is_success() => {
+                warn!(
+                    "Reply to server request {method} returned status {}",
+                    response.status()
+                );
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to answer server request {method}: {e}"),
+        }
+    }
+
+    /// Replace the session `stale` with a fresh one by replaying the last
+    /// `initialize`. A no-op if another call already did so.
+    async fn reinitialize(&self, stale: &str) -> Result<(), AppError> {
+        let _guard = self.reinit_lock.lock().await;
+        {
+            let mut sid = self.session_id.lock().await;
+            if sid.as_deref() != Some(stale) {
+                return Ok(());
+            }
+            *sid = None;
+        }
+
+        let params = self.init_params.lock().await.clone();
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(encode_request_id(id, self.id_format)),
+            method: "initialize".to_string(),
+            params,
+        };
+        let body = serde_json::to_value(&request)
+            .map_err(|e| AppError::Transport(format!("Failed to serialize request: {e}")))?;
+
+        let (response, _) = self.post(&body).await?;
+        self.read_response(response, id, "initialize")
+            .await?
+            .into_result()?;
+        self.send_notification("notifications/initialized", None)
+            .await?;
+        info!("Started a new session on {}", self.post_url);
+        Ok(())
     }
 
     /// Legacy SSE: POST the request and wait for the response on the SSE stream.
@@ -346,16 +536,8 @@ impl HttpTransport {
             .header("Content-Type", "application/json")
             .header("Accept", "application/json, text/event-stream");
 
-        for (k, v) in &self.headers {
-            req = req.header(k.as_str(), v.as_str());
-        }
-
-        {
-            let tok = self.access_token.lock().await;
-            if let Some(ref token) = *tok {
-                req = req.header("Authorization", format!("Bearer {token}"));
-            }
-        }
+        let token = self.current_token().await;
+        req = with_auth_headers(req, &self.headers, token.as_deref());
 
         {
             let sid = self.session_id.lock().await;
@@ -391,19 +573,14 @@ impl HttpTransport {
         // Wait for the response to arrive on the SSE stream
         let timeout = tokio::time::Duration::from_secs(60);
         match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(rpc_response)) => {
-                if let Some(err) = &rpc_response.error {
-                    return Err(AppError::Protocol(format!("{}: {}", err.code, err.message)));
-                }
-                Ok(rpc_response)
-            }
+            Ok(Ok(rpc_response)) => Ok(rpc_response),
             Ok(Err(_)) => Err(AppError::Transport(
                 "SSE stream closed while waiting for response".to_string(),
             )),
             Err(_) => {
                 self.pending.lock().await.remove(&id_str);
-                Err(AppError::Transport(format!(
-                    "Timeout waiting for SSE response to {method} (id={id})"
+                Err(AppError::Timeout(format!(
+                    "waiting for SSE response to {method} (id={id})"
                 )))
             }
         }
@@ -427,44 +604,7 @@ impl HttpTransport {
 
         debug!("HTTP send_notification method={method}");
 
-        let mut req = self
-            .client
-            .post(&self.post_url)
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json, text/event-stream");
-
-        for (k, v) in &self.headers {
-            req = req.header(k.as_str(), v.as_str());
-        }
-
-        {
-            let tok = self.access_token.lock().await;
-            if let Some(ref token) = *tok {
-                req = req.header("Authorization", format!("Bearer {token}"));
-            }
-        }
-
-        {
-            let sid = self.session_id.lock().await;
-            if let Some(ref s) = *sid {
-                req = req.header("Mcp-Session-Id", s.as_str());
-            }
-        }
-
-        let response = req
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| AppError::Transport(format!("HTTP notification failed: {e}")))?;
-
-        if let Some(new_sid) = response
-            .headers()
-            .get("mcp-session-id")
-            .and_then(|v| v.to_str().ok())
-        {
-            let mut sid = self.session_id.lock().await;
-            *sid = Some(new_sid.to_string());
-        }
+        let (response, _) = self.post(&body).await?;
 
         // Notifications may return 200 or 202; we don't need the body.
         if !response.status().is_success() {
@@ -618,28 +758,213 @@ async fn dispatch_sse_responses(buffer: &mut String, pending: &PendingMap) {
     }
 }
 
-/// Extract JSON-RPC response data from an SSE response body (streamable HTTP mode).
-/// SSE responses contain `data:` lines with JSON fragments.
-fn extract_json_from_sse(body: &str) -> Result<String, AppError> {
-    let mut json_parts = Vec::new();
-    let mut current_event = String::new();
+/// Add the configured headers and the OAuth token to a request. Headers using
+/// [`OAUTH_TOKEN_PLACEHOLDER`] get the current token, and are left out while
+/// there is none. Unless a header already sets `Authorization`, the token is
+/// also sent as a Bearer credential.
+fn with_auth_headers(
+    mut req: RequestBuilder,
+    headers: &HashMap<String, String>,
+    token: Option<&str>,
+) -> RequestBuilder {
+    let mut has_authorization = false;
+    for (k, v) in headers {
+        let value = match (v.contains(OAUTH_TOKEN_PLACEHOLDER), token) {
+            (false, _) => v.clone(),
+            (true, Some(token)) => v.replace(OAUTH_TOKEN_PLACEHOLDER, token),
+            (true, None) => continue,
+        };
+        has_authorization |= k.eq_ignore_ascii_case("authorization");
+        req = req.header(k.as_str(), value);
+    }
+    match token {
+        Some(token) if !has_authorization => req.header("Authorization", format!("Bearer {token}")),
+        _ => req,
+    }
+}
 
-    for line in body.lines() {
-        if let Some(event_type) = line.strip_prefix("event:") {
-            current_event = event_type.trim().to_string();
-        } else if let Some(data) = line.strip_prefix("data:") {
-            // Accept "message" events or events with no type (default is "message")
-            if current_event.is_empty() || current_event == "message" {
-                json_parts.push(data.trim().to_string());
+/// What a `message` event on a response stream asks of us.
+#[derive(Debug, PartialEq)]
+enum SseMessage {
+    /// The JSON-RPC response to the request being waited on, as raw JSON.
+    Response(String),
+    /// A request from the server, which needs a reply of its own.
+    ServerRequest {
+        id: serde_json::Value,
+        method: String,
+    },
+}
+
+/// Append a body chunk to an SSE buffer. Carriage returns are dropped so
+/// CRLF-framed streams split on `\n\n` too, even when a CRLF straddles chunks.
+fn push_sse_chunk(buffer: &mut Vec<u8>, chunk: &[u8]) {
+    buffer.extend(chunk.iter().filter(|&&b| b != b'\r'));
+}
+
+/// Consume complete SSE events from the buffer until one is the response to
+/// request `id` or a request from the server. Events are only decoded once
+/// complete, so a character split across chunks survives. Other events are
+/// dropped; any incomplete trailing event is left in the buffer.
+fn take_sse_message(buffer: &mut Vec<u8>, id: u64) -> Option<SseMessage> {
+    while let Some(event_end) = buffer.windows(2).position(|w| w == b"\n\n") {
+        let event: Vec<u8> = buffer.drain(..event_end + 2).collect();
+        let event_block = String::from_utf8_lossy(&event[..event_end]);
+
+        let mut event_type = "";
+        let mut data_parts = Vec::new();
+        for line in event_block.lines() {
+            if let Some(et) = line.strip_prefix("event:") {
+                event_type = et.trim();
+            } else if let Some(d) = line.strip_prefix("data:") {
+                data_parts.push(d.trim());
             }
         }
+
+        // Events with no explicit type default to "message"
+        if (!event_type.is_empty() && event_type != "message") || data_parts.is_empty() {
+            continue;
+        }
+
+        let data = data_parts.join("\n");
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(&data) else {
+            warn!("Ignoring SSE event with non-JSON data: {data}");
+            continue;
+        };
+        match (
+            message.get("method").and_then(|m| m.as_str()),
+            message.get("id"),
+        ) {
+            (Some(method), Some(request_id)) => {
+                return Some(SseMessage::ServerRequest {
+                    id: request_id.clone(),
+                    method: method.to_string(),
+                });
+            }
+            (None, Some(response_id)) if decode_request_id(response_id) == Some(id) => {
+                return Some(SseMessage::Response(data));
+            }
+            _ => debug!("Skipping SSE message while waiting for response id={id}: {data}"),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_headers(headers: &[(&str, &str)], token: Option<&str>) -> reqwest::header::HeaderMap {
+        let headers: HashMap<String, String> = headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let req = with_auth_headers(Client::new().post("http://localhost/mcp"), &headers, token);
+        req.build().expect("valid request").headers().clone()
+    }
+
+    #[test]
+    fn token_is_sent_as_bearer_by_default() {
+        let headers = auth_headers(&[("X-Team", "core")], Some("tok"));
+        assert_eq!(headers["authorization"], "Bearer tok");
+        assert_eq!(headers["x-team"], "core");
+
+        assert!(auth_headers(&[], None).get("authorization").is_none());
     }
 
-    if json_parts.is_empty() {
-        return Err(AppError::Transport(
-            "No JSON data found in SSE response".to_string(),
+    #[test]
+    fn placeholder_headers_get_the_current_token() {
+        let template = [("X-Api-Key", "key ${oauth.access_token}")];
+        let headers = auth_headers(&template, Some("tok"));
+        assert_eq!(headers["x-api-key"], "key tok");
+        assert_eq!(headers["authorization"], "Bearer tok");
+
+        // Left out entirely until there is a token to fill in
+        assert!(auth_headers(&template, None).get("x-api-key").is_none());
+    }
+
+    #[test]
+    fn configured_authorization_is_not_overridden() {
+        let headers = auth_headers(
+            &[("authorization", "Token ${oauth.access_token}")],
+            Some("tok"),
+        );
+        assert_eq!(headers["authorization"], "Token tok");
+        assert_eq!(headers.get_all("authorization").iter().count(), 1);
+    }
+
+    #[test]
+    fn takes_the_response_matching_the_request_id() {
+        let mut buffer = Vec::new();
+        push_sse_chunk(
+            &mut buffer,
+            concat!(
+                "event: message\r\n",
+                "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\r\n\r\n",
+                "data: {\"jsonrpc\":\"2.0\",\"id\":6,\"result\":{}}\n\n",
+                "data: {\"jsonrpc\":\"2.0\",\"id\":\"7\",\"result\":{\"ok\":true}}\n\n",
+                "data: {\"jsonrpc\":\"2.0\",\"method\":\"later\"",
+            )
+            .as_bytes(),
+        );
+
+        let Some(SseMessage::Response(data)) = take_sse_message(&mut buffer, 7) else {
+            panic!("expected the response to id 7");
+        };
+        assert!(data.contains("\"ok\":true"));
+        assert!(buffer.starts_with(b"data: {\"jsonrpc\""));
+    }
+
+    #[test]
+    fn surfaces_server_requests_before_the_response() {
+        let mut buffer = Vec::new();
+        push_sse_chunk(
+            &mut buffer,
+            concat!(
+                "event: message\n",
+                "data: {\"jsonrpc\":\"2.0\",\"id\":\"s1\",\"method\":\"ping\"}\n\n",
+                "data: {\"jsonrpc\":\"2.0\",\"id\":7,\"result\":{}}\n\n",
+            )
+            .as_bytes(),
+        );
+
+        assert_eq!(
+            take_sse_message(&mut buffer, 7),
+            Some(SseMessage::ServerRequest {
+                id: serde_json::json!("s1"),
+                method: "ping".into(),
+            })
+        );
+        assert!(matches!(
+            take_sse_message(&mut buffer, 7),
+            Some(SseMessage::Response(_))
         ));
     }
 
-    Ok(json_parts.last().expect("non-empty after guard").clone())
+    #[test]
+    fn waits_for_a_complete_event() {
+        let mut buffer = Vec::new();
+        push_sse_chunk(
+            &mut buffer,
+            b"event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,",
+        );
+        assert_eq!(take_sse_message(&mut buffer, 1), None);
+
+        push_sse_chunk(&mut buffer, b"\"result\":{}}\r\n\r\n");
+        assert!(take_sse_message(&mut buffer, 1).is_some());
+    }
+
+    #[test]
+    fn keeps_characters_split_across_chunks() {
+        let event = "data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"s\":\"é\"}}\n\n";
+        let split = event.find('é').unwrap() + 1;
+        let mut buffer = Vec::new();
+        push_sse_chunk(&mut buffer, &event.as_bytes()[..split]);
+        assert_eq!(take_sse_message(&mut buffer, 1), None);
+        push_sse_chunk(&mut buffer, &event.as_bytes()[split..]);
+
+        let Some(SseMessage::Response(data)) = take_sse_message(&mut buffer, 1) else {
+            panic!("expected a response");
+        };
+        assert!(data.contains("\"s\":\"é\""));
+    }
 }
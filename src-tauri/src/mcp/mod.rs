@@ -4,6 +4,10 @@ pub mod http_common;
 pub mod http_transport;
 pub mod oauth;
 pub mod oauth_callback;
+pub mod prewarm;
 pub mod proxy;
+pub mod sampling;
+pub mod trace;
 pub mod transport;
 pub mod types;
+pub mod validation;
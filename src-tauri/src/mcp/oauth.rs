@@ -1,8 +1,10 @@
+use std::time::Duration;
+
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use rand::Rng;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use sha2::{Digest, Sha256};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use url::Url;
 
 use crate::error::AppError;
@@ -12,6 +14,88 @@ use crate::state::{AuthServerMetadata, OAuthTokens, SharedOAuthStore};
 pub struct PkceChallenge {
     pub code_verifier: String,
     pub code_challenge: String,
+    /// `code_challenge_method` sent with the authorization request.
+    pub method: &'static str,
+}
+
+// --- Retry ---
+
+/// How hard to retry an OAuth HTTP call before giving up.
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    attempts: u32,
+    base_delay: Duration,
+    /// Also retry timeouts, 5xx, and 429. Only safe for idempotent requests —
+    /// otherwise we retry only when the connection never got established.
+    idempotent: bool,
+}
+
+/// Metadata discovery is a plain GET, so retry it liberally.
+const DISCOVERY_RETRY: RetryPolicy = RetryPolicy {
+    attempts: 4,
+    base_delay: Duration::from_millis(250),
+    idempotent: true,
+};
+
+/// Registration and token requests consume one-shot codes or rotate refresh
+/// tokens, so only retry when the request provably never reached the server.
+const TOKEN_RETRY: RetryPolicy = RetryPolicy {
+    attempts: 3,
+    base_delay: Duration::from_millis(500),
+    idempotent: false,
+};
+
+impl RetryPolicy {
+    /// Exponential backoff before retry number `attempt` (1-based).
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay * 2u32.saturating_pow(attempt.saturating_sub(1))
+    }
+
+    fn should_retry_error(&self, e: &reqwest::Error) -> bool {
+        e.is_connect() || (self.idempotent && (e.is_timeout() || e.is_request()))
+    }
+
+    fn should_retry_status(&self, status: StatusCode) -> bool {
+        self.idempotent && (status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS)
+    }
+}
+
+/// Send a request built by `build`, retrying network failures per `policy`.
+///
+/// Any HTTP response that isn't retryable is returned as-is so callers can
+/// report OAuth protocol errors (4xx bodies) without retrying them.
+async fn send_with_retry(
+    policy: RetryPolicy,
+    what: &str,
+    build: impl Fn() -> RequestBuilder,
+) -> Result<Response, AppError> {
+    let mut attempt = 1;
+    loop {
+        let can_retry = attempt < policy.attempts;
+        match build().send().await {
+            Ok(response) if can_retry && policy.should_retry_status(response.status()) => {
+                warn!(
+                    "{what} returned {} (attempt {attempt}/{}), retrying",
+                    response.status(),
+                    policy.attempts
+                );
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if can_retry && policy.should_retry_error(&e) => {
+                warn!(
+                    "{what} failed (attempt {attempt}/{}): {e}, retrying",
+                    policy.attempts
+                );
+            }
+            Err(e) => {
+                return Err(AppError::OAuth(format!(
+                    "{what} failed after {attempt} attempt(s): {e}"
+                )));
+            }
+        }
+        tokio::time::sleep(policy.delay(attempt)).await;
+        attempt += 1;
+    }
 }
 
 // --- Discovery ---
@@ -33,7 +117,11 @@ pub async fn discover_metadata(server_url: &str) -> Result<AuthServerMetadata, A
     let pr_url = format!("{origin}/.well-known/oauth-protected-resource");
     debug!("Trying protected resource discovery at {pr_url}");
 
-    if let Ok(response) = client.get(&pr_url).send().await {
+    if let Ok(response) = send_with_retry(DISCOVERY_RETRY, "Protected resource discovery", || {
+        client.get(&pr_url)
+    })
+    .await
+    {
         if response.status().is_success() {
             if let Ok(body) = response.json::<serde_json::Value>().await {
                 let auth_servers = body
@@ -74,11 +162,10 @@ async fn fetch_auth_server_metadata(
     let well_known_url = format!("{origin}/.well-known/oauth-authorization-server");
     debug!("Fetching auth server metadata at {well_known_url}");
 
-    let response = client
-        .get(&well_known_url)
-        .send()
-        .await
-        .map_err(|e| AppError::OAuth(format!("Auth server discovery failed: {e}")))?;
+    let response = send_with_retry(DISCOVERY_RETRY, "Auth server discovery", || {
+        client.get(&well_known_url)
+    })
+    .await?;
 
     if !response.status().is_success() {
         return Err(AppError::OAuth(format!(
@@ -102,22 +189,36 @@ async fn fetch_auth_server_metadata(
 
 // --- PKCE ---
 
-/// Generate a PKCE code_verifier and code_challenge (S256).
-pub fn generate_pkce() -> PkceChallenge {
+/// Generate a PKCE code_verifier and code_challenge. Uses S256 unless the
+/// server advertises only `plain`; an empty list (not advertised) gets S256.
+pub fn generate_pkce(methods_supported: &[String]) -> PkceChallenge {
     let mut rng = rand::rng();
     let mut bytes = [0u8; 32];
     rng.fill(&mut bytes);
 
     let code_verifier = URL_SAFE_NO_PAD.encode(bytes);
-    let digest = Sha256::digest(code_verifier.as_bytes());
-    let code_challenge = URL_SAFE_NO_PAD.encode(digest);
+    let method = pkce_method(methods_supported);
+    let code_challenge = match method {
+        "plain" => code_verifier.clone(),
+        _ => URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes())),
+    };
 
     PkceChallenge {
         code_verifier,
         code_challenge,
+        method,
     }
 }
 
+fn pkce_method(methods_supported: &[String]) -> &'static str {
+    let advertises = |m: &str| methods_supported.iter().any(|s| s == m);
+    if advertises("plain") && !advertises("S256") {
+        warn!("Authorization server does not support S256 PKCE, falling back to plain");
+        return "plain";
+    }
+    "S256"
+}
+
 /// Generate a random state nonce for CSRF protection.
 pub fn generate_state_nonce() -> String {
     let mut rng = rand::rng();
@@ -145,13 +246,13 @@ pub async fn dynamic_register(
 
     debug!("Dynamic client registration at {registration_endpoint}");
 
-    let response = client
-        .post(registration_endpoint)
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| AppError::OAuth(format!("Dynamic registration failed: {e}")))?;
+    let response = send_with_retry(TOKEN_RETRY, "Dynamic registration", || {
+        client
+            .post(registration_endpoint)
+            .header("Content-Type", "application/json")
+            .json(&body)
+    })
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -199,7 +300,7 @@ pub fn build_authorization_url(
         .append_pair("client_id", client_id)
         .append_pair("redirect_uri", redirect_uri)
         .append_pair("code_challenge", &pkce.code_challenge)
-        .append_pair("code_challenge_method", "S256")
+        .append_pair("code_challenge_method", pkce.method)
         .append_pair("state", state);
 
     // Add scopes if the server supports any
@@ -241,12 +342,10 @@ pub async fn exchange_code(
 
     debug!("Exchanging code at {}", metadata.token_endpoint);
 
-    let response = client
-        .post(&metadata.token_endpoint)
-        .form(&params)
-        .send()
-        .await
-        .map_err(|e| AppError::OAuth(format!("Token exchange failed: {e}")))?;
+    let response = send_with_retry(TOKEN_RETRY, "Token exchange", || {
+        client.post(&metadata.token_endpoint).form(&params)
+    })
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -312,12 +411,10 @@ pub async fn refresh_token(
 
     debug!("Refreshing token at {}", metadata.token_endpoint);
 
-    let response = client
-        .post(&metadata.token_endpoint)
-        .form(&params)
-        .send()
-        .await
-        .map_err(|e| AppError::OAuth(format!("Token refresh failed: {e}")))?;
+    let response = send_with_retry(TOKEN_RETRY, "Token refresh", || {
+        client.post(&metadata.token_endpoint).form(&params)
+    })
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -425,3 +522,76 @@ pub async fn try_refresh_token(
 
     Ok(new_access)
 }
+
+/// The server's stored access token, refreshed first if it has expired.
+/// Concurrent callers share one refresh: the rest wait for it and then pick up
+/// the new token, since the old refresh token may already be spent.
+pub async fn resolve_access_token(
+    oauth_store: &SharedOAuthStore,
+    id: &str,
+    app: &tauri::AppHandle,
+) -> Option<String> {
+    let refresh_lock = {
+        let mut store = oauth_store.lock().await;
+        let tokens = store.get(id)?.tokens.as_ref()?;
+        if !is_token_expired(tokens) {
+            return Some(tokens.access_token.clone());
+        }
+        tokens.refresh_token.as_ref()?;
+        store.refresh_lock(id)
+    };
+    let _refreshing = refresh_lock.lock().await;
+
+    // Another caller may have refreshed while we waited
+    {
+        let store = oauth_store.lock().await;
+        let tokens = store.get(id)?.tokens.as_ref()?;
+        if !is_token_expired(tokens) {
+            return Some(tokens.access_token.clone());
+        }
+    }
+    match try_refresh_token(oauth_store, id, app).await {
+        Ok(new_token) => Some(new_token),
+        Err(e) => {
+            warn!("Token refresh failed: {e}, will try without token");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_s256_and_falls_back_to_plain() {
+        let methods = |m: &[&str]| m.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(pkce_method(&[]), "S256");
+        assert_eq!(pkce_method(&methods(&["plain", "S256"])), "S256");
+        assert_eq!(pkce_method(&methods(&["plain"])), "plain");
+
+        let pkce = generate_pkce(&methods(&["plain"]));
+        assert_eq!(pkce.code_challenge, pkce.code_verifier);
+        let pkce = generate_pkce(&[]);
+        assert_eq!(pkce.code_verifier.len(), 43);
+        assert_ne!(pkce.code_challenge, pkce.code_verifier);
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt() {
+        assert_eq!(DISCOVERY_RETRY.delay(1), Duration::from_millis(250));
+        assert_eq!(DISCOVERY_RETRY.delay(2), Duration::from_millis(500));
+        assert_eq!(DISCOVERY_RETRY.delay(3), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn only_idempotent_requests_retry_server_errors() {
+        for status in [StatusCode::BAD_GATEWAY, StatusCode::TOO_MANY_REQUESTS] {
+            assert!(DISCOVERY_RETRY.should_retry_status(status));
+            assert!(!TOKEN_RETRY.should_retry_status(status));
+        }
+        // OAuth protocol errors are terminal for every request
+        assert!(!DISCOVERY_RETRY.should_retry_status(StatusCode::BAD_REQUEST));
+        assert!(!DISCOVERY_RETRY.should_retry_status(StatusCode::UNAUTHORIZED));
+    }
+}
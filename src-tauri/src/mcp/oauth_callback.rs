@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{extract::Query, extract::State as AxumState, response::Html, routing::get, Router};
 use tokio::net::TcpListener;
-use tokio::sync::oneshot;
-use tracing::{debug, info};
+use tokio::sync::{oneshot, Mutex};
+use tracing::{debug, info, warn};
 
 use crate::error::AppError;
 
@@ -11,7 +13,6 @@ use crate::error::AppError;
 #[derive(Debug)]
 pub struct CallbackResult {
     pub code: String,
-    pub state: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -22,92 +23,196 @@ struct CallbackParams {
     error_description: Option<String>,
 }
 
-struct CallbackState {
-    tx: Arc<tokio::sync::Mutex<Option<oneshot::Sender<Result<CallbackResult, AppError>>>>>,
+/// Flows waiting on the callback server, keyed by the `state` each one sent.
+type PendingCallbacks = HashMap<String, oneshot::Sender<Result<CallbackResult, AppError>>>;
+
+/// How long a flow waits for the browser to come back.
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The localhost server that receives OAuth redirects. One listener is shared
+/// by every in-flight flow and callbacks are routed by their `state`, so
+/// several servers can be authorized at once.
+#[derive(Default)]
+pub struct OAuthCallbacks(Arc<Mutex<CallbackServer>>);
+
+#[derive(Default)]
+struct CallbackServer {
+    /// Port of the running listener, if any.
+    port: Option<u16>,
+    pending: PendingCallbacks,
+    /// Stops the listener once no flow is waiting.
+    shutdown: Option<oneshot::Sender<()>>,
 }
 
-/// Start a temporary localhost HTTP server to capture the OAuth callback.
-/// Returns (port, receiver) — the receiver will yield the callback result.
-/// The server auto-shuts down after the first request or a 2-minute timeout.
-pub async fn start_callback_server(
-) -> Result<(u16, oneshot::Receiver<Result<CallbackResult, AppError>>), AppError> {
-    let (tx, rx) = oneshot::channel();
-
-    let state = Arc::new(CallbackState {
-        tx: Arc::new(tokio::sync::Mutex::new(Some(tx))),
-    });
-
-    let app = Router::new()
-        .route("/oauth/callback", get(handle_callback))
-        .with_state(state.clone());
-
-    let listener = TcpListener::bind("127.0.0.1:0")
-        .await
-        .map_err(|e| AppError::OAuth(format!("Failed to bind callback server: {e}")))?;
-
-    let port = listener
-        .local_addr()
-        .map_err(|e| AppError::OAuth(format!("Failed to get callback server address: {e}")))?
-        .port();
-
-    info!("OAuth callback server listening on http://127.0.0.1:{port}/oauth/callback");
-
-    // Spawn the server with a 2-minute timeout
-    tokio::spawn(async move {
-        let server = axum::serve(listener, app);
-        let timeout = tokio::time::sleep(std::time::Duration::from_secs(120));
-
-        tokio::select! {
-            result = server => {
-                if let Err(e) = result {
-                    debug!("OAuth callback server error: {e}");
-                }
+impl CallbackServer {
+    /// Stop waiting on `state`, stopping the listener when it was the last
+    /// flow. Returns the flow's sender, if one sent `state`.
+    fn take(&mut self, state: &str) -> Option<oneshot::Sender<Result<CallbackResult, AppError>>> {
+        let tx = self.pending.remove(state)?;
+        if self.pending.is_empty() {
+            if let Some(shutdown) = self.shutdown.take() {
+                let _ = shutdown.send(());
+            }
+            self.port = None;
+        }
+        Some(tx)
+    }
+
+    /// Hand `result` to the flow that sent `state`. Returns false if no flow
+    /// sent `state`.
+    fn resolve(&mut self, state: &str, result: Result<CallbackResult, AppError>) -> bool {
+        let Some(tx) = self.take(state) else {
+            return false;
+        };
+        let _ = tx.send(result);
+        true
+    }
+}
+
+impl OAuthCallbacks {
+    /// Wait for the callback carrying `expected_state`. Starts the listener if
+    /// no other flow is waiting — on `preferred_port` when it's free, so a
+    /// redirect URI registered earlier stays valid, and on any free port
+    /// otherwise — or joins the running one.
+    /// Returns (port, receiver) — the receiver yields the callback result, or
+    /// an error after a 2-minute timeout.
+    pub async fn register(
+        &self,
+        preferred_port: Option<u16>,
+        expected_state: String,
+    ) -> Result<(u16, oneshot::Receiver<Result<CallbackResult, AppError>>), AppError> {
+        let mut server = self.0.lock().await;
+        let port = match server.port {
+            Some(port) => port,
+            None => {
+                let port = self.listen(&mut server, preferred_port).await?;
+                server.port = Some(port);
+                port
             }
-            _ = timeout => {
-                debug!("OAuth callback server timed out after 2 minutes");
-                // Send timeout error if nobody has claimed the sender yet
-                let mut guard = state.tx.lock().await;
-                if let Some(tx) = guard.take() {
-                    let _ = tx.send(Err(AppError::OAuth(
-                        "OAuth callback timed out — no response received within 2 minutes".into(),
-                    )));
+        };
+
+        let (tx, rx) = oneshot::channel();
+        server.pending.insert(expected_state.clone(), tx);
+        drop(server);
+
+        // Fail this flow if the browser never comes back
+        let inner = self.0.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(CALLBACK_TIMEOUT).await;
+            let timed_out = inner.lock().await.resolve(
+                &expected_state,
+                Err(AppError::OAuth(
+                    "OAuth callback timed out — no response received within 2 minutes".into(),
+                )),
+            );
+            if timed_out {
+                debug!("OAuth callback timed out after 2 minutes");
+            }
+        });
+
+        Ok((port, rx))
+    }
+
+    /// Stop waiting for the callback carrying `state`, e.g. when the flow
+    /// failed before the browser was opened.
+    pub async fn deregister(&self, state: &str) {
+        self.0.lock().await.take(state);
+    }
+
+    async fn listen(
+        &self,
+        server: &mut CallbackServer,
+        preferred_port: Option<u16>,
+    ) -> Result<u16, AppError> {
+        let preferred = match preferred_port {
+            Some(port) => match TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(listener) => Some(listener),
+                Err(e) => {
+                    warn!("OAuth callback port {port} unavailable ({e}), using a random port");
+                    None
                 }
+            },
+            None => None,
+        };
+        let listener = match preferred {
+            Some(listener) => listener,
+            None => TcpListener::bind("127.0.0.1:0")
+                .await
+                .map_err(|e| AppError::OAuth(format!("Failed to bind callback server: {e}")))?,
+        };
+
+        let port = listener
+            .local_addr()
+            .map_err(|e| AppError::OAuth(format!("Failed to get callback server address: {e}")))?
+            .port();
+
+        info!("OAuth callback server listening on http://127.0.0.1:{port}/oauth/callback");
+
+        let app = Router::new()
+            .route("/oauth/callback", get(handle_callback))
+            .with_state(self.0.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        server.shutdown = Some(shutdown_tx);
+
+        tokio::spawn(async move {
+            let result = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+            match result {
+                Ok(()) => debug!("OAuth callback server on port {port} stopped"),
+                Err(e) => debug!("OAuth callback server error: {e}"),
             }
-        }
-    });
+        });
 
-    Ok((port, rx))
+        Ok(port)
+    }
 }
 
 async fn handle_callback(
-    AxumState(state): AxumState<Arc<CallbackState>>,
+    AxumState(server): AxumState<Arc<Mutex<CallbackServer>>>,
     Query(params): Query<CallbackParams>,
 ) -> Html<&'static str> {
-    let result = if let Some(error) = params.error {
-        let desc = params.error_description.unwrap_or_default();
-        Err(AppError::OAuth(format!(
-            "Authorization denied: {error} — {desc}"
-        )))
+    let mut server = server.lock().await;
+    // A state no flow sent means the redirect didn't come from one of our
+    // authorization requests (CSRF); it must not hand anyone a code. Nor may it
+    // fail a waiting flow, or anyone could cancel a sign-in; those still finish
+    // or time out on their own
+    let Some(state) = params
+        .state
+        .clone()
+        .filter(|state| server.pending.contains_key(state))
+    else {
+        warn!("Rejected OAuth callback: state mismatch");
+        return Html(FAILED_PAGE);
+    };
+    let result = callback_result(params);
+    let page = if result.is_ok() {
+        COMPLETE_PAGE
     } else {
-        match (params.code, params.state) {
-            (Some(code), Some(state_param)) => Ok(CallbackResult {
-                code,
-                state: state_param,
-            }),
-            _ => Err(AppError::OAuth(
-                "Missing code or state in OAuth callback".into(),
-            )),
-        }
+        FAILED_PAGE
     };
+    server.resolve(&state, result);
+    Html(page)
+}
 
-    // Send the result through the oneshot channel
-    let mut guard = state.tx.lock().await;
-    if let Some(tx) = guard.take() {
-        let _ = tx.send(result);
+/// Turn callback query params into the code to exchange, or the error the
+/// authorization server reported.
+fn callback_result(params: CallbackParams) -> Result<CallbackResult, AppError> {
+    if let Some(error) = params.error {
+        let desc = params.error_description.unwrap_or_default();
+        return Err(AppError::OAuth(format!(
+            "Authorization denied: {error} — {desc}"
+        )));
     }
+    params
+        .code
+        .map(|code| CallbackResult { code })
+        .ok_or_else(|| AppError::OAuth("Missing code in OAuth callback".into()))
+}
 
-    Html(
-        r#"<!DOCTYPE html>
+const COMPLETE_PAGE: &str = r#"<!DOCTYPE html>
 <html>
 <head><title>Agent Hub</title></head>
 <body style="font-family: system-ui, sans-serif; display: flex; justify-content: center; align-items: center; min-height: 100vh; margin: 0; background: #1a1a2e; color: #e0e0e0;">
@@ -116,6 +221,65 @@ async fn handle_callback(
 <p>You can close this tab and return to Agent Hub.</p>
 </div>
 </body>
-</html>"#,
-    )
+</html>"#;
+
+const FAILED_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Agent Hub</title></head>
+<body style="font-family: system-ui, sans-serif; display: flex; justify-content: center; align-items: center; min-height: 100vh; margin: 0; background: #1a1a2e; color: #e0e0e0;">
+<div style="text-align: center;">
+<h1 style="font-size: 1.5rem; margin-bottom: 0.5rem;">Authorization Failed</h1>
+<p>Return to Agent Hub for details and try again.</p>
+</div>
+</body>
+</html>"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callback(code: &str) -> Result<CallbackResult, AppError> {
+        Ok(CallbackResult { code: code.into() })
+    }
+
+    #[test]
+    fn routes_callbacks_to_the_flow_that_sent_the_state() {
+        let mut server = CallbackServer::default();
+        let (tx_a, mut rx_a) = oneshot::channel();
+        let (tx_b, mut rx_b) = oneshot::channel();
+        server.pending.insert("a".into(), tx_a);
+        server.pending.insert("b".into(), tx_b);
+        server.port = Some(4000);
+
+        assert!(!server.resolve("forged", callback("x")));
+        assert!(server.resolve("b", callback("code-b")));
+        assert_eq!(rx_b.try_recv().unwrap().unwrap().code, "code-b");
+        assert!(rx_a.try_recv().is_err());
+        assert_eq!(server.port, Some(4000));
+
+        assert!(server.resolve("a", callback("code-a")));
+        assert_eq!(rx_a.try_recv().unwrap().unwrap().code, "code-a");
+        assert_eq!(server.port, None);
+    }
+
+    #[tokio::test]
+    async fn foreign_state_leaves_the_waiting_flow_pending() {
+        let server = Arc::new(Mutex::new(CallbackServer::default()));
+        let (tx, mut rx) = oneshot::channel();
+        server.lock().await.pending.insert("a".into(), tx);
+
+        let page = handle_callback(
+            AxumState(server.clone()),
+            Query(CallbackParams {
+                code: Some("stolen".into()),
+                state: Some("forged".into()),
+                error: None,
+                error_description: None,
+            }),
+        )
+        .await;
+        assert_eq!(page.0, FAILED_PAGE);
+        assert!(rx.try_recv().is_err());
+        assert!(server.lock().await.pending.contains_key("a"));
+    }
 }
@@ -0,0 +1,297 @@
+//! Fetch a package-runner server's package before the timed handshake.
+//!
+//! `npx -y some-mcp` and `uvx some-mcp` download the package on first run,
+//! which can outlast the initialize timeout. Prewarming has the launcher's
+//! package manager fetch the same package into its cache without running it
+//! (`npm cache add`, or `uv run` of an empty script with the package
+//! installed), under its own, longer timeout, so the real spawn starts from a
+//! warm cache.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
+use tracing::{info, warn};
+
+use crate::error::AppError;
+use crate::mcp::transport;
+
+/// How long a prewarm may take before it is killed and the connect goes ahead.
+pub const PREWARM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Launchers with a fetch-only equivalent, with their flags that take a value.
+const LAUNCHERS: &[(&str, &[&str])] = &[
+    ("npx", &["-p", "--package", "-c", "--call"]),
+    (
+        "uvx",
+        &[
+            "--from",
+            "--with",
+            "--python",
+            "-p",
+            "--index",
+            "--index-url",
+        ],
+    ),
+];
+
+/// A package manager invocation that fetches a launcher's package into the
+/// cache the launcher reads from, without running it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FetchCommand {
+    pub program: &'static str,
+    pub args: Vec<String>,
+}
+
+/// The fetch-only equivalent of `command args`. `None` when `command` isn't a
+/// known package launcher or no package argument is found.
+pub fn fetch_command(command: &str, args: &[String]) -> Option<FetchCommand> {
+    let launcher = Path::new(command).file_stem()?.to_str()?;
+    let (_, value_flags) = LAUNCHERS.iter().find(|(name, _)| *name == launcher)?;
+
+    // Launcher flags come first; the first positional is the package
+    let mut flags: Vec<(&str, &str)> = Vec::new();
+    let mut package = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if value_flags.contains(&arg.as_str()) {
+            flags.push((arg.as_str(), iter.next()?.as_str()));
+        } else if !arg.starts_with('-') {
+            package = Some(arg.as_str());
+            break;
+        }
+    }
+    let values = |names: &[&str]| -> Vec<String> {
+        flags
+            .iter()
+            .filter(|(flag, _)| names.contains(flag))
+            .map(|(_, value)| value.to_string())
+            .collect()
+    };
+
+    if launcher == "npx" {
+        // With `-p`, the positional is a command those packages provide
+        let mut packages = values(&["-p", "--package"]);
+        if packages.is_empty() {
+            packages.push(package?.to_string());
+        }
+        let mut args = vec!["cache".to_string(), "add".to_string()];
+        args.extend(packages);
+        return Some(FetchCommand {
+            program: "npm",
+            args,
+        });
+    }
+
+    let spec = match values(&["--from"]).pop() {
+        Some(from) => from,
+        None => uv_requirement(package?),
+    };
+    let mut args = vec!["run".to_string(), "--no-project".to_string()];
+    for (flag, value) in &flags {
+        if *flag != "--from" {
+            args.extend([flag.to_string(), value.to_string()]);
+        }
+    }
+    args.extend(["--with".to_string(), spec]);
+    args.extend(["--", "python", "-c", ""].map(String::from));
+    Some(FetchCommand {
+        program: "uv",
+        args,
+    })
+}
+
+/// uvx accepts `name@version` and `name@latest`; `uv run --with` wants a
+/// plain requirement.
+fn uv_requirement(package: &str) -> String {
+    match package.split_once('@') {
+        Some((name, "latest")) => name.to_string(),
+        Some((name, version)) if !name.is_empty() && !version.contains('/') => {
+            format!("{name}=={version}")
+        }
+        _ => package.to_string(),
+    }
+}
+
+/// The package manager installed alongside `launcher`, so both share a
+/// cache, falling back to the usual lookup.
+fn fetch_program(launcher: &Path, name: &str) -> Result<PathBuf, AppError> {
+    let sibling = launcher
+        .with_file_name(name)
+        .with_extension(launcher.extension().unwrap_or_default());
+    if sibling.is_file() {
+        return Ok(sibling);
+    }
+    crate::commands::resolve_binary(name)
+}
+
+/// Run the fetch step for a stdio server, forwarding its stderr as
+/// `server-log` events so the UI shows download progress. Failures are
+/// logged and otherwise ignored, and a fetch still running after
+/// [`PREWARM_TIMEOUT`] is killed: the handshake that follows reports the real
+/// error if the server can't start.
+pub async fn prewarm(
+    app: &AppHandle,
+    server_id: &str,
+    launcher: &Path,
+    command: &str,
+    args: &[String],
+    env: &HashMap<String, String>,
+) {
+    let Some(fetch) = fetch_command(command, args) else {
+        info!("Server {server_id}: '{command}' has no package to prewarm");
+        return;
+    };
+    let program = match fetch_program(launcher, fetch.program) {
+        Ok(program) => program,
+        Err(e) => {
+            warn!(
+                "Server {server_id}: can't prewarm without {}: {e}",
+                fetch.program
+            );
+            return;
+        }
+    };
+    info!(
+        "Prewarming server {server_id}: {} {}",
+        fetch.program,
+        fetch.args.join(" ")
+    );
+
+    let mut cmd = app.shell().command(&program).args(&fetch.args);
+    if !env.keys().any(|k| k.eq_ignore_ascii_case("PATH")) {
+        if let Some(path) = transport::child_path(&program) {
+            cmd = cmd.env("PATH", path);
+        }
+    }
+    let (mut rx, child) = match cmd.envs(env).spawn() {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            warn!("Server {server_id}: prewarm failed to start: {e}");
+            return;
+        }
+    };
+
+    let run = async {
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stderr(line) => {
+                    let line = String::from_utf8_lossy(&line);
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let _ = app.emit(
+                        "server-log",
+                        serde_json::json!({
+                            "serverId": server_id,
+                            "level": "info",
+                            "message": format!("[prewarm] {line}"),
+                        }),
+                    );
+                }
+                CommandEvent::Error(e) => warn!("Server {server_id}: prewarm: {e}"),
+                CommandEvent::Terminated(status) => return Some(status),
+                _ => {}
+            }
+        }
+        None
+    };
+    match tokio::time::timeout(PREWARM_TIMEOUT, run).await {
+        Ok(Some(status)) if status.code == Some(0) => {
+            info!("Server {server_id}: prewarm finished")
+        }
+        Ok(Some(status)) => warn!("Server {server_id}: prewarm exited with {:?}", status.code),
+        Ok(None) => warn!("Server {server_id}: prewarm output ended without an exit status"),
+        Err(_) => {
+            let _ = child.kill();
+            warn!(
+                "Server {server_id}: prewarm still running after {}s; killed it and continuing",
+                PREWARM_TIMEOUT.as_secs()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn fetch(program: &'static str, list: &[&str]) -> Option<FetchCommand> {
+        Some(FetchCommand {
+            program,
+            args: args(list),
+        })
+    }
+
+    #[test]
+    fn npx_packages_are_added_to_the_npm_cache() {
+        assert_eq!(
+            fetch_command(
+                "npx",
+                &args(&["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]),
+            ),
+            fetch(
+                "npm",
+                &["cache", "add", "@modelcontextprotocol/server-filesystem"]
+            )
+        );
+        assert_eq!(
+            fetch_command("npx", &args(&["-p", "a", "--package", "b", "serve", "--x"])),
+            fetch("npm", &["cache", "add", "a", "b"])
+        );
+    }
+
+    #[test]
+    fn uvx_packages_are_installed_into_the_uv_cache_without_running() {
+        assert_eq!(
+            fetch_command(
+                "/opt/homebrew/bin/uvx",
+                &args(&[
+                    "--from",
+                    "mcp-git==1.0",
+                    "--python",
+                    "3.12",
+                    "mcp-server-git",
+                    "--repository",
+                    ".",
+                ]),
+            ),
+            fetch(
+                "uv",
+                &[
+                    "run",
+                    "--no-project",
+                    "--python",
+                    "3.12",
+                    "--with",
+                    "mcp-git==1.0",
+                    "--",
+                    "python",
+                    "-c",
+                    "",
+                ]
+            )
+        );
+        assert_eq!(
+            fetch_command("uvx", &args(&["mcp-server-time@1.2"])).map(|f| f.args[3].clone()),
+            Some("mcp-server-time==1.2".to_string())
+        );
+        assert_eq!(uv_requirement("mcp-server-time@latest"), "mcp-server-time");
+    }
+
+    #[test]
+    fn ignores_commands_without_a_fetch_step() {
+        assert_eq!(fetch_command("node", &args(&["server.js"])), None);
+        assert_eq!(fetch_command("bunx", &args(&["some-mcp"])), None);
+        assert_eq!(fetch_command("npx", &args(&["-y"])), None);
+        assert_eq!(fetch_command("npx", &args(&["-c", "echo hi"])), None);
+    }
+}
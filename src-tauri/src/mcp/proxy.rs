@@ -2,31 +2,48 @@ use std::collections::HashMap;
 use std::convert::Infallible;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::Duration;
 
+use axum::body::Bytes;
 use axum::extract::{Path, Query, State as AxumState};
-use axum::http::HeaderMap;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::IntoResponse;
-use axum::routing::post;
-use axum::{Json, Router};
+use axum::routing::{get, post};
+use axum::Router;
 use futures::stream::Stream;
 use serde_json::Value;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::net::TcpListener;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, oneshot, RwLock};
 use tokio::time::Instant;
 use tracing::{error, info};
 
-use crate::mcp::client::SharedConnections;
+use crate::audit;
+use crate::error::AppError;
+use crate::mcp::client::{CallToolResult, SharedConnections};
 use crate::mcp::http_common::{
     accepted_response, client_accepts_sse, mcp_response, negotiate_version, new_session_id,
-    validate_origin,
+    request_session_id, validate_origin,
 };
+use crate::mcp::validation;
 use crate::persistence::save_stats;
-use crate::state::SharedState;
+use crate::state::{FallbackGroup, SharedState};
 use crate::stats::{unix_now, StatsStore, ToolCallEntry, ToolStats};
 
-/// Shared proxy state tracking whether the server is running and on which port.
+/// How long a tool call may wait for a free slot on a saturated server before
+/// the proxy gives up with a "server busy" error.
+pub(crate) const CALL_QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `service` reported by the health route, which other instances probe to
+/// tell a running proxy from an unrelated process on the same port.
+pub(crate) const PROXY_SERVICE: &str = "agent-hub";
+
+/// Sessions idle for longer than this are forgotten; clients must re-initialize.
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Shared proxy state tracking whether the server is running, on which port,
+/// and which MCP sessions are active.
 #[derive(Clone)]
 pub struct ProxyState {
     inner: Arc<RwLock<ProxyStateInner>>,
@@ -35,6 +52,18 @@ pub struct ProxyState {
 struct ProxyStateInner {
     running: bool,
     port: u16,
+    /// Why the proxy failed to start, if it did.
+    error: Option<String>,
+    sessions: HashMap<String, ProxySession>,
+    /// Signals the running server to stop accepting connections.
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+/// A session issued by `initialize` on one proxy endpoint.
+struct ProxySession {
+    /// Server ID (or `"discovery"`) of the endpoint that issued the session.
+    endpoint: String,
+    last_seen: Instant,
 }
 
 impl ProxyState {
@@ -43,14 +72,44 @@ impl ProxyState {
             inner: Arc::new(RwLock::new(ProxyStateInner {
                 running: false,
                 port: 0,
+                error: None,
+                sessions: HashMap::new(),
+                shutdown: None,
             })),
         }
     }
 
-    pub async fn set_running(&self, port: u16) {
+    /// Mark the proxy as listening on `port`. The returned receiver fires
+    /// when `stop` is called.
+    pub async fn set_running(&self, port: u16) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
         let mut inner = self.inner.write().await;
         inner.running = true;
         inner.port = port;
+        inner.error = None;
+        inner.shutdown = Some(tx);
+        rx
+    }
+
+    /// Ask the running server to shut down gracefully. Existing sessions are
+    /// dropped since clients must re-initialize against the new listener.
+    pub async fn stop(&self) {
+        let mut inner = self.inner.write().await;
+        inner.running = false;
+        inner.sessions.clear();
+        if let Some(tx) = inner.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    pub async fn set_error(&self, error: String) {
+        let mut inner = self.inner.write().await;
+        inner.running = false;
+        inner.error = Some(error);
+    }
+
+    pub async fn error(&self) -> Option<String> {
+        self.inner.read().await.error.clone()
     }
 
     pub async fn is_running(&self) -> bool {
@@ -65,6 +124,170 @@ impl ProxyState {
     pub fn port_blocking(&self) -> u16 {
         self.inner.blocking_read().port
     }
+
+    /// Register a new session for `endpoint` and return its ID.
+    pub async fn create_session(&self, endpoint: &str) -> String {
+        let mut inner = self.inner.write().await;
+        prune_expired_sessions(&mut inner.sessions);
+        let session_id = new_session_id();
+        inner.sessions.insert(
+            session_id.clone(),
+            ProxySession {
+                endpoint: endpoint.to_string(),
+                last_seen: Instant::now(),
+            },
+        );
+        session_id
+    }
+
+    /// Refresh a session's idle timer. Returns `false` if the session is unknown,
+    /// has expired, or was issued by a different endpoint.
+    pub async fn touch_session(&self, session_id: &str, endpoint: &str) -> bool {
+        let mut inner = self.inner.write().await;
+        prune_expired_sessions(&mut inner.sessions);
+        match inner.sessions.get_mut(session_id) {
+            Some(session) if session.endpoint == endpoint => {
+                session.last_seen = Instant::now();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Forget a session explicitly. Returns `false` if it was not active on `endpoint`.
+    pub async fn remove_session(&self, session_id: &str, endpoint: &str) -> bool {
+        let mut inner = self.inner.write().await;
+        prune_expired_sessions(&mut inner.sessions);
+        match inner.sessions.get(session_id) {
+            Some(session) if session.endpoint == endpoint => {
+                inner.sessions.remove(session_id);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prune_expired_sessions(sessions: &mut HashMap<String, ProxySession>) {
+    sessions.retain(|_, s| s.last_seen.elapsed() < SESSION_IDLE_TIMEOUT);
+}
+
+/// Validate the `Mcp-Session-Id` of a non-initialize request against the sessions
+/// issued for `endpoint`.
+///
+/// A missing header is a 400 Bad Request; an unknown or expired session is a
+/// 404 Not Found, which tells spec-compliant clients to start a new session.
+pub(crate) async fn require_session(
+    app: &AppHandle,
+    headers: &HeaderMap,
+    endpoint: &str,
+) -> Result<String, (StatusCode, HeaderMap, String)> {
+    let proxy_state = app.state::<ProxyState>().inner().clone();
+    validate_session(&proxy_state, headers, endpoint).await
+}
+
+async fn validate_session(
+    proxy_state: &ProxyState,
+    headers: &HeaderMap,
+    endpoint: &str,
+) -> Result<String, (StatusCode, HeaderMap, String)> {
+    let session_id = match request_session_id(headers) {
+        Some(id) => id,
+        None => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                HeaderMap::new(),
+                "Missing Mcp-Session-Id header".into(),
+            ));
+        }
+    };
+
+    if !proxy_state.touch_session(&session_id, endpoint).await {
+        return Err((
+            StatusCode::NOT_FOUND,
+            HeaderMap::new(),
+            format!("Unknown or expired session: {session_id}"),
+        ));
+    }
+    Ok(session_id)
+}
+
+/// The `serverInfo` for an `initialize` result, applying the user's identity
+/// overrides. `label` names the endpoint (a server name, or "Tool Discovery").
+pub(crate) fn advertised_server_info(app: &AppHandle, label: &str) -> Value {
+    let identity = {
+        let app_state = app.state::<SharedState>();
+        let s = app_state.lock().unwrap();
+        s.proxy_settings.identity.clone()
+    };
+    let name = identity.name.as_deref().unwrap_or("Agent Hub");
+    let version = identity
+        .version
+        .as_deref()
+        .unwrap_or(env!("CARGO_PKG_VERSION"));
+    serde_json::json!({
+        "name": format!("{name} — {label}"),
+        "version": version
+    })
+}
+
+/// Validate the request `Origin` against the built-in defaults plus the
+/// user's configured allow-list.
+pub(crate) fn check_origin(
+    app: &AppHandle,
+    headers: &HeaderMap,
+) -> Result<(), (StatusCode, String)> {
+    validate_origin(headers, &allowed_origins(app))
+}
+
+fn allowed_origins(app: &AppHandle) -> Vec<String> {
+    let app_state = app.state::<SharedState>();
+    let s = app_state.lock().unwrap();
+    s.proxy_settings.allowed_origins.clone()
+}
+
+/// Terminate the session named by `Mcp-Session-Id` on `endpoint`.
+///
+/// Returns 204 on success, 400 when the header is missing, and 404 when the
+/// session is unknown or already expired.
+pub(crate) async fn end_session(
+    app: &AppHandle,
+    headers: &HeaderMap,
+    endpoint: &str,
+) -> (StatusCode, HeaderMap, String) {
+    if let Err((status, msg)) = check_origin(app, headers) {
+        return (status, HeaderMap::new(), msg);
+    }
+    let proxy_state = app.state::<ProxyState>().inner().clone();
+    terminate_session(&proxy_state, headers, endpoint).await
+}
+
+async fn terminate_session(
+    proxy_state: &ProxyState,
+    headers: &HeaderMap,
+    endpoint: &str,
+) -> (StatusCode, HeaderMap, String) {
+    let session_id = match request_session_id(headers) {
+        Some(id) => id,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                HeaderMap::new(),
+                "Missing Mcp-Session-Id header".into(),
+            );
+        }
+    };
+
+    if proxy_state.remove_session(&session_id, endpoint).await {
+        info!("Proxy session {session_id} terminated by client");
+        (StatusCode::NO_CONTENT, HeaderMap::new(), String::new())
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            HeaderMap::new(),
+            format!("Unknown or expired session: {session_id}"),
+        )
+    }
 }
 
 /// Wrapper for the broadcast sender so it can be managed as Tauri state.
@@ -91,13 +314,45 @@ pub fn hash_tool_names(tools: &[crate::state::McpTool]) -> u64 {
 }
 
 /// Check if the tool list for a server has changed, and notify SSE clients if so.
-/// Call this after connect/disconnect updates the connection state.
+/// Call this after connect/disconnect updates the connection state, or after
+/// the server's exposed/blocked tool lists change.
 pub async fn notify_if_tools_changed(
     app: &AppHandle,
     server_id: &str,
     new_tools: &[crate::state::McpTool],
 ) {
-    let new_hash = hash_tool_names(new_tools);
+    // Hash the list as proxy clients see it: exposed tools under their advertised
+    // names, plus any fallback aliases served from this endpoint
+    let exposed: Vec<crate::state::McpTool> = {
+        let state = app.state::<SharedState>();
+        let s = state.lock().unwrap();
+        let server = s.servers.iter().find(|srv| srv.id == server_id);
+        new_tools
+            .iter()
+            .filter(|t| server.is_none_or(|srv| srv.exposes_tool(&t.name)))
+            .map(|t| {
+                let mut tool = t.clone();
+                if let Some(srv) = server {
+                    tool.name = srv.advertised_tool_name(&t.name).to_string();
+                }
+                tool
+            })
+            .chain(
+                s.fallback_groups
+                    .iter()
+                    .filter(|g| g.serves(server_id))
+                    .map(|g| crate::state::McpTool {
+                        name: g.alias.clone(),
+                        title: None,
+                        description: None,
+                        input_schema: None,
+                        server_id: server_id.to_string(),
+                        server_name: String::new(),
+                    }),
+            )
+            .collect()
+    };
+    let new_hash = hash_tool_names(&exposed);
 
     if let Some(hashes) = app.try_state::<ToolListHashes>() {
         let mut map = hashes.0.write().await;
@@ -130,11 +385,125 @@ pub async fn start_proxy(
     app_handle: AppHandle,
     proxy_state: ProxyState,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let (notify_tx, _) = broadcast::channel::<String>(64);
+    let (listener, shutdown_rx) = bind_proxy(&app_handle, &proxy_state).await?;
 
-    // Manage the sender and hash tracker as Tauri state so connections.rs can push notifications
-    app_handle.manage(NotifySender(notify_tx.clone()));
-    app_handle.manage(ToolListHashes::new());
+    let port = proxy_state.port().await;
+    if let Err(e) =
+        crate::commands::integrations::refresh_integration_configs(&app_handle, port).await
+    {
+        tracing::warn!("Failed to point integration configs at port {port}: {e}");
+    }
+
+    serve_proxy(app_handle, listener, shutdown_rx).await
+}
+
+/// Move the proxy to `pinned_port`, or to a preferred port when `None`. The
+/// new listener is bound before the running proxy is stopped, so a port that
+/// can't be bound leaves the current proxy serving as it was. Returns once
+/// the new listener is bound, so the new port is known.
+pub async fn restart_proxy(
+    app_handle: &AppHandle,
+    proxy_state: &ProxyState,
+    pinned_port: Option<u16>,
+) -> Result<(), AppError> {
+    // Already serving on the port being pinned, which couldn't be bound twice
+    if proxy_state.is_running().await && pinned_port == Some(proxy_state.port().await) {
+        return Ok(());
+    }
+    let listener = bind_listener(pinned_port)
+        .await
+        .map_err(AppError::Transport)?;
+    proxy_state.stop().await;
+    let shutdown_rx = mark_listening(proxy_state, &listener).await?;
+    // The port was changed on purpose, so configs must follow it
+    update_integration_ports(app_handle, proxy_state).await;
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve_proxy(handle, listener, shutdown_rx).await {
+            error!("MCP proxy server failed: {e}");
+        }
+    });
+    Ok(())
+}
+
+/// Point enabled integrations at the port the proxy is bound to.
+async fn update_integration_ports(app_handle: &AppHandle, proxy_state: &ProxyState) {
+    let port = proxy_state.port().await;
+    if let Err(e) =
+        crate::commands::integrations::update_all_integration_configs(app_handle, port).await
+    {
+        tracing::warn!("Failed to point integration configs at port {port}: {e}");
+    }
+}
+
+/// Bind the proxy listener per the current settings and mark the proxy running.
+async fn bind_proxy(
+    app_handle: &AppHandle,
+    proxy_state: &ProxyState,
+) -> Result<(TcpListener, oneshot::Receiver<()>), Box<dyn std::error::Error + Send + Sync>> {
+    // A user-pinned port must be honoured exactly; otherwise bind a stable
+    // preferred port, falling back to OS-assigned if busy
+    let pinned_port = {
+        let app_state = app_handle.state::<SharedState>();
+        let s = app_state.lock().unwrap();
+        s.proxy_settings.preferred_port
+    };
+    let listener = match bind_listener(pinned_port).await {
+        Ok(listener) => listener,
+        Err(message) => {
+            error!("{message}");
+            proxy_state.set_error(message.clone()).await;
+            let _ = app_handle.emit("proxy-error", serde_json::json!({ "error": message }));
+            return Err(message.into());
+        }
+    };
+    let shutdown_rx = mark_listening(proxy_state, &listener).await?;
+    Ok((listener, shutdown_rx))
+}
+
+/// Bind `pinned_port` exactly, or a preferred port when `None`.
+async fn bind_listener(pinned_port: Option<u16>) -> Result<TcpListener, String> {
+    match pinned_port {
+        Some(port) => TcpListener::bind(format!("127.0.0.1:{port}"))
+            .await
+            .map_err(|e| format!("Proxy port {port} is unavailable: {e}")),
+        None => bind_preferred_port()
+            .await
+            .map_err(|e| format!("Failed to bind the proxy: {e}")),
+    }
+}
+
+/// Record the proxy as serving on `listener`'s port.
+async fn mark_listening(
+    proxy_state: &ProxyState,
+    listener: &TcpListener,
+) -> std::io::Result<oneshot::Receiver<()>> {
+    let port = listener.local_addr()?.port();
+    let shutdown_rx = proxy_state.set_running(port).await;
+
+    info!("MCP proxy server listening on http://127.0.0.1:{port}/mcp/{{server_id}}");
+
+    Ok(shutdown_rx)
+}
+
+/// Serve proxy requests on `listener` until `shutdown_rx` fires.
+async fn serve_proxy(
+    app_handle: AppHandle,
+    listener: TcpListener,
+    shutdown_rx: oneshot::Receiver<()>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Manage the sender and hash tracker as Tauri state so connections.rs can
+    // push notifications. A restarted proxy keeps the original sender.
+    let notify_tx = match app_handle.try_state::<NotifySender>() {
+        Some(sender) => sender.0.clone(),
+        None => {
+            let (notify_tx, _) = broadcast::channel::<String>(64);
+            app_handle.manage(NotifySender(notify_tx.clone()));
+            app_handle.manage(ToolListHashes::new());
+            notify_tx
+        }
+    };
 
     let state = ProxyAppState {
         app_handle: app_handle.clone(),
@@ -142,33 +511,27 @@ pub async fn start_proxy(
     };
 
     let app = Router::new()
+        .route("/health", get(handle_health))
         .route(
             "/mcp/discovery",
-            post(super::discovery::handle_discovery_post),
+            post(super::discovery::handle_discovery_post)
+                .delete(super::discovery::handle_discovery_delete),
         )
         .route(
             "/mcp/{server_id}",
-            post(handle_mcp_post).get(handle_mcp_get),
+            post(handle_mcp_post)
+                .get(handle_mcp_get)
+                .delete(handle_mcp_delete),
         )
         .with_state(state);
 
-    // Bind to a stable preferred port, falling back to OS-assigned if busy
-    let listener = bind_preferred_port().await?;
-    let addr = listener.local_addr()?;
-    let port = addr.port();
-
-    proxy_state.set_running(port).await;
-
-    // Update all enabled AI tool integration configs with the new port
-    if let Err(e) = crate::commands::integrations::update_all_integration_configs(&app_handle, port)
-    {
-        tracing::warn!("Failed to update integration configs on startup: {e}");
-    }
-
-    info!("MCP proxy server listening on http://127.0.0.1:{port}/mcp/{{server_id}}");
-
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.await;
+        })
+        .await?;
 
+    info!("MCP proxy server stopped");
     Ok(())
 }
 
@@ -210,13 +573,50 @@ async fn bind_preferred_port() -> Result<TcpListener, Box<dyn std::error::Error
     Ok(TcpListener::bind("127.0.0.1:0").await?)
 }
 
+/// Identify the listener as this app's proxy.
+async fn handle_health() -> axum::Json<Value> {
+    axum::Json(serde_json::json!({
+        "service": PROXY_SERVICE,
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
 /// Handle GET requests — open SSE stream for server-initiated notifications.
 /// Per MCP spec, clients can open a GET to receive `notifications/tools/list_changed`.
 async fn handle_mcp_get(
     AxumState(state): AxumState<ProxyAppState>,
+    headers: HeaderMap,
     Path(server_id): Path<String>,
+) -> axum::response::Response {
+    let proxy_state = state.app_handle.state::<ProxyState>().inner().clone();
+    let allowed_origins = allowed_origins(&state.app_handle);
+    if let Err(rejection) =
+        authorize_stream(&proxy_state, &allowed_origins, &headers, &server_id).await
+    {
+        return rejection.into_response();
+    }
+    notification_stream(state.notify_tx.subscribe(), server_id).into_response()
+}
+
+/// Apply the POST rules to a GET stream on `endpoint`: a rejected origin is a
+/// 403, a missing session a 400 and an unknown session a 404.
+async fn authorize_stream(
+    proxy_state: &ProxyState,
+    allowed_origins: &[String],
+    headers: &HeaderMap,
+    endpoint: &str,
+) -> Result<(), (StatusCode, HeaderMap, String)> {
+    validate_origin(headers, allowed_origins)
+        .map_err(|(status, msg)| (status, HeaderMap::new(), msg))?;
+    validate_session(proxy_state, headers, endpoint).await?;
+    Ok(())
+}
+
+/// SSE stream of `notifications/tools/list_changed` for `server_id`.
+fn notification_stream(
+    mut rx: broadcast::Receiver<String>,
+    server_id: String,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let mut rx = state.notify_tx.subscribe();
     let stream = async_stream::stream! {
         loop {
             match rx.recv().await {
@@ -236,38 +636,158 @@ async fn handle_mcp_get(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
-/// Handle POST requests — per-server JSON-RPC handler.
+/// Handle DELETE requests — the client is explicitly ending its session.
+async fn handle_mcp_delete(
+    AxumState(state): AxumState<ProxyAppState>,
+    headers: HeaderMap,
+    Path(server_id): Path<String>,
+) -> impl IntoResponse {
+    end_session(&state.app_handle, &headers, &server_id).await
+}
+
+/// Handle POST requests — per-server JSON-RPC handler. Accepts a single
+/// message or a JSON-RPC batch (array), answered with an array holding one
+/// response per request in the batch.
 async fn handle_mcp_post(
     AxumState(state): AxumState<ProxyAppState>,
     headers: HeaderMap,
     Path(server_id): Path<String>,
     Query(query): Query<HashMap<String, String>>,
-    Json(body): Json<Value>,
+    body: Bytes,
 ) -> impl IntoResponse {
     // Origin validation (MCP Streamable HTTP spec)
-    if let Err((status, msg)) = validate_origin(&headers) {
+    if let Err((status, msg)) = check_origin(&state.app_handle, &headers) {
         return (status, HeaderMap::new(), msg);
     }
 
-    let method = body
-        .get("method")
-        .and_then(|m| m.as_str())
-        .unwrap_or_default();
-    let id = body.get("id").cloned();
-    let params = body.get("params").cloned();
     let client = query.get("client").cloned().unwrap_or_default();
-
     let use_sse = client_accepts_sse(&headers);
-    let req_session: Option<String> = headers
-        .get("mcp-session-id")
-        .and_then(|v| v.to_str().ok())
-        .map(String::from);
+    let body = match parse_request_body(&body, &headers) {
+        Ok(body) => body,
+        Err(rejection) => return rejection,
+    };
 
-    // Per spec: if the message has no "id", it's a notification or response.
-    // Notifications must get 202 Accepted with no body.
-    if id.is_none() {
-        return accepted_response(req_session.as_deref());
+    let (messages, is_batch) = match split_batch(body) {
+        Ok(split) => split,
+        Err(resp) => return mcp_response(&resp, None, use_sse),
+    };
+
+    // Every message after `initialize` must carry the session it was issued.
+    // A batch led by `initialize` uses the session it opens.
+    let opens_session = match batch_opens_session(&messages) {
+        Ok(opens) => opens,
+        Err(resp) => return mcp_response(&resp, None, use_sse),
+    };
+    let mut session = if opens_session {
+        None
+    } else {
+        match require_session(&state.app_handle, &headers, &server_id).await {
+            Ok(session_id) => Some(session_id),
+            Err(rejection) => return rejection,
+        }
+    };
+
+    let mut responses = Vec::new();
+    for message in messages {
+        if let Some(resp) =
+            handle_mcp_message(&state, &server_id, &client, message, &mut session).await
+        {
+            responses.push(resp);
+        }
+    }
+
+    post_reply(responses, is_batch, session.as_deref(), use_sse)
+}
+
+/// The HTTP reply carrying the answers to a POST body's messages: an array
+/// for a batch, otherwise the single response.
+pub(crate) fn post_reply(
+    mut responses: Vec<Value>,
+    is_batch: bool,
+    session: Option<&str>,
+    use_sse: bool,
+) -> (StatusCode, HeaderMap, String) {
+    // Per spec: notifications and responses alone get 202 Accepted with no body
+    match (responses.len(), is_batch) {
+        (0, _) => accepted_response(session),
+        (_, true) => mcp_response(&Value::Array(responses), session, use_sse),
+        (_, false) => mcp_response(&responses.swap_remove(0), session, use_sse),
+    }
+}
+
+/// The messages of a POST body, and whether it was a batch.
+pub(crate) fn split_batch(body: Value) -> Result<(Vec<Value>, bool), Value> {
+    match body {
+        Value::Array(items) if items.is_empty() => Err(make_error_response(
+            None,
+            -32600,
+            "Invalid Request: empty batch",
+        )),
+        Value::Array(items) => Ok((items, true)),
+        single => Ok((vec![single], false)),
     }
+}
+
+/// Whether `messages` start a new session. `initialize` may only lead a
+/// batch, and only once: anything before it would run without a session
+/// having been checked, and a second one would open another session.
+pub(crate) fn batch_opens_session(messages: &[Value]) -> Result<bool, Value> {
+    let is_initialize = |m: &Value| m.get("method").and_then(|v| v.as_str()) == Some("initialize");
+    if messages.iter().skip(1).any(is_initialize) {
+        return Err(make_error_response(
+            None,
+            -32600,
+            "Invalid Request: initialize must be the first message in a batch",
+        ));
+    }
+    Ok(messages.first().is_some_and(is_initialize))
+}
+
+/// What one message of a POST body is, before it's dispatched.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Incoming<'a> {
+    /// Not a JSON object.
+    Invalid,
+    /// No `id`: a notification, or a response to one of our requests. Neither
+    /// gets an answer.
+    Notification,
+    Request {
+        id: &'a Value,
+        method: &'a str,
+    },
+}
+
+pub(crate) fn classify_message(body: &Value) -> Incoming<'_> {
+    if !body.is_object() {
+        return Incoming::Invalid;
+    }
+    match body.get("id") {
+        Some(id) => Incoming::Request {
+            id,
+            method: body
+                .get("method")
+                .and_then(|m| m.as_str())
+                .unwrap_or_default(),
+        },
+        None => Incoming::Notification,
+    }
+}
+
+/// Answer one JSON-RPC message sent to a server endpoint. Returns `None` for
+/// notifications. `initialize` opens a new session and stores it in `session`.
+async fn handle_mcp_message(
+    state: &ProxyAppState,
+    server_id: &str,
+    client: &str,
+    body: Value,
+    session: &mut Option<String>,
+) -> Option<Value> {
+    let (id, method) = match classify_message(&body) {
+        Incoming::Invalid => return Some(make_error_response(None, -32600, "Invalid Request")),
+        Incoming::Notification => return None,
+        Incoming::Request { id, method } => (Some(id.clone()), method),
+    };
+    let params = body.get("params").cloned();
 
     // Look up the server by ID
     let server_name = {
@@ -282,15 +802,17 @@ async fn handle_mcp_post(
     let server_name = match server_name {
         Some(name) => name,
         None => {
-            let resp =
-                make_error_response(id, -32602, &format!("No server found with ID: {server_id}"));
-            return mcp_response(&resp, req_session.as_deref(), use_sse);
+            return Some(make_error_response(
+                id,
+                -32602,
+                &format!("No server found with ID: {server_id}"),
+            ));
         }
     };
 
     info!("Proxy [{server_name}] {method}");
 
-    match method {
+    let response = match method {
         "initialize" => {
             // Negotiate protocol version from client's requested version
             let client_version = params
@@ -300,10 +822,17 @@ async fn handle_mcp_post(
                 .unwrap_or("");
             let negotiated = negotiate_version(client_version);
 
-            // Generate a session ID for this connection
-            let session_id = new_session_id();
-
-            let response = serde_json::json!({
+            // Issue and track a session ID for this connection
+            let session_id = state
+                .app_handle
+                .state::<ProxyState>()
+                .inner()
+                .clone()
+                .create_session(server_id)
+                .await;
+            *session = Some(session_id);
+
+            let mut response = serde_json::json!({
                 "jsonrpc": "2.0",
                 "id": id,
                 "result": {
@@ -313,29 +842,49 @@ async fn handle_mcp_post(
                             "listChanged": true
                         }
                     },
-                    "serverInfo": {
-                        "name": format!("Agent Hub — {server_name}"),
-                        "version": env!("CARGO_PKG_VERSION")
-                    }
+                    "serverInfo": advertised_server_info(&state.app_handle, &server_name)
                 }
             });
-            mcp_response(&response, Some(&session_id), use_sse)
-        }
-        "tools/list" => {
-            let response = handle_tools_list(id, &server_id, &state);
-            mcp_response(&response, req_session.as_deref(), use_sse)
+            if let Some(notice) = empty_tools_notice(server_id, &server_name, state) {
+                response["result"]["instructions"] = Value::String(notice);
+            }
+            response
         }
+        "tools/list" => handle_tools_list(id, server_id, state),
         "tools/call" => {
-            let response =
-                handle_tools_call(id, params, &server_id, &server_name, &client, &state).await;
-            mcp_response(&response, req_session.as_deref(), use_sse)
-        }
-        _ => {
-            let response =
-                make_error_response(id, -32601, &format!("Method not found: {method}"));
-            mcp_response(&response, req_session.as_deref(), use_sse)
+            handle_tools_call(
+                id,
+                params,
+                server_id,
+                &server_name,
+                client,
+                session.as_deref(),
+                state,
+            )
+            .await
         }
+        _ => make_error_response(id, -32601, &format!("Method not found: {method}")),
+    };
+    Some(response)
+}
+
+/// If the user opted in and this server currently exposes no tools, return an
+/// explanation for the `initialize` result's `instructions` field.
+fn empty_tools_notice(server_id: &str, server_name: &str, state: &ProxyAppState) -> Option<String> {
+    let enabled = {
+        let app_state = state.app_handle.state::<SharedState>();
+        let s = app_state.lock().unwrap();
+        s.proxy_settings.empty_tools_notice
+    };
+    if !enabled || !collect_server_tools(server_id, state).is_empty() {
+        return None;
     }
+    Some(format!(
+        "No tools are currently available from '{server_name}'. The server is either \
+         disconnected in Agent Hub or exposes no tools. This is not a proxy error — \
+         ask the user to connect '{server_name}' in Agent Hub; the tool list will \
+         update automatically once it is connected."
+    ))
 }
 
 /// Handle `tools/list` -- return tools for this specific server only.
@@ -351,13 +900,15 @@ fn handle_tools_list(id: Option<Value>, server_id: &str, state: &ProxyAppState)
     })
 }
 
-/// Handle `tools/call` -- route directly to this server's backend.
+/// Handle `tools/call` -- route directly to this server's backend, or through
+/// a fallback group when the name is one of its aliases.
 async fn handle_tools_call(
     id: Option<Value>,
     params: Option<Value>,
     server_id: &str,
     server_name: &str,
     client_id: &str,
+    session_id: Option<&str>,
     state: &ProxyAppState,
 ) -> Value {
     let params = match params {
@@ -367,8 +918,8 @@ async fn handle_tools_call(
         }
     };
 
-    let tool_name = match params.get("name").and_then(|n| n.as_str()) {
-        Some(n) => n.to_string(),
+    let requested_name = match params.get("name").and_then(|n| n.as_str()) {
+        Some(n) => n,
         None => {
             return make_error_response(id, -32602, "Missing tool name in params");
         }
@@ -379,28 +930,232 @@ async fn handle_tools_call(
         .cloned()
         .unwrap_or(serde_json::json!({}));
 
+    let group = {
+        let app_state = state.app_handle.state::<SharedState>();
+        let s = app_state.lock().unwrap();
+        fallback_group_for(&s, server_id, requested_name)
+    };
+    if let Some(group) = group {
+        return call_fallback_group(id, &group, &arguments, client_id, session_id, state).await;
+    }
+
+    let (response, _) = call_server_tool(
+        id,
+        server_id,
+        server_name,
+        requested_name,
+        arguments,
+        client_id,
+        session_id,
+        state,
+    )
+    .await;
+    response
+}
+
+/// The fallback group a call to `requested_name` on `server_id`'s endpoint
+/// routes through. A real tool the server advertises under that name wins, as
+/// it does in `tools/list`.
+fn fallback_group_for(
+    s: &crate::state::AppState,
+    server_id: &str,
+    requested_name: &str,
+) -> Option<FallbackGroup> {
+    let server = s.servers.iter().find(|srv| srv.id == server_id);
+    let shadowed = s.connections.get(server_id).is_some_and(|conn| {
+        conn.tools.iter().any(|tool| {
+            server.is_none_or(|srv| srv.exposes_tool(&tool.name))
+                && server.map_or(tool.name.as_str(), |srv| {
+                    srv.advertised_tool_name(&tool.name)
+                }) == requested_name
+        })
+    });
+    if shadowed {
+        return None;
+    }
+    s.fallback_groups
+        .iter()
+        .find(|g| g.alias == requested_name && g.serves(server_id))
+        .cloned()
+}
+
+/// How a single routed tool call ended, for deciding whether to fall back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallOutcome {
+    /// The server is unknown, disconnected, too busy to take the call, or
+    /// doesn't expose the tool.
+    Unavailable,
+    /// The request never got an answer: the transport or connection failed.
+    Failed,
+    /// The server answered, or the call was rejected before it was sent.
+    /// A tool result with `isError` or a JSON-RPC error reply still counts,
+    /// as does a timeout, since the server may have run the tool; retrying
+    /// it elsewhere could repeat a side effect.
+    Completed,
+}
+
+impl CallOutcome {
+    fn of(call_result: &Result<CallToolResult, AppError>) -> Self {
+        match call_result {
+            Err(AppError::Transport(_) | AppError::ConnectionFailed(_)) => Self::Failed,
+            _ => Self::Completed,
+        }
+    }
+}
+
+/// Try each candidate of `group` in order and return the first answered
+/// response. Only unreachable candidates and transport failures move on to the
+/// next one; if none answers, the last failure is returned.
+async fn call_fallback_group(
+    id: Option<Value>,
+    group: &FallbackGroup,
+    arguments: &Value,
+    client_id: &str,
+    session_id: Option<&str>,
+    state: &ProxyAppState,
+) -> Value {
+    let mut last_failure = None;
+    for candidate in &group.candidates {
+        let server_name = {
+            let app_state = state.app_handle.state::<SharedState>();
+            let s = app_state.lock().unwrap();
+            s.servers
+                .iter()
+                .find(|srv| srv.id == candidate.server_id)
+                .map(|srv| srv.name.clone())
+        };
+        let Some(server_name) = server_name else {
+            continue;
+        };
+
+        let (response, outcome) = call_server_tool(
+            id.clone(),
+            &candidate.server_id,
+            &server_name,
+            &candidate.tool,
+            arguments.clone(),
+            client_id,
+            session_id,
+            state,
+        )
+        .await;
+        match outcome {
+            CallOutcome::Completed => return response,
+            CallOutcome::Failed => last_failure = Some(response),
+            CallOutcome::Unavailable => {}
+        }
+        info!(
+            "Fallback '{}': {server_name}.{} {outcome:?}, trying next candidate",
+            group.alias, candidate.tool
+        );
+    }
+
+    last_failure.unwrap_or_else(|| {
+        make_error_response(
+            id,
+            -32603,
+            &format!("No server for '{}' is connected", group.alias),
+        )
+    })
+}
+
+/// Call `requested_name` on one server's backend, recording stats and audit.
+#[allow(clippy::too_many_arguments)]
+async fn call_server_tool(
+    id: Option<Value>,
+    server_id: &str,
+    server_name: &str,
+    requested_name: &str,
+    arguments: Value,
+    client_id: &str,
+    session_id: Option<&str>,
+    state: &ProxyAppState,
+) -> (Value, CallOutcome) {
+    // Map an overridden display name back to the backend's real tool name
+    let (tool_name, checked) = {
+        let app_state = state.app_handle.state::<SharedState>();
+        let s = app_state.lock().unwrap();
+        let server = s.servers.iter().find(|srv| srv.id == server_id);
+        let tool_name = server.map_or_else(
+            || requested_name.to_string(),
+            |srv| srv.resolve_tool_name(requested_name),
+        );
+        let checked = if server.is_some_and(|srv| !srv.exposes_tool(&tool_name)) {
+            Err((
+                -32601,
+                format!("Tool not found: {requested_name}"),
+                CallOutcome::Unavailable,
+            ))
+        } else {
+            validation::check_tool_call(&s, server_id, &tool_name, &arguments)
+                .map_err(|msg| (-32602, msg, CallOutcome::Completed))
+        };
+        (tool_name, checked)
+    };
+
+    // Calls refused before reaching the backend are audited too
+    let refuse = |response: Value, outcome: CallOutcome| {
+        audit::record_tool_call(
+            &state.app_handle,
+            audit::ToolCallAudit {
+                server_id,
+                server_name,
+                tool: &tool_name,
+                session_id,
+                client: client_id,
+                success: false,
+                duration_ms: 0,
+                arguments: &arguments,
+                result: response.get("error").unwrap_or(&Value::Null),
+            },
+        );
+        (response, outcome)
+    };
+    if let Err((code, msg, outcome)) = checked {
+        return refuse(make_error_response(id, code, &msg), outcome);
+    }
+
     // Clone an Arc handle and drop the lock before doing async I/O.
     // This avoids blocking all other proxy requests while a tool call is in flight.
     let connections = state.app_handle.state::<SharedConnections>();
     let client = {
         let conns = connections.lock().await;
-        match conns.get(server_id).cloned() {
+        match conns.get(server_id) {
             Some(c) => c,
             None => {
-                return make_error_response(
-                    id,
-                    -32602,
-                    &format!("Server '{server_name}' is not connected"),
+                return refuse(
+                    make_error_response(
+                        id,
+                        -32602,
+                        &format!("Server '{server_name}' is not connected"),
+                    ),
+                    CallOutcome::Unavailable,
                 );
             }
         }
     };
 
+    // Queue behind other in-flight calls to this server
+    let _slot = match client.acquire_call_slot(CALL_QUEUE_TIMEOUT).await {
+        Some(slot) => slot,
+        None => {
+            return refuse(
+                make_error_response(
+                    id,
+                    -32603,
+                    &format!("Server busy: '{server_name}' has too many tool calls in flight"),
+                ),
+                CallOutcome::Unavailable,
+            );
+        }
+    };
+
     info!("Proxy tool call: {server_name}.{tool_name}");
 
     let start = Instant::now();
-    let call_result = client.call_tool(&tool_name, arguments).await;
+    let call_result = client.call_tool(&tool_name, arguments.clone()).await;
     let duration_ms = start.elapsed().as_millis() as u64;
+    let outcome = CallOutcome::of(&call_result);
 
     let (response, is_error) = match call_result {
         Ok(result) => {
@@ -413,10 +1168,13 @@ async fn handle_tools_call(
             let result_value = match serde_json::to_value(&result) {
                 Ok(v) => v,
                 Err(e) => {
-                    return make_error_response(
-                        id,
-                        -32603,
-                        &format!("Failed to serialize tool result: {e}"),
+                    return (
+                        make_error_response(
+                            id,
+                            -32603,
+                            &format!("Failed to serialize tool result: {e}"),
+                        ),
+                        CallOutcome::Completed,
                     );
                 }
             };
@@ -449,7 +1207,25 @@ async fn handle_tools_call(
     )
     .await;
 
-    response
+    audit::record_tool_call(
+        &state.app_handle,
+        audit::ToolCallAudit {
+            server_id,
+            server_name,
+            tool: &tool_name,
+            session_id,
+            client: client_id,
+            success: !is_error,
+            duration_ms,
+            arguments: &arguments,
+            result: response
+                .get("result")
+                .or(response.get("error"))
+                .unwrap_or(&Value::Null),
+        },
+    );
+
+    (response, outcome)
 }
 
 /// Record a tool call in the stats store, persist periodically, and emit event.
@@ -525,24 +1301,80 @@ fn collect_server_tools(server_id: &str, state: &ProxyAppState) -> Vec<Value> {
         Some(c) => c,
         None => return Vec::new(),
     };
+    let server = s.servers.iter().find(|srv| srv.id == server_id);
 
     let mut tools = Vec::new();
     for tool in &conn_state.tools {
+        if server.is_some_and(|srv| !srv.exposes_tool(&tool.name)) {
+            continue;
+        }
+        let name = server.map_or(tool.name.as_str(), |srv| {
+            srv.advertised_tool_name(&tool.name)
+        });
+        let description = server
+            .and_then(|srv| srv.advertised_tool_description(&tool.name))
+            .or(tool.description.as_deref());
         let mut entry = serde_json::json!({
-            "name": tool.name,
+            "name": name,
             "inputSchema": tool.input_schema,
         });
-        if let Some(ref desc) = tool.description {
-            entry["description"] = serde_json::Value::String(desc.clone());
+        if let Some(desc) = description {
+            entry["description"] = serde_json::Value::String(desc.to_string());
         }
         if let Some(ref title) = tool.title {
             entry["title"] = serde_json::Value::String(title.clone());
         }
         tools.push(entry);
     }
+
+    // Fallback aliases this endpoint answers, unless a real tool already has the name
+    for group in s.fallback_groups.iter().filter(|g| g.serves(server_id)) {
+        if tools.iter().any(|t| t["name"] == group.alias.as_str()) {
+            continue;
+        }
+        tools.push(fallback_tool_entry(&s, group));
+    }
     tools
 }
 
+/// Advertise a fallback alias with the schema and description of its first
+/// candidate whose tool is known, so the entry is stable while servers come
+/// and go.
+fn fallback_tool_entry(s: &crate::state::AppState, group: &FallbackGroup) -> Value {
+    let known = group.candidates.iter().find_map(|candidate| {
+        let server = s.servers.iter().find(|srv| srv.id == candidate.server_id)?;
+        let tool_name = server.resolve_tool_name(&candidate.tool);
+        let conn = s.connections.get(&candidate.server_id)?;
+        let tool = conn.tools.iter().find(|t| t.name == tool_name)?;
+        let description = server
+            .advertised_tool_description(&tool.name)
+            .or(tool.description.as_deref());
+        Some((tool.input_schema.clone(), description.map(String::from)))
+    });
+
+    let (schema, description) = known.unwrap_or_default();
+    let mut entry = serde_json::json!({
+        "name": group.alias,
+        "inputSchema": schema.unwrap_or_else(|| serde_json::json!({ "type": "object" })),
+    });
+    if let Some(desc) = description {
+        entry["description"] = Value::String(desc);
+    }
+    entry
+}
+
+/// Parse a POST body as JSON. Malformed JSON is answered with a JSON-RPC
+/// parse error (sent with HTTP 200) rather than the extractor's 400/422.
+pub(crate) fn parse_request_body(
+    body: &[u8],
+    headers: &HeaderMap,
+) -> Result<Value, (StatusCode, HeaderMap, String)> {
+    serde_json::from_slice(body).map_err(|e| {
+        let resp = make_error_response(None, -32700, &format!("Parse error: {e}"));
+        mcp_response(&resp, None, client_accepts_sse(headers))
+    })
+}
+
 /// Build a JSON-RPC error response.
 pub(crate) fn make_error_response(id: Option<Value>, code: i64, message: &str) -> Value {
     serde_json::json!({
@@ -554,3 +1386,336 @@ pub(crate) fn make_error_response(id: Option<Value>, code: i64, message: &str) -
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{AppState, ConnectionState, FallbackCandidate, McpTool, ServerConfig};
+
+    fn session_headers(session_id: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("mcp-session-id", session_id.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn session_is_accepted_only_on_its_endpoint() {
+        let proxy = ProxyState::new();
+        let id = proxy.create_session("a").await;
+
+        let accepted = validate_session(&proxy, &session_headers(&id), "a").await;
+        assert_eq!(accepted.unwrap(), id);
+
+        let (status, _, _) = validate_session(&proxy, &session_headers(&id), "b")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn missing_or_unknown_session_is_rejected() {
+        let proxy = ProxyState::new();
+        proxy.create_session("a").await;
+
+        let (status, _, _) = validate_session(&proxy, &HeaderMap::new(), "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _, _) = validate_session(&proxy, &session_headers("nope"), "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn delete_ends_the_session() {
+        let proxy = ProxyState::new();
+        let id = proxy.create_session("a").await;
+
+        // Only the issuing endpoint can end it
+        let (status, _, _) = terminate_session(&proxy, &session_headers(&id), "b").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, _) = terminate_session(&proxy, &session_headers(&id), "a").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, _, _) = validate_session(&proxy, &session_headers(&id), "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _, _) = terminate_session(&proxy, &session_headers(&id), "a").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn idle_session_expires() {
+        let proxy = ProxyState::new();
+        let id = proxy.create_session("a").await;
+        proxy
+            .inner
+            .write()
+            .await
+            .sessions
+            .get_mut(&id)
+            .unwrap()
+            .last_seen = Instant::now()
+            .checked_sub(SESSION_IDLE_TIMEOUT)
+            .expect("monotonic clock older than the idle timeout");
+
+        let (status, _, _) = validate_session(&proxy, &session_headers(&id), "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(proxy.inner.read().await.sessions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stream_requires_allowed_origin_and_session() {
+        let proxy = ProxyState::new();
+        let id = proxy.create_session("a").await;
+
+        let (status, _, _) = authorize_stream(&proxy, &[], &HeaderMap::new(), "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _, _) = authorize_stream(&proxy, &[], &session_headers("nope"), "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let mut headers = session_headers(&id);
+        headers.insert("origin", "https://evil.example".parse().unwrap());
+        let (status, _, _) = authorize_stream(&proxy, &[], &headers, "a")
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let allowed = vec!["https://evil.example".to_string()];
+        assert!(authorize_stream(&proxy, &allowed, &headers, "a")
+            .await
+            .is_ok());
+        assert!(authorize_stream(&proxy, &[], &session_headers(&id), "a")
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn malformed_json_is_a_parse_error_with_http_200() {
+        for accept in ["application/json", "application/json, text/event-stream"] {
+            let mut headers = HeaderMap::new();
+            headers.insert("accept", accept.parse().unwrap());
+
+            let (status, _, text) =
+                parse_request_body(b"{\"jsonrpc\": ", &headers).expect_err("malformed body");
+            assert_eq!(status, StatusCode::OK);
+            let json = text.trim_start_matches("event: message\ndata: ").trim();
+            let resp: Value = serde_json::from_str(json).unwrap();
+            assert_eq!(resp["error"]["code"], -32700);
+            assert_eq!(resp["id"], Value::Null);
+        }
+    }
+
+    #[test]
+    fn well_formed_json_is_parsed() {
+        let body = parse_request_body(br#"{"jsonrpc":"2.0","id":1}"#, &HeaderMap::new());
+        assert_eq!(body.unwrap()["id"], 1);
+    }
+
+    fn tool(name: &str) -> McpTool {
+        McpTool {
+            name: name.into(),
+            title: None,
+            description: None,
+            input_schema: None,
+            server_id: "a".into(),
+            server_name: "a".into(),
+        }
+    }
+
+    fn state_with_alias(tools: &[&str]) -> AppState {
+        let mut state = AppState::new();
+        state.servers.push(ServerConfig {
+            id: "a".into(),
+            ..Default::default()
+        });
+        state.connections.insert(
+            "a".into(),
+            ConnectionState {
+                tools: tools.iter().copied().map(tool).collect(),
+                server_name: None,
+                server_version: None,
+                capabilities: Default::default(),
+            },
+        );
+        state.fallback_groups.push(FallbackGroup {
+            alias: "search".into(),
+            candidates: vec![FallbackCandidate {
+                server_id: "a".into(),
+                tool: "web_search".into(),
+            }],
+        });
+        state
+    }
+
+    #[test]
+    fn alias_routes_through_fallback_group() {
+        let state = state_with_alias(&["web_search"]);
+        let group = fallback_group_for(&state, "a", "search").expect("alias");
+        assert_eq!(group.alias, "search");
+        assert!(fallback_group_for(&state, "b", "search").is_none());
+    }
+
+    #[test]
+    fn real_tool_shadows_fallback_alias() {
+        let state = state_with_alias(&["web_search", "search"]);
+        assert!(fallback_group_for(&state, "a", "search").is_none());
+
+        // A blocked tool is invisible, so the alias applies again
+        let mut state = state;
+        state.servers[0].blocked_tools = Some(vec!["search".into()]);
+        assert!(fallback_group_for(&state, "a", "search").is_some());
+    }
+
+    #[test]
+    fn only_transport_errors_fail_over() {
+        let tool_error = CallToolResult {
+            content: Vec::new(),
+            is_error: Some(true),
+            validation_errors: Vec::new(),
+        };
+        assert_eq!(CallOutcome::of(&Ok(tool_error)), CallOutcome::Completed);
+        assert_eq!(
+            CallOutcome::of(&Err(AppError::Transport("connection reset".into()))),
+            CallOutcome::Failed
+        );
+        // The server answered with a JSON-RPC error
+        assert_eq!(
+            CallOutcome::of(&Err(AppError::Protocol("Invalid params".into()))),
+            CallOutcome::Completed
+        );
+        // The request went out, so the tool may already have run
+        assert_eq!(
+            CallOutcome::of(&Err(AppError::Timeout(
+                "waiting for response to tools/call (id=1)".into()
+            ))),
+            CallOutcome::Completed
+        );
+    }
+
+    // -- batches --------------------------------------------------------------
+
+    fn request(id: u64, method: &str) -> Value {
+        serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method })
+    }
+
+    fn notification(method: &str) -> Value {
+        serde_json::json!({ "jsonrpc": "2.0", "method": method })
+    }
+
+    #[test]
+    fn empty_batch_is_invalid() {
+        let err = split_batch(serde_json::json!([])).unwrap_err();
+        assert_eq!(err["error"]["code"], -32600);
+
+        let (messages, is_batch) = split_batch(request(1, "ping")).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(!is_batch);
+    }
+
+    #[test]
+    fn notification_only_batch_needs_no_answer() {
+        let batch = serde_json::json!([
+            notification("notifications/initialized"),
+            { "jsonrpc": "2.0", "id": 9, "result": {} },
+        ]);
+        let (messages, is_batch) = split_batch(batch).unwrap();
+        assert!(is_batch);
+        assert_eq!(batch_opens_session(&messages), Ok(false));
+        assert!(messages
+            .iter()
+            .all(|m| classify_message(m) == Incoming::Notification));
+    }
+
+    #[test]
+    fn mixed_batch_classifies_each_message() {
+        let batch = serde_json::json!([
+            request(1, "tools/list"),
+            notification("notifications/cancelled"),
+            42,
+            "tools/call",
+        ]);
+        let (messages, _) = split_batch(batch).unwrap();
+        let kinds: Vec<_> = messages.iter().map(classify_message).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Incoming::Request {
+                    id: &serde_json::json!(1),
+                    method: "tools/list"
+                },
+                Incoming::Notification,
+                Incoming::Invalid,
+                Incoming::Invalid,
+            ]
+        );
+    }
+
+    #[test]
+    fn initialize_must_lead_a_batch() {
+        let leading = [request(1, "initialize"), request(2, "tools/list")];
+        assert_eq!(batch_opens_session(&leading), Ok(true));
+
+        // A call smuggled ahead of initialize would skip the session check
+        let trailing = [request(1, "tools/call"), request(2, "initialize")];
+        let err = batch_opens_session(&trailing).unwrap_err();
+        assert_eq!(err["error"]["code"], -32600);
+
+        // A second initialize would open another session
+        let repeated = [
+            request(1, "initialize"),
+            request(2, "tools/list"),
+            request(3, "initialize"),
+        ];
+        let err = batch_opens_session(&repeated).unwrap_err();
+        assert_eq!(err["error"]["code"], -32600);
+
+        assert_eq!(batch_opens_session(&[request(1, "tools/call")]), Ok(false));
+    }
+
+    #[test]
+    fn discovery_batch_is_answered_with_an_array() {
+        let batch = serde_json::json!([
+            request(1, "tools/list"),
+            notification("notifications/initialized"),
+            request(2, "bogus"),
+        ]);
+        let (messages, is_batch) = split_batch(batch).unwrap();
+        assert!(is_batch);
+        assert_eq!(batch_opens_session(&messages), Ok(false));
+
+        let responses: Vec<Value> = messages
+            .iter()
+            .filter_map(|m| match classify_message(m) {
+                Incoming::Request { id, method } => Some(
+                    super::super::discovery::stateless_response(Some(id.clone()), method),
+                ),
+                _ => None,
+            })
+            .collect();
+        let (status, _, text) = post_reply(responses, is_batch, Some("s"), false);
+        assert_eq!(status, StatusCode::OK);
+        let reply: Value = serde_json::from_str(&text).unwrap();
+        let reply = reply.as_array().expect("batch reply is an array");
+        assert_eq!(reply.len(), 2);
+        assert_eq!(reply[0]["id"], 1);
+        assert!(reply[0]["result"]["tools"].is_array());
+        assert_eq!(reply[1]["id"], 2);
+        assert_eq!(reply[1]["error"]["code"], -32601);
+
+        // A batch of notifications alone needs no answer
+        let (status, _, _) = post_reply(Vec::new(), true, Some("s"), false);
+        assert_eq!(status, StatusCode::ACCEPTED);
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{oneshot, Mutex};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// How long a backend's `sampling/createMessage` waits for the user to answer.
+const SAMPLING_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// JSON-RPC error code MCP uses when the user declines a sampling request.
+const USER_REJECTED: i64 = -1;
+
+/// Sampling requests forwarded to the frontend and awaiting an answer,
+/// keyed by the request ID sent in the `sampling-request` event.
+#[derive(Default)]
+pub struct SamplingRequests {
+    pending: Mutex<HashMap<String, oneshot::Sender<Result<Value, String>>>>,
+}
+
+impl SamplingRequests {
+    /// Deliver the frontend's answer. Returns `false` if the request is unknown
+    /// (already answered or timed out).
+    pub async fn resolve(&self, request_id: &str, outcome: Result<Value, String>) -> bool {
+        match self.pending.lock().await.remove(request_id) {
+            Some(sender) => sender.send(outcome).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// Forward a backend's `sampling/createMessage` request to the frontend as a
+/// `sampling-request` event and wait for `respond_to_sampling`. Returns the
+/// JSON-RPC reply to send back to the server.
+pub async fn forward_to_frontend(
+    app: &AppHandle,
+    server_id: &str,
+    id: Value,
+    params: Option<Value>,
+) -> Value {
+    let Some(requests) = app.try_state::<SamplingRequests>() else {
+        return error_reply(id, -32603, "Sampling is not available");
+    };
+
+    let request_id = Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    requests.pending.lock().await.insert(request_id.clone(), tx);
+
+    info!("Forwarding sampling request {request_id} from server {server_id}");
+    let _ = app.emit(
+        "sampling-request",
+        serde_json::json!({
+            "requestId": request_id,
+            "serverId": server_id,
+            "params": params,
+        }),
+    );
+
+    let outcome = tokio::time::timeout(SAMPLING_TIMEOUT, rx).await;
+    requests.pending.lock().await.remove(&request_id);
+
+    match outcome {
+        Ok(Ok(Ok(result))) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Ok(Ok(Err(reason))) => error_reply(id, USER_REJECTED, &reason),
+        Ok(Err(_)) => error_reply(id, -32603, "Sampling request was dropped"),
+        Err(_) => {
+            warn!("Sampling request {request_id} from server {server_id} timed out");
+            error_reply(id, -32603, "Timed out waiting for the user to answer")
+        }
+    }
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
@@ -0,0 +1,153 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+/// Messages kept per server; older ones are dropped first.
+const TRACE_BUFFER_SIZE: usize = 200;
+
+/// Payloads longer than this many characters are cut short.
+const TRACE_PAYLOAD_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceDirection {
+    Sent,
+    Received,
+}
+
+/// One JSON-RPC message as it crossed a server's stdio transport.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEntry {
+    pub direction: TraceDirection,
+    /// The message's method; for responses, the method of the request they answer.
+    pub method: Option<String>,
+    pub id: Option<serde_json::Value>,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub payload: String,
+    pub truncated: bool,
+}
+
+/// Ring buffer of one server's recent messages, shared with its transport.
+pub type TraceBuffer = Arc<Mutex<VecDeque<TraceEntry>>>;
+
+/// Per-server message traces. Buffers outlive individual connections so a
+/// failed handshake can still be inspected after the process exits.
+#[derive(Default)]
+pub struct ServerTraces(Mutex<HashMap<String, TraceBuffer>>);
+
+impl ServerTraces {
+    /// The buffer for `server_id`, created on first use.
+    pub fn buffer(&self, server_id: &str) -> TraceBuffer {
+        self.0
+            .lock()
+            .unwrap()
+            .entry(server_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Oldest-first copy of the messages recorded for `server_id`.
+    pub fn entries(&self, server_id: &str) -> Vec<TraceEntry> {
+        let buffers = self.0.lock().unwrap();
+        buffers
+            .get(server_id)
+            .map(|b| b.lock().unwrap().iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Record a raw JSON-RPC line. Method and id are read from the message when
+/// it parses; a response inherits the method of the request with its id.
+pub fn record(buffer: &TraceBuffer, direction: TraceDirection, line: &str) {
+    let message: Option<serde_json::Value> = serde_json::from_str(line).ok();
+    let id = message
+        .as_ref()
+        .and_then(|m| m.get("id"))
+        .filter(|id| !id.is_null())
+        .cloned();
+    let mut method = message
+        .as_ref()
+        .and_then(|m| m.get("method"))
+        .and_then(|m| m.as_str())
+        .map(String::from);
+
+    let (payload, truncated) = match line.char_indices().nth(TRACE_PAYLOAD_CHARS) {
+        Some((cut, _)) => (line[..cut].to_string(), true),
+        None => (line.to_string(), false),
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+
+    let mut buf = buffer.lock().unwrap();
+    if method.is_none() && id.is_some() {
+        method = buf
+            .iter()
+            .rev()
+            .find(|e| e.direction != direction && e.id == id && e.method.is_some())
+            .and_then(|e| e.method.clone());
+    }
+    buf.push_back(TraceEntry {
+        direction,
+        method,
+        id,
+        timestamp,
+        payload,
+        truncated,
+    });
+    while buf.len() > TRACE_BUFFER_SIZE {
+        buf.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_inherit_request_method() {
+        let buffer = TraceBuffer::default();
+        record(
+            &buffer,
+            TraceDirection::Sent,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        );
+        record(
+            &buffer,
+            TraceDirection::Received,
+            r#"{"jsonrpc":"2.0","id":1,"result":{}}"#,
+        );
+        record(&buffer, TraceDirection::Received, "not json");
+
+        let entries: Vec<TraceEntry> = buffer.lock().unwrap().iter().cloned().collect();
+        assert_eq!(entries[1].method.as_deref(), Some("tools/list"));
+        assert_eq!(entries[1].id, Some(serde_json::json!(1)));
+        assert_eq!(entries[2].method, None);
+        assert_eq!(entries[2].id, None);
+    }
+
+    #[test]
+    fn truncates_payloads_and_drops_oldest() {
+        let buffer = TraceBuffer::default();
+        let long = "é".repeat(TRACE_PAYLOAD_CHARS + 10);
+        record(&buffer, TraceDirection::Received, &long);
+        for _ in 0..TRACE_BUFFER_SIZE {
+            record(&buffer, TraceDirection::Sent, "{}");
+        }
+
+        let buf = buffer.lock().unwrap();
+        assert_eq!(buf.len(), TRACE_BUFFER_SIZE);
+        assert!(buf.iter().all(|e| e.direction == TraceDirection::Sent));
+
+        drop(buf);
+        record(&buffer, TraceDirection::Received, &long);
+        let last = buffer.lock().unwrap().back().cloned().unwrap();
+        assert!(last.truncated);
+        assert_eq!(last.payload.chars().count(), TRACE_PAYLOAD_CHARS);
+    }
+}
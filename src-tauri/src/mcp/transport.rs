@@ -1,25 +1,53 @@
 use std::collections::{HashMap, VecDeque};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tracing::{debug, error, info, warn};
 
 use crate::error::AppError;
+use crate::mcp::client::ClientOptions;
+use crate::mcp::sampling;
+use crate::mcp::trace::{self, ServerTraces, TraceDirection};
 use crate::mcp::types::{JsonRpcRequest, JsonRpcResponse};
+use crate::state::JsonRpcIdFormat;
 
-/// A pending request awaiting a response from the MCP server.
-type PendingRequest = oneshot::Sender<JsonRpcResponse>;
+/// A pending request awaiting a response from the MCP server, or the reason
+/// its response couldn't be delivered.
+type PendingRequest = oneshot::Sender<Result<JsonRpcResponse, AppError>>;
 
 /// Max number of recent error-level stderr lines to keep for error context.
 const STDERR_BUFFER_SIZE: usize = 10;
 
+/// The app's PATH with `program`'s directory in front, unless it's already there.
+pub(crate) fn child_path(program: &Path) -> Option<OsString> {
+    let dir = program.parent()?.to_path_buf();
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    if dirs.contains(&dir) {
+        return None;
+    }
+    dirs.insert(0, dir);
+    std::env::join_paths(dirs).ok()
+}
+
+/// Sent by a server whose tool set changed; we re-list and tell proxy clients.
+const TOOLS_LIST_CHANGED: &str = "notifications/tools/list_changed";
+
+/// Largest stdout line (one JSON-RPC message) accepted when not configured.
+pub const DEFAULT_MAX_FRAME_BYTES: u64 = 16 * 1024 * 1024;
+
 /// Handle for writing to a running MCP server's stdin and tracking pending requests.
 pub struct StdioTransport {
     next_id: AtomicU64,
+    /// Wire format for outgoing request ids.
+    id_format: JsonRpcIdFormat,
     /// PID of the spawned child process.
     pid: u32,
     /// Channel to send raw JSON lines to the stdin writer task.
@@ -36,18 +64,30 @@ impl StdioTransport {
     /// `command` is the program name (e.g. "node", "npx", "python").
     /// `args` are the command-line arguments.
     /// `env` is an optional set of extra environment variables.
+    /// `options` carries per-server settings such as the request id format.
     pub fn spawn(
         app: &AppHandle,
         server_id: &str,
         command: &str,
         args: &[String],
         env: &HashMap<String, String>,
+        options: &ClientOptions,
     ) -> Result<Self, AppError> {
-        let mut cmd = app.shell().command(command);
+        let program = crate::commands::resolve_binary(command)?;
+        // Raw chunks rather than lines, so an oversized message is caught while
+        // it's still arriving instead of after the whole line is buffered
+        let mut cmd = app.shell().command(&program).set_raw_out(true);
 
         for arg in args {
             cmd = cmd.arg(arg);
         }
+        // Scripts like npx start with `#!/usr/bin/env node`, so the directory
+        // the command was found in has to be on the child's PATH too
+        if !env.keys().any(|k| k.eq_ignore_ascii_case("PATH")) {
+            if let Some(path) = child_path(&program) {
+                cmd = cmd.env("PATH", path);
+            }
+        }
         for (k, v) in env {
             cmd = cmd.env(k, v);
         }
@@ -57,21 +97,41 @@ impl StdioTransport {
             .map_err(|e| AppError::Transport(format!("Failed to spawn process: {e}")))?;
 
         let pid = child.pid();
+        crate::child_processes::track(app, server_id, pid, command);
+        let untrack_app = app.clone();
 
         // Channel for sending lines to stdin
         let (stdin_tx, mut stdin_rx) = mpsc::channel::<String>(64);
 
+        // Lets the reader tear the process down after a protocol violation
+        let (kill_tx, mut kill_rx) = oneshot::channel::<()>();
+
+        // Every message in either direction lands in the server's trace buffer
+        let trace_buf = app
+            .try_state::<ServerTraces>()
+            .map(|traces| traces.buffer(server_id))
+            .unwrap_or_default();
+        let sent_trace = trace_buf.clone();
+
         // Stdin writer task
         tauri::async_runtime::spawn(async move {
-            while let Some(line) = stdin_rx.recv().await {
-                if let Err(e) = child.write(line.as_bytes()) {
-                    error!("Failed to write to stdin: {e}");
-                    break;
+            loop {
+                tokio::select! {
+                    line = stdin_rx.recv() => {
+                        let Some(line) = line else { break };
+                        trace::record(&sent_trace, TraceDirection::Sent, line.trim_end());
+                        if let Err(e) = child.write(line.as_bytes()) {
+                            error!("Failed to write to stdin: {e}");
+                            break;
+                        }
+                    }
+                    _ = &mut kill_rx => break,
                 }
             }
             // When channel closes, kill the child process
             debug!("Stdin channel closed, killing child process");
             let _ = child.kill();
+            crate::child_processes::untrack(&untrack_app, pid);
         });
 
         let pending: Arc<Mutex<HashMap<u64, PendingRequest>>> =
@@ -88,36 +148,130 @@ impl StdioTransport {
         let log_app = app.clone();
         let log_server_id = server_id.to_string();
 
+        // Replies to server-initiated requests go out over stdin. Hold only a weak
+        // handle so the reader doesn't keep the child alive after shutdown.
+        let reply_tx = stdin_tx.downgrade();
+        let roots = options.roots.clone();
+        let sampling_enabled = options.sampling;
+        let max_frame_bytes = options.max_frame_bytes.unwrap_or(DEFAULT_MAX_FRAME_BYTES) as usize;
+        let mut kill_tx = Some(kill_tx);
+        let output_encoding = options.output_encoding;
+
         // Stdout/stderr reader task
         tauri::async_runtime::spawn(async move {
-            let mut stdout_buf = String::new();
+            let mut stdout_lines = LineBuffer::default();
+            let mut stderr_lines = LineBuffer::default();
             while let Some(event) = rx.recv().await {
                 match event {
                     CommandEvent::Stdout(bytes) => {
-                        let chunk = String::from_utf8_lossy(&bytes);
-                        stdout_buf.push_str(&chunk);
-
-                        // Process complete lines
-                        while let Some(newline_pos) = stdout_buf.find('\n') {
-                            let line = stdout_buf[..newline_pos].trim().to_string();
-                            stdout_buf = stdout_buf[newline_pos + 1..].to_string();
+                        let lines = match stdout_lines.push(&bytes, max_frame_bytes) {
+                            Ok(lines) => lines,
+                            Err(size) => {
+                                let message = format!(
+                                    "Message of {size} bytes exceeds the {max_frame_bytes}-byte frame limit; closing connection"
+                                );
+                                error!("MCP stdout: {message}");
+                                push_recent_stderr(&stderr_buf_clone, message.clone());
+                                // Fail in-flight requests now rather than at their timeout
+                                pending_clone.lock().await.clear();
+                                let _ = log_app.emit(
+                                    "server-log",
+                                    serde_json::json!({
+                                        "serverId": log_server_id,
+                                        "level": "error",
+                                        "message": message,
+                                    }),
+                                );
+                                if let Some(kill_tx) = kill_tx.take() {
+                                    let _ = kill_tx.send(());
+                                }
+                                break;
+                            }
+                        };
+
+                        for frame in lines {
+                            let line = match frame_text(frame, output_encoding) {
+                                Ok(line) => line,
+                                Err(id) => {
+                                    let sender = match id {
+                                        Some(id) => pending_clone.lock().await.remove(&id),
+                                        None => None,
+                                    };
+                                    match sender {
+                                        Some(sender) => {
+                                            let _ = sender.send(Err(AppError::Protocol(
+                                                "Server sent a response that is not valid UTF-8"
+                                                    .to_string(),
+                                            )));
+                                        }
+                                        None => warn!(
+                                            "Dropping non-UTF-8 JSON-RPC message with no pending request"
+                                        ),
+                                    }
+                                    continue;
+                                }
+                            };
+                            debug!("MCP stdout: {line}");
+                            trace::record(&trace_buf, TraceDirection::Received, &line);
 
-                            if line.is_empty() {
+                            let message: serde_json::Value = match serde_json::from_str(&line) {
+                                Ok(v) => v,
+                                Err(e) => {
+                                    warn!("Failed to parse JSON-RPC message: {e} — raw: {line}");
+                                    continue;
+                                }
+                            };
+
+                            // A request from the server (has both method and id) — answer it
+                            if let (Some(method), Some(id)) = (
+                                message.get("method").and_then(|m| m.as_str()),
+                                message.get("id"),
+                            ) {
+                                if method == "sampling/createMessage" && sampling_enabled {
+                                    // Waits on the user, so answer off the reader task
+                                    let app = log_app.clone();
+                                    let server_id = log_server_id.clone();
+                                    let reply_tx = reply_tx.clone();
+                                    let id = id.clone();
+                                    let params = message.get("params").cloned();
+                                    tauri::async_runtime::spawn(async move {
+                                        let reply = sampling::forward_to_frontend(
+                                            &app, &server_id, id, params,
+                                        )
+                                        .await;
+                                        send_reply(&reply_tx, &reply).await;
+                                    });
+                                } else {
+                                    let reply = server_request_reply(id.clone(), method, &roots);
+                                    send_reply(&reply_tx, &reply).await;
+                                }
                                 continue;
                             }
 
-                            debug!("MCP stdout: {line}");
+                            if message.get("method").and_then(|m| m.as_str())
+                                == Some(TOOLS_LIST_CHANGED)
+                            {
+                                let app = log_app.clone();
+                                let server_id = log_server_id.clone();
+                                tauri::async_runtime::spawn(async move {
+                                    crate::commands::connections::refresh_server_tools(
+                                        &app, &server_id,
+                                    )
+                                    .await;
+                                });
+                                continue;
+                            }
 
-                            match serde_json::from_str::<JsonRpcResponse>(&line) {
+                            match serde_json::from_value::<JsonRpcResponse>(message) {
                                 Ok(response) => {
                                     // Check if this is a response to a pending request
-                                    if let Some(serde_json::Value::Number(n)) = &response.id {
-                                        if let Some(id) = n.as_u64() {
-                                            let mut map = pending_clone.lock().await;
-                                            if let Some(sender) = map.remove(&id) {
-                                                let _ = sender.send(response);
-                                                continue;
-                                            }
+                                    if let Some(id) =
+                                        response.id.as_ref().and_then(decode_request_id)
+                                    {
+                                        let mut map = pending_clone.lock().await;
+                                        if let Some(sender) = map.remove(&id) {
+                                            let _ = sender.send(Ok(response));
+                                            continue;
                                         }
                                     }
                                     // Not a response to a pending request — treat as notification
@@ -130,32 +284,34 @@ impl StdioTransport {
                         }
                     }
                     CommandEvent::Stderr(bytes) => {
-                        let text = String::from_utf8_lossy(&bytes).trim().to_string();
-                        if !text.is_empty() {
-                            // Many servers send all logging to stderr — detect the
-                            // actual level from the message content instead of
-                            // treating everything as an error.
-                            let level = detect_log_level(&text);
-                            match level {
-                                "error" => {
-                                    error!("MCP stderr: {text}");
-                                    let mut buf = stderr_buf_clone.lock().unwrap();
-                                    buf.push_back(text.clone());
-                                    if buf.len() > STDERR_BUFFER_SIZE {
-                                        buf.pop_front();
+                        // An overlong stderr line is logged as it stands
+                        let lines = stderr_lines
+                            .push(&bytes, max_frame_bytes)
+                            .unwrap_or_else(|_| vec![stderr_lines.take_all()]);
+                        for line in lines {
+                            let text = decode_output(&line, output_encoding).trim().to_string();
+                            if !text.is_empty() {
+                                // Many servers send all logging to stderr — detect the
+                                // actual level from the message content instead of
+                                // treating everything as an error.
+                                let level = detect_log_level(&text);
+                                match level {
+                                    "error" => {
+                                        error!("MCP stderr: {text}");
+                                        push_recent_stderr(&stderr_buf_clone, text.clone());
                                     }
+                                    "info" => info!("MCP stderr: {text}"),
+                                    _ => warn!("MCP stderr: {text}"),
                                 }
-                                "info" => info!("MCP stderr: {text}"),
-                                _ => warn!("MCP stderr: {text}"),
+                                let _ = log_app.emit(
+                                    "server-log",
+                                    serde_json::json!({
+                                        "serverId": log_server_id,
+                                        "level": level,
+                                        "message": text,
+                                    }),
+                                );
                             }
-                            let _ = log_app.emit(
-                                "server-log",
-                                serde_json::json!({
-                                    "serverId": log_server_id,
-                                    "level": level,
-                                    "message": text,
-                                }),
-                            );
                         }
                     }
                     CommandEvent::Terminated(status) => {
@@ -165,6 +321,7 @@ impl StdioTransport {
                         // timeout. This lets stderr_enriched_error() surface
                         // the real crash reason right away.
                         pending_clone.lock().await.clear();
+                        crate::child_processes::untrack(&log_app, pid);
                         let _ = log_app.emit(
                             "server-log",
                             serde_json::json!({
@@ -182,6 +339,7 @@ impl StdioTransport {
 
         Ok(Self {
             next_id: AtomicU64::new(1),
+            id_format: options.id_format,
             pid,
             stdin_tx,
             pending,
@@ -194,12 +352,22 @@ impl StdioTransport {
         &self,
         method: &str,
         params: Option<serde_json::Value>,
+    ) -> Result<JsonRpcResponse, AppError> {
+        self.send_request_raw(method, params).await?.into_result()
+    }
+
+    /// Like [`Self::send_request`], but a JSON-RPC error response is returned
+    /// as-is rather than converted into an `Err`.
+    pub async fn send_request_raw(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
     ) -> Result<JsonRpcResponse, AppError> {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
 
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
-            id: Some(serde_json::Value::Number(id.into())),
+            id: Some(encode_request_id(id, self.id_format)),
             method: method.to_string(),
             params,
         };
@@ -223,18 +391,10 @@ impl StdioTransport {
 
         let response = tokio::time::timeout(std::time::Duration::from_secs(60), rx)
             .await
-            .map_err(|_| {
-                AppError::Transport(format!(
-                    "Timeout waiting for response to {method} (id={id})"
-                ))
-            })?
+            .map_err(|_| AppError::Timeout(format!("waiting for response to {method} (id={id})")))?
             .map_err(|_| self.stderr_enriched_error("Server process exited unexpectedly"))?;
 
-        if let Some(err) = &response.error {
-            return Err(AppError::Protocol(format!("{}: {}", err.code, err.message)));
-        }
-
-        Ok(response)
+        response
     }
 
     /// Send a JSON-RPC notification (no id, no response expected).
@@ -289,6 +449,151 @@ impl StdioTransport {
     }
 }
 
+/// Encode a request id in the wire format the backend expects.
+pub(crate) fn encode_request_id(id: u64, format: JsonRpcIdFormat) -> serde_json::Value {
+    match format {
+        JsonRpcIdFormat::Numeric => serde_json::Value::Number(id.into()),
+        JsonRpcIdFormat::String => serde_json::Value::String(id.to_string()),
+    }
+}
+
+/// Recover our numeric request id from a response id, whichever form the server
+/// echoed back. Servers occasionally stringify numeric ids (or vice versa).
+pub(crate) fn decode_request_id(id: &serde_json::Value) -> Option<u64> {
+    match id {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// JSON-RPC is UTF-8 by spec, so a frame with invalid bytes is rejected rather
+/// than dispatched with replacement characters in it. The error carries the
+/// frame's request id, recovered from a lossy parse used for nothing else, so
+/// the matching pending request can be failed instead of left to time out. The
+/// configured encoding is only used to make the frame readable in the warning.
+pub(crate) fn frame_text(
+    frame: Vec<u8>,
+    encoding: Option<&'static encoding_rs::Encoding>,
+) -> Result<String, Option<u64>> {
+    String::from_utf8(frame).map_err(|e| {
+        let raw = decode_output(e.as_bytes(), encoding);
+        warn!("Rejecting non-UTF-8 JSON-RPC message — raw: {raw}");
+        serde_json::from_str::<serde_json::Value>(&String::from_utf8_lossy(e.as_bytes()))
+            .ok()
+            .and_then(|message| message.get("id").and_then(decode_request_id))
+    })
+}
+
+/// Reassembles newline-delimited messages from raw output chunks.
+#[derive(Default)]
+pub(crate) struct LineBuffer {
+    buf: Vec<u8>,
+    /// Bytes of `buf` already searched for a newline, so each chunk is only
+    /// scanned once however long the message it belongs to.
+    scanned: usize,
+}
+
+impl LineBuffer {
+    /// Append `chunk` and return the messages it completes, as trimmed raw
+    /// bytes with blank lines dropped; a partial trailing message is kept for
+    /// the next chunk. Returns the offending size if a message, complete or
+    /// still arriving, is larger than `max_bytes`.
+    pub(crate) fn push(&mut self, chunk: &[u8], max_bytes: usize) -> Result<Vec<Vec<u8>>, usize> {
+        self.buf.extend_from_slice(chunk);
+        let mut frames = Vec::new();
+        let mut start = 0;
+        let mut search_from = self.scanned;
+        while let Some(offset) = self.buf[search_from..].iter().position(|&b| b == b'\n') {
+            let end = search_from + offset;
+            if end - start > max_bytes {
+                return Err(end - start);
+            }
+            let line = self.buf[start..end].trim_ascii();
+            if !line.is_empty() {
+                frames.push(line.to_vec());
+            }
+            start = end + 1;
+            search_from = start;
+        }
+        self.buf.drain(..start);
+        self.scanned = self.buf.len();
+        if self.buf.len() > max_bytes {
+            return Err(self.buf.len());
+        }
+        Ok(frames)
+    }
+
+    /// Take everything buffered, complete or not.
+    pub(crate) fn take_all(&mut self) -> Vec<u8> {
+        self.scanned = 0;
+        std::mem::take(&mut self.buf)
+    }
+}
+
+/// Decode process output for logging, using the server's configured charset
+/// or lossy UTF-8 when none is set.
+pub(crate) fn decode_output(
+    bytes: &[u8],
+    encoding: Option<&'static encoding_rs::Encoding>,
+) -> String {
+    match encoding {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Remember an error line for `stderr_enriched_error`, keeping the newest few.
+fn push_recent_stderr(buf: &std::sync::Mutex<VecDeque<String>>, line: String) {
+    let mut buf = buf.lock().unwrap();
+    buf.push_back(line);
+    if buf.len() > STDERR_BUFFER_SIZE {
+        buf.pop_front();
+    }
+}
+
+/// Write a JSON-RPC reply to the server's stdin, if the transport is still alive.
+async fn send_reply(reply_tx: &mpsc::WeakSender<String>, reply: &serde_json::Value) {
+    if let (Some(tx), Ok(line)) = (reply_tx.upgrade(), serde_json::to_string(reply)) {
+        let _ = tx.send(format!("{line}\n")).await;
+    }
+}
+
+/// Build the reply to a request the server sent us. We act as a minimal MCP
+/// client: `roots/list` returns the configured roots and `ping` is answered;
+/// anything else is reported as unsupported.
+pub(crate) fn server_request_reply(
+    id: serde_json::Value,
+    method: &str,
+    roots: &[String],
+) -> serde_json::Value {
+    match method {
+        "roots/list" => {
+            let roots: Vec<serde_json::Value> = roots
+                .iter()
+                .map(|uri| {
+                    let name = uri
+                        .trim_end_matches('/')
+                        .rsplit('/')
+                        .next()
+                        .filter(|n| !n.is_empty());
+                    match name {
+                        Some(name) => serde_json::json!({ "uri": uri, "name": name }),
+                        None => serde_json::json!({ "uri": uri }),
+                    }
+                })
+                .collect();
+            serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": { "roots": roots } })
+        }
+        "ping" => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+        _ => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32601, "message": format!("Method not found: {method}") }
+        }),
+    }
+}
+
 /// Detect the log level from stderr content. Many servers (Python, Node, Go)
 /// send all logging to stderr, so we parse the message to find the actual level.
 fn detect_log_level(text: &str) -> &'static str {
@@ -313,3 +618,137 @@ fn detect_log_level(text: &str) -> &'static str {
         "warn"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // -- request id encoding ------------------------------------------------
+
+    #[test]
+    fn encodes_numeric_and_string_ids() {
+        assert_eq!(
+            encode_request_id(7, JsonRpcIdFormat::Numeric),
+            serde_json::json!(7)
+        );
+        assert_eq!(
+            encode_request_id(7, JsonRpcIdFormat::String),
+            serde_json::json!("7")
+        );
+    }
+
+    #[test]
+    fn correlates_regardless_of_id_type() {
+        for format in [JsonRpcIdFormat::Numeric, JsonRpcIdFormat::String] {
+            let sent = encode_request_id(42, format);
+            assert_eq!(decode_request_id(&sent), Some(42), "{format:?} round-trip");
+        }
+        // Server echoed the other representation back
+        assert_eq!(decode_request_id(&serde_json::json!("42")), Some(42));
+        assert_eq!(decode_request_id(&serde_json::json!(42)), Some(42));
+    }
+
+    #[test]
+    fn ignores_foreign_ids() {
+        assert_eq!(decode_request_id(&serde_json::json!("req-abc")), None);
+        assert_eq!(decode_request_id(&serde_json::json!(-1)), None);
+        assert_eq!(decode_request_id(&serde_json::Value::Null), None);
+    }
+
+    #[test]
+    fn correlates_parsed_responses() {
+        for raw in [
+            r#"{"jsonrpc":"2.0","id":3,"result":{}}"#,
+            r#"{"jsonrpc":"2.0","id":"3","result":{}}"#,
+        ] {
+            let response: JsonRpcResponse = serde_json::from_str(raw).expect("valid response");
+            assert_eq!(response.id.as_ref().and_then(decode_request_id), Some(3));
+        }
+    }
+
+    // -- framing --------------------------------------------------------------
+
+    #[test]
+    fn splits_frames_and_keeps_partial_tail() {
+        let mut lines = LineBuffer::default();
+        let frames = lines
+            .push(b"{\"a\":1}\n\n{\"b\":2}\r\n{\"c\"", 1024)
+            .expect("within limit");
+        assert_eq!(frames, vec![br#"{"a":1}"#.to_vec(), br#"{"b":2}"#.to_vec()]);
+        assert_eq!(lines.push(b":3}\n", 1024), Ok(vec![br#"{"c":3}"#.to_vec()]));
+        assert!(lines.take_all().is_empty());
+    }
+
+    #[test]
+    fn keeps_multibyte_chars_split_across_chunks() {
+        let encoded = "{\"s\":\"é\"}\n".as_bytes();
+        let (head, tail) = encoded.split_at(7);
+        let mut lines = LineBuffer::default();
+        assert!(lines.push(head, 1024).expect("partial").is_empty());
+        let frames = lines.push(tail, 1024).expect("complete");
+        assert_eq!(
+            String::from_utf8(frames[0].clone()).expect("utf-8"),
+            "{\"s\":\"é\"}"
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_frames() {
+        let mut complete = LineBuffer::default();
+        assert_eq!(complete.push(b"0123456789\n", 8), Err(10));
+
+        let mut partial = LineBuffer::default();
+        assert_eq!(partial.push(b"0123456789", 8), Err(10));
+    }
+
+    #[test]
+    fn rejects_oversized_frame_arriving_across_chunks() {
+        let mut lines = LineBuffer::default();
+        assert_eq!(
+            lines.push(b"{\"ok\":1}\n0123", 8),
+            Ok(vec![br#"{"ok":1}"#.to_vec()])
+        );
+        assert_eq!(lines.push(b"4567", 8), Ok(Vec::new()));
+        // Caught before the newline ever arrives
+        assert_eq!(lines.push(b"89", 8), Err(10));
+    }
+
+    #[test]
+    fn decodes_output_with_configured_charset() {
+        let latin1 = b"caf\xe9";
+        assert_eq!(decode_output(latin1, None), "caf\u{fffd}");
+        let windows_1252 = encoding_rs::Encoding::for_label(b"latin1");
+        assert_eq!(decode_output(latin1, windows_1252), "café");
+    }
+
+    #[test]
+    fn rejects_non_utf8_frames_with_their_request_id() {
+        let frame = b"{\"jsonrpc\":\"2.0\",\"id\":7,\"result\":{\"text\":\"caf\xe9\"}}".to_vec();
+        assert_eq!(frame_text(frame, None), Err(Some(7)));
+        assert_eq!(frame_text(b"caf\xe9".to_vec(), None), Err(None));
+        assert_eq!(
+            frame_text(b"{\"id\":1}".to_vec(), None),
+            Ok("{\"id\":1}".to_string())
+        );
+    }
+
+    // -- server-initiated requests ------------------------------------------
+
+    #[test]
+    fn answers_roots_list_with_configured_roots() {
+        let roots = vec!["file:///home/me/project/".to_string()];
+        let reply = server_request_reply(serde_json::json!(1), "roots/list", &roots);
+        assert_eq!(
+            reply["result"]["roots"],
+            serde_json::json!([{ "uri": "file:///home/me/project/", "name": "project" }])
+        );
+        assert_eq!(reply["id"], serde_json::json!(1));
+    }
+
+    #[test]
+    fn rejects_unsupported_server_requests() {
+        let reply = server_request_reply(serde_json::json!("a"), "elicitation/create", &[]);
+        assert_eq!(reply["error"]["code"], serde_json::json!(-32601));
+        assert_eq!(reply["id"], serde_json::json!("a"));
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::AppError;
+use crate::state::CapabilityFlags;
+
 /// JSON-RPC 2.0 request
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRpcRequest {
@@ -21,6 +24,16 @@ pub struct JsonRpcResponse {
     pub error: Option<JsonRpcError>,
 }
 
+impl JsonRpcResponse {
+    /// Turn a JSON-RPC error response into an `Err`.
+    pub fn into_result(self) -> Result<Self, AppError> {
+        match &self.error {
+            Some(err) => Err(AppError::Protocol(format!("{}: {}", err.code, err.message))),
+            None => Ok(self),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRpcError {
     pub code: i64,
@@ -61,7 +74,7 @@ pub struct InitializeResult {
     pub server_info: ServerInfo,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -72,14 +85,31 @@ pub struct ServerCapabilities {
     pub prompts: Option<PromptsCapability>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ServerCapabilities {
+    pub fn flags(&self) -> CapabilityFlags {
+        let tools = self.tools.as_ref();
+        let resources = self.resources.as_ref();
+        let prompts = self.prompts.as_ref();
+        CapabilityFlags {
+            tools: tools.is_some(),
+            tools_list_changed: tools.and_then(|c| c.list_changed).unwrap_or(false),
+            resources: resources.is_some(),
+            resources_subscribe: resources.and_then(|c| c.subscribe).unwrap_or(false),
+            resources_list_changed: resources.and_then(|c| c.list_changed).unwrap_or(false),
+            prompts: prompts.is_some(),
+            prompts_list_changed: prompts.and_then(|c| c.list_changed).unwrap_or(false),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ToolsCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub list_changed: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourcesCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -88,7 +118,7 @@ pub struct ResourcesCapability {
     pub list_changed: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PromptsCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -0,0 +1,366 @@
+use serde_json::Value;
+
+use crate::state::AppState;
+
+/// Largest serialized `arguments` object forwarded to a backend when neither
+/// the server nor the proxy settings override it.
+pub const DEFAULT_MAX_ARGUMENT_BYTES: u64 = 1024 * 1024;
+
+/// Effective argument size limit for `server_id`: the server's own override,
+/// then the proxy-wide setting, then [`DEFAULT_MAX_ARGUMENT_BYTES`].
+pub fn max_argument_bytes(state: &AppState, server_id: &str) -> u64 {
+    state
+        .servers
+        .iter()
+        .find(|s| s.id == server_id)
+        .and_then(|s| s.max_argument_bytes)
+        .or(state.proxy_settings.max_argument_bytes)
+        .unwrap_or(DEFAULT_MAX_ARGUMENT_BYTES)
+}
+
+/// Reject tool-call arguments whose serialized size exceeds `max_bytes`.
+pub fn check_argument_size(arguments: &Value, max_bytes: u64) -> Result<(), String> {
+    let size = serde_json::to_vec(arguments)
+        .map(|v| v.len() as u64)
+        .unwrap_or(0);
+    if size > max_bytes {
+        return Err(format!(
+            "Tool arguments are {size} bytes, exceeding the {max_bytes}-byte limit"
+        ));
+    }
+    Ok(())
+}
+
+/// Pre-flight checks run before a tool call is forwarded: the argument size
+/// limit always, and the tool's `inputSchema` when the server opted in.
+pub fn check_tool_call(
+    state: &AppState,
+    server_id: &str,
+    tool_name: &str,
+    arguments: &Value,
+) -> Result<(), String> {
+    check_argument_size(arguments, max_argument_bytes(state, server_id))?;
+
+    let validate = state
+        .servers
+        .iter()
+        .find(|s| s.id == server_id)
+        .is_some_and(|s| s.validate_arguments);
+    if !validate {
+        return Ok(());
+    }
+
+    let errors = argument_errors(state, server_id, tool_name, arguments);
+    if !errors.is_empty() {
+        return Err(invalid_arguments_message(tool_name, &errors));
+    }
+    Ok(())
+}
+
+/// The message reported when `errors` were found in a call to `tool_name`.
+pub fn invalid_arguments_message(tool_name: &str, errors: &[String]) -> String {
+    format!(
+        "Invalid arguments for tool '{tool_name}': {}",
+        errors.join("; ")
+    )
+}
+
+/// Check `arguments` against the `inputSchema` stored for `tool_name` on a
+/// connected server. Empty if they conform or the tool has no schema.
+pub fn argument_errors(
+    state: &AppState,
+    server_id: &str,
+    tool_name: &str,
+    arguments: &Value,
+) -> Vec<String> {
+    state
+        .connections
+        .get(server_id)
+        .and_then(|c| c.tools.iter().find(|t| t.name == tool_name))
+        .and_then(|t| t.input_schema.as_ref())
+        .map(|schema| validate_schema(arguments, schema))
+        .unwrap_or_default()
+}
+
+/// Validate `value` against a JSON Schema, returning one message per failure.
+///
+/// Covers the keywords MCP tool schemas use in practice (`type`, `enum`,
+/// `const`, `required`, `properties`, `additionalProperties`, `items`, and
+/// numeric/length/size bounds). Unsupported keywords such as `$ref` or
+/// `anyOf` are ignored, so unusual schemas err on the side of forwarding.
+pub fn validate_schema(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(value, schema, "arguments", &mut errors);
+    errors
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            // Further keywords assume the right type
+            return;
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{path}: must be one of {}",
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(format!("{path}: must equal {expected}"));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(|k| k.as_str()) {
+                    if !map.contains_key(key) {
+                        errors.push(format!("{path}: missing required property '{key}'"));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            for (key, item) in map {
+                let item_path = format!("{path}.{key}");
+                match properties.and_then(|p| p.get(key)) {
+                    Some(prop_schema) => validate_at(item, prop_schema, &item_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{path}: unexpected property '{key}'"));
+                        }
+                        Some(extra) => validate_at(item, extra, &item_path, errors),
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_bound(
+                schema,
+                "minItems",
+                items.len() as f64,
+                path,
+                "items",
+                errors,
+            );
+            check_bound(
+                schema,
+                "maxItems",
+                items.len() as f64,
+                path,
+                "items",
+                errors,
+            );
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item, item_schema, &format!("{path}[{i}]"), errors);
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as f64;
+            check_bound(schema, "minLength", len, path, "characters", errors);
+            check_bound(schema, "maxLength", len, path, "characters", errors);
+        }
+        Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                for keyword in ["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum"] {
+                    check_bound(schema, keyword, n, path, "", errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check a numeric bound keyword; `unit` distinguishes size bounds from value bounds.
+fn check_bound(
+    schema: &serde_json::Map<String, Value>,
+    keyword: &str,
+    actual: f64,
+    path: &str,
+    unit: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(limit) = schema.get(keyword).and_then(|v| v.as_f64()) else {
+        return;
+    };
+    let (ok, relation) = match keyword {
+        "minItems" | "minLength" | "minimum" => (actual >= limit, "at least"),
+        "maxItems" | "maxLength" | "maximum" => (actual <= limit, "at most"),
+        "exclusiveMinimum" => (actual > limit, "greater than"),
+        "exclusiveMaximum" => (actual < limit, "less than"),
+        _ => (true, ""),
+    };
+    if !ok {
+        let suffix = if unit.is_empty() {
+            String::new()
+        } else {
+            format!(" {unit}")
+        };
+        errors.push(format!("{path}: must be {relation} {limit}{suffix}"));
+    }
+}
+
+fn matches_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        // Unknown type names shouldn't block the call
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ConnectionState, McpTool, ServerConfig};
+
+    /// `{"s":"…"}` is 8 bytes of framing around the string payload.
+    fn args_of_size(total: usize) -> Value {
+        serde_json::json!({ "s": "x".repeat(total - 8) })
+    }
+
+    #[test]
+    fn arguments_at_limit_are_accepted() {
+        let args = args_of_size(64);
+        assert_eq!(serde_json::to_vec(&args).unwrap().len(), 64);
+        assert!(check_argument_size(&args, 64).is_ok());
+    }
+
+    #[test]
+    fn arguments_one_byte_over_limit_are_rejected() {
+        let err = check_argument_size(&args_of_size(65), 64).unwrap_err();
+        assert!(err.contains("65 bytes"));
+        assert!(err.contains("64-byte limit"));
+    }
+
+    fn sample_schema() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "minLength": 1 },
+                "mode": { "enum": ["read", "write"] },
+                "limit": { "type": "integer", "minimum": 1 },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["path"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn valid_arguments_pass_schema() {
+        let args = serde_json::json!({ "path": "/tmp", "mode": "read", "limit": 5, "tags": ["a"] });
+        assert!(validate_schema(&args, &sample_schema()).is_empty());
+    }
+
+    #[test]
+    fn schema_failures_are_reported_with_paths() {
+        let args = serde_json::json!({ "mode": "delete", "limit": 0, "tags": [1], "extra": true });
+        let errors = validate_schema(&args, &sample_schema());
+        assert_eq!(errors.len(), 5, "{errors:?}");
+        assert!(errors.contains(&"arguments: missing required property 'path'".to_string()));
+        assert!(errors.contains(&"arguments: unexpected property 'extra'".to_string()));
+        assert!(errors.contains(&"arguments.limit: must be at least 1".to_string()));
+        assert!(errors.contains(&"arguments.tags[0]: expected string, got number".to_string()));
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("arguments.mode: must be one of")));
+    }
+
+    #[test]
+    fn opted_in_server_rejects_calls_that_break_the_schema() {
+        let mut state = AppState::new();
+        state.servers.push(ServerConfig {
+            id: "a".into(),
+            validate_arguments: true,
+            ..Default::default()
+        });
+        state.connections.insert(
+            "a".into(),
+            ConnectionState {
+                tools: vec![McpTool {
+                    name: "write".into(),
+                    title: None,
+                    description: None,
+                    input_schema: Some(sample_schema()),
+                    server_id: "a".into(),
+                    server_name: "a".into(),
+                }],
+                server_name: None,
+                server_version: None,
+                capabilities: Default::default(),
+            },
+        );
+
+        let args = serde_json::json!({ "mode": "read" });
+        let errors = argument_errors(&state, "a", "write", &args);
+        assert_eq!(
+            check_tool_call(&state, "a", "write", &args),
+            Err(invalid_arguments_message("write", &errors))
+        );
+
+        state.servers[0].validate_arguments = false;
+        assert_eq!(check_tool_call(&state, "a", "write", &args), Ok(()));
+    }
+
+    #[test]
+    fn unsupported_keywords_are_ignored() {
+        let schema = serde_json::json!({ "anyOf": [{ "type": "string" }] });
+        assert!(validate_schema(&serde_json::json!(42), &schema).is_empty());
+    }
+
+    #[test]
+    fn server_override_beats_proxy_setting() {
+        let mut state = AppState::new();
+        assert_eq!(max_argument_bytes(&state, "a"), DEFAULT_MAX_ARGUMENT_BYTES);
+
+        state.proxy_settings.max_argument_bytes = Some(2048);
+        state.servers.push(ServerConfig {
+            id: "a".into(),
+            max_argument_bytes: Some(512),
+            ..Default::default()
+        });
+        assert_eq!(max_argument_bytes(&state, "a"), 512);
+        assert_eq!(max_argument_bytes(&state, "b"), 2048);
+    }
+}
@@ -1,36 +1,115 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
-use tauri::AppHandle;
-use tauri_plugin_store::StoreExt;
-use tracing::{error, info};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_store::{resolve_store_path, Store, StoreExt};
+use tracing::{error, info, warn};
 
-use crate::state::{EmbeddingConfig, InstalledSkill, OAuthState, RedisConfig, ServerConfig};
+use crate::child_processes::TrackedProcess;
+use crate::state::{
+    EmbeddingConfig, FallbackGroup, InstalledSkill, OAuthState, ProxySettings, RedisConfig,
+    ServerConfig, ServerLoadError, SkillAssetStore,
+};
 use crate::stats::ServerStats;
 
 const STORE_FILE: &str = "config.json";
 const SERVERS_KEY: &str = "servers";
+const SERVERS_SCHEMA_VERSION_KEY: &str = "servers_schema_version";
+/// Raw server list kept whenever migration had to drop data, for recovery.
+const SERVERS_BACKUP_KEY: &str = "servers_migration_backup";
 const INTEGRATIONS_KEY: &str = "enabled_integrations";
 const STATS_KEY: &str = "stats";
 const EMBEDDING_CONFIG_KEY: &str = "embedding_config";
 const OPENAI_API_KEY_KEY: &str = "openai_api_key";
+const COHERE_API_KEY_KEY: &str = "cohere_api_key";
 const REDIS_CONFIG_KEY: &str = "redis_config";
 const OAUTH_STORE_KEY: &str = "oauth_store";
 const TOOL_DISCOVERY_KEY: &str = "tool_discovery_enabled";
 const INSTALLED_SKILLS_KEY: &str = "installed_skills";
 const ENABLED_SKILL_INTEGRATIONS_KEY: &str = "enabled_skill_integrations";
+const SKILL_SYNC_HASHES_KEY: &str = "skill_sync_hashes";
+const SKILL_DIR_OVERRIDES_KEY: &str = "skill_dir_overrides";
+const PROXY_SETTINGS_KEY: &str = "proxy_settings";
+const DEFAULT_ENV_KEY: &str = "default_env";
+const FALLBACK_GROUPS_KEY: &str = "fallback_groups";
+const PLUGIN_CACHE_TTL_KEY: &str = "plugin_cache_ttl_secs";
+const CHILD_PROCESSES_KEY: &str = "child_processes";
+const OAUTH_CALLBACK_PORT_KEY: &str = "oauth_callback_port";
+const LOG_LEVEL_KEY: &str = "log_level";
+
+/// Shape of the stored server list. Bump when a change to `ServerConfig`
+/// needs more than serde defaults, and add the step to `upgrade_server`.
+/// - 0: unversioned; Memory servers flagged with `managed: true`
+/// - 1: `managedBy` names the owning subsystem
+const SERVERS_SCHEMA_VERSION: u32 = 1;
+
+/// How long the store waits after the last change before writing to disk.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
 
 // --- Generic helpers ---
 
+/// Coalesces store writes so bulk edits hit the disk once. Values land in the
+/// in-memory store right away; the file is written after [`SAVE_DEBOUNCE`] of
+/// quiet, or by [`flush_store`] on exit.
+#[derive(Default)]
+pub struct PendingSave {
+    generation: AtomicU64,
+    dirty: AtomicBool,
+}
+
+impl PendingSave {
+    fn schedule(&self, app: &AppHandle) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.dirty.store(true, Ordering::SeqCst);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(SAVE_DEBOUNCE).await;
+            let pending = app.state::<PendingSave>();
+            // A later change rescheduled the write
+            if pending.generation.load(Ordering::SeqCst) == generation {
+                flush_store(&app);
+            }
+        });
+    }
+}
+
+/// Write any changes still waiting on the debounce to disk now.
+pub fn flush_store(app: &AppHandle) {
+    if let Some(pending) = app.try_state::<PendingSave>() {
+        if !pending.dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+    }
+    let result = match open_store(app) {
+        Ok(store) => save_store(app, &store),
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        error!("Failed to persist {STORE_FILE}: {e}");
+    }
+}
+
+/// The config store, with the plugin's autosave off: it rewrites the file in
+/// place, so a crash mid-write could truncate it. Writes go through
+/// `save_store` instead.
+fn open_store(app: &AppHandle) -> tauri_plugin_store::Result<Arc<Store<Wry>>> {
+    app.store_builder(STORE_FILE).disable_auto_save().build()
+}
+
 fn store_get<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Option<T> {
-    let store = app.store(STORE_FILE).ok()?;
+    let store = open_store(app).ok()?;
     let value = store.get(key)?;
     serde_json::from_value(value.clone()).ok()
 }
 
 fn store_set<T: Serialize>(app: &AppHandle, key: &str, value: &T) {
-    let store = match app.store(STORE_FILE) {
+    let store = match open_store(app) {
         Ok(s) => s,
         Err(e) => {
             error!("Failed to open store for {key}: {e}");
@@ -38,28 +117,92 @@ fn store_set<T: Serialize>(app: &AppHandle, key: &str, value: &T) {
         }
     };
     store.set(key, serde_json::to_value(value).unwrap_or_default());
-    if let Err(e) = store.save() {
-        error!("Failed to persist {key}: {e}");
+    match app.try_state::<PendingSave>() {
+        Some(pending) => pending.schedule(app),
+        // Not managed yet during startup; write through
+        None => {
+            if let Err(e) = save_store(app, &store) {
+                error!("Failed to persist {key}: {e}");
+            }
+        }
     }
 }
 
+/// Write the whole store to disk atomically, in the plugin's own format.
+fn save_store(app: &AppHandle, store: &Store<Wry>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = resolve_store_path(app, STORE_FILE)?;
+    let entries: HashMap<String, serde_json::Value> = store.entries().into_iter().collect();
+    write_atomic(&path, serde_json::to_vec_pretty(&entries)?)?;
+    Ok(())
+}
+
+/// Distinguishes temp files of writes in flight at the same time.
+static TEMP_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Replace `path` with `contents` without ever leaving a partially written
+/// file: write a temp file alongside it, flush, then rename over the target.
+/// Symlinks are followed so a linked config (e.g. from a dotfiles repo) stays
+/// a link.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = target.parent().unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(dir)?;
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Unique per write, so concurrent saves of the same file never share one
+    let seq = TEMP_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
+    let tmp = dir.join(format!(".{file_name}.{}.{seq}.tmp", std::process::id()));
+    let result = (|| {
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, &target)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
 // --- Public API ---
 
-pub fn load_servers(app: &AppHandle) -> Vec<ServerConfig> {
-    let mut servers: Vec<ServerConfig> = store_get(app, SERVERS_KEY).unwrap_or_default();
-    // Migrate legacy `managed: true` → `managed_by: "memory"`
-    let mut migrated = false;
-    for server in &mut servers {
-        if server.managed == Some(true) && server.managed_by.is_none() {
-            server.managed_by = Some("memory".into());
-            server.managed = None;
-            migrated = true;
+/// Load saved servers, plus a description of each stored entry that couldn't
+/// be read in full. Readable entries load even when others don't.
+pub fn load_servers(app: &AppHandle) -> (Vec<ServerConfig>, Vec<ServerLoadError>) {
+    let raw: Option<serde_json::Value> = store_get(app, SERVERS_KEY);
+    let version: u32 = store_get(app, SERVERS_SCHEMA_VERSION_KEY).unwrap_or(0);
+    let (mut servers, errors) = match raw {
+        Some(raw) => {
+            let migration = migrate_servers(&raw, version);
+            for note in &migration.notes {
+                info!("Server config migration: {note}");
+            }
+            for e in &migration.errors {
+                error!(
+                    "Failed to load stored server entry {}: {}",
+                    e.index, e.error
+                );
+            }
+            if !migration.errors.is_empty() {
+                warn!("Server config migration dropped data; original kept under '{SERVERS_BACKUP_KEY}'");
+                store_set(app, SERVERS_BACKUP_KEY, &raw);
+            }
+            if version != SERVERS_SCHEMA_VERSION
+                || !migration.notes.is_empty()
+                || !migration.errors.is_empty()
+            {
+                save_servers(app, &migration.servers);
+            }
+            (migration.servers, migration.errors)
         }
-    }
-    if migrated {
-        info!("Migrated legacy 'managed' field to 'managed_by'");
-        save_servers(app, &servers);
-    }
+        None => (Vec::new(), Vec::new()),
+    };
 
     // One-time fixup: restore managed_by on Memory servers corrupted by integration import
     let mut fixed = false;
@@ -78,14 +221,138 @@ pub fn load_servers(app: &AppHandle) -> Vec<ServerConfig> {
     }
 
     info!("Loaded {} server configs from store", servers.len());
-    servers
+    (servers, errors)
 }
 
 pub fn save_servers(app: &AppHandle, servers: &[ServerConfig]) {
+    store_set(app, SERVERS_SCHEMA_VERSION_KEY, &SERVERS_SCHEMA_VERSION);
     store_set(app, SERVERS_KEY, &servers);
     info!("Saved {} server configs to store", servers.len());
 }
 
+/// Result of upgrading a stored server list to the current schema.
+struct ServerMigration {
+    servers: Vec<ServerConfig>,
+    /// Schema upgrades applied, one line per server and step.
+    notes: Vec<String>,
+    /// Entries that couldn't be carried over in full.
+    errors: Vec<ServerLoadError>,
+}
+
+/// Upgrade a stored server list entry by entry. A server that fails to
+/// decode keeps every field that still fits `ServerConfig`, with defaults for
+/// the rest, rather than taking the whole list down with it.
+fn migrate_servers(raw: &serde_json::Value, from_version: u32) -> ServerMigration {
+    let mut migration = ServerMigration {
+        servers: Vec::new(),
+        notes: Vec::new(),
+        errors: Vec::new(),
+    };
+    let Some(entries) = raw.as_array() else {
+        migration.errors.push(ServerLoadError {
+            index: 0,
+            name: None,
+            error: "Stored server list is not an array; nothing was loaded".into(),
+            recovered: false,
+        });
+        return migration;
+    };
+
+    for (index, entry) in entries.iter().enumerate() {
+        let name = entry.get("name").and_then(|n| n.as_str()).map(String::from);
+        let Some(mut fields) = entry.as_object().cloned() else {
+            migration.errors.push(ServerLoadError {
+                index,
+                name,
+                error: "Entry is not a JSON object".into(),
+                recovered: false,
+            });
+            continue;
+        };
+
+        for step in upgrade_server(&mut fields, from_version) {
+            let label = name.as_deref().unwrap_or("(unnamed)");
+            migration.notes.push(format!("'{label}': {step}"));
+        }
+
+        let value = serde_json::Value::Object(fields.clone());
+        let err = match serde_json::from_value::<ServerConfig>(value) {
+            Ok(server) => {
+                migration.servers.push(server);
+                continue;
+            }
+            Err(e) => e,
+        };
+        match decode_server_leniently(fields) {
+            Some((server, dropped)) => {
+                migration.errors.push(ServerLoadError {
+                    index,
+                    name,
+                    error: format!("{err}; dropped field(s) {}", dropped.join(", ")),
+                    recovered: true,
+                });
+                migration.servers.push(server);
+            }
+            None => migration.errors.push(ServerLoadError {
+                index,
+                name,
+                error: err.to_string(),
+                recovered: false,
+            }),
+        }
+    }
+    migration
+}
+
+/// Apply the schema steps between `from_version` and the current version to
+/// one raw server object. Returns a description of each change made.
+fn upgrade_server(
+    fields: &mut serde_json::Map<String, serde_json::Value>,
+    from_version: u32,
+) -> Vec<String> {
+    let mut steps = Vec::new();
+    if from_version < 1 {
+        // `managed: true` only ever marked the Memory server
+        if fields.get("managed") == Some(&serde_json::Value::Bool(true))
+            && fields.get("managedBy").is_none_or(|v| v.is_null())
+        {
+            fields.remove("managed");
+            fields.insert("managedBy".into(), "memory".into());
+            steps.push("replaced legacy 'managed' flag with managedBy 'memory'".into());
+        }
+    }
+    steps
+}
+
+/// Rebuild a server from whichever of its fields still decode, on top of
+/// `ServerConfig::default()`. Returns the server and the fields left out, or
+/// `None` if it has no usable id or name.
+fn decode_server_leniently(
+    fields: serde_json::Map<String, serde_json::Value>,
+) -> Option<(ServerConfig, Vec<String>)> {
+    let serde_json::Value::Object(mut kept) = serde_json::to_value(ServerConfig::default()).ok()?
+    else {
+        return None;
+    };
+    let mut dropped = Vec::new();
+    for (key, value) in fields {
+        let previous = kept.insert(key.clone(), value);
+        if serde_json::from_value::<ServerConfig>(serde_json::Value::Object(kept.clone())).is_err()
+        {
+            match previous {
+                Some(previous) => kept.insert(key.clone(), previous),
+                None => kept.remove(&key),
+            };
+            dropped.push(key);
+        }
+    }
+    let server: ServerConfig = serde_json::from_value(serde_json::Value::Object(kept)).ok()?;
+    if server.id.is_empty() || server.name.is_empty() {
+        return None;
+    }
+    Some((server, dropped))
+}
+
 pub fn load_enabled_integrations(app: &AppHandle) -> Vec<String> {
     store_get(app, INTEGRATIONS_KEY).unwrap_or_default()
 }
@@ -118,6 +385,14 @@ pub fn save_openai_api_key(app: &AppHandle, key: &str) {
     store_set(app, OPENAI_API_KEY_KEY, &key.to_string());
 }
 
+pub fn load_cohere_api_key(app: &AppHandle) -> Option<String> {
+    store_get(app, COHERE_API_KEY_KEY)
+}
+
+pub fn save_cohere_api_key(app: &AppHandle, key: &str) {
+    store_set(app, COHERE_API_KEY_KEY, &key.to_string());
+}
+
 pub fn load_redis_config(app: &AppHandle) -> RedisConfig {
     store_get(app, REDIS_CONFIG_KEY).unwrap_or_default()
 }
@@ -142,8 +417,53 @@ pub fn save_tool_discovery(app: &AppHandle, enabled: bool) {
     store_set(app, TOOL_DISCOVERY_KEY, &enabled);
 }
 
-pub fn load_installed_skills(app: &AppHandle) -> Vec<InstalledSkill> {
-    let mut skills: Vec<InstalledSkill> = store_get(app, INSTALLED_SKILLS_KEY).unwrap_or_default();
+/// Installed skills read from the store. `complete` is false when a stored
+/// entry couldn't be decoded, so `skills` may not reference every asset in use.
+pub struct LoadedSkills {
+    pub skills: Vec<InstalledSkill>,
+    pub complete: bool,
+}
+
+/// Decode stored skills one entry at a time, so one unreadable entry doesn't
+/// take the rest with it.
+fn decode_installed_skills(raw: Option<serde_json::Value>) -> LoadedSkills {
+    let entries = match raw {
+        None => {
+            return LoadedSkills {
+                skills: Vec::new(),
+                complete: true,
+            }
+        }
+        Some(serde_json::Value::Array(entries)) => entries,
+        Some(_) => {
+            error!("Stored installed skills aren't a list; ignoring them");
+            return LoadedSkills {
+                skills: Vec::new(),
+                complete: false,
+            };
+        }
+    };
+    let mut complete = true;
+    let skills = entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| match serde_json::from_value(entry) {
+            Ok(skill) => Some(skill),
+            Err(e) => {
+                error!("Failed to load stored skill entry {index}: {e}");
+                complete = false;
+                None
+            }
+        })
+        .collect();
+    LoadedSkills { skills, complete }
+}
+
+pub fn load_installed_skills(app: &AppHandle) -> LoadedSkills {
+    let LoadedSkills {
+        mut skills,
+        complete,
+    } = decode_installed_skills(store_get(app, INSTALLED_SKILLS_KEY));
     // Migrate legacy `managed: true` → `managed_by: "memory"`
     let mut migrated = false;
     for skill in &mut skills {
@@ -174,10 +494,24 @@ pub fn load_installed_skills(app: &AppHandle) -> Vec<InstalledSkill> {
         info!("Migrated skill IDs/source from mcp-manager to agent-hub");
     }
 
-    if migrated || renamed {
+    // Saving now would drop the entries that failed to decode
+    if complete && (migrated || renamed) {
         save_installed_skills(app, &skills);
     }
-    skills
+    LoadedSkills { skills, complete }
+}
+
+/// Delete asset blobs none of `loaded` refer to. Returns `Ok(None)` without
+/// touching the store when some skills failed to decode, since an unreadable
+/// entry may be the only reference to a blob.
+pub fn prune_skill_assets(
+    store: &SkillAssetStore,
+    loaded: &LoadedSkills,
+) -> std::io::Result<Option<usize>> {
+    if !loaded.complete {
+        return Ok(None);
+    }
+    store.prune(&loaded.skills).map(Some)
 }
 
 pub fn save_installed_skills(app: &AppHandle, skills: &[InstalledSkill]) {
@@ -191,3 +525,195 @@ pub fn load_enabled_skill_integrations(app: &AppHandle) -> Vec<String> {
 pub fn save_enabled_skill_integrations(app: &AppHandle, ids: &[String]) {
     store_set(app, ENABLED_SKILL_INTEGRATIONS_KEY, &ids);
 }
+
+pub fn load_skill_sync_hashes(app: &AppHandle) -> HashMap<String, HashMap<String, String>> {
+    store_get(app, SKILL_SYNC_HASHES_KEY).unwrap_or_default()
+}
+
+pub fn save_skill_sync_hashes(app: &AppHandle, hashes: &HashMap<String, HashMap<String, String>>) {
+    store_set(app, SKILL_SYNC_HASHES_KEY, hashes);
+}
+
+pub fn load_skill_dir_overrides(app: &AppHandle) -> HashMap<String, String> {
+    store_get(app, SKILL_DIR_OVERRIDES_KEY).unwrap_or_default()
+}
+
+pub fn save_skill_dir_overrides(app: &AppHandle, overrides: &HashMap<String, String>) {
+    store_set(app, SKILL_DIR_OVERRIDES_KEY, overrides);
+}
+
+pub fn load_proxy_settings(app: &AppHandle) -> ProxySettings {
+    store_get(app, PROXY_SETTINGS_KEY).unwrap_or_default()
+}
+
+pub fn save_proxy_settings(app: &AppHandle, settings: &ProxySettings) {
+    store_set(app, PROXY_SETTINGS_KEY, settings);
+}
+
+pub fn load_default_env(app: &AppHandle) -> HashMap<String, String> {
+    store_get(app, DEFAULT_ENV_KEY).unwrap_or_default()
+}
+
+pub fn save_default_env(app: &AppHandle, env: &HashMap<String, String>) {
+    store_set(app, DEFAULT_ENV_KEY, env);
+}
+
+pub fn load_fallback_groups(app: &AppHandle) -> Vec<FallbackGroup> {
+    store_get(app, FALLBACK_GROUPS_KEY).unwrap_or_default()
+}
+
+pub fn save_fallback_groups(app: &AppHandle, groups: &[FallbackGroup]) {
+    store_set(app, FALLBACK_GROUPS_KEY, &groups);
+}
+
+pub fn load_oauth_callback_port(app: &AppHandle) -> Option<u16> {
+    store_get(app, OAUTH_CALLBACK_PORT_KEY)
+}
+
+pub fn save_oauth_callback_port(app: &AppHandle, port: Option<u16>) {
+    store_set(app, OAUTH_CALLBACK_PORT_KEY, &port);
+}
+
+pub fn load_child_processes(app: &AppHandle) -> Vec<TrackedProcess> {
+    store_get(app, CHILD_PROCESSES_KEY).unwrap_or_default()
+}
+
+pub fn save_child_processes(app: &AppHandle, processes: &[TrackedProcess]) {
+    store_set(app, CHILD_PROCESSES_KEY, &processes);
+}
+
+pub fn load_plugin_cache_ttl(app: &AppHandle) -> u64 {
+    store_get(app, PLUGIN_CACHE_TTL_KEY)
+        .unwrap_or(crate::commands::plugins::DEFAULT_PLUGIN_CACHE_TTL_SECS)
+}
+
+pub fn save_plugin_cache_ttl(app: &AppHandle, ttl_secs: u64) {
+    store_set(app, PLUGIN_CACHE_TTL_KEY, &ttl_secs);
+}
+
+pub fn load_log_level(app: &AppHandle) -> String {
+    store_get(app, LOG_LEVEL_KEY).unwrap_or_else(|| crate::logging::DEFAULT_LOG_LEVEL.to_string())
+}
+
+pub fn save_log_level(app: &AppHandle, level: &str) {
+    store_set(app, LOG_LEVEL_KEY, &level.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ServerTransport;
+    use serde_json::json;
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_writes_replace_content_and_keep_symlinks() {
+        let dir = std::env::temp_dir().join(format!("agent-hub-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let real = dir.join("real.json");
+        let link = dir.join("link.json");
+        std::fs::write(&real, "old").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write_atomic(&link, "new").unwrap();
+        let link_meta = std::fs::symlink_metadata(&link).unwrap();
+        assert!(link_meta.file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_atomic_writes_use_separate_temp_files() {
+        let dir = std::env::temp_dir().join(format!("agent-hub-concurrent-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("store.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || write_atomic(&path, format!("writer {i}")))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        // One writer won whole, and no temp file was left behind
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("writer "));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_skill_entry_keeps_other_skills_and_their_assets() {
+        let dir =
+            std::env::temp_dir().join(format!("agent-hub-load-skills-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SkillAssetStore::new(&dir);
+        let good = store.put("run.sh".into(), b"echo good").unwrap();
+        let other = store.put("run.sh".into(), b"echo other").unwrap();
+
+        let raw = json!([
+            {
+                "id": "owner/repo/good", "name": "good", "skillId": "good",
+                "source": "owner/repo", "description": "", "content": "",
+                "enabled": true, "installs": null,
+                "assets": [{ "path": "run.sh", "sha256": good.sha256 }],
+            },
+            // Unreadable, but still the only reference to `other`
+            { "id": "owner/repo/bad", "assets": [{ "path": "run.sh", "sha256": other.sha256 }] },
+        ]);
+        let loaded = decode_installed_skills(Some(raw));
+        assert!(!loaded.complete);
+        assert_eq!(loaded.skills.len(), 1);
+        assert_eq!(loaded.skills[0].skill_id, "good");
+
+        assert_eq!(prune_skill_assets(&store, &loaded).unwrap(), None);
+        assert_eq!(store.read(&good).unwrap(), b"echo good");
+        assert_eq!(store.read(&other).unwrap(), b"echo other");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn upgrades_legacy_managed_flag() {
+        let raw = json!([
+            { "id": "m", "name": "Memory", "enabled": true, "transport": "http", "managed": true },
+        ]);
+        let migration = migrate_servers(&raw, 0);
+        assert!(migration.errors.is_empty());
+        assert_eq!(migration.servers[0].managed_by.as_deref(), Some("memory"));
+        assert_eq!(migration.servers[0].managed, None);
+        assert_eq!(migration.notes.len(), 1);
+
+        // Already-current lists pass through untouched
+        let current = migrate_servers(&raw, SERVERS_SCHEMA_VERSION);
+        assert!(current.notes.is_empty());
+    }
+
+    #[test]
+    fn keeps_servers_with_unreadable_fields() {
+        let raw = json!([
+            { "id": "a", "name": "ok", "enabled": true, "transport": "stdio", "command": "npx" },
+            { "id": "b", "name": "bad", "enabled": true, "transport": "carrier-pigeon",
+              "command": "uvx", "tags": ["x"] },
+            { "name": "no id", "enabled": true, "transport": "stdio" },
+        ]);
+        let migration = migrate_servers(&raw, SERVERS_SCHEMA_VERSION);
+        assert_eq!(migration.servers.len(), 2);
+        assert_eq!(migration.errors.len(), 2);
+        assert!(migration.errors[0].recovered);
+        assert!(migration.errors[0].error.contains("transport"));
+        assert_eq!(migration.errors[1].index, 2);
+        assert!(!migration.errors[1].recovered);
+
+        let bad = &migration.servers[1];
+        assert_eq!(bad.command.as_deref(), Some("uvx"));
+        assert_eq!(bad.tags, Some(vec!["x".to_string()]));
+        assert!(matches!(bad.transport, ServerTransport::Stdio));
+    }
+}
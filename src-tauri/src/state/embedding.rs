@@ -13,6 +13,44 @@ pub struct EmbeddingConfig {
 pub enum EmbeddingProvider {
     Ollama,
     Openai,
+    Cohere,
+    /// Self-hosted or gateway endpoint speaking the OpenAI embeddings API.
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        base_url: String,
+    },
+}
+
+impl EmbeddingProvider {
+    /// Short identifier used in status payloads and logs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbeddingProvider::Ollama => "ollama",
+            EmbeddingProvider::Openai => "openai",
+            EmbeddingProvider::Cohere => "cohere",
+            EmbeddingProvider::Custom { .. } => "custom",
+        }
+    }
+
+    /// Output dimensions of well-known models for this provider, or `None`
+    /// if the model isn't one we recognise (always the case for `Custom`).
+    pub fn known_dimensions(&self, model: &str) -> Option<u32> {
+        let dims = match (self, model) {
+            (EmbeddingProvider::Ollama, "mxbai-embed-large") => 1024,
+            (EmbeddingProvider::Ollama, "nomic-embed-text") => 768,
+            (EmbeddingProvider::Ollama, "snowflake-arctic-embed:335m") => 1024,
+            (EmbeddingProvider::Ollama, "all-minilm") => 384,
+            (EmbeddingProvider::Openai, "text-embedding-3-small") => 1536,
+            (EmbeddingProvider::Openai, "text-embedding-3-large") => 3072,
+            (EmbeddingProvider::Openai, "text-embedding-ada-002") => 1536,
+            (EmbeddingProvider::Cohere, "embed-english-v3.0") => 1024,
+            (EmbeddingProvider::Cohere, "embed-multilingual-v3.0") => 1024,
+            (EmbeddingProvider::Cohere, "embed-english-light-v3.0") => 384,
+            (EmbeddingProvider::Cohere, "embed-multilingual-light-v3.0") => 384,
+            _ => return None,
+        };
+        Some(dims)
+    }
 }
 
 impl Default for EmbeddingConfig {
@@ -53,3 +91,45 @@ impl Default for RedisConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_serialization_is_backwards_compatible() {
+        let json = serde_json::to_value(EmbeddingProvider::Openai).unwrap();
+        assert_eq!(json, serde_json::json!("openai"));
+        let parsed: EmbeddingProvider = serde_json::from_str("\"ollama\"").unwrap();
+        assert_eq!(parsed, EmbeddingProvider::Ollama);
+    }
+
+    #[test]
+    fn custom_provider_round_trips() {
+        let provider = EmbeddingProvider::Custom {
+            base_url: "http://localhost:8080/v1".into(),
+        };
+        let json = serde_json::to_value(&provider).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "custom": { "baseUrl": "http://localhost:8080/v1" } })
+        );
+        assert_eq!(
+            serde_json::from_value::<EmbeddingProvider>(json).unwrap(),
+            provider
+        );
+    }
+
+    #[test]
+    fn known_dimensions_only_for_recognised_models() {
+        assert_eq!(
+            EmbeddingProvider::Cohere.known_dimensions("embed-english-v3.0"),
+            Some(1024)
+        );
+        assert_eq!(EmbeddingProvider::Openai.known_dimensions("my-model"), None);
+        let custom = EmbeddingProvider::Custom {
+            base_url: "http://gateway".into(),
+        };
+        assert_eq!(custom.known_dimensions("text-embedding-3-small"), None);
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// A logical tool that the proxy resolves to the first working candidate.
+/// Calling `alias` on any candidate server's endpoint tries each candidate in
+/// order, moving on when a server is disconnected or the call fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackGroup {
+    pub alias: String,
+    pub candidates: Vec<FallbackCandidate>,
+}
+
+/// One `(server, tool)` pair in a [`FallbackGroup`]. `tool` is the name the
+/// server advertises through the proxy, i.e. after any display-name override.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackCandidate {
+    pub server_id: String,
+    pub tool: String,
+}
+
+impl FallbackGroup {
+    /// Whether calls to `alias` on `server_id`'s endpoint route through this group.
+    pub fn serves(&self, server_id: &str) -> bool {
+        self.candidates.iter().any(|c| c.server_id == server_id)
+    }
+}
@@ -1,16 +1,20 @@
 mod embedding;
+mod fallback;
 mod oauth;
 pub mod plugin;
 mod providers;
+mod proxy;
 pub mod registry;
 pub mod skill;
 pub mod skills_registry;
 mod server;
 
 pub use embedding::*;
+pub use fallback::*;
 pub use oauth::*;
+pub use proxy::*;
 pub use server::*;
-pub use skill::InstalledSkill;
+pub use skill::{InstalledSkill, SkillAssetStore};
 
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -24,6 +28,31 @@ pub struct BufferedLog {
     pub message: String,
 }
 
+/// A stored server entry that couldn't be read in full at startup.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerLoadError {
+    /// Position of the entry in the stored list.
+    pub index: usize,
+    pub name: Option<String>,
+    pub error: String,
+    /// Whether the server was still loaded, minus the unreadable fields.
+    pub recovered: bool,
+}
+
+/// Which optional MCP features a connected server declared during `initialize`.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityFlags {
+    pub tools: bool,
+    pub tools_list_changed: bool,
+    pub resources: bool,
+    pub resources_subscribe: bool,
+    pub resources_list_changed: bool,
+    pub prompts: bool,
+    pub prompts_list_changed: bool,
+}
+
 pub struct AppState {
     pub servers: Vec<ServerConfig>,
     pub connections: HashMap<String, ConnectionState>,
@@ -39,12 +68,36 @@ pub struct AppState {
     pub tool_discovery_enabled: bool,
     /// Skills installed from the skills.sh marketplace.
     pub installed_skills: Vec<InstalledSkill>,
+    /// Where the installed skills' supporting files are kept.
+    pub skill_assets: SkillAssetStore,
     /// IDs of AI tools that should receive SKILL.md files (separate from MCP integrations).
     pub enabled_skill_integrations: Vec<String>,
+    /// Hash of the skill files last written, keyed by tool ID then skill ID,
+    /// so syncing can skip skills that haven't changed.
+    pub skill_sync_hashes: HashMap<String, HashMap<String, String>>,
+    /// Skills directory chosen by the user, keyed by tool ID. Tools without
+    /// an entry use their default location.
+    pub skill_dir_overrides: HashMap<String, String>,
+    /// User-configurable proxy behaviour (origin allow-list, etc.).
+    pub proxy_settings: ProxySettings,
+    /// Env vars applied to every stdio server; a server's own `env` wins on conflict.
+    pub default_env: HashMap<String, String>,
+    /// Logical tools the proxy resolves to an ordered list of server tools.
+    pub fallback_groups: Vec<FallbackGroup>,
+    /// Port the OAuth callback server prefers, so a pre-registered redirect
+    /// URI keeps matching. `None` uses any free port.
+    pub oauth_callback_port: Option<u16>,
+    /// Stored server entries that failed to load on startup.
+    pub server_load_errors: Vec<ServerLoadError>,
 }
 
 pub struct ConnectionState {
     pub tools: Vec<McpTool>,
+    /// Software name the server reported in its `initialize` result.
+    pub server_name: Option<String>,
+    /// Software version the server reported in its `initialize` result.
+    pub server_version: Option<String>,
+    pub capabilities: CapabilityFlags,
 }
 
 impl AppState {
@@ -59,7 +112,15 @@ impl AppState {
             log_buffer: Vec::new(),
             tool_discovery_enabled: false,
             installed_skills: Vec::new(),
+            skill_assets: SkillAssetStore::unavailable(),
             enabled_skill_integrations: Vec::new(),
+            skill_sync_hashes: HashMap::new(),
+            skill_dir_overrides: HashMap::new(),
+            proxy_settings: ProxySettings::default(),
+            default_env: HashMap::new(),
+            fallback_groups: Vec::new(),
+            oauth_callback_port: None,
+            server_load_errors: Vec::new(),
         }
     }
 }
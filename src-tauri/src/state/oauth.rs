@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuthTokens {
@@ -33,17 +34,34 @@ pub struct OAuthState {
     pub client_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_secret: Option<String>,
+    /// Redirect URI `client_id` was dynamically registered with. `None` for
+    /// client ids that weren't obtained through registration.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub registered_redirect_uri: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tokens: Option<OAuthTokens>,
 }
 
 pub struct OAuthStore {
     entries: HashMap<String, OAuthState>,
+    /// Held while a server's token is being refreshed, so concurrent requests
+    /// share one refresh instead of each spending the refresh token.
+    refresh_locks: HashMap<String, Arc<tokio::sync::Mutex<()>>>,
 }
 
 impl OAuthStore {
     pub fn from_entries(entries: HashMap<String, OAuthState>) -> Self {
-        Self { entries }
+        Self {
+            entries,
+            refresh_locks: HashMap::new(),
+        }
+    }
+
+    pub fn refresh_lock(&mut self, server_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.refresh_locks
+            .entry(server_id.to_string())
+            .or_default()
+            .clone()
     }
 
     pub fn entries(&self) -> &HashMap<String, OAuthState> {
@@ -234,3 +234,69 @@ impl PluginInfo {
         }
     }
 }
+
+// ---------------------------------------------------------------------------
+// Marketplaces — `claude plugin marketplace list --json`
+// ---------------------------------------------------------------------------
+
+/// A marketplace entry as printed by the CLI.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplaceRaw {
+    pub name: String,
+    /// Either a plain string or an object like `{"source":"github","repo":"owner/repo"}`.
+    #[serde(default)]
+    pub source: Option<serde_json::Value>,
+    #[serde(default)]
+    pub install_location: Option<String>,
+    #[serde(default)]
+    pub last_updated: Option<String>,
+}
+
+/// The CLI prints either a bare array or `{"marketplaces": [...]}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum MarketplaceListOutput {
+    List(Vec<MarketplaceRaw>),
+    Wrapped { marketplaces: Vec<MarketplaceRaw> },
+}
+
+impl MarketplaceListOutput {
+    pub fn into_marketplaces(self) -> Vec<MarketplaceInfo> {
+        let raw = match self {
+            MarketplaceListOutput::List(list) => list,
+            MarketplaceListOutput::Wrapped { marketplaces } => marketplaces,
+        };
+        raw.into_iter().map(MarketplaceInfo::from).collect()
+    }
+}
+
+/// Marketplace info sent to the frontend for display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplaceInfo {
+    pub name: String,
+    /// Where the marketplace comes from: a URL, `owner/repo`, or a local path.
+    pub source: Option<String>,
+    pub install_location: Option<String>,
+    pub last_updated: Option<String>,
+}
+
+impl From<MarketplaceRaw> for MarketplaceInfo {
+    fn from(raw: MarketplaceRaw) -> Self {
+        let source = match raw.source {
+            Some(serde_json::Value::String(s)) => Some(s),
+            Some(serde_json::Value::Object(obj)) => ["url", "repo", "path"]
+                .iter()
+                .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+                .map(str::to_string),
+            _ => None,
+        };
+        MarketplaceInfo {
+            name: raw.name,
+            source,
+            install_location: raw.install_location,
+            last_updated: raw.last_updated,
+        }
+    }
+}
@@ -18,6 +18,10 @@ pub struct SkillsshEntry {
     pub name: String,
     pub installs: u64,
     pub source: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 /// Search the skills.sh API for skills matching a query.
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// User-configurable proxy behaviour, persisted as a single store entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxySettings {
+    /// Extra `Origin` values accepted on top of the always-allowed
+    /// localhost and Tauri origins (e.g. `http://192.168.1.20:3000`).
+    pub allowed_origins: Vec<String>,
+    /// Fixed port to bind the proxy on. When unset, a stable per-user port is
+    /// chosen with fallback; when set, startup fails rather than moving ports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_port: Option<u16>,
+    /// When a server endpoint has no tools, explain why in the `initialize`
+    /// result's `instructions` so agents don't assume the proxy is broken.
+    pub empty_tools_notice: bool,
+    /// Default cap on tool-call argument size in bytes; servers may override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_argument_bytes: Option<u64>,
+    pub audit: AuditConfig,
+    pub identity: ProxyIdentity,
+    /// Rewrite enabled integration configs on launch and whenever a server
+    /// connects, disconnects or restarts. Users with a pinned port can turn
+    /// this off to leave their files untouched.
+    pub auto_update_integration_ports: bool,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            preferred_port: None,
+            empty_tools_notice: false,
+            max_argument_bytes: None,
+            audit: AuditConfig::default(),
+            identity: ProxyIdentity::default(),
+            auto_update_integration_ports: true,
+        }
+    }
+}
+
+/// Overrides for the `serverInfo` the proxy advertises in `initialize`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxyIdentity {
+    /// Replaces the "Agent Hub" prefix in advertised server names.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Replaces the app version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Settings for the per-call audit log written by the proxy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    /// Size at which the log rotates to `audit.jsonl.1`.
+    pub max_bytes: u64,
+    /// Record argument/result sizes and hashes instead of their contents.
+    pub redact: bool,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_bytes: 10 * 1024 * 1024,
+            redact: true,
+        }
+    }
+}
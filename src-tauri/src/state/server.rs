@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerConfig {
     pub id: String,
@@ -14,8 +14,15 @@ pub struct ServerConfig {
     pub args: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    /// Absolute path of a dotenv file read at connect time and layered under
+    /// `env`, so secrets can stay out of the store.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub env_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// Sent with every HTTP request. Values may reference `$VAR`/`${VAR}`,
+    /// expanded at connect time, and `${oauth.access_token}`, filled with the
+    /// current OAuth token on each request.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -24,6 +31,12 @@ pub struct ServerConfig {
     pub status: Option<ServerStatus>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_connected: Option<String>,
+    /// Message from the most recent failed connection attempt, cleared on success.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_error: Option<String>,
+    /// When `last_error` was recorded, in seconds since the epoch.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_error_at: Option<u64>,
     /// Legacy field — only used for deserialization of old configs.
     /// Migrated to `managed_by` on load.
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -32,15 +45,190 @@ pub struct ServerConfig {
     pub managed_by: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub registry_name: Option<String>,
+    /// Wire format for JSON-RPC request ids. `None` means numeric.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id_format: Option<JsonRpcIdFormat>,
+    /// Skip TLS certificate verification for this HTTP server (self-signed dev certs).
+    #[serde(default)]
+    pub allow_insecure_tls: bool,
+    /// Max tool calls the proxy forwards to this server at once (default 8).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_concurrent_calls: Option<u32>,
+    /// Largest tool-call `arguments` payload forwarded to this server, in bytes.
+    /// Overrides the proxy-wide limit.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_argument_bytes: Option<u64>,
+    /// Largest single message accepted from a stdio server, in bytes (default 16 MB).
+    /// Larger messages close the connection.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_frame_bytes: Option<u64>,
+    /// Charset of a stdio server's stderr, as a WHATWG label (e.g. "windows-1252").
+    /// `None` means UTF-8. JSON-RPC messages on stdout are always UTF-8.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub output_encoding: Option<String>,
+    /// Check tool-call arguments against the tool's `inputSchema` before
+    /// forwarding. Off by default since some servers accept extra fields.
+    #[serde(default)]
+    pub validate_arguments: bool,
+    /// If set, only these tools are exposed through the proxy.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub exposed_tools: Option<Vec<String>>,
+    /// Tools never exposed through the proxy, even if listed in `exposed_tools`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub blocked_tools: Option<Vec<String>>,
+    /// Name/description overrides applied to this server's tools in the proxy,
+    /// keyed by the backend's real tool name.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tool_overrides: Option<HashMap<String, ToolOverride>>,
+    /// Hide all of this server's tools from the discovery endpoint while
+    /// keeping them in the server's own `tools/list`.
+    #[serde(default)]
+    pub exclude_from_discovery: bool,
+    /// Individual tools hidden from the discovery endpoint.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub discovery_excluded_tools: Option<Vec<String>>,
+    /// Workspace roots (file URIs or absolute paths) advertised to a stdio
+    /// server and returned from its `roots/list` requests.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub roots: Option<Vec<String>>,
+    /// Let this stdio server request LLM completions (`sampling/createMessage`),
+    /// which are forwarded to the app for the user to answer.
+    #[serde(default)]
+    pub enable_sampling: bool,
+    /// Fetch a package-runner server's package (`npx -y pkg`, `uvx pkg`)
+    /// before connecting, so a first-run download doesn't hit the
+    /// handshake timeout.
+    #[serde(default)]
+    pub prewarm: bool,
+}
+
+/// Replacement name and/or description for one tool as seen by proxy clients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolOverride {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+}
+
+/// A name `overrides` would advertise for two different tools: two display
+/// names that match, or a display name that matches a tool keeping its real
+/// name. `tool_names` are the backend's tools, when known; every tool with an
+/// override counts as well.
+pub fn duplicate_advertised_name<'a>(
+    overrides: &'a HashMap<String, ToolOverride>,
+    tool_names: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let mut tools: Vec<&str> = tool_names
+        .into_iter()
+        .chain(overrides.keys().map(String::as_str))
+        .collect();
+    tools.sort_unstable();
+    tools.dedup();
+
+    let mut seen = HashSet::new();
+    tools
+        .into_iter()
+        .map(|tool| {
+            overrides
+                .get(tool)
+                .and_then(|o| o.display_name.as_deref())
+                .unwrap_or(tool)
+        })
+        .find(|name| !seen.insert(*name))
+}
+
+impl ServerConfig {
+    /// Whether the discovery endpoint may surface and route `tool_name`.
+    pub fn discoverable_tool(&self, tool_name: &str) -> bool {
+        let excluded = self.exclude_from_discovery
+            || self
+                .discovery_excluded_tools
+                .as_ref()
+                .is_some_and(|tools| tools.iter().any(|t| t == tool_name));
+        self.exposes_tool(tool_name) && !excluded
+    }
+
+    fn tool_override(&self, tool_name: &str) -> Option<&ToolOverride> {
+        self.tool_overrides.as_ref()?.get(tool_name)
+    }
+
+    /// The name proxy clients see for the backend tool `tool_name`.
+    pub fn advertised_tool_name<'a>(&'a self, tool_name: &'a str) -> &'a str {
+        self.tool_override(tool_name)
+            .and_then(|o| o.display_name.as_deref())
+            .unwrap_or(tool_name)
+    }
+
+    /// The description proxy clients see, if overridden.
+    pub fn advertised_tool_description(&self, tool_name: &str) -> Option<&str> {
+        self.tool_override(tool_name)?.description.as_deref()
+    }
+
+    /// Map a name a proxy client called back to the backend's real tool name.
+    pub fn resolve_tool_name(&self, advertised: &str) -> String {
+        self.tool_overrides
+            .iter()
+            .flatten()
+            .find(|(_, o)| o.display_name.as_deref() == Some(advertised))
+            .map(|(real, _)| real.clone())
+            .unwrap_or_else(|| advertised.to_string())
+    }
+
+    /// Whether `other` reaches the same backend: the same command and args, or
+    /// the same URL. Names are ignored since users rename imported servers.
+    pub fn same_endpoint(&self, other: &ServerConfig) -> bool {
+        if self.command.is_none() && self.url.is_none() {
+            return false;
+        }
+        let args = |s: &ServerConfig| s.args.clone().unwrap_or_default();
+        self.command == other.command && args(self) == args(other) && self.url == other.url
+    }
+
+    /// Take the connection details from a fresh import of this server while
+    /// keeping its id, name and everything configured in Agent Hub.
+    pub fn apply_import(&mut self, imported: ServerConfig) {
+        self.transport = imported.transport;
+        self.command = imported.command;
+        self.args = imported.args;
+        self.env = imported.env;
+        self.url = imported.url;
+        self.headers = imported.headers;
+    }
+
+    /// Whether the proxy may list and route `tool_name` for this server.
+    pub fn exposes_tool(&self, tool_name: &str) -> bool {
+        let allowed = self
+            .exposed_tools
+            .as_ref()
+            .is_none_or(|tools| tools.iter().any(|t| t == tool_name));
+        let blocked = self
+            .blocked_tools
+            .as_ref()
+            .is_some_and(|tools| tools.iter().any(|t| t == tool_name));
+        allowed && !blocked
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ServerTransport {
+    #[default]
     Stdio,
     Http,
 }
 
+/// How JSON-RPC request ids are encoded when talking to a backend.
+/// Most servers accept either, but a few only correlate one kind.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonRpcIdFormat {
+    #[default]
+    Numeric,
+    String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ServerStatus {
@@ -50,7 +238,8 @@ pub enum ServerStatus {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ServerConfigInput {
     pub name: String,
     pub enabled: bool,
@@ -58,9 +247,41 @@ pub struct ServerConfigInput {
     pub command: Option<String>,
     pub args: Option<Vec<String>>,
     pub env: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub env_file: Option<String>,
     pub url: Option<String>,
     pub headers: Option<HashMap<String, String>>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub id_format: Option<JsonRpcIdFormat>,
+    #[serde(default)]
+    pub allow_insecure_tls: bool,
+    #[serde(default)]
+    pub max_concurrent_calls: Option<u32>,
+    #[serde(default)]
+    pub max_argument_bytes: Option<u64>,
+    #[serde(default)]
+    pub max_frame_bytes: Option<u64>,
+    #[serde(default)]
+    pub output_encoding: Option<String>,
+    #[serde(default)]
+    pub validate_arguments: bool,
+    #[serde(default)]
+    pub exposed_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub blocked_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub tool_overrides: Option<HashMap<String, ToolOverride>>,
+    #[serde(default)]
+    pub exclude_from_discovery: bool,
+    #[serde(default)]
+    pub discovery_excluded_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub roots: Option<Vec<String>>,
+    #[serde(default)]
+    pub enable_sampling: bool,
+    #[serde(default)]
+    pub prewarm: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -73,3 +294,104 @@ pub struct McpTool {
     pub server_id: String,
     pub server_name: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_tools_win_over_exposed_tools() {
+        let server = ServerConfig {
+            exposed_tools: Some(vec!["read".into(), "delete".into()]),
+            blocked_tools: Some(vec!["delete".into()]),
+            ..Default::default()
+        };
+        assert!(server.exposes_tool("read"));
+        assert!(!server.exposes_tool("delete"));
+        assert!(!server.exposes_tool("write"));
+    }
+
+    #[test]
+    fn discovery_exclusion_keeps_tool_exposed() {
+        let server = ServerConfig {
+            discovery_excluded_tools: Some(vec!["drop_table".into()]),
+            ..Default::default()
+        };
+        assert!(server.exposes_tool("drop_table"));
+        assert!(!server.discoverable_tool("drop_table"));
+        assert!(server.discoverable_tool("select"));
+    }
+
+    #[test]
+    fn matches_renamed_servers_by_endpoint() {
+        let existing = ServerConfig {
+            name: "files (renamed)".into(),
+            command: Some("npx".into()),
+            args: Some(vec!["server-filesystem".into()]),
+            tags: Some(vec!["local".into()]),
+            ..Default::default()
+        };
+        let imported = ServerConfig {
+            name: "files".into(),
+            command: Some("npx".into()),
+            args: Some(vec!["server-filesystem".into()]),
+            env: Some(HashMap::from([("ROOT".to_string(), "/tmp".to_string())])),
+            ..Default::default()
+        };
+        assert!(existing.same_endpoint(&imported));
+        assert!(!ServerConfig::default().same_endpoint(&ServerConfig::default()));
+
+        let mut merged = existing.clone();
+        merged.apply_import(imported);
+        assert_eq!(merged.name, "files (renamed)");
+        assert_eq!(merged.tags, existing.tags);
+        assert!(merged.env.is_some());
+    }
+
+    #[test]
+    fn tool_overrides_round_trip_to_real_name() {
+        let server = ServerConfig {
+            tool_overrides: Some(HashMap::from([(
+                "fs_rd".to_string(),
+                ToolOverride {
+                    display_name: Some("read_file".into()),
+                    description: None,
+                },
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(server.advertised_tool_name("fs_rd"), "read_file");
+        assert_eq!(server.resolve_tool_name("read_file"), "fs_rd");
+        assert_eq!(server.resolve_tool_name("other"), "other");
+        assert_eq!(server.advertised_tool_description("fs_rd"), None);
+    }
+
+    fn renamed(display_name: &str) -> ToolOverride {
+        ToolOverride {
+            display_name: Some(display_name.into()),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn detects_colliding_display_names() {
+        let swapped = HashMap::from([
+            ("a".to_string(), renamed("b")),
+            ("b".to_string(), renamed("a")),
+        ]);
+        assert_eq!(duplicate_advertised_name(&swapped, ["a", "b", "c"]), None);
+
+        let twice = HashMap::from([
+            ("a".to_string(), renamed("x")),
+            ("b".to_string(), renamed("x")),
+        ]);
+        assert_eq!(duplicate_advertised_name(&twice, []), Some("x"));
+
+        let shadows_real = HashMap::from([("a".to_string(), renamed("c"))]);
+        assert_eq!(duplicate_advertised_name(&shadows_real, []), None);
+        assert_eq!(
+            duplicate_advertised_name(&shadows_real, ["a", "c"]),
+            Some("c")
+        );
+    }
+}
@@ -1,4 +1,15 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::persistence;
+
+/// Upper bound on the number of supporting files captured per skill.
+pub const MAX_SKILL_ASSETS: usize = 200;
+/// Upper bound on the combined size of a skill's supporting files.
+pub const MAX_SKILL_ASSET_BYTES: u64 = 10 * 1024 * 1024;
 
 /// An installed skill, persisted in the store and synced to AI tool directories.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,4 +37,179 @@ pub struct InstalledSkill {
     /// Which feature manages this skill (e.g. "memory", "discovery"). Managed skills cannot be uninstalled directly.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub managed_by: Option<String>,
+    /// SHA-256 of SKILL.md as fetched at install or last update, used to detect upstream changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Supporting files shipped alongside SKILL.md (scripts, references, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<SkillAsset>,
+}
+
+/// A file in a skill directory other than the top-level SKILL.md. Only the
+/// path and hash are persisted; the bytes live in the [`SkillAssetStore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillAsset {
+    /// Path relative to the skill directory, `/`-separated, e.g. "scripts/run.sh"
+    pub path: String,
+    /// SHA-256 of the file contents, which names its blob in the asset store
+    pub sha256: String,
+}
+
+impl SkillAsset {
+    /// An asset path must be relative, `/`-separated, and stay inside the
+    /// skill directory. The top-level SKILL.md is tracked separately.
+    pub fn is_safe_path(path: &str) -> bool {
+        !path.is_empty()
+            && path != "SKILL.md"
+            && !path.starts_with('/')
+            && !path.contains('\\')
+            && path
+                .split('/')
+                .all(|c| !c.is_empty() && c != "." && c != ".." && !c.contains(':'))
+    }
+}
+
+/// Content-addressed storage for skill asset bytes, one file per SHA-256
+/// under the app data directory, so the JSON store only carries each asset's
+/// path and hash.
+#[derive(Debug, Clone)]
+pub struct SkillAssetStore {
+    dir: Option<PathBuf>,
+}
+
+impl SkillAssetStore {
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            dir: Some(app_data_dir.join("skill-assets")),
+        }
+    }
+
+    /// A store for when the app data directory can't be resolved: writes
+    /// fail, so skills install without their assets.
+    pub fn unavailable() -> Self {
+        Self { dir: None }
+    }
+
+    fn dir(&self) -> io::Result<&Path> {
+        self.dir.as_deref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "skill asset store is unavailable")
+        })
+    }
+
+    /// Store `bytes` as the asset at `path`.
+    pub fn put(&self, path: String, bytes: &[u8]) -> io::Result<SkillAsset> {
+        let sha256 = format!("{:x}", Sha256::digest(bytes));
+        let blob = self.dir()?.join(&sha256);
+        if !blob.exists() {
+            persistence::write_atomic(&blob, bytes)?;
+        }
+        Ok(SkillAsset { path, sha256 })
+    }
+
+    /// The contents of `asset`.
+    pub fn read(&self, asset: &SkillAsset) -> io::Result<Vec<u8>> {
+        // The hash names a file, so anything else could point outside the store
+        if asset.sha256.len() != 64 || !asset.sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid hash for skill asset {}", asset.path),
+            ));
+        }
+        std::fs::read(self.dir()?.join(&asset.sha256))
+    }
+
+    /// Delete blobs that none of `skills` refer to. Returns how many were removed.
+    pub fn prune(&self, skills: &[InstalledSkill]) -> io::Result<usize> {
+        let dir = self.dir()?;
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let referenced = skills
+                .iter()
+                .flat_map(|sk| &sk.assets)
+                .any(|a| name == a.sha256.as_str());
+            if !referenced && std::fs::remove_file(entry.path()).is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_asset_paths_outside_skill_dir() {
+        assert!(SkillAsset::is_safe_path("scripts/run.sh"));
+        assert!(SkillAsset::is_safe_path("references/SKILL.md"));
+        assert!(SkillAsset::is_safe_path("notes..md"));
+        assert!(!SkillAsset::is_safe_path(""));
+        assert!(!SkillAsset::is_safe_path("SKILL.md"));
+        assert!(!SkillAsset::is_safe_path(".."));
+        assert!(!SkillAsset::is_safe_path("../escape"));
+        assert!(!SkillAsset::is_safe_path("scripts/../../escape"));
+        assert!(!SkillAsset::is_safe_path("./run.sh"));
+        assert!(!SkillAsset::is_safe_path("/etc/passwd"));
+        assert!(!SkillAsset::is_safe_path("C:/x"));
+        assert!(!SkillAsset::is_safe_path("a//b"));
+        assert!(!SkillAsset::is_safe_path("a\\b"));
+        assert!(!SkillAsset::is_safe_path("..\\escape"));
+        assert!(!SkillAsset::is_safe_path("\\\\server\\share"));
+    }
+
+    fn skill_with(assets: Vec<SkillAsset>) -> InstalledSkill {
+        InstalledSkill {
+            id: "owner/repo/x".into(),
+            name: "x".into(),
+            skill_id: "x".into(),
+            source: "owner/repo".into(),
+            description: String::new(),
+            content: String::new(),
+            enabled: true,
+            installs: None,
+            managed: None,
+            managed_by: None,
+            content_hash: None,
+            assets,
+        }
+    }
+
+    #[test]
+    fn stores_asset_bytes_by_hash() {
+        let dir = std::env::temp_dir().join(format!("agent-hub-assets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SkillAssetStore::new(&dir);
+
+        let kept = store.put("run.sh".into(), b"echo hi").unwrap();
+        let dropped = store.put("old.sh".into(), b"echo bye").unwrap();
+        assert_eq!(store.read(&kept).unwrap(), b"echo hi");
+
+        assert_eq!(store.prune(&[skill_with(vec![kept.clone()])]).unwrap(), 1);
+        assert_eq!(store.read(&kept).unwrap(), b"echo hi");
+        assert!(store.read(&dropped).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_bad_hashes() {
+        let store = SkillAssetStore::unavailable();
+        let forged = SkillAsset {
+            path: "run.sh".into(),
+            sha256: "../../config.json".into(),
+        };
+        assert_eq!(
+            store.read(&forged).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(store.put("run.sh".into(), b"x").is_err());
+    }
 }
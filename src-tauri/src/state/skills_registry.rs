@@ -1,6 +1,8 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use super::providers;
+use super::skill::{SkillAsset, SkillAssetStore, MAX_SKILL_ASSETS, MAX_SKILL_ASSET_BYTES};
 
 // ---------------------------------------------------------------------------
 // Frontend-facing types (returned to Vue via serde)
@@ -15,6 +17,8 @@ pub struct MarketplaceSkillSummary {
     pub skill_id: String,
     pub installs: u64,
     pub installed: bool,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -27,6 +31,10 @@ pub struct MarketplaceSkillDetail {
     pub installs: u64,
     pub description: String,
     pub content: String,
+    /// From SKILL.md frontmatter.
+    pub category: Option<String>,
+    /// From SKILL.md frontmatter.
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -60,20 +68,31 @@ impl SkillsMarketplaceCache {
     /// is in `installed_ids` (installed via the marketplace) **or** its `skill_id`
     /// matches a directory found on disk in any tool's skills directory
     /// (`local_skill_ids`).
+    ///
+    /// With a `category`, only skills whose category or tags match it
+    /// (case-insensitively) are returned, and an empty query browses by the
+    /// category name instead of the generic default term.
     pub async fn search(
         &self,
         query: &str,
+        category: Option<&str>,
         limit: u32,
         installed_ids: &[String],
         local_skill_ids: &[String],
     ) -> SkillsSearchResult {
+        let category = category.map(str::trim).filter(|c| !c.is_empty());
+        let query = match category {
+            Some(category) if query.trim().len() < 2 => category,
+            _ => query,
+        };
         let resp = providers::skillssh::search_skills(&self.http, query, limit).await;
 
         match resp {
-            Some(data) => SkillsSearchResult {
-                skills: data
+            Some(data) => {
+                let skills: Vec<MarketplaceSkillSummary> = data
                     .skills
                     .into_iter()
+                    .filter(|entry| category.is_none_or(|c| matches_category(entry, c)))
                     .map(|entry| MarketplaceSkillSummary {
                         installed: installed_ids.contains(&entry.id)
                             || local_skill_ids.contains(&entry.skill_id),
@@ -82,10 +101,18 @@ impl SkillsMarketplaceCache {
                         source: entry.source,
                         skill_id: entry.skill_id,
                         installs: entry.installs,
+                        category: entry.category,
+                        tags: entry.tags,
                     })
-                    .collect(),
-                count: data.count,
-            },
+                    .collect();
+                // The API's count is for the unfiltered query
+                let count = if category.is_some() {
+                    skills.len() as u64
+                } else {
+                    data.count
+                };
+                SkillsSearchResult { skills, count }
+            }
             None => SkillsSearchResult {
                 skills: vec![],
                 count: 0,
@@ -115,6 +142,102 @@ impl SkillsMarketplaceCache {
         self.try_fetch(&url2).await
     }
 
+    /// Fetch the files next to SKILL.md through the GitHub contents API into
+    /// `store`, trying the same two locations as [`Self::fetch_skill_content`].
+    /// Returns `None` when neither directory can be listed.
+    pub async fn fetch_skill_assets(
+        &self,
+        source: &str,
+        skill_id: &str,
+        store: &SkillAssetStore,
+    ) -> Option<Vec<SkillAsset>> {
+        for dir in [skill_id.to_string(), format!("skills/{skill_id}")] {
+            let Some(entries) = self.list_contents(source, &dir).await else {
+                continue;
+            };
+            if !entries
+                .iter()
+                .any(|e| e.kind == "file" && e.name == "SKILL.md")
+            {
+                continue;
+            }
+            return Some(self.collect_assets(source, &dir, entries, store).await);
+        }
+        None
+    }
+
+    /// Walk a skill directory and download every file except
+    /// the top-level SKILL.md, within the asset count and size limits.
+    async fn collect_assets(
+        &self,
+        source: &str,
+        root: &str,
+        entries: Vec<GithubContentEntry>,
+        store: &SkillAssetStore,
+    ) -> Vec<SkillAsset> {
+        let mut assets = Vec::new();
+        let mut total = 0u64;
+        let mut pending = vec![(String::new(), entries)];
+
+        while let Some((prefix, entries)) = pending.pop() {
+            for entry in entries {
+                let rel = format!("{prefix}{}", entry.name);
+                if entry.name.starts_with('.') {
+                    continue;
+                }
+                if entry.kind == "dir" {
+                    if let Some(children) =
+                        self.list_contents(source, &format!("{root}/{rel}")).await
+                    {
+                        pending.push((format!("{rel}/"), children));
+                    }
+                    continue;
+                }
+                if entry.kind != "file" || !SkillAsset::is_safe_path(&rel) {
+                    continue;
+                }
+                if assets.len() >= MAX_SKILL_ASSETS || total + entry.size > MAX_SKILL_ASSET_BYTES {
+                    warn!(
+                        "Skill {source}/{root} exceeds asset limits; ignoring {rel} and the rest"
+                    );
+                    return assets;
+                }
+                let Some(url) = entry.download_url else {
+                    continue;
+                };
+                match self.try_fetch_bytes(&url).await {
+                    Some(bytes) => match store.put(rel, &bytes) {
+                        Ok(asset) => {
+                            total += entry.size;
+                            assets.push(asset);
+                        }
+                        Err(e) => warn!("Failed to store skill asset {url}: {e}"),
+                    },
+                    None => warn!("Failed to download skill asset {url}"),
+                }
+            }
+        }
+
+        assets
+    }
+
+    async fn list_contents(&self, source: &str, path: &str) -> Option<Vec<GithubContentEntry>> {
+        let url = format!("https://api.github.com/repos/{source}/contents/{path}");
+        let resp = self.http.get(&url).send().await.ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        resp.json().await.ok()
+    }
+
+    async fn try_fetch_bytes(&self, url: &str) -> Option<Vec<u8>> {
+        let resp = self.http.get(url).send().await.ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        resp.bytes().await.ok().map(|b| b.to_vec())
+    }
+
     async fn try_fetch(&self, url: &str) -> Option<String> {
         let resp = self.http.get(url).send().await.ok()?;
         if !resp.status().is_success() {
@@ -123,3 +246,22 @@ impl SkillsMarketplaceCache {
         resp.text().await.ok()
     }
 }
+
+/// An entry from `GET /repos/{owner}/{repo}/contents/{path}`.
+#[derive(Debug, Deserialize)]
+struct GithubContentEntry {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    size: u64,
+    download_url: Option<String>,
+}
+
+fn matches_category(entry: &providers::skillssh::SkillsshEntry, category: &str) -> bool {
+    entry
+        .category
+        .iter()
+        .chain(entry.tags.iter())
+        .any(|t| t.eq_ignore_ascii_case(category))
+}
@@ -48,6 +48,41 @@ impl ServerStats {
     }
 }
 
+/// Call counters plus latency percentiles over the recent-call window.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallMetrics {
+    pub total_calls: u64,
+    pub successes: u64,
+    pub errors: u64,
+    /// Median latency of the recent calls, if any were recorded.
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+}
+
+impl CallMetrics {
+    pub fn from_calls(total_calls: u64, errors: u64, recent: &[&ToolCallEntry]) -> Self {
+        let mut durations: Vec<u64> = recent.iter().map(|c| c.duration_ms).collect();
+        durations.sort_unstable();
+        Self {
+            total_calls,
+            successes: total_calls.saturating_sub(errors),
+            errors,
+            p50_ms: percentile(&durations, 50),
+            p95_ms: percentile(&durations, 95),
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending-sorted slice.
+pub fn percentile(sorted: &[u64], pct: u32) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (sorted.len() * pct as usize).div_ceil(100).max(1);
+    Some(sorted[rank.min(sorted.len()) - 1])
+}
+
 pub fn unix_now() -> u64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -56,3 +91,22 @@ pub fn unix_now() -> u64 {
 }
 
 pub type StatsStore = Arc<RwLock<HashMap<String, ServerStats>>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_empty_is_none() {
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let durations: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&durations, 50), Some(50));
+        assert_eq!(percentile(&durations, 95), Some(95));
+        assert_eq!(percentile(&[7], 95), Some(7));
+        assert_eq!(percentile(&[1, 2, 3], 50), Some(2));
+    }
+}
@@ -1,8 +1,9 @@
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
 use tauri::tray::TrayIconBuilder;
-use tauri::{AppHandle, Emitter, Manager};
-use tracing::error;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use tracing::{error, warn};
 
+use crate::commands::connections::{connect_server, disconnect_server};
 use crate::state::{ServerStatus, SharedState};
 
 /// Set up the system tray icon and initial menu. Called once from `lib.rs` setup.
@@ -16,6 +17,14 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         .on_menu_event(handle_menu_event)
         .build(app)?;
 
+    // Keep status indicators current however the status changed. Rebuild off
+    // the emitting thread, which may still be holding the state lock.
+    let handle = app.handle().clone();
+    app.listen("server-status-changed", move |_| {
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move { rebuild_tray_menu(&handle) });
+    });
+
     Ok(())
 }
 
@@ -58,11 +67,28 @@ fn build_tray_menu(
                 Some(ServerStatus::Disconnected) | Some(ServerStatus::Error) | None => "○",
             };
 
-            let label = format!("{indicator}  {}", server.name);
-            let item = MenuItemBuilder::new(label)
+            let action = match server.status.as_ref() {
+                Some(ServerStatus::Connected) => {
+                    MenuItemBuilder::new("Disconnect").id(format!("disconnect:{}", server.id))
+                }
+                Some(ServerStatus::Connecting) => MenuItemBuilder::new("Connecting…")
+                    .id(format!("connecting:{}", server.id))
+                    .enabled(false),
+                Some(ServerStatus::Disconnected) | Some(ServerStatus::Error) | None => {
+                    MenuItemBuilder::new("Connect").id(format!("connect:{}", server.id))
+                }
+            }
+            .build(app)?;
+            let details = MenuItemBuilder::new("Show Details")
                 .id(format!("server:{}", server.id))
                 .build(app)?;
-            builder = builder.item(&item);
+
+            let label = format!("{indicator}  {}", server.name);
+            let submenu = SubmenuBuilder::new(app, label)
+                .item(&action)
+                .item(&details)
+                .build()?;
+            builder = builder.item(&submenu);
         }
     }
 
@@ -89,6 +115,36 @@ fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         "show" => {
             focus_main_window(app);
         }
+        _ if id.starts_with("connect:") => {
+            let server_id = id["connect:".len()..].to_string();
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                // Failures are already reported through `server-error` and the status
+                if let Err(e) = connect_server(
+                    app.clone(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    server_id.clone(),
+                )
+                .await
+                {
+                    warn!("Tray connect of {server_id} failed: {e}");
+                }
+            });
+        }
+        _ if id.starts_with("disconnect:") => {
+            let server_id = id["disconnect:".len()..].to_string();
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) =
+                    disconnect_server(app.clone(), app.state(), app.state(), server_id.clone())
+                        .await
+                {
+                    warn!("Tray disconnect of {server_id} failed: {e}");
+                }
+            });
+        }
         _ if id.starts_with("server:") => {
             let server_id = &id["server:".len()..];
             focus_main_window(app);
@@ -6,17 +6,20 @@ import type { OAuthStatus } from '@/types/oauth';
 import { useToolsStore } from '@/stores/tools';
 import type { ServerStatus } from '@/types/server';
 import type { McpTool } from '@/types/mcp';
+import type { AppErrorCode } from '@/types/error';
 
 interface ServerStatusPayload {
   serverId: string;
   status: ServerStatus;
   error?: string;
+  errorCode?: AppErrorCode;
   lastConnected?: string;
 }
 
 interface ServerErrorPayload {
   serverId: string;
   error: string;
+  errorCode?: AppErrorCode;
 }
 
 interface ToolsUpdatedPayload {
@@ -49,14 +52,14 @@ export function useEvents() {
       await listen<ServerStatusPayload>('server-status-changed', (event) => {
         serversStore.updateServerStatus(event.payload.serverId, event.payload.status);
         if (event.payload.status === 'error' && event.payload.error) {
-          serversStore.setError(event.payload.serverId, event.payload.error);
+          serversStore.setError(event.payload.serverId, event.payload.error, event.payload.errorCode);
         }
       })
     );
 
     unlisteners.push(
       await listen<ServerErrorPayload>('server-error', (event) => {
-        serversStore.setError(event.payload.serverId, event.payload.error);
+        serversStore.setError(event.payload.serverId, event.payload.error, event.payload.errorCode);
       })
     );
 
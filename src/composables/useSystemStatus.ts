@@ -1,6 +1,7 @@
 import { ref, onMounted, onUnmounted } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import type { SystemStatusResponse, HealthCheckEntry } from '@/types/status';
+import { errorMessage } from '@/types/error';
 
 export function useSystemStatus() {
   const status = ref<SystemStatusResponse | null>(null);
@@ -27,7 +28,7 @@ export function useSystemStatus() {
         history.value = history.value.slice(-60);
       }
     } catch (e) {
-      error.value = String(e);
+      error.value = errorMessage(e);
     } finally {
       loading.value = false;
     }
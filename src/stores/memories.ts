@@ -2,6 +2,7 @@ import { defineStore } from 'pinia';
 import { ref } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import type { MemoryItem, MemorySearchResult, SearchFilters } from '@/types/memory';
+import { errorMessage } from '@/types/error';
 
 const PAGE_SIZE = 50;
 
@@ -55,7 +56,7 @@ export const useMemoriesStore = defineStore('memories', () => {
       hasMore.value = result.memories.length === PAGE_SIZE;
       offset.value = items.value.length;
     } catch (e) {
-      error.value = String(e);
+      error.value = errorMessage(e);
     } finally {
       loading.value = false;
     }
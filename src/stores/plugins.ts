@@ -2,6 +2,7 @@ import { defineStore } from 'pinia';
 import { ref } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import type { PluginInfo } from '@/types/plugin';
+import { errorMessage } from '@/types/error';
 
 export const usePluginsStore = defineStore('plugins', () => {
   // --- Installed plugins ---
@@ -60,7 +61,7 @@ export const usePluginsStore = defineStore('plugins', () => {
       const search = query.trim() || undefined;
       marketplacePlugins.value = await invoke<PluginInfo[]>('list_available_plugins', { search });
     } catch (e) {
-      marketplaceError.value = String(e);
+      marketplaceError.value = errorMessage(e);
       console.error('Failed to search plugins marketplace:', e);
     } finally {
       marketplaceLoading.value = false;
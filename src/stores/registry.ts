@@ -8,6 +8,7 @@ import type {
   RuntimeDeps,
 } from '@/types/registry';
 import type { ServerConfig } from '@/types/server';
+import { errorMessage } from '@/types/error';
 
 export const useRegistryStore = defineStore('registry', () => {
   const servers = ref<RegistryServerSummary[]>([]);
@@ -40,7 +41,7 @@ export const useRegistryStore = defineStore('registry', () => {
       }
       hasMore.value = result.hasMore;
     } catch (e) {
-      error.value = String(e);
+      error.value = errorMessage(e);
     } finally {
       loading.value = false;
     }
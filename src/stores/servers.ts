@@ -3,10 +3,12 @@ import { ref } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import type { ServerConfig, ServerConfigInput } from '@/types/server';
 import type { OAuthStatus } from '@/types/oauth';
+import { errorCode, errorMessage, type AppErrorCode } from '@/types/error';
 
 export const useServersStore = defineStore('servers', () => {
   const servers = ref<ServerConfig[]>([]);
   const lastError = ref<Record<string, string>>({});
+  const lastErrorCode = ref<Record<string, AppErrorCode>>({});
   const oauthStatus = ref<Record<string, OAuthStatus>>({});
 
   async function loadServers() {
@@ -50,6 +52,29 @@ export const useServersStore = defineStore('servers', () => {
     }
   }
 
+  async function duplicateServer(id: string): Promise<ServerConfig> {
+    try {
+      const copy = await invoke<ServerConfig>('duplicate_server', { id });
+      const idx = servers.value.findIndex(s => s.id === id);
+      servers.value.splice(idx + 1, 0, copy);
+      return copy;
+    } catch (e) {
+      console.error('Failed to duplicate server:', e);
+      throw e;
+    }
+  }
+
+  async function reorderServers(ids: string[]) {
+    try {
+      await invoke('reorder_servers', { ids });
+      const byId = new Map(servers.value.map(s => [s.id, s]));
+      servers.value = ids.map(id => byId.get(id)).filter((s): s is ServerConfig => !!s);
+    } catch (e) {
+      console.error('Failed to reorder servers:', e);
+      throw e;
+    }
+  }
+
   async function autoConnectServers() {
     for (const server of servers.value) {
       if (server.enabled && (!server.status || server.status === 'disconnected')) {
@@ -65,8 +90,7 @@ export const useServersStore = defineStore('servers', () => {
     try {
       await invoke('connect_server', { id });
     } catch (e) {
-      const errorMsg = String(e);
-      setError(id, errorMsg);
+      setError(id, errorMessage(e), errorCode(e));
       if (server) server.status = 'error';
     }
   }
@@ -79,17 +103,35 @@ export const useServersStore = defineStore('servers', () => {
     }
   }
 
+  async function restartServer(id: string) {
+    clearError(id);
+    const server = servers.value.find(s => s.id === id);
+    if (server) server.status = 'connecting';
+    try {
+      await invoke('restart_server', { id });
+    } catch (e) {
+      setError(id, errorMessage(e), errorCode(e));
+      if (server) server.status = 'error';
+    }
+  }
+
   function updateServerStatus(id: string, status: ServerConfig['status']) {
     const server = servers.value.find(s => s.id === id);
     if (server) server.status = status;
   }
 
-  function setError(serverId: string, error: string) {
+  function setError(serverId: string, error: string, code?: AppErrorCode) {
     lastError.value[serverId] = error;
+    if (code) {
+      lastErrorCode.value[serverId] = code;
+    } else {
+      delete lastErrorCode.value[serverId];
+    }
   }
 
   function clearError(serverId: string) {
     delete lastError.value[serverId];
+    delete lastErrorCode.value[serverId];
   }
 
   function setOAuthStatus(serverId: string, status: OAuthStatus) {
@@ -107,7 +149,7 @@ export const useServersStore = defineStore('servers', () => {
       await invoke('start_oauth_flow', { id });
     } catch (e) {
       oauthStatus.value[id] = 'error';
-      setError(id, String(e));
+      setError(id, errorMessage(e), errorCode(e));
     }
   }
 
@@ -123,14 +165,18 @@ export const useServersStore = defineStore('servers', () => {
   return {
     servers,
     lastError,
+    lastErrorCode,
     oauthStatus,
     loadServers,
     autoConnectServers,
     addServer,
     updateServer,
     removeServer,
+    duplicateServer,
+    reorderServers,
     connectServer,
     disconnectServer,
+    restartServer,
     updateServerStatus,
     setError,
     clearError,
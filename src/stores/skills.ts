@@ -8,6 +8,7 @@ import type {
   MarketplaceSkillSummary,
   MarketplaceSkillDetail,
 } from '@/types/skill';
+import { errorMessage } from '@/types/error';
 
 export const useSkillsStore = defineStore('skills', () => {
   // --- Installed skills ---
@@ -68,18 +69,19 @@ export const useSkillsStore = defineStore('skills', () => {
   const marketplaceError = ref<string | null>(null);
   const marketplaceCount = ref(0);
 
-  async function searchMarketplace(query: string) {
+  async function searchMarketplace(query: string, category?: string) {
     marketplaceLoading.value = true;
     marketplaceError.value = null;
     try {
       const result = await invoke<SkillsSearchResult>('search_skills_marketplace', {
         search: query,
+        category,
         limit: 30,
       });
       marketplaceSkills.value = result.skills;
       marketplaceCount.value = result.count;
     } catch (e) {
-      marketplaceError.value = String(e);
+      marketplaceError.value = errorMessage(e);
       console.error('Failed to search skills marketplace:', e);
     } finally {
       marketplaceLoading.value = false;
@@ -1,4 +1,17 @@
-export type EmbeddingProvider = 'ollama' | 'openai';
+export type EmbeddingProvider = 'ollama' | 'openai' | 'cohere' | { custom: { baseUrl: string } };
+
+/** Provider without its settings, for tabs and model lists. */
+export type EmbeddingProviderKind = 'ollama' | 'openai' | 'cohere' | 'custom';
+
+export function providerKind(p: EmbeddingProvider): EmbeddingProviderKind {
+  return typeof p === 'string' ? p : 'custom';
+}
+
+/** Structural equality; `custom` providers are objects, so `===` won't do. */
+export function sameProvider(a: EmbeddingProvider, b: EmbeddingProvider): boolean {
+  if (typeof a === 'string' || typeof b === 'string') return a === b;
+  return a.custom.baseUrl === b.custom.baseUrl;
+}
 
 export interface EmbeddingConfig {
   provider: EmbeddingProvider;
@@ -9,6 +22,7 @@ export interface EmbeddingConfig {
 export interface EmbeddingConfigStatus {
   config: EmbeddingConfig;
   hasOpenaiKey: boolean;
+  hasCohereKey: boolean;
   pulledOllamaModels: string[];
   redisConfig: RedisConfig;
 }
@@ -48,3 +62,22 @@ export const OPENAI_MODELS: EmbeddingModelInfo[] = [
   { model: 'text-embedding-3-large', displayName: 'text-embedding-3-large', dimensions: 3072,
     sizeLabel: '$0.13 / 1M tokens', quality: 'high' },
 ];
+
+export const COHERE_MODELS: EmbeddingModelInfo[] = [
+  { model: 'embed-english-v3.0', displayName: 'embed-english-v3.0', dimensions: 1024,
+    sizeLabel: '$0.10 / 1M tokens', quality: 'high', recommended: true },
+  { model: 'embed-multilingual-v3.0', displayName: 'embed-multilingual-v3.0', dimensions: 1024,
+    sizeLabel: '$0.10 / 1M tokens', quality: 'high' },
+  { model: 'embed-english-light-v3.0', displayName: 'embed-english-light-v3.0', dimensions: 384,
+    sizeLabel: '$0.10 / 1M tokens', quality: 'light' },
+];
+
+/** Suggested models for a provider; custom endpoints have none. */
+export function modelsFor(kind: EmbeddingProviderKind): EmbeddingModelInfo[] {
+  switch (kind) {
+    case 'ollama': return OLLAMA_MODELS;
+    case 'openai': return OPENAI_MODELS;
+    case 'cohere': return COHERE_MODELS;
+    case 'custom': return [];
+  }
+}
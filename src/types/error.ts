@@ -0,0 +1,38 @@
+/** Matches `AppError::code()` in the backend. */
+export type AppErrorCode =
+  | 'server_not_found'
+  | 'already_connected'
+  | 'connection_failed'
+  | 'protocol'
+  | 'transport'
+  | 'timeout'
+  | 'auth_required'
+  | 'oauth'
+  | 'integration_not_found'
+  | 'dependency_not_found'
+  | 'validation'
+  | 'marketplace_not_found'
+  | 'plugin_already_installed'
+  | 'io'
+  | 'json';
+
+/** What a failed `invoke` rejects with. */
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+}
+
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === 'object' && e !== null && 'code' in e && 'message' in e;
+}
+
+/** Display text for anything a command can reject with. */
+export function errorMessage(e: unknown): string {
+  if (isAppError(e)) return e.message;
+  if (e instanceof Error) return e.message;
+  return String(e);
+}
+
+export function errorCode(e: unknown): AppErrorCode | undefined {
+  return isAppError(e) ? e.code : undefined;
+}
@@ -1,3 +1,5 @@
+import type { ImportedServer } from './server';
+
 export interface ExistingMcpServer {
   name: string;
   transport: string;
@@ -14,4 +16,66 @@ export interface AiToolInfo {
   configPath: string;
   configuredPort: number;
   existingServers: ExistingMcpServer[];
+  /** Servers imported by `enable_integration`, with any config warnings. */
+  importedServers: ImportedServer[];
+  /** Entries written under a name used by older versions (e.g. `mcp-manager`). */
+  legacyKeys: string[];
+  /** Set when the config on disk disagrees with the persisted enabled state. */
+  drift: IntegrationDrift | null;
+}
+
+/**
+ * `entriesMissing`: enabled, but our entries were removed (offer `overwrite_integration_port`).
+ * `entriesPresent`: not enabled, but the config still has our entries.
+ */
+export type IntegrationDrift = 'entriesMissing' | 'entriesPresent';
+
+export interface IntegrationKeyMigration {
+  id: string;
+  configPath: string;
+  migratedKeys: string[];
+}
+
+export type IntegrationHealthStatus = 'healthy' | 'portMismatch' | 'missing' | 'parseError';
+
+export interface IntegrationHealth {
+  id: string;
+  status: IntegrationHealthStatus;
+  configPath: string;
+  configuredPort: number;
+  expectedPort: number;
+  error?: string;
+}
+
+/** An enabled integration whose config points at another running proxy. */
+export interface PortConflict {
+  id: string;
+  name: string;
+  configPath: string;
+  configuredPort: number;
+  currentPort: number;
+}
+
+export type PlannedImportAction = 'new' | 'update' | 'skip';
+
+/** A server `enable_integration` would import. */
+export interface PlannedImport {
+  name: string;
+  action: PlannedImportAction;
+  /** For `update`, the existing server that would change; for `skip`, why. */
+  detail: string | null;
+  warnings: string[];
+}
+
+/** From `preview_enable_integration`: what enabling would change, without writing anything. */
+export interface EnableIntegrationPreview {
+  id: string;
+  name: string;
+  configPath: string;
+  strategy: 'file' | 'cli';
+  /** `null` if the file doesn't exist, or for the CLI strategy. */
+  currentConfig: string | null;
+  /** The whole file after enabling, or for the CLI strategy the entries registered. */
+  proposedConfig: string;
+  imports: PlannedImport[];
 }
@@ -19,6 +19,20 @@ export interface McpResource {
 export interface McpToolCallResult {
   content: McpContent[];
   isError?: boolean;
+  /** Schema violations from `call_tool` with `validate`; the call wasn't sent. */
+  validationErrors?: string[];
+}
+
+export interface BatchToolCall {
+  serverId: string;
+  toolName: string;
+  arguments?: Record<string, unknown>;
+}
+
+/** One result of `call_tools_batch`, in request order. */
+export interface BatchToolCallResult {
+  result: McpToolCallResult | null;
+  error: string | null;
 }
 
 export interface McpContent {
@@ -34,3 +48,35 @@ export interface McpServerCapabilities {
   prompts?: { listChanged?: boolean };
   logging?: Record<string, unknown>;
 }
+
+/** Payload of the `sampling-request` event; answer with `respond_to_sampling`. */
+export interface SamplingRequest {
+  requestId: string;
+  serverId: string;
+  params: Record<string, unknown> | null;
+}
+
+export interface JsonRpcError {
+  code: number;
+  message: string;
+  data?: unknown;
+}
+
+/** Untouched response from `send_raw_request` (debug builds only). */
+export interface JsonRpcResponse {
+  jsonrpc: string;
+  id: string | number | null;
+  result?: unknown;
+  error?: JsonRpcError;
+}
+
+/** One JSON-RPC message captured by `get_server_trace`. */
+export interface TraceEntry {
+  direction: "sent" | "received";
+  method: string | null;
+  id: string | number | null;
+  /** Milliseconds since the Unix epoch. */
+  timestamp: number;
+  payload: string;
+  truncated: boolean;
+}
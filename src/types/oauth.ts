@@ -1 +1 @@
-export type OAuthStatus = 'idle' | 'discovering' | 'awaiting_browser' | 'exchanging_code' | 'authorized' | 'error';
+export type OAuthStatus = 'idle' | 'discovering' | 'registering' | 'awaiting_browser' | 'exchanging_code' | 'authorized' | 'error';
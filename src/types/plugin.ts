@@ -19,3 +19,19 @@ export interface PluginInfo {
   /** What this plugin includes — grouped by category with individual item names */
   components: PluginComponent[];
 }
+
+/** Payload of the `plugin-install-progress` event. */
+export interface PluginProgress {
+  /** `plugin@marketplace` key, or the marketplace name for updates. */
+  operationId: string;
+  stream: 'stdout' | 'stderr';
+  line: string;
+}
+
+export interface MarketplaceInfo {
+  name: string;
+  /** A URL, `owner/repo`, or local path. */
+  source: string | null;
+  installLocation: string | null;
+  lastUpdated: string | null;
+}
@@ -1,6 +1,14 @@
 export interface ProxyStatus {
   running: boolean;
   port: number;
+  error?: string | null;
+}
+
+/** From `get_proxy_endpoint`; servers live at `{url}/{serverId}`, discovery at `{url}/discovery`. */
+export interface ProxyEndpoint {
+  running: boolean;
+  port: number;
+  url: string | null;
 }
 
 export interface ManagedConfigPreview {
@@ -10,3 +18,47 @@ export interface ManagedConfigPreview {
   content: string;
   strategy: string;
 }
+
+export interface AuditConfig {
+  enabled: boolean;
+  maxBytes: number;
+  redact: boolean;
+}
+
+export interface ProxyIdentity {
+  name?: string;
+  version?: string;
+}
+
+/** Persisted proxy settings, as accepted by `set_proxy_config`. */
+export interface ProxySettings {
+  allowedOrigins: string[];
+  preferredPort?: number;
+  /** Explain in the `initialize` instructions when an endpoint has no tools. */
+  emptyToolsNotice: boolean;
+  /** Default cap on tool-call argument size in bytes; servers may override. */
+  maxArgumentBytes?: number;
+  audit: AuditConfig;
+  identity: ProxyIdentity;
+  /** Rewrite enabled integration configs on launch and on server connect/disconnect. */
+  autoUpdateIntegrationPorts: boolean;
+}
+
+/** Proxy settings plus runtime status, from `get_proxy_config`. */
+export interface ProxyConfig extends ProxySettings {
+  running: boolean;
+  port: number;
+  error?: string | null;
+}
+
+/** A server tool tried as part of a fallback group. `tool` is the advertised name. */
+export interface FallbackCandidate {
+  serverId: string;
+  tool: string;
+}
+
+/** Logical tool routed to the first connected candidate whose call succeeds. */
+export interface FallbackGroup {
+  alias: string;
+  candidates: FallbackCandidate[];
+}
@@ -1,6 +1,20 @@
 export type ServerTransport = 'stdio' | 'http';
 export type ServerStatus = 'connected' | 'connecting' | 'disconnected' | 'error';
 
+/** Handshake step reported by the `server-connect-phase` event while connecting. */
+export type ConnectPhase = 'prewarming' | 'initializing' | 'discovering-tools';
+
+export interface ConnectPhasePayload {
+  serverId: string;
+  phase: ConnectPhase;
+}
+export type JsonRpcIdFormat = 'numeric' | 'string';
+
+export interface ToolOverride {
+  displayName?: string;
+  description?: string;
+}
+
 export interface ServerConfig {
   id: string;
   name: string;
@@ -10,16 +24,149 @@ export interface ServerConfig {
   command?: string;
   args?: string[];
   env?: Record<string, string>;
+  /** Absolute path of a dotenv file layered under `env` at connect time. */
+  envFile?: string;
   // http
   url?: string;
+  /** Values may use `${VAR}` (expanded at connect) and `${oauth.access_token}` (per request). */
   headers?: Record<string, string>;
   // metadata
   tags?: string[];
   status?: ServerStatus;
   lastConnected?: string;
+  lastError?: string;
+  /** Unix seconds when `lastError` was recorded. */
+  lastErrorAt?: number;
   managed?: boolean;
   managedBy?: string;
   registryName?: string;
+  idFormat?: JsonRpcIdFormat;
+  allowInsecureTls?: boolean;
+  maxConcurrentCalls?: number;
+  maxArgumentBytes?: number;
+  maxFrameBytes?: number;
+  /** WHATWG encoding label for stderr, e.g. "windows-1252". Defaults to UTF-8. */
+  outputEncoding?: string;
+  validateArguments?: boolean;
+  exposedTools?: string[];
+  blockedTools?: string[];
+  toolOverrides?: Record<string, ToolOverride>;
+  excludeFromDiscovery?: boolean;
+  discoveryExcludedTools?: string[];
+  roots?: string[];
+  enableSampling?: boolean;
+  /** Fetch an npx/uvx package before connecting so first-run downloads don't time out. */
+  prewarm?: boolean;
+}
+
+export type ServerConfigInput = Omit<ServerConfig, 'id' | 'status' | 'lastConnected' | 'lastError' | 'lastErrorAt'>;
+
+/** A stored server entry that couldn't be read in full at startup. */
+export interface ServerLoadError {
+  index: number;
+  name: string | null;
+  error: string;
+  /** True if the server still loaded, minus the unreadable fields. */
+  recovered: boolean;
+}
+
+/** Which optional MCP features a connected server declared during `initialize`. */
+export interface CapabilityFlags {
+  tools: boolean;
+  toolsListChanged: boolean;
+  resources: boolean;
+  resourcesSubscribe: boolean;
+  resourcesListChanged: boolean;
+  prompts: boolean;
+  promptsListChanged: boolean;
+}
+
+/** Entry returned by `list_servers`; connection fields are null unless connected. */
+export interface ServerSummary extends ServerConfig {
+  toolCount: number | null;
+  serverName: string | null;
+  serverVersion: string | null;
+  capabilities: CapabilityFlags | null;
+}
+
+export interface ServerGroup {
+  count: number;
+  toolCount: number;
+  servers: ServerConfig[];
+}
+
+export interface GroupedServers {
+  connected: ServerGroup;
+  connecting: ServerGroup;
+  disconnected: ServerGroup;
+  error: ServerGroup;
+}
+
+export type TagMode = 'add' | 'remove' | 'replace';
+
+export interface ServerStatusSnapshot {
+  serverId: string;
+  status: ServerStatus;
+  lastError?: string;
+  lastErrorAt?: number;
 }
 
-export type ServerConfigInput = Omit<ServerConfig, 'id' | 'status' | 'lastConnected'>;
+export interface ReconcileSummary {
+  skillsInstalled: string[];
+  statusesReset: string[];
+  orphanedClientsRemoved: string[];
+  staleConnectionsCleared: string[];
+}
+
+/** Milliseconds spent in each phase of one connect. */
+export interface ConnectPhaseTimings {
+  spawnMs: number;
+  initializeMs: number;
+  discoveryMs: number;
+  totalMs: number;
+  toolCount: number;
+}
+
+/** Two back-to-back throwaway connects: cold caches first, then warm. */
+export interface ConnectionTiming {
+  serverId: string;
+  cold: ConnectPhaseTimings;
+  warm: ConnectPhaseTimings;
+}
+
+/** Result of `test_server_config`: a throwaway connect to an unsaved config. */
+export interface ServerConfigTest {
+  ok: boolean;
+  serverInfo: { name: string; version: string } | null;
+  toolCount: number;
+  error: string | null;
+}
+
+/** Env vars applied to every stdio server; per-server `env` takes precedence. */
+export type DefaultEnv = Record<string, string>;
+
+/** A server added by an import, plus non-fatal problems found in its config. */
+export interface ImportedServer extends ServerConfig {
+  warnings: string[];
+}
+
+export interface SkippedImport {
+  name: string;
+  reason: string;
+}
+
+/** Result of `import_servers` and `import_servers_from_json`. */
+export interface ServerImportReport {
+  imported: ImportedServer[];
+  skipped: SkippedImport[];
+}
+
+export type CheckStatus = 'pass' | 'fail' | 'skip';
+
+/** One step of `diagnose_server`, with guidance in `detail` when it fails. */
+export interface DiagnosticCheck {
+  id: string;
+  label: string;
+  status: CheckStatus;
+  detail: string;
+}
@@ -11,6 +11,8 @@ export interface MarketplaceSkillSummary {
   skillId: string;
   installs: number;
   installed: boolean;
+  category?: string;
+  tags: string[];
 }
 
 export interface MarketplaceSkillDetail {
@@ -21,6 +23,8 @@ export interface MarketplaceSkillDetail {
   installs: number;
   description: string;
   content: string;
+  category?: string;
+  tags: string[];
 }
 
 // Installed skill (persisted state — content excluded for list payloads)
@@ -34,6 +38,31 @@ export interface InstalledSkill {
   installs?: number;
   managed?: boolean;
   managedBy?: string;
+  // Supporting files shipped next to SKILL.md, relative to the skill directory
+  assets: string[];
+}
+
+// Installed marketplace skill with newer upstream content
+export interface SkillUpdateInfo {
+  id: string;
+  name: string;
+  source: string;
+  skillId: string;
+  installedHash: string;
+  latestHash: string;
+}
+
+// Lint result from `validate_skill`
+export type SkillLintKind =
+  | 'missingFrontmatter'
+  | 'invalidYaml'
+  | 'missingName'
+  | 'emptyDescription'
+  | 'shortBody';
+
+export interface SkillLintWarning {
+  kind: SkillLintKind;
+  message: string;
 }
 
 // Skill content response (for detail view)
@@ -58,4 +87,45 @@ export interface SkillToolInfo {
   enabled: boolean;
   skillsPath: string;
   existingSkills: ExistingSkillInfo[];
+  conflicts: SkillContentConflict[];
+  collisions: SkillIdCollision[];
+}
+
+// Untracked skill_id present in several tools with differing content
+export interface SkillIdCollision {
+  skillId: string;
+  copies: SkillCopy[];
 }
+
+export interface SkillCopy {
+  toolId: string;
+  path: string;
+  sha256: string;
+}
+
+export type SkillConflict = 'skip' | 'replace';
+
+export interface SkillsBundleImport {
+  installed: string[];
+  replaced: string[];
+  skipped: string[];
+}
+
+export interface SkillContentConflict {
+  skillId: string;
+  installedId: string;
+  path: string;
+  trackedSha256: string;
+  diskSha256: string;
+}
+
+// Result of `update_skill`; copies edited on disk keep their local version
+export interface SkillUpdateResult {
+  skill: InstalledSkill;
+  conflicts: SkillContentConflict[];
+}
+
+export type SkillConflictResolution =
+  | { action: 'keepTheirs' }
+  | { action: 'overwrite' }
+  | { action: 'rename'; newSkillId: string };
@@ -21,3 +21,34 @@ export interface ServerStats {
   clients: Record<string, number>;
   recentCalls: ToolCallEntry[];
 }
+
+export interface CallMetrics {
+  totalCalls: number;
+  successes: number;
+  errors: number;
+  p50Ms: number | null;
+  p95Ms: number | null;
+}
+
+export interface ServerCallMetrics extends CallMetrics {
+  serverId: string;
+}
+
+export interface ProxyStats extends CallMetrics {
+  servers: ServerCallMetrics[];
+}
+
+export interface CallLogFilter {
+  /** Server ID or name. */
+  server?: string;
+  tool?: string;
+  /** Inclusive Unix-seconds bounds. */
+  since?: number;
+  until?: number;
+  errorsOnly?: boolean;
+}
+
+export interface CallLogEntry extends ToolCallEntry {
+  serverId: string;
+  serverName: string;
+}
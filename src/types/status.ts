@@ -35,3 +35,17 @@ export interface HealthCheckEntry {
   proxyOk: boolean;
   redisOk: boolean;
 }
+
+export interface PidOwner {
+  serverId: string;
+  serverName: string;
+  serverPid: number;
+}
+
+/** A server process from a previous session that is still running. */
+export interface OrphanedProcess {
+  pid: number;
+  serverId: string;
+  serverName: string;
+  command: string;
+}